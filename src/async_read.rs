@@ -0,0 +1,80 @@
+//! Parsing from an `AsyncRead` source, enabled with the `async` feature.
+
+use std::future::Future;
+use std::io;
+use std::mem;
+use std::pin::Pin;
+use std::str;
+use std::task::{ Context, Poll };
+
+use futures_io::AsyncRead;
+
+use { parse, JsonValue, Error, Result };
+
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// Future returned by `json::parse_from_async_reader`.
+#[must_use = "futures do nothing unless polled"]
+#[derive(Debug)]
+pub struct ParseAsyncReader<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    filled: usize,
+}
+
+/// Parse JSON from an `AsyncRead` source, such as an HTTP request body.
+/// The returned future reads the source in chunks, yielding to the runtime
+/// whenever no data is available, and parses the document once the source
+/// reaches its end.
+///
+/// ```
+/// # extern crate futures;
+/// # extern crate json;
+/// # fn main() {
+/// use futures::executor::block_on;
+/// use futures::io::Cursor;
+///
+/// let body = Cursor::new(br#"{"status":"ok"}"#.to_vec());
+/// let data = block_on(json::parse_from_async_reader(body)).unwrap();
+///
+/// assert_eq!(data["status"], "ok");
+/// # }
+/// ```
+pub fn parse_from_async_reader<R: AsyncRead + Unpin>(reader: R) -> ParseAsyncReader<R> {
+    ParseAsyncReader {
+        reader,
+        buffer: Vec::new(),
+        filled: 0,
+    }
+}
+
+impl<R: AsyncRead + Unpin> Future for ParseAsyncReader<R> {
+    type Output = Result<JsonValue>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<JsonValue>> {
+        let this = &mut *self;
+
+        loop {
+            if this.filled == this.buffer.len() {
+                this.buffer.resize(this.filled + CHUNK_SIZE, 0);
+            }
+
+            match Pin::new(&mut this.reader).poll_read(cx, &mut this.buffer[this.filled..]) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Ok(0)) => break,
+                Poll::Ready(Ok(read)) => this.filled += read,
+                Poll::Ready(Err(ref error)) if error.kind() == io::ErrorKind::Interrupted => {},
+                Poll::Ready(Err(error)) => return Poll::Ready(Err(error.into())),
+            }
+        }
+
+        let mut buffer = mem::take(&mut this.buffer);
+
+        buffer.truncate(mem::replace(&mut this.filled, 0));
+
+        Poll::Ready(match str::from_utf8(&buffer) {
+            Ok(source) => parse(source),
+            Err(_)     => Err(Error::FailedUtf8Parsing),
+        })
+    }
+}
@@ -0,0 +1,355 @@
+//! A read-only document borrowing from the source it was parsed from.
+//!
+//! `json::parse_borrowed` produces a `JsonValueRef`, in which strings and
+//! object keys without escape sequences are slices of the source instead of
+//! newly allocated `String`s. Only strings that had to be unescaped are
+//! owned. For documents that are parsed, inspected and thrown away, this
+//! saves most of the allocations `json::parse` would make.
+//!
+//! ```
+//! let source = r#"{ "name": "Jörg", "quote": "\"hi\"", "tags": ["a", "b"] }"#;
+//! let data = json::parse_borrowed(source).unwrap();
+//!
+//! assert_eq!(data["name"], "Jörg");
+//! assert_eq!(data["quote"], "\"hi\"");
+//! assert_eq!(data["tags"].len(), 2);
+//! assert!(data["missing"].is_null());
+//!
+//! // Borrowed documents can be turned into regular ones at any time.
+//! let owned: json::JsonValue = data.into();
+//!
+//! assert_eq!(owned["tags"][1], "b");
+//! ```
+
+use std::borrow::Cow;
+use std::ops::Index;
+use std::{ fmt, io, slice };
+use prelude::*;
+
+use codegen::{ DumpGenerator, Generator };
+use number::Number;
+use object::Object;
+use JsonValue;
+
+static NULL: JsonValueRef<'static> = JsonValueRef::Null;
+
+/// Entries of a `JsonValueRef::Object`, in the order of the source.
+pub type EntriesRef<'v, 'a> = slice::Iter<'v, (Cow<'a, str>, JsonValueRef<'a>)>;
+
+/// Members of a `JsonValueRef::Array`.
+pub type MembersRef<'v, 'a> = slice::Iter<'v, JsonValueRef<'a>>;
+
+/// Value of a document parsed with `json::parse_borrowed`, with strings
+/// borrowed from the source where possible.
+///
+/// Objects are kept as a list of entries in the order of the source, which
+/// is cheaper to build than a map. Keys appearing more than once are all
+/// kept, and lookups find the last of them, which is the value `json::parse`
+/// keeps as well.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValueRef<'a> {
+    Null,
+    String(Cow<'a, str>),
+    Number(Number),
+    Boolean(bool),
+    Object(Vec<(Cow<'a, str>, JsonValueRef<'a>)>),
+    Array(Vec<JsonValueRef<'a>>),
+}
+
+impl<'a> JsonValueRef<'a> {
+    pub fn is_string(&self) -> bool {
+        matches!(*self, JsonValueRef::String(_))
+    }
+
+    pub fn is_number(&self) -> bool {
+        matches!(*self, JsonValueRef::Number(_))
+    }
+
+    pub fn is_boolean(&self) -> bool {
+        matches!(*self, JsonValueRef::Boolean(_))
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(*self, JsonValueRef::Null)
+    }
+
+    pub fn is_object(&self) -> bool {
+        matches!(*self, JsonValueRef::Object(_))
+    }
+
+    pub fn is_array(&self) -> bool {
+        matches!(*self, JsonValueRef::Array(_))
+    }
+
+    /// Whether the value is a string borrowed from the source, as opposed
+    /// to one that had to be unescaped into a new `String`.
+    pub fn is_borrowed(&self) -> bool {
+        matches!(*self, JsonValueRef::String(Cow::Borrowed(_)))
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            JsonValueRef::String(ref value) => Some(value),
+            _                               => None
+        }
+    }
+
+    pub fn as_number(&self) -> Option<Number> {
+        match *self {
+            JsonValueRef::Number(value) => Some(value),
+            _                           => None
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_number().map(|value| value.into())
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_number().and_then(|value| {
+            if value.is_sign_positive() {
+                Some(value.into())
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_number().map(|value| value.into())
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            JsonValueRef::Boolean(value) => Some(value),
+            _                            => None
+        }
+    }
+
+    /// Works on `JsonValueRef::Object` - get the value of `key`, finding the
+    /// last one if the key appears more than once.
+    pub fn get(&self, key: &str) -> Option<&JsonValueRef<'a>> {
+        match *self {
+            JsonValueRef::Object(ref entries) => {
+                entries.iter().rev().find(|entry| entry.0 == key).map(|entry| &entry.1)
+            },
+            _ => None
+        }
+    }
+
+    /// Works on `JsonValueRef::Object` - checks if the object has a key.
+    pub fn has_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns length of array or object (number of entries, including
+    /// duplicate keys), defaults to `0` for other types.
+    pub fn len(&self) -> usize {
+        match *self {
+            JsonValueRef::Array(ref members)  => members.len(),
+            JsonValueRef::Object(ref entries) => entries.len(),
+            _                                 => 0
+        }
+    }
+
+    /// Checks whether the array or object has no members, or the string is
+    /// empty. Numbers are empty if they are zero, `false` and `null` are
+    /// empty too.
+    pub fn is_empty(&self) -> bool {
+        match *self {
+            JsonValueRef::Null                => true,
+            JsonValueRef::String(ref value)   => value.is_empty(),
+            JsonValueRef::Number(ref value)   => value.is_empty(),
+            JsonValueRef::Boolean(value)      => !value,
+            JsonValueRef::Array(ref members)  => members.is_empty(),
+            JsonValueRef::Object(ref entries) => entries.is_empty(),
+        }
+    }
+
+    /// Works on `JsonValueRef::Array` - returns an iterator over members.
+    /// Will return an empty iterator if called on non-array types.
+    pub fn members<'v>(&'v self) -> MembersRef<'v, 'a> {
+        match *self {
+            JsonValueRef::Array(ref members) => members.iter(),
+            _                                => [].iter()
+        }
+    }
+
+    /// Works on `JsonValueRef::Object` - returns an iterator over key value
+    /// pairs, including duplicate keys. Will return an empty iterator if
+    /// called on non-object types.
+    pub fn entries<'v>(&'v self) -> EntriesRef<'v, 'a> {
+        match *self {
+            JsonValueRef::Object(ref entries) => entries.iter(),
+            _                                 => [].iter()
+        }
+    }
+
+    /// Copy all borrowed strings, detaching the value from the source.
+    pub fn into_owned(self) -> JsonValueRef<'static> {
+        match self {
+            JsonValueRef::Null            => JsonValueRef::Null,
+            JsonValueRef::String(value)   => JsonValueRef::String(Cow::Owned(value.into_owned())),
+            JsonValueRef::Number(value)   => JsonValueRef::Number(value),
+            JsonValueRef::Boolean(value)  => JsonValueRef::Boolean(value),
+            JsonValueRef::Array(members)  => {
+                JsonValueRef::Array(members.into_iter().map(JsonValueRef::into_owned).collect())
+            },
+            JsonValueRef::Object(entries) => {
+                JsonValueRef::Object(entries.into_iter().map(|(key, value)| {
+                    (Cow::Owned(key.into_owned()), value.into_owned())
+                }).collect())
+            },
+        }
+    }
+
+    /// Prints out the value as JSON string. Keys appearing more than once
+    /// are all written, in order.
+    pub fn dump(&self) -> String {
+        let mut gen = DumpGenerator::new();
+        write(&mut gen, self).expect("Can't fail");
+        gen.consume()
+    }
+}
+
+fn write<G: Generator>(gen: &mut G, value: &JsonValueRef) -> io::Result<()> {
+    match *value {
+        JsonValueRef::Null               => gen.write_null(),
+        JsonValueRef::String(ref value)  => gen.write_string(value),
+        JsonValueRef::Number(ref value)  => gen.write_number(value),
+        JsonValueRef::Boolean(value)     => gen.write_bool(value),
+        JsonValueRef::Array(ref members) => {
+            gen.write_char(b'[')?;
+
+            for (index, member) in members.iter().enumerate() {
+                if index > 0 {
+                    gen.write_char(b',')?;
+                }
+                write(gen, member)?;
+            }
+
+            gen.write_char(b']')
+        },
+        JsonValueRef::Object(ref entries) => {
+            gen.write_char(b'{')?;
+
+            for (index, (key, value)) in entries.iter().enumerate() {
+                if index > 0 {
+                    gen.write_char(b',')?;
+                }
+                gen.write_string(key)?;
+                gen.write_char(b':')?;
+                write(gen, value)?;
+            }
+
+            gen.write_char(b'}')
+        },
+    }
+}
+
+impl<'a> fmt::Display for JsonValueRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            JsonValueRef::String(ref value) => value.fmt(f),
+            _                               => f.write_str(&self.dump())
+        }
+    }
+}
+
+impl<'a, 'v> From<&'v JsonValueRef<'a>> for JsonValue {
+    fn from(value: &'v JsonValueRef<'a>) -> JsonValue {
+        match *value {
+            JsonValueRef::Null               => JsonValue::Null,
+            JsonValueRef::String(ref value)  => JsonValue::from(&**value),
+            JsonValueRef::Number(value)      => JsonValue::Number(value),
+            JsonValueRef::Boolean(value)     => JsonValue::Boolean(value),
+            JsonValueRef::Array(ref members) => {
+                JsonValue::Array(members.iter().map(JsonValue::from).collect())
+            },
+            JsonValueRef::Object(ref entries) => {
+                let mut object = Object::with_capacity(entries.len());
+
+                for (key, value) in entries {
+                    object.insert(key, value.into());
+                }
+
+                JsonValue::Object(object)
+            },
+        }
+    }
+}
+
+impl<'a> From<JsonValueRef<'a>> for JsonValue {
+    fn from(value: JsonValueRef<'a>) -> JsonValue {
+        JsonValue::from(&value)
+    }
+}
+
+/// Indexing by `usize` into arrays, yielding `Null` for anything else or
+/// indexes out of bounds.
+impl<'a> Index<usize> for JsonValueRef<'a> {
+    type Output = JsonValueRef<'a>;
+
+    fn index(&self, index: usize) -> &JsonValueRef<'a> {
+        match *self {
+            JsonValueRef::Array(ref members) => members.get(index).unwrap_or(&NULL),
+            _                                => &NULL
+        }
+    }
+}
+
+/// Indexing by `&str` into objects, yielding `Null` for anything else or
+/// missing keys.
+impl<'a> Index<&str> for JsonValueRef<'a> {
+    type Output = JsonValueRef<'a>;
+
+    fn index(&self, key: &str) -> &JsonValueRef<'a> {
+        self.get(key).unwrap_or(&NULL)
+    }
+}
+
+impl<'a, 'b> PartialEq<&'b str> for JsonValueRef<'a> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == Some(*other)
+    }
+}
+
+impl<'a> PartialEq<str> for JsonValueRef<'a> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == Some(other)
+    }
+}
+
+/// Compares the way `JsonValue`s compare, as if the value was converted
+/// into one first.
+impl<'a> PartialEq<JsonValue> for JsonValueRef<'a> {
+    fn eq(&self, other: &JsonValue) -> bool {
+        match (self, other) {
+            (JsonValueRef::Null, JsonValue::Null)                    => true,
+            (JsonValueRef::Number(left), JsonValue::Number(right))   => left == right,
+            (JsonValueRef::Boolean(left), JsonValue::Boolean(right)) => left == right,
+            (JsonValueRef::String(left), _)                          => other.as_str() == Some(&**left),
+            (JsonValueRef::Array(left), JsonValue::Array(right))     => {
+                left.len() == right.len() && left.iter().zip(right).all(|(left, right)| left == right)
+            },
+            (JsonValueRef::Object(left), JsonValue::Object(right))   => {
+                // Every key must have the value of its last occurrence, and
+                // there must be as many distinct keys as `right` has.
+                let mut distinct = 0;
+
+                for (index, (key, value)) in left.iter().enumerate() {
+                    if left[index + 1..].iter().any(|entry| entry.0 == *key) {
+                        continue;
+                    }
+                    match right.get(key) {
+                        Some(right) if value == right => distinct += 1,
+                        _                             => return false,
+                    }
+                }
+
+                distinct == right.len()
+            },
+            _ => false
+        }
+    }
+}
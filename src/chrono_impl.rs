@@ -0,0 +1,87 @@
+// Conversions between `JsonValue` and `chrono` dates and times, enabled with
+// the `chrono` feature. Dates and times are stored as RFC 3339 strings, the
+// profile of ISO 8601 commonly used in JSON.
+
+use chrono::{ DateTime, FixedOffset, NaiveDate, SecondsFormat, Utc };
+
+use convert::{ FromJson, FromJsonError };
+use JsonValue;
+
+// Format of dates without time, such as `2020-01-31`.
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+impl From<DateTime<Utc>> for JsonValue {
+    fn from(val: DateTime<Utc>) -> JsonValue {
+        val.to_rfc3339_opts(SecondsFormat::AutoSi, true).into()
+    }
+}
+
+impl From<DateTime<FixedOffset>> for JsonValue {
+    fn from(val: DateTime<FixedOffset>) -> JsonValue {
+        val.to_rfc3339_opts(SecondsFormat::AutoSi, false).into()
+    }
+}
+
+impl From<NaiveDate> for JsonValue {
+    fn from(val: NaiveDate) -> JsonValue {
+        val.format(DATE_FORMAT).to_string().into()
+    }
+}
+
+impl FromJson for DateTime<FixedOffset> {
+    fn from_json(value: &JsonValue) -> Result<Self, FromJsonError> {
+        value.as_datetime().ok_or_else(|| FromJsonError::new("datetime", value))
+    }
+}
+
+impl FromJson for DateTime<Utc> {
+    fn from_json(value: &JsonValue) -> Result<Self, FromJsonError> {
+        value.as_datetime_utc().ok_or_else(|| FromJsonError::new("datetime", value))
+    }
+}
+
+impl FromJson for NaiveDate {
+    fn from_json(value: &JsonValue) -> Result<Self, FromJsonError> {
+        value.as_naive_date().ok_or_else(|| FromJsonError::new("date", value))
+    }
+}
+
+impl JsonValue {
+    /// Parse a string holding an RFC 3339 date and time, such as
+    /// `"2020-01-31T12:30:00+02:00"`, keeping its offset from UTC. Returns
+    /// `None` if the value is not a string, or the string can't be parsed.
+    ///
+    /// Requires the `chrono` feature.
+    ///
+    /// ```
+    /// # extern crate chrono;
+    /// # extern crate json;
+    /// # fn main() {
+    /// use chrono::{ TimeZone, Utc };
+    ///
+    /// let value = json::JsonValue::from("2020-01-31T12:30:00+02:00");
+    ///
+    /// assert_eq!(value.as_datetime().unwrap(), Utc.with_ymd_and_hms(2020, 1, 31, 10, 30, 0).unwrap());
+    /// assert_eq!(json::JsonValue::from("yesterday").as_datetime(), None);
+    /// # }
+    /// ```
+    pub fn as_datetime(&self) -> Option<DateTime<FixedOffset>> {
+        self.as_str().and_then(|string| DateTime::parse_from_rfc3339(string).ok())
+    }
+
+    /// Analog to `as_datetime`, converting the date and time to UTC.
+    ///
+    /// Requires the `chrono` feature.
+    pub fn as_datetime_utc(&self) -> Option<DateTime<Utc>> {
+        self.as_datetime().map(|datetime| datetime.with_timezone(&Utc))
+    }
+
+    /// Parse a string holding a date without time, such as `"2020-01-31"`.
+    /// Returns `None` if the value is not a string, or the string can't be
+    /// parsed.
+    ///
+    /// Requires the `chrono` feature.
+    pub fn as_naive_date(&self) -> Option<NaiveDate> {
+        self.as_str().and_then(|string| NaiveDate::parse_from_str(string, DATE_FORMAT).ok())
+    }
+}
@@ -1,9 +1,10 @@
-use std::ptr;
-use std::io::Write;
-use JsonValue;
+use std::{ ptr, fmt, str };
+use std::io::{ Write, IoSlice };
+use { JsonValue, RawJson };
 use number::Number;
-use object::Object;
+use object::{ Object, MultiObject, SortedObject };
 use std::io;
+use prelude::*;
 
 use util::print_dec;
 
@@ -38,6 +39,9 @@ static ESCAPED: [u8; 256] = [
   __, __, __, __, __, __, __, __, __, __, __, __, __, __, __, __, // F
 ];
 
+// Digits of `\uXXXX` escape sequences
+static HEX: [u8; 16] = *b"0123456789abcdef";
+
 #[cfg(test)]
 mod gen_test {
     use codegen::DumpGenerator;
@@ -110,58 +114,255 @@ mod gen_test {
 
 }
 
+/// How numbers with a fractional part are written, set on a generator with
+/// `format_floats`. Integers are always written as they are.
+///
+/// ```
+/// use json::codegen::{ Generator, DumpGenerator, FloatFormat };
+///
+/// let data = json::parse("[19.999, 0.5, 3, 0.001234]").unwrap();
+///
+/// let mut gen = DumpGenerator::new();
+/// gen.format_floats(FloatFormat::DecimalPlaces(2));
+/// gen.write_json(&data).unwrap();
+///
+/// assert_eq!(gen.consume(), "[20.00,0.50,3,0.00]");
+///
+/// let mut gen = DumpGenerator::new();
+/// gen.format_floats(FloatFormat::SignificantDigits(2));
+/// gen.write_json(&data).unwrap();
+///
+/// assert_eq!(gen.consume(), "[20,0.5,3,0.0012]");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FloatFormat {
+    /// Write every digit of the number.
+    #[default]
+    Shortest,
+
+    /// Round to at most this many significant digits, dropping trailing
+    /// zeros of the fraction.
+    SignificantDigits(u8),
+
+    /// Round to exactly this many digits after the decimal point.
+    DecimalPlaces(u8),
+}
+
+/// Serializer of JSON, writing into a `Write` implementor. The required
+/// methods define where the output goes and how it is formatted, while the
+/// provided `write_*` methods can be used to build custom serializers, for
+/// example ones that interleave JSON with other output:
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// use json::codegen::{ Generator, DumpGenerator };
+///
+/// let mut gen = DumpGenerator::new();
+///
+/// // A record separated sequence of values (RFC 7464).
+/// for value in &[object!{ "id" => 1 }, array![true, json::Null]] {
+///     gen.write_char(0x1E).unwrap();
+///     gen.write_json(value).unwrap();
+///     gen.write_char(b'\n').unwrap();
+/// }
+///
+/// gen.write_char(0x1E).unwrap();
+/// gen.write_string("done").unwrap();
+/// gen.write_char(b'\n').unwrap();
+///
+/// assert_eq!(gen.consume(), "\u{1E}{\"id\":1}\n\u{1E}[true,null]\n\u{1E}\"done\"\n");
+/// # }
+/// ```
 pub trait Generator {
     type T: Write;
 
+    /// Slow path of `write_string`, escaping characters starting with
+    /// the byte at `start`. The opening quote has to be written already.
     #[inline(never)]
     fn write_string_complex(&mut self, string: &str, mut start: usize) -> io::Result<()> {
         let bytes = string.as_bytes();
-        try!(self.write(&bytes[ .. start]));
+        let ascii = self.escapes_non_ascii();
+        let mut index = start;
+        self.write(&bytes[ .. start])?;
 
-        for (index, ch) in string.bytes().enumerate().skip(start) {
+        while index < bytes.len() {
+            let ch = bytes[index];
             let escape = ESCAPED[ch as usize];
-            if escape > 0 {
-                try!(self.write(&bytes[start .. index]));
-                try!(self.write(&[b'\\', escape]));
-                start = index + 1;
-            }
-            if escape == b'u' {
-                try!(write!(self.get_writer(), "{:04x}", ch));
+            if let Some(sequence) = if ch < 0x80 { self.escape_byte(ch) } else { None } {
+                self.write(&bytes[start .. index])?;
+                self.write(sequence)?;
+                index += 1;
+                start = index;
+            } else if escape > 0 {
+                self.write(&bytes[start .. index])?;
+                if escape == b'u' {
+                    self.write(&[b'\\', b'u', b'0', b'0', HEX[(ch >> 4) as usize], HEX[(ch & 0xf) as usize]])?;
+                } else {
+                    self.write(&[b'\\', escape])?;
+                }
+                index += 1;
+                start = index;
+            } else if ch >= 0x80 && ascii {
+                self.write(&bytes[start .. index])?;
+                let character = string[index ..].chars().next().expect("Must have a character");
+                for &mut unit in character.encode_utf16(&mut [0; 2]) {
+                    self.write(&[
+                        b'\\', b'u',
+                        HEX[(unit >> 12) as usize],
+                        HEX[((unit >> 8) & 0xf) as usize],
+                        HEX[((unit >> 4) & 0xf) as usize],
+                        HEX[(unit & 0xf) as usize]
+                    ])?;
+                }
+                index += character.len_utf8();
+                start = index;
+            } else {
+                index += 1;
             }
         }
-        try!(self.write(&bytes[start ..]));
+        self.write(&bytes[start ..])?;
         self.write_char(b'"')
     }
 
+    /// Obtain the underlying writer.
     fn get_writer(&mut self) -> &mut Self::T;
 
+    /// Write raw bytes, as they are.
     #[inline(always)]
     fn write(&mut self, slice: &[u8]) -> io::Result<()> {
         self.get_writer().write_all(slice)
     }
 
+    /// Write a single raw byte.
     #[inline(always)]
     fn write_char(&mut self, ch: u8) -> io::Result<()> {
         self.get_writer().write_all(&[ch])
     }
 
+    /// Write either the `slice` or the single `min` byte, depending on
+    /// whether the generator prints whitespace.
     fn write_min(&mut self, slice: &[u8], min: u8) -> io::Result<()>;
 
+    /// Start a new line at the current indentation, if the generator
+    /// prints whitespace.
     #[inline(always)]
     fn new_line(&mut self) -> io::Result<()> { Ok(()) }
 
+    /// Increase the indentation of subsequent lines by one level.
     #[inline(always)]
     fn indent(&mut self) {}
 
+    /// Decrease the indentation of subsequent lines by one level.
     #[inline(always)]
     fn dedent(&mut self) {}
 
+    // Whether values within an object should be vertically aligned by
+    // padding them after the colon to the length of the longest key, as
+    // written, escape sequences included.
+    #[inline(always)]
+    fn aligns_values(&self) -> bool { false }
+
+    /// How numbers with a fractional part should be written.
+    #[inline(always)]
+    fn float_format(&self) -> FloatFormat { FloatFormat::Shortest }
+
+    /// Whether characters outside of ASCII should be escaped as `\uXXXX`,
+    /// using surrogate pairs for characters outside of the BMP.
+    #[inline(always)]
+    fn escapes_non_ascii(&self) -> bool { false }
+
+    /// Custom escape sequence to write in place of the ASCII character `byte`
+    /// within strings, or `None` to escape it as usual. Allows generators to
+    /// escape characters that are valid in JSON but unsafe elsewhere, for
+    /// example `<` and `>` in JSON embedded within HTML:
+    ///
+    /// ```
+    /// use json::codegen::Generator;
+    ///
+    /// struct HtmlSafe(Vec<u8>);
+    ///
+    /// impl Generator for HtmlSafe {
+    ///     type T = Vec<u8>;
+    ///
+    ///     fn get_writer(&mut self) -> &mut Vec<u8> { &mut self.0 }
+    ///
+    ///     fn write_min(&mut self, _: &[u8], min: u8) -> std::io::Result<()> {
+    ///         self.write_char(min)
+    ///     }
+    ///
+    ///     fn escape_byte(&self, byte: u8) -> Option<&'static [u8]> {
+    ///         match byte {
+    ///             b'<' => Some(b"\\u003c"),
+    ///             b'>' => Some(b"\\u003e"),
+    ///             _    => None,
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let mut gen = HtmlSafe(Vec::new());
+    /// gen.write_string("</script>").unwrap();
+    ///
+    /// assert_eq!(gen.0, br#""\u003c/script\u003e""#.to_vec());
+    /// ```
+    #[inline(always)]
+    fn escape_byte(&self, _byte: u8) -> Option<&'static [u8]> { None }
+
+    /// Width in bytes that arrays and objects may take up on a single line,
+    /// if the generator keeps them on one line when they fit.
+    #[inline(always)]
+    fn wraps_at(&self) -> Option<usize> { None }
+
+    /// Number of bytes written on the current line so far. Only needs to be
+    /// accurate if `wraps_at` returns a width.
+    #[inline(always)]
+    fn column(&self) -> usize { 0 }
+
+    /// Custom separators written between members of arrays and entries of
+    /// objects, and between keys and values, if the generator uses any.
+    /// Generators keeping arrays and objects on a single line use them too.
+    #[inline(always)]
+    fn separators(&self) -> Option<(&str, &str)> { None }
+
+    /// Whether entries of objects are written sorted by key. Generators
+    /// keeping arrays and objects on a single line sort them too.
+    #[inline(always)]
+    fn sorts_keys(&self) -> bool { false }
+
+    /// Write the separator between members of arrays and entries of objects.
+    /// With pretty printing, it's followed by a line break.
+    #[inline(always)]
+    fn write_item_separator(&mut self) -> io::Result<()> {
+        self.write_char(b',')
+    }
+
+    /// Write the separator between a key and a value within an object.
+    #[inline(always)]
+    fn write_key_separator(&mut self) -> io::Result<()> {
+        self.write_min(b": ", b':')
+    }
+
+    /// Write `null`.
+    #[inline(always)]
+    fn write_null(&mut self) -> io::Result<()> {
+        self.write(b"null")
+    }
+
+    /// Write `true` or `false`.
+    #[inline(always)]
+    fn write_bool(&mut self, value: bool) -> io::Result<()> {
+        self.write(if value { b"true" } else { b"false" })
+    }
+
+    /// Write a string, quoted and escaped.
     #[inline(always)]
     fn write_string(&mut self, string: &str) -> io::Result<()> {
         try!(self.write_char(b'"'));
 
+        let ascii = self.escapes_non_ascii();
+
         for (index, ch) in string.bytes().enumerate() {
-            if ESCAPED[ch as usize] > 0 {
+            if ESCAPED[ch as usize] > 0 || (ch >= 0x80 && ascii) || (ch < 0x80 && self.escape_byte(ch).is_some()) {
                 return self.write_string_complex(string, index)
             }
         }
@@ -170,105 +371,183 @@ pub trait Generator {
         self.write_char(b'"')
     }
 
+    /// Write a number. NaN and infinite numbers are written as `null`.
     #[inline(always)]
     fn write_number(&mut self, num: &Number) -> io::Result<()> {
-        if num.is_nan() {
-            return self.write(b"null");
-        }
-        let (positive, mantissa, exponent) = num.as_parts();
-        unsafe {
-            print_dec::write(
-                self.get_writer(),
-                positive,
-                mantissa,
-                exponent
-            )
+        if num.is_nan() || num.is_infinite() {
+            return self.write_null();
         }
+        let format = self.float_format();
+        write_finite_number(self.get_writer(), num, format)
     }
 
+    /// Write an object along with all of its entries.
     #[inline(always)]
     fn write_object(&mut self, object: &Object) -> io::Result<()> {
-        try!(self.write_char(b'{'));
-        let mut iter = object.iter();
+        if !object.is_empty() && write_inline(self, |gen| gen.write_object(object))? {
+            return Ok(());
+        }
+        self.write_entries(object.iter().map(|(key, value)| (key.as_str(), value)))
+    }
+
+    /// Write a `MultiObject`, including entries with duplicate keys.
+    #[inline(always)]
+    fn write_multi_object(&mut self, object: &MultiObject) -> io::Result<()> {
+        if !object.is_empty() && write_inline(self, |gen| gen.write_multi_object(object))? {
+            return Ok(());
+        }
+        self.write_entries(object.iter().map(|(key, value)| (key.as_str(), value)))
+    }
+
+    /// Write a `SortedObject`, with keys in sorted order.
+    #[inline(always)]
+    fn write_sorted_object(&mut self, object: &SortedObject) -> io::Result<()> {
+        if !object.is_empty() && write_inline(self, |gen| gen.write_sorted_object(object))? {
+            return Ok(());
+        }
+        self.write_entries(object.iter().map(|(key, value)| (key.as_str(), value)))
+    }
+
+    #[inline(always)]
+    fn write_entries<'e, I>(&mut self, entries: I) -> io::Result<()>
+    where I: Iterator<Item = (&'e str, &'e JsonValue)> + Clone {
+        self.write_char(b'{')?;
+
+        let width = if self.aligns_values() {
+            entries.clone().map(|(key, _)| written_width(self, key)).max().unwrap_or(0)
+        } else {
+            0
+        };
+
+        let mut iter = entries;
 
         if let Some((key, value)) = iter.next() {
             self.indent();
-            try!(self.new_line());
-            try!(self.write_string(key));
-            try!(self.write_min(b": ", b':'));
-            try!(self.write_json(value));
+            self.new_line()?;
+            self.write_string(key)?;
+            self.write_key_separator()?;
+            self.write_padding(width, key)?;
+            self.write_json(value)?;
         } else {
-            try!(self.write_char(b'}'));
+            self.write_char(b'}')?;
             return Ok(());
         }
 
         for (key, value) in iter {
-            try!(self.write_char(b','));
-            try!(self.new_line());
-            try!(self.write_string(key));
-            try!(self.write_min(b": ", b':'));
-            try!(self.write_json(value));
+            self.write_item_separator()?;
+            self.new_line()?;
+            self.write_string(key)?;
+            self.write_key_separator()?;
+            self.write_padding(width, key)?;
+            self.write_json(value)?;
         }
 
         self.dedent();
-        try!(self.new_line());
+        self.new_line()?;
         self.write_char(b'}')
     }
 
+    #[inline(always)]
+    fn write_padding(&mut self, width: usize, key: &str) -> io::Result<()> {
+        if width == 0 {
+            return Ok(());
+        }
+
+        for _ in written_width(self, key) .. width {
+            self.write_char(b' ')?;
+        }
+
+        Ok(())
+    }
+
+    /// Write an array along with all of its members.
+    fn write_array(&mut self, array: &[JsonValue]) -> io::Result<()> {
+        if !array.is_empty() && write_inline(self, |gen| gen.write_array(array))? {
+            return Ok(());
+        }
+        self.write_char(b'[')?;
+        let mut iter = array.iter();
+
+        if let Some(item) = iter.next() {
+            self.indent();
+            self.new_line()?;
+            self.write_json(item)?;
+        } else {
+            return self.write_char(b']');
+        }
+
+        for item in iter {
+            self.write_item_separator()?;
+            self.new_line()?;
+            self.write_json(item)?;
+        }
+
+        self.dedent();
+        self.new_line()?;
+        self.write_char(b']')
+    }
+
+    /// Write any `JsonValue`.
     fn write_json(&mut self, json: &JsonValue) -> io::Result<()> {
         match *json {
-            JsonValue::Null               => self.write(b"null"),
+            JsonValue::Null               => self.write_null(),
             JsonValue::Short(ref short)   => self.write_string(short.as_str()),
             JsonValue::String(ref string) => self.write_string(string),
             JsonValue::Number(ref number) => self.write_number(number),
-            JsonValue::Boolean(true)      => self.write(b"true"),
-            JsonValue::Boolean(false)     => self.write(b"false"),
-            JsonValue::Array(ref array)   => {
-                try!(self.write_char(b'['));
-                let mut iter = array.iter();
-
-                if let Some(item) = iter.next() {
-                    self.indent();
-                    try!(self.new_line());
-                    try!(self.write_json(item));
-                } else {
-                    try!(self.write_char(b']'));
-                    return Ok(());
-                }
-
-                for item in iter {
-                    try!(self.write_char(b','));
-                    try!(self.new_line());
-                    try!(self.write_json(item));
-                }
-
-                self.dedent();
-                try!(self.new_line());
-                self.write_char(b']')
-            },
-            JsonValue::Object(ref object) => {
-                self.write_object(object)
-            }
+            JsonValue::Boolean(value)     => self.write_bool(value),
+            JsonValue::Array(ref array)   => self.write_array(array),
+            JsonValue::Object(ref object) => self.write_object(object),
+            JsonValue::Raw(ref raw)       => self.write_raw(raw),
         }
     }
+
+    /// Write the text of a `JsonValue::Raw`, as it is.
+    #[inline(always)]
+    fn write_raw(&mut self, raw: &RawJson) -> io::Result<()> {
+        self.write(raw.as_str().as_bytes())
+    }
 }
 
 pub struct DumpGenerator {
     code: Vec<u8>,
+    float_format: FloatFormat,
+    escape_non_ascii: bool,
 }
 
 impl DumpGenerator {
     pub fn new() -> Self {
+        DumpGenerator::with_buffer(Vec::with_capacity(1024))
+    }
+
+    // Create a generator appending to `code`, reusing its allocation.
+    pub(crate) fn with_buffer(code: Vec<u8>) -> Self {
         DumpGenerator {
-            code: Vec::with_capacity(1024),
+            code,
+            float_format: FloatFormat::Shortest,
+            escape_non_ascii: false,
         }
     }
 
+    /// Write numbers with a fractional part according to `format`.
+    pub fn format_floats(&mut self, format: FloatFormat) {
+        self.float_format = format;
+    }
+
+    /// Escape all characters outside of ASCII as `\uXXXX`, so the output
+    /// survives transports and systems that only handle ASCII.
+    pub fn escape_non_ascii(&mut self, escape: bool) {
+        self.escape_non_ascii = escape;
+    }
+
     pub fn consume(self) -> String {
         // Original strings were unicode, numbers are all ASCII,
         // therefore this is safe.
         unsafe { String::from_utf8_unchecked(self.code) }
     }
+
+    pub(crate) fn into_bytes(self) -> Vec<u8> {
+        self.code
+    }
 }
 
 impl Generator for DumpGenerator {
@@ -295,23 +574,250 @@ impl Generator for DumpGenerator {
         self.code.push(min);
         Ok(())
     }
+
+    fn float_format(&self) -> FloatFormat {
+        self.float_format
+    }
+
+    fn escapes_non_ascii(&self) -> bool {
+        self.escape_non_ascii
+    }
+}
+
+/// Generator writing canonical JSON as defined by the JSON Canonicalization
+/// Scheme (RFC 8785), producing the same bytes for equal values regardless
+/// of how they were created. Useful for hashing and signing.
+///
+/// Object keys are sorted by their UTF-16 code units, numbers are written
+/// the way ECMAScript writes them, and no whitespace is added. NaN and
+/// infinite numbers can't be written and fail with
+/// `io::ErrorKind::InvalidData`.
+pub struct CanonicalGenerator {
+    code: Vec<u8>,
+}
+
+impl CanonicalGenerator {
+    pub fn new() -> Self {
+        CanonicalGenerator {
+            code: Vec::with_capacity(1024),
+        }
+    }
+
+    pub fn consume(self) -> String {
+        unsafe { String::from_utf8_unchecked(self.code) }
+    }
+}
+
+impl Default for CanonicalGenerator {
+    fn default() -> Self {
+        CanonicalGenerator::new()
+    }
+}
+
+impl Generator for CanonicalGenerator {
+    type T = Vec<u8>;
+
+    #[inline(always)]
+    fn write(&mut self, slice: &[u8]) -> io::Result<()> {
+        extend_from_slice(&mut self.code, slice);
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn write_char(&mut self, ch: u8) -> io::Result<()> {
+        self.code.push(ch);
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn get_writer(&mut self) -> &mut Vec<u8> {
+        &mut self.code
+    }
+
+    #[inline(always)]
+    fn write_min(&mut self, _: &[u8], min: u8) -> io::Result<()> {
+        self.code.push(min);
+        Ok(())
+    }
+
+    fn write_number(&mut self, num: &Number) -> io::Result<()> {
+        if num.is_nan() || num.is_infinite() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "NaN and Infinity are not allowed in canonical JSON"
+            ));
+        }
+
+        // Going through the decimal text rounds to the nearest `f64`, which
+        // `f64::from` doesn't do for every exponent.
+        match num.to_string().parse::<f64>() {
+            Ok(float) if float.is_finite() => write_ecmascript_number(&mut self.code, float),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} is out of the range of canonical JSON numbers", num)
+            )),
+        }
+    }
+
+    fn write_object(&mut self, object: &Object) -> io::Result<()> {
+        let mut entries: Vec<_> = object.iter().map(|(key, value)| (key.as_str(), value)).collect();
+
+        entries.sort_by(|a, b| a.0.encode_utf16().cmp(b.0.encode_utf16()));
+
+        self.write_entries(entries.into_iter())
+    }
+
+    fn write_multi_object(&mut self, object: &MultiObject) -> io::Result<()> {
+        let mut entries: Vec<_> = object.iter().map(|(key, value)| (key.as_str(), value)).collect();
+
+        entries.sort_by(|a, b| a.0.encode_utf16().cmp(b.0.encode_utf16()));
+
+        self.write_entries(entries.into_iter())
+    }
+
+    // Keys are sorted by their bytes, not by UTF-16 code units.
+    fn write_sorted_object(&mut self, object: &SortedObject) -> io::Result<()> {
+        let mut entries: Vec<_> = object.iter().map(|(key, value)| (key.as_str(), value)).collect();
+
+        entries.sort_by(|a, b| a.0.encode_utf16().cmp(b.0.encode_utf16()));
+
+        self.write_entries(entries.into_iter())
+    }
+
+    // Raw JSON is written the canonical way, rather than as it is.
+    fn write_raw(&mut self, raw: &RawJson) -> io::Result<()> {
+        match raw.value() {
+            Some(value) => self.write_json(&value),
+            None        => Err(io::Error::new(io::ErrorKind::InvalidData, "raw JSON text is invalid")),
+        }
+    }
+}
+
+// Write a finite `float` like ECMAScript's `Number.prototype.toString`,
+// with the shortest digits that round-trip, switching to the exponential
+// form for numbers below 1e-6 or from 1e21 on.
+fn write_ecmascript_number<W: Write>(wr: &mut W, float: f64) -> io::Result<()> {
+    if float == 0.0 {
+        return wr.write_all(b"0");
+    }
+
+    if float < 0.0 {
+        wr.write_all(b"-")?;
+    }
+
+    // Shortest round-tripping digits, such as `1.2345e-7`.
+    let scientific = format!("{:e}", float.abs());
+    let (mantissa, point) = match scientific.split_once('e') {
+        // Position of the decimal point relative to the first digit.
+        Some((mantissa, exponent)) if float.is_finite() => match exponent.parse::<i32>() {
+            Ok(exponent) => (mantissa, exponent + 1),
+            Err(error)   => return Err(io::Error::new(io::ErrorKind::InvalidData, error.to_string())),
+        },
+        _ => return Err(io::Error::new(io::ErrorKind::InvalidData, format!("{} is not a finite number", float))),
+    };
+    let digits = mantissa.replace('.', "");
+    let digits = digits.as_str();
+    let count = digits.len() as i32;
+
+    if count <= point && point <= 21 {
+        wr.write_all(digits.as_bytes())?;
+
+        for _ in count..point {
+            wr.write_all(b"0")?;
+        }
+
+        Ok(())
+    } else if 0 < point && point <= 21 {
+        let (integer, fraction) = digits.split_at(point as usize);
+
+        write!(wr, "{}.{}", integer, fraction)
+    } else if -6 < point && point <= 0 {
+        wr.write_all(b"0.")?;
+
+        for _ in point..0 {
+            wr.write_all(b"0")?;
+        }
+
+        wr.write_all(digits.as_bytes())
+    } else {
+        let sign = if point > 0 { '+' } else { '-' };
+
+        if count == 1 {
+            write!(wr, "{}e{}{}", digits, sign, (point - 1).abs())
+        } else {
+            write!(wr, "{}.{}e{}{}", &digits[..1], &digits[1..], sign, (point - 1).abs())
+        }
+    }
 }
 
 pub struct PrettyGenerator {
     code: Vec<u8>,
+    line_start: usize,
     dent: u16,
-    spaces_per_indent: u16,
+    indent: String,
+    wrap_at: Option<usize>,
+    align_values: bool,
+    float_format: FloatFormat,
+    escape_non_ascii: bool,
 }
 
 impl PrettyGenerator {
     pub fn new(spaces: u16) -> Self {
+        PrettyGenerator::with_indent(&" ".repeat(spaces as usize))
+    }
+
+    /// Create a generator indenting new blocks with the `indent` string,
+    /// such as `"\t"`, instead of a number of spaces.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// use json::codegen::{ Generator, PrettyGenerator };
+    ///
+    /// let mut gen = PrettyGenerator::with_indent("\t");
+    /// gen.write_json(&object!{ "a" => array![1] }).unwrap();
+    ///
+    /// assert_eq!(gen.consume(), "{\n\t\"a\": [\n\t\t1\n\t]\n}");
+    /// # }
+    /// ```
+    pub fn with_indent(indent: &str) -> Self {
         PrettyGenerator {
             code: Vec::with_capacity(1024),
+            line_start: 0,
             dent: 0,
-            spaces_per_indent: spaces
+            indent: indent.into(),
+            wrap_at: None,
+            align_values: false,
+            float_format: FloatFormat::Shortest,
+            escape_non_ascii: false,
         }
     }
 
+    /// Vertically align values within objects, padding them after the colon
+    /// to the length of the longest key in the object, counting escape
+    /// sequences the way they are written.
+    pub fn align_values(&mut self, align: bool) {
+        self.align_values = align;
+    }
+
+    /// Keep arrays and objects on a single line, with a space after each
+    /// comma and colon, when the line would not exceed `width` bytes.
+    /// Larger ones are broken into lines as usual.
+    pub fn wrap_at(&mut self, width: usize) {
+        self.wrap_at = Some(width);
+    }
+
+    /// Write numbers with a fractional part according to `format`.
+    pub fn format_floats(&mut self, format: FloatFormat) {
+        self.float_format = format;
+    }
+
+    /// Escape all characters outside of ASCII as `\uXXXX`, so the output
+    /// survives transports and systems that only handle ASCII.
+    pub fn escape_non_ascii(&mut self, escape: bool) {
+        self.escape_non_ascii = escape;
+    }
+
     pub fn consume(self) -> String {
         unsafe { String::from_utf8_unchecked(self.code) }
     }
@@ -345,8 +851,9 @@ impl Generator for PrettyGenerator {
 
     fn new_line(&mut self) -> io::Result<()> {
         self.code.push(b'\n');
-        for _ in 0..(self.dent * self.spaces_per_indent) {
-            self.code.push(b' ');
+        self.line_start = self.code.len();
+        for _ in 0..self.dent {
+            extend_from_slice(&mut self.code, self.indent.as_bytes());
         }
         Ok(())
     }
@@ -358,69 +865,272 @@ impl Generator for PrettyGenerator {
     fn dedent(&mut self) {
         self.dent -= 1;
     }
+
+    fn aligns_values(&self) -> bool {
+        self.align_values
+    }
+
+    fn wraps_at(&self) -> Option<usize> {
+        self.wrap_at
+    }
+
+    fn column(&self) -> usize {
+        self.code.len() - self.line_start
+    }
+
+    fn float_format(&self) -> FloatFormat {
+        self.float_format
+    }
+
+    fn escapes_non_ascii(&self) -> bool {
+        self.escape_non_ascii
+    }
 }
 
+// Size of the buffer used by generators writing into an `io::Write`.
+// Output is handed to the writer in chunks of this size, rather than
+// a byte or a few at a time. The buffer starts out empty and grows up to
+// this size as needed, so writing out small values allocates little.
+const BUFFER_SIZE: usize = 8 * 1024;
+
+/// Generator writing compact JSON into an implementor of `io::Write`.
+///
+/// Output is buffered and handed to the writer in large chunks, which
+/// makes a difference for writers that perform a system call per write,
+/// such as `TcpStream`.
+///
+/// It is critical to call `finish` once done writing. Dropping the
+/// generator attempts to write out the buffer as well, but any error
+/// happening in the process is ignored. Output written directly into
+/// the writer obtained with `get_writer` skips the buffer, so `finish`
+/// has to be called before that too.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// use json::codegen::{ Generator, WriterGenerator };
+///
+/// let mut output = Vec::new();
+///
+/// {
+///     let mut gen = WriterGenerator::new(&mut output);
+///     gen.write_json(&array![1, 2]).unwrap();
+///     gen.finish().unwrap();
+/// }
+///
+/// assert_eq!(output, b"[1,2]");
+/// # }
+/// ```
 pub struct WriterGenerator<'a, W: 'a + Write> {
-    writer: &'a mut W
+    writer: &'a mut W,
+    buffer: Vec<u8>,
+    vectored: bool,
+    float_format: FloatFormat,
+    escape_non_ascii: bool,
 }
 
 impl<'a, W> WriterGenerator<'a, W> where W: 'a + Write {
     pub fn new(writer: &'a mut W) -> Self {
         WriterGenerator {
-            writer: writer
+            writer,
+            buffer: Vec::new(),
+            vectored: false,
+            float_format: FloatFormat::Shortest,
+            escape_non_ascii: false,
         }
     }
+
+    /// Write numbers with a fractional part according to `format`.
+    pub fn format_floats(&mut self, format: FloatFormat) {
+        self.float_format = format;
+    }
+
+    /// Escape all characters outside of ASCII as `\uXXXX`, so the output
+    /// survives transports and systems that only handle ASCII.
+    pub fn escape_non_ascii(&mut self, escape: bool) {
+        self.escape_non_ascii = escape;
+    }
+
+    /// Use `Write::write_vectored` to write out the buffer together with
+    /// large strings, instead of writing them one after the other. Only
+    /// worth enabling for writers with an efficient `write_vectored`.
+    pub fn vectored(&mut self, vectored: bool) {
+        self.vectored = vectored;
+    }
+
+    /// Write out all buffered output to the underlying writer.
+    pub fn finish(&mut self) -> io::Result<()> {
+        write_out(self.writer, &mut self.buffer)
+    }
 }
 
 impl<'a, W> Generator for WriterGenerator<'a, W> where W: Write {
     type T = W;
 
+    /// Obtain the underlying writer. Buffered output is not written out
+    /// before, see `finish`.
     #[inline(always)]
     fn get_writer(&mut self) -> &mut W {
-        &mut self.writer
+        self.writer
+    }
+
+    #[inline(always)]
+    fn write(&mut self, slice: &[u8]) -> io::Result<()> {
+        write_buffered(self.writer, &mut self.buffer, slice, self.vectored)
+    }
+
+    #[inline(always)]
+    fn write_char(&mut self, ch: u8) -> io::Result<()> {
+        write_buffered(self.writer, &mut self.buffer, &[ch], false)
     }
 
     #[inline(always)]
     fn write_min(&mut self, _: &[u8], min: u8) -> io::Result<()> {
-        self.writer.write_all(&[min])
+        self.write_char(min)
+    }
+
+    // Numbers go into the buffer, rather than straight into the writer.
+    fn write_number(&mut self, num: &Number) -> io::Result<()> {
+        if num.is_nan() || num.is_infinite() {
+            return self.write_null();
+        }
+        write_number_buffered(self.writer, &mut self.buffer, num, self.float_format).map(|_| ())
+    }
+
+    fn float_format(&self) -> FloatFormat {
+        self.float_format
+    }
+
+    fn escapes_non_ascii(&self) -> bool {
+        self.escape_non_ascii
     }
 }
 
+impl<'a, W> Drop for WriterGenerator<'a, W> where W: 'a + Write {
+    fn drop(&mut self) {
+        // Errors can't be reported from here, use `finish` to handle them.
+        let _ = self.finish();
+    }
+}
 
+/// Generator writing pretty printed JSON into an implementor of `io::Write`.
+///
+/// Output is buffered the same way as with `WriterGenerator`. It is
+/// critical to call `finish` once done writing, as errors writing out
+/// the buffer when the generator is dropped are ignored.
 pub struct PrettyWriterGenerator<'a, W: 'a + Write> {
     writer: &'a mut W,
+    buffer: Vec<u8>,
+    vectored: bool,
+    column: usize,
     dent: u16,
-    spaces_per_indent: u16,
+    indent: String,
+    wrap_at: Option<usize>,
+    align_values: bool,
+    float_format: FloatFormat,
+    escape_non_ascii: bool,
 }
 
 impl<'a, W> PrettyWriterGenerator<'a, W> where W: 'a + Write {
     pub fn new(writer: &'a mut W, spaces: u16) -> Self {
+        PrettyWriterGenerator::with_indent(writer, &" ".repeat(spaces as usize))
+    }
+
+    /// Create a generator indenting new blocks with the `indent` string,
+    /// see `PrettyGenerator::with_indent`.
+    pub fn with_indent(writer: &'a mut W, indent: &str) -> Self {
         PrettyWriterGenerator {
-            writer: writer,
+            writer,
+            buffer: Vec::new(),
+            vectored: false,
+            column: 0,
             dent: 0,
-            spaces_per_indent: spaces,
+            indent: indent.into(),
+            wrap_at: None,
+            align_values: false,
+            float_format: FloatFormat::Shortest,
+            escape_non_ascii: false,
         }
     }
+
+    /// Vertically align values within objects, padding them after the colon
+    /// to the length of the longest key in the object, counting escape
+    /// sequences the way they are written.
+    pub fn align_values(&mut self, align: bool) {
+        self.align_values = align;
+    }
+
+    /// Keep arrays and objects on a single line, with a space after each
+    /// comma and colon, when the line would not exceed `width` bytes.
+    /// Larger ones are broken into lines as usual.
+    pub fn wrap_at(&mut self, width: usize) {
+        self.wrap_at = Some(width);
+    }
+
+    /// Write numbers with a fractional part according to `format`.
+    pub fn format_floats(&mut self, format: FloatFormat) {
+        self.float_format = format;
+    }
+
+    /// Escape all characters outside of ASCII as `\uXXXX`, so the output
+    /// survives transports and systems that only handle ASCII.
+    pub fn escape_non_ascii(&mut self, escape: bool) {
+        self.escape_non_ascii = escape;
+    }
+
+    /// Use `Write::write_vectored`, see `WriterGenerator::vectored`.
+    pub fn vectored(&mut self, vectored: bool) {
+        self.vectored = vectored;
+    }
+
+    /// Write out all buffered output to the underlying writer.
+    pub fn finish(&mut self) -> io::Result<()> {
+        write_out(self.writer, &mut self.buffer)
+    }
 }
 
 impl<'a, W> Generator for PrettyWriterGenerator<'a, W> where W: Write {
     type T = W;
 
+    /// Obtain the underlying writer. Buffered output is not written out
+    /// before, see `finish`.
     #[inline(always)]
     fn get_writer(&mut self) -> &mut W {
-        &mut self.writer
+        self.writer
+    }
+
+    #[inline(always)]
+    fn write(&mut self, slice: &[u8]) -> io::Result<()> {
+        self.column += slice.len();
+        write_buffered(self.writer, &mut self.buffer, slice, self.vectored)
+    }
+
+    #[inline(always)]
+    fn write_char(&mut self, ch: u8) -> io::Result<()> {
+        self.column += 1;
+        write_buffered(self.writer, &mut self.buffer, &[ch], false)
     }
 
     #[inline(always)]
     fn write_min(&mut self, slice: &[u8], _: u8) -> io::Result<()> {
-        self.writer.write_all(slice)
+        self.write(slice)
+    }
+
+    // Numbers go into the buffer, rather than straight into the writer.
+    fn write_number(&mut self, num: &Number) -> io::Result<()> {
+        if num.is_nan() || num.is_infinite() {
+            return self.write_null();
+        }
+        self.column += write_number_buffered(self.writer, &mut self.buffer, num, self.float_format)?;
+        Ok(())
     }
 
     fn new_line(&mut self) -> io::Result<()> {
-        try!(self.write_char(b'\n'));
-        for _ in 0..(self.dent * self.spaces_per_indent) {
-            try!(self.write_char(b' '));
+        self.write_char(b'\n')?;
+        for _ in 0..self.dent {
+            write_buffered(self.writer, &mut self.buffer, self.indent.as_bytes(), false)?;
         }
+        self.column = self.dent as usize * self.indent.len();
         Ok(())
     }
 
@@ -431,6 +1141,749 @@ impl<'a, W> Generator for PrettyWriterGenerator<'a, W> where W: Write {
     fn dedent(&mut self) {
         self.dent -= 1;
     }
+
+    fn aligns_values(&self) -> bool {
+        self.align_values
+    }
+
+    fn wraps_at(&self) -> Option<usize> {
+        self.wrap_at
+    }
+
+    fn column(&self) -> usize {
+        self.column
+    }
+
+    fn float_format(&self) -> FloatFormat {
+        self.float_format
+    }
+
+    fn escapes_non_ascii(&self) -> bool {
+        self.escape_non_ascii
+    }
+}
+
+impl<'a, W> Drop for PrettyWriterGenerator<'a, W> where W: 'a + Write {
+    fn drop(&mut self) {
+        // Errors can't be reported from here, use `finish` to handle them.
+        let _ = self.finish();
+    }
+}
+
+/// Generator writing JSON into an implementor of `fmt::Write`, such as
+/// a `String` or a `fmt::Formatter`, without going through `io::Write`.
+/// Output is compact, or pretty printed if created with `pretty`.
+///
+/// Output is buffered the same way as with `WriterGenerator`, and written
+/// out when the generator is dropped, or explicitly with `finish`.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// use json::codegen::{ Generator, FmtGenerator };
+///
+/// let mut code = String::new();
+///
+/// {
+///     let mut gen = FmtGenerator::pretty(&mut code, 2);
+///     gen.write_json(&object!{ "a" => array![1, 2] }).unwrap();
+///     gen.finish().unwrap();
+/// }
+///
+/// assert_eq!(code, "{\n  \"a\": [\n    1,\n    2\n  ]\n}");
+/// # }
+/// ```
+pub struct FmtGenerator<'a, W: 'a + fmt::Write + ?Sized> {
+    writer: &'a mut W,
+    buffer: Vec<u8>,
+    dent: u16,
+    indent: Option<String>,
+}
+
+impl<'a, W> FmtGenerator<'a, W> where W: 'a + fmt::Write + ?Sized {
+    pub fn new(writer: &'a mut W) -> Self {
+        FmtGenerator {
+            writer,
+            buffer: Vec::new(),
+            dent: 0,
+            indent: None,
+        }
+    }
+
+    /// Create a generator pretty printing with `spaces` spaces per level
+    /// of indentation, like `PrettyGenerator`.
+    pub fn pretty(writer: &'a mut W, spaces: u16) -> Self {
+        let mut gen = FmtGenerator::new(writer);
+        gen.indent = Some(" ".repeat(spaces as usize));
+        gen
+    }
+
+    /// Write out all buffered output to the underlying writer.
+    pub fn finish(&mut self) -> fmt::Result {
+        write_out_fmt(self.writer, &mut self.buffer)
+    }
+}
+
+impl<'a, W> Generator for FmtGenerator<'a, W> where W: fmt::Write + ?Sized {
+    type T = Vec<u8>;
+
+    #[inline(always)]
+    fn get_writer(&mut self) -> &mut Vec<u8> {
+        &mut self.buffer
+    }
+
+    #[inline(always)]
+    fn write(&mut self, slice: &[u8]) -> io::Result<()> {
+        if self.buffer.len() + slice.len() > BUFFER_SIZE {
+            write_out_fmt(self.writer, &mut self.buffer)
+                .map_err(|_| io::Error::other("formatter error"))?;
+        }
+        extend_from_slice(&mut self.buffer, slice);
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn write_char(&mut self, ch: u8) -> io::Result<()> {
+        self.write(&[ch])
+    }
+
+    #[inline(always)]
+    fn write_min(&mut self, slice: &[u8], min: u8) -> io::Result<()> {
+        if self.indent.is_some() {
+            self.write(slice)
+        } else {
+            self.write_char(min)
+        }
+    }
+
+    fn new_line(&mut self) -> io::Result<()> {
+        if let Some(ref indent) = self.indent {
+            self.buffer.push(b'\n');
+            for _ in 0..self.dent {
+                extend_from_slice(&mut self.buffer, indent.as_bytes());
+            }
+        }
+        Ok(())
+    }
+
+    fn indent(&mut self) {
+        self.dent += 1;
+    }
+
+    fn dedent(&mut self) {
+        self.dent -= 1;
+    }
+}
+
+impl<'a, W> Drop for FmtGenerator<'a, W> where W: 'a + fmt::Write + ?Sized {
+    fn drop(&mut self) {
+        // Errors can't be reported from here, use `finish` to handle them.
+        let _ = self.finish();
+    }
+}
+
+/// Options for `JsonValue::dump_with` and `JsonValue::write_with`, combining
+/// everything the generators can do. By default the output is the same as
+/// with `JsonValue::dump`.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// use json::StringifyOptions;
+/// use json::codegen::FloatFormat;
+///
+/// let data = object!{ "price" => 5.5, "item" => "café" };
+///
+/// let options = StringifyOptions::new()
+///     .indent("\t")
+///     .newline("\r\n")
+///     .sort_keys(true)
+///     .escape_non_ascii(true)
+///     .float_format(FloatFormat::DecimalPlaces(2));
+///
+/// assert_eq!(
+///     data.dump_with(&options),
+///     "{\r\n\t\"item\": \"caf\\u00e9\",\r\n\t\"price\": 5.50\r\n}"
+/// );
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StringifyOptions {
+    indent: Option<String>,
+    newline: String,
+    sort_keys: bool,
+    escape_non_ascii: bool,
+    float_format: FloatFormat,
+    align_values: bool,
+    wrap_at: Option<usize>,
+    separators: Option<(String, String)>,
+}
+
+impl Default for StringifyOptions {
+    fn default() -> Self {
+        StringifyOptions {
+            indent: None,
+            newline: "\n".into(),
+            sort_keys: false,
+            escape_non_ascii: false,
+            float_format: FloatFormat::Shortest,
+            align_values: false,
+            wrap_at: None,
+            separators: None,
+        }
+    }
+}
+
+impl StringifyOptions {
+    /// Options for compact output, same as `StringifyOptions::default()`.
+    pub fn new() -> Self {
+        StringifyOptions::default()
+    }
+
+    /// Pretty print, indenting new blocks with the `indent` string.
+    pub fn indent(mut self, indent: &str) -> Self {
+        self.indent = Some(indent.into());
+        self
+    }
+
+    /// Pretty print, indenting new blocks with a number of spaces.
+    pub fn spaces(self, spaces: u16) -> Self {
+        self.indent(&" ".repeat(spaces as usize))
+    }
+
+    /// Line break to use when pretty printing, `"\n"` by default.
+    pub fn newline(mut self, newline: &str) -> Self {
+        self.newline = newline.into();
+        self
+    }
+
+    /// Write the entries of objects sorted by their keys, instead of in
+    /// insertion order.
+    pub fn sort_keys(mut self, sort: bool) -> Self {
+        self.sort_keys = sort;
+        self
+    }
+
+    /// Escape all characters outside of ASCII, see
+    /// `DumpGenerator::escape_non_ascii`.
+    pub fn escape_non_ascii(mut self, escape: bool) -> Self {
+        self.escape_non_ascii = escape;
+        self
+    }
+
+    /// How to write numbers with a fractional part.
+    pub fn float_format(mut self, format: FloatFormat) -> Self {
+        self.float_format = format;
+        self
+    }
+
+    /// Vertically align values within objects when pretty printing, see
+    /// `PrettyGenerator::align_values`.
+    pub fn align_values(mut self, align: bool) -> Self {
+        self.align_values = align;
+        self
+    }
+
+    /// Keep arrays and objects that fit within `width` bytes on a single
+    /// line when pretty printing, see `PrettyGenerator::wrap_at`.
+    pub fn wrap_at(mut self, width: usize) -> Self {
+        self.wrap_at = Some(width);
+        self
+    }
+
+    /// Write `item` between members of arrays and entries of objects, and
+    /// `key` between keys and values, instead of `","` and `":"`, or `": "`
+    /// when pretty printing. Line breaks of pretty printing follow `item`,
+    /// and arrays and objects kept on a single line by `wrap_at` use both
+    /// exactly as given.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// use json::StringifyOptions;
+    ///
+    /// let data = object!{ "ids" => array![1, 2], "ok" => true };
+    ///
+    /// assert_eq!(
+    ///     data.dump_with(&StringifyOptions::new().separators(", ", ": ")),
+    ///     r#"{"ids": [1, 2], "ok": true}"#
+    /// );
+    ///
+    /// assert_eq!(
+    ///     data.dump_with(&StringifyOptions::new().spaces(2).separators(",", " = ")),
+    ///     "{\n  \"ids\" = [\n    1,\n    2\n  ],\n  \"ok\" = true\n}"
+    /// );
+    /// # }
+    /// ```
+    pub fn separators(mut self, item: &str, key: &str) -> Self {
+        self.separators = Some((item.into(), key.into()));
+        self
+    }
+}
+
+/// Generator writing into an implementor of `io::Write` according to
+/// `StringifyOptions`. Output is buffered the same way as with
+/// `WriterGenerator`.
+pub struct OptionsGenerator<'a, 'o, W: 'a + Write> {
+    writer: &'a mut W,
+    buffer: Vec<u8>,
+    options: &'o StringifyOptions,
+    column: usize,
+    dent: u16,
+}
+
+impl<'a, 'o, W> OptionsGenerator<'a, 'o, W> where W: 'a + Write {
+    pub fn new(writer: &'a mut W, options: &'o StringifyOptions) -> Self {
+        OptionsGenerator {
+            writer,
+            buffer: Vec::new(),
+            options,
+            column: 0,
+            dent: 0,
+        }
+    }
+
+    /// Write out all buffered output to the underlying writer.
+    pub fn finish(&mut self) -> io::Result<()> {
+        write_out(self.writer, &mut self.buffer)
+    }
+}
+
+impl<'a, 'o, W> Generator for OptionsGenerator<'a, 'o, W> where W: Write {
+    type T = Vec<u8>;
+
+    #[inline(always)]
+    fn get_writer(&mut self) -> &mut Vec<u8> {
+        &mut self.buffer
+    }
+
+    #[inline(always)]
+    fn write(&mut self, slice: &[u8]) -> io::Result<()> {
+        self.column += slice.len();
+        write_buffered(self.writer, &mut self.buffer, slice, false)
+    }
+
+    #[inline(always)]
+    fn write_char(&mut self, ch: u8) -> io::Result<()> {
+        self.column += 1;
+        write_buffered(self.writer, &mut self.buffer, &[ch], false)
+    }
+
+    #[inline(always)]
+    fn write_min(&mut self, slice: &[u8], min: u8) -> io::Result<()> {
+        if self.options.indent.is_some() {
+            self.write(slice)
+        } else {
+            self.write_char(min)
+        }
+    }
+
+    fn new_line(&mut self) -> io::Result<()> {
+        let options = self.options;
+
+        if let Some(ref indent) = options.indent {
+            write_buffered(self.writer, &mut self.buffer, options.newline.as_bytes(), false)?;
+            for _ in 0..self.dent {
+                write_buffered(self.writer, &mut self.buffer, indent.as_bytes(), false)?;
+            }
+            self.column = self.dent as usize * indent.len();
+        }
+        Ok(())
+    }
+
+    fn indent(&mut self) {
+        self.dent += 1;
+    }
+
+    fn dedent(&mut self) {
+        self.dent -= 1;
+    }
+
+    fn aligns_values(&self) -> bool {
+        self.options.indent.is_some() && self.options.align_values
+    }
+
+    fn float_format(&self) -> FloatFormat {
+        self.options.float_format
+    }
+
+    fn escapes_non_ascii(&self) -> bool {
+        self.options.escape_non_ascii
+    }
+
+    fn wraps_at(&self) -> Option<usize> {
+        self.options.indent.as_ref().and(self.options.wrap_at)
+    }
+
+    fn column(&self) -> usize {
+        self.column
+    }
+
+    fn separators(&self) -> Option<(&str, &str)> {
+        self.options.separators.as_ref().map(|(item, key)| (item.as_str(), key.as_str()))
+    }
+
+    fn sorts_keys(&self) -> bool {
+        self.options.sort_keys
+    }
+
+    fn write_item_separator(&mut self) -> io::Result<()> {
+        match self.options.separators {
+            Some((ref item, _)) => self.write(item.as_bytes()),
+            None                => self.write_char(b','),
+        }
+    }
+
+    fn write_key_separator(&mut self) -> io::Result<()> {
+        match self.options.separators {
+            Some((_, ref key)) => self.write(key.as_bytes()),
+            None               => self.write_min(b": ", b':'),
+        }
+    }
+
+    fn write_object(&mut self, object: &Object) -> io::Result<()> {
+        if !self.options.sort_keys {
+            if !object.is_empty() && write_inline(self, |gen| gen.write_object(object))? {
+                return Ok(());
+            }
+            return self.write_entries(object.iter().map(|(key, value)| (key.as_str(), value)));
+        }
+
+        let entries = sorted_entries(object);
+
+        if !entries.is_empty() && write_inline(self, |gen| gen.write_entries(entries.iter().cloned()))? {
+            return Ok(());
+        }
+        self.write_entries(entries.into_iter())
+    }
+}
+
+impl<'a, 'o, W> Drop for OptionsGenerator<'a, 'o, W> where W: 'a + Write {
+    fn drop(&mut self) {
+        // Errors can't be reported from here, use `finish` to handle them.
+        let _ = self.finish();
+    }
+}
+
+// Generator writing arrays and objects on a single line, failing as soon as
+// the output exceeds `limit` bytes.
+struct InlineGenerator {
+    code: Vec<u8>,
+    limit: usize,
+    float_format: FloatFormat,
+    escape_non_ascii: bool,
+    separators: Option<(String, String)>,
+    sort_keys: bool,
+}
+
+impl InlineGenerator {
+    fn check(&self) -> io::Result<()> {
+        if self.code.len() > self.limit {
+            return Err(io::Error::other("line too long"));
+        }
+        Ok(())
+    }
+}
+
+impl Generator for InlineGenerator {
+    type T = Vec<u8>;
+
+    fn write(&mut self, slice: &[u8]) -> io::Result<()> {
+        extend_from_slice(&mut self.code, slice);
+        self.check()
+    }
+
+    fn write_char(&mut self, ch: u8) -> io::Result<()> {
+        self.code.push(ch);
+        self.check()
+    }
+
+    fn get_writer(&mut self) -> &mut Vec<u8> {
+        &mut self.code
+    }
+
+    fn write_min(&mut self, slice: &[u8], _: u8) -> io::Result<()> {
+        self.write(slice)
+    }
+
+    // Separate members with a space after the comma, unless the separators
+    // are custom.
+    fn new_line(&mut self) -> io::Result<()> {
+        if self.separators.is_none() && self.code.last() == Some(&b',') {
+            self.write_char(b' ')?;
+        }
+        Ok(())
+    }
+
+    fn separators(&self) -> Option<(&str, &str)> {
+        self.separators.as_ref().map(|(item, key)| (item.as_str(), key.as_str()))
+    }
+
+    fn write_item_separator(&mut self) -> io::Result<()> {
+        match self.separators {
+            Some((ref item, _)) => extend_from_slice(&mut self.code, item.as_bytes()),
+            None                => self.code.push(b','),
+        }
+        self.check()
+    }
+
+    fn write_key_separator(&mut self) -> io::Result<()> {
+        match self.separators {
+            Some((_, ref key)) => extend_from_slice(&mut self.code, key.as_bytes()),
+            None               => extend_from_slice(&mut self.code, b": "),
+        }
+        self.check()
+    }
+
+    fn float_format(&self) -> FloatFormat {
+        self.float_format
+    }
+
+    fn escapes_non_ascii(&self) -> bool {
+        self.escape_non_ascii
+    }
+
+    fn sorts_keys(&self) -> bool {
+        self.sort_keys
+    }
+
+    fn write_object(&mut self, object: &Object) -> io::Result<()> {
+        if !self.sort_keys {
+            return self.write_entries(object.iter().map(|(key, value)| (key.as_str(), value)));
+        }
+        self.write_entries(sorted_entries(object).into_iter())
+    }
+}
+
+// Entries of `object` sorted by key.
+fn sorted_entries(object: &Object) -> Vec<(&str, &JsonValue)> {
+    let mut entries: Vec<_> = object.iter().map(|(key, value)| (key.as_str(), value)).collect();
+
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+}
+
+// Number of characters `write_string` writes for `string`, without the
+// quotes, used to align values within objects.
+fn written_width<G: Generator + ?Sized>(gen: &G, string: &str) -> usize {
+    let ascii = gen.escapes_non_ascii();
+
+    string.chars().map(|ch| {
+        if !ch.is_ascii() {
+            return if ascii { ch.len_utf16() * 6 } else { 1 };
+        }
+
+        if let Some(escaped) = gen.escape_byte(ch as u8) {
+            return escaped.len();
+        }
+
+        match ESCAPED[ch as usize] {
+            0  => 1,
+            UU => 6,
+            _  => 2,
+        }
+    }).sum()
+}
+
+// Write an array or object with `write` on a single line, if the generator
+// wraps lines and it fits on the current one. Returns whether it was written.
+fn write_inline<G, F>(gen: &mut G, write: F) -> io::Result<bool>
+where G: Generator + ?Sized, F: FnOnce(&mut InlineGenerator) -> io::Result<()> {
+    let limit = match gen.wraps_at() {
+        Some(width) if width > gen.column() => width - gen.column(),
+        _ => return Ok(false),
+    };
+
+    let mut inline = InlineGenerator {
+        code: Vec::new(),
+        limit,
+        float_format: gen.float_format(),
+        escape_non_ascii: gen.escapes_non_ascii(),
+        separators: gen.separators().map(|(item, key)| (item.into(), key.into())),
+        sort_keys: gen.sorts_keys(),
+    };
+
+    if write(&mut inline).and_then(|_| inline.check()).is_err() {
+        return Ok(false);
+    }
+
+    gen.write(&inline.code)?;
+    Ok(true)
+}
+
+// Write the number `mantissa * 10^exponent`, which has a fractional part,
+// rounded half away from zero according to `format`.
+fn write_float<W: Write>(wr: &mut W, format: FloatFormat, positive: bool, mut mantissa: u64, mut exponent: i16) -> io::Result<()> {
+    match format {
+        FloatFormat::Shortest => {},
+        FloatFormat::SignificantDigits(max) => {
+            let digits = mantissa.to_string().len();
+            let max = max.max(1) as usize;
+
+            if digits > max {
+                let divisor = 10u64.pow((digits - max) as u32);
+
+                mantissa = mantissa / divisor + (mantissa % divisor >= divisor / 2) as u64;
+                exponent += (digits - max) as i16;
+            }
+
+            while exponent < 0 && mantissa != 0 && mantissa % 10 == 0 {
+                mantissa /= 10;
+                exponent += 1;
+            }
+        },
+        FloatFormat::DecimalPlaces(places) => {
+            let fraction = -(exponent as isize) as usize;
+            let places = places as usize;
+
+            // Pad the digits so there is at least one before the point.
+            let mut digits: Vec<u8> = format!("{:0>width$}", mantissa, width = fraction + 1).into_bytes();
+
+            if fraction > places {
+                let round_up = digits[digits.len() - fraction + places] >= b'5';
+
+                digits.truncate(digits.len() - fraction + places);
+
+                if round_up {
+                    increment(&mut digits);
+                }
+            } else {
+                digits.resize(digits.len() + places - fraction, b'0');
+            }
+
+            if !positive && digits.iter().any(|&digit| digit != b'0') {
+                wr.write_all(b"-")?;
+            }
+
+            let (integer, decimal) = digits.split_at(digits.len() - places);
+
+            wr.write_all(integer)?;
+
+            if places > 0 {
+                wr.write_all(b".")?;
+                wr.write_all(decimal)?;
+            }
+
+            return Ok(());
+        },
+    }
+
+    unsafe { print_dec::write(wr, positive, mantissa, exponent) }
+}
+
+// Add one to a number written out in ASCII digits.
+fn increment(digits: &mut Vec<u8>) {
+    for digit in digits.iter_mut().rev() {
+        if *digit == b'9' {
+            *digit = b'0';
+        } else {
+            *digit += 1;
+            return;
+        }
+    }
+
+    digits.insert(0, b'1');
+}
+
+// Append the `slice` to the `buffer`, writing the buffer out first if it
+// would overflow. Slices that won't fit in the buffer at all skip it.
+#[inline(always)]
+fn write_buffered<W: Write>(writer: &mut W, buffer: &mut Vec<u8>, slice: &[u8], vectored: bool) -> io::Result<()> {
+    if buffer.len() + slice.len() <= BUFFER_SIZE {
+        extend_from_slice(buffer, slice);
+        return Ok(());
+    }
+
+    if vectored && slice.len() > BUFFER_SIZE {
+        write_all_vectored(writer, &mut [IoSlice::new(buffer), IoSlice::new(slice)])?;
+        buffer.clear();
+        return Ok(());
+    }
+
+    write_out(writer, buffer)?;
+
+    if slice.len() > BUFFER_SIZE {
+        writer.write_all(slice)
+    } else {
+        extend_from_slice(buffer, slice);
+        Ok(())
+    }
+}
+
+// Write a number that is neither NaN nor infinite.
+#[inline(always)]
+fn write_finite_number<W: Write>(writer: &mut W, num: &Number, format: FloatFormat) -> io::Result<()> {
+    let (positive, mantissa, exponent) = num.as_parts();
+    if format != FloatFormat::Shortest && exponent < 0 {
+        return write_float(writer, format, positive, mantissa, exponent);
+    }
+    if num.has_source_text() {
+        return num.write_source_text(writer);
+    }
+    unsafe {
+        print_dec::write(
+            writer,
+            positive,
+            mantissa,
+            exponent
+        )
+    }
+}
+
+// Append a number that is neither NaN nor infinite to the `buffer`, writing
+// the buffer out if that makes it overflow. Returns the length of the number.
+fn write_number_buffered<W: Write>(writer: &mut W, buffer: &mut Vec<u8>, num: &Number, format: FloatFormat) -> io::Result<usize> {
+    let start = buffer.len();
+    write_finite_number(buffer, num, format)?;
+    let len = buffer.len() - start;
+
+    if buffer.len() > BUFFER_SIZE {
+        write_out(writer, buffer)?;
+    }
+
+    Ok(len)
+}
+
+fn write_out<W: Write>(writer: &mut W, buffer: &mut Vec<u8>) -> io::Result<()> {
+    if !buffer.is_empty() {
+        writer.write_all(buffer)?;
+        buffer.clear();
+    }
+
+    Ok(())
+}
+
+// Write out the complete characters in the `buffer`, keeping the bytes
+// of a character split at its end for the next call.
+fn write_out_fmt<W: fmt::Write + ?Sized>(writer: &mut W, buffer: &mut Vec<u8>) -> fmt::Result {
+    let valid = match str::from_utf8(buffer) {
+        Ok(code)   => code.len(),
+        Err(error) => error.valid_up_to(),
+    };
+
+    if valid > 0 {
+        // Validated above.
+        writer.write_str(unsafe { str::from_utf8_unchecked(&buffer[..valid]) })?;
+        buffer.drain(..valid);
+    }
+
+    Ok(())
+}
+
+// Analog to the unstable `Write::write_all_vectored`.
+fn write_all_vectored<W: Write>(writer: &mut W, mut slices: &mut [IoSlice]) -> io::Result<()> {
+    IoSlice::advance_slices(&mut slices, 0);
+
+    while !slices.is_empty() {
+        match writer.write_vectored(slices) {
+            Ok(0) => return Err(io::Error::new(io::ErrorKind::WriteZero, "failed to write whole buffer")),
+            Ok(written) => IoSlice::advance_slices(&mut slices, written),
+            Err(ref error) if error.kind() == io::ErrorKind::Interrupted => {},
+            Err(error) => return Err(error),
+        }
+    }
+
+    Ok(())
 }
 
 // From: https://github.com/dtolnay/fastwrite/blob/master/src/lib.rs#L68
@@ -0,0 +1,119 @@
+// Stable, order independent digests of `JsonValue`s, see
+// `JsonValue::content_hash_128`. Unlike the `Hash` implementation, which
+// feeds whatever `Hasher` it's given, the digest is computed the same way on
+// every platform and in every version of the crate, so it can be stored.
+
+use prelude::*;
+
+use JsonValue;
+
+const OFFSET: u128 = 0x6c62_272e_07bb_0142_62b8_2175_6295_c58d;
+const PRIME: u128 = 0x0000_0000_0100_0000_0000_0000_0000_013b;
+
+// 128 bit FNV-1a.
+struct Fnv128(u128);
+
+impl Fnv128 {
+    fn new(tag: u8) -> Self {
+        let mut fnv = Fnv128(OFFSET);
+        fnv.write(&[tag]);
+        fnv
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u128).wrapping_mul(PRIME);
+        }
+    }
+
+    fn write_len(&mut self, len: usize) {
+        self.write(&(len as u64).to_le_bytes());
+    }
+
+    fn write_str(&mut self, value: &str) {
+        self.write_len(value.len());
+        self.write(value.as_bytes());
+    }
+
+    fn write_digest(&mut self, digest: u128) {
+        self.write(&digest.to_le_bytes());
+    }
+}
+
+pub(crate) fn digest(value: &JsonValue) -> u128 {
+    match *value {
+        JsonValue::Null => Fnv128::new(0).0,
+        JsonValue::Boolean(value) => {
+            let mut fnv = Fnv128::new(1);
+            fnv.write(&[value as u8]);
+            fnv.0
+        },
+        JsonValue::Number(ref value) => {
+            let mut fnv = Fnv128::new(2);
+
+            if value.is_nan() {
+                fnv.write(&[0]);
+            } else if value.is_zero() {
+                fnv.write(&[1]);
+            } else {
+                let (positive, mut mantissa, mut exponent) = value.as_parts();
+
+                while mantissa % 10 == 0 && exponent < i16::MAX {
+                    mantissa /= 10;
+                    exponent += 1;
+                }
+
+                fnv.write(&[2, positive as u8]);
+                fnv.write(&mantissa.to_le_bytes());
+                fnv.write(&exponent.to_le_bytes());
+            }
+
+            fnv.0
+        },
+        JsonValue::Short(ref value) => string(value),
+        JsonValue::String(ref value) => string(value),
+        JsonValue::Array(ref members) => {
+            let mut fnv = Fnv128::new(4);
+
+            fnv.write_len(members.len());
+
+            for member in members {
+                fnv.write_digest(digest(member));
+            }
+
+            fnv.0
+        },
+        JsonValue::Object(ref object) => {
+            let mut entries: Vec<u128> = object.iter().map(|(key, value)| {
+                let mut fnv = Fnv128(OFFSET);
+                fnv.write_str(key);
+                fnv.write_digest(digest(value));
+                fnv.0
+            }).collect();
+
+            // Sorting the digests of the entries makes the result independent
+            // of their order.
+            entries.sort_unstable();
+
+            let mut fnv = Fnv128::new(5);
+
+            fnv.write_len(entries.len());
+
+            for entry in entries {
+                fnv.write_digest(entry);
+            }
+
+            fnv.0
+        },
+        JsonValue::Raw(ref raw) => match raw.value() {
+            Some(value) => digest(&value),
+            None        => string(raw.as_str()),
+        },
+    }
+}
+
+fn string(value: &str) -> u128 {
+    let mut fnv = Fnv128::new(3);
+    fnv.write_str(value);
+    fnv.0
+}
@@ -0,0 +1,374 @@
+// This is a private module that contains the conversion traits between
+// `JsonValue` and other types, re-exported from the crate root.
+
+use std::collections::BTreeMap;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::{ error, fmt };
+use prelude::*;
+
+use number::Number;
+use error::ErrorCategory;
+use { value, JsonValue };
+
+/// Conversion into a `JsonValue`. Types that convert into a `JsonValue` via
+/// `Into` implement this trait automatically, for other types it can be
+/// implemented directly:
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// use json::{ JsonValue, ToJson };
+///
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// impl ToJson for Point {
+///     fn into_json(self) -> JsonValue {
+///         array![self.x, self.y]
+///     }
+/// }
+///
+/// let data = object!{
+///     "origin" => Point { x: 0, y: 0 },
+///     "path" => array![Point { x: 1, y: 2 }, Point { x: 3, y: 4 }]
+/// };
+///
+/// assert_eq!(data.dump(), r#"{"origin":[0,0],"path":[[1,2],[3,4]]}"#);
+/// # }
+/// ```
+///
+/// The `object!` and `array!` macros, as well as `JsonValue::insert`, accept
+/// any value implementing this trait.
+pub trait ToJson {
+    /// Consume `self`, turning it into a `JsonValue`.
+    fn into_json(self) -> JsonValue where Self: Sized;
+
+    /// Create a new `JsonValue` out of a clone of `self`.
+    fn to_json(&self) -> JsonValue where Self: Clone {
+        self.clone().into_json()
+    }
+}
+
+impl<T> ToJson for T where T: Into<JsonValue> {
+    #[inline]
+    fn into_json(self) -> JsonValue {
+        self.into()
+    }
+}
+
+/// Error returned when a `JsonValue` can't be converted into a Rust type
+/// with `FromJson`. Carries a JSON Pointer (RFC 6901) to the offending value,
+/// along with the expected and the actual type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FromJsonError {
+    path: String,
+    expected: String,
+    actual: &'static str,
+}
+
+impl FromJsonError {
+    /// Create a new error for a value of the wrong type. The `expected`
+    /// argument describes the type that was expected.
+    pub fn new(expected: &str, found: &JsonValue) -> Self {
+        FromJsonError {
+            path: String::new(),
+            expected: expected.into(),
+            actual: type_name(found),
+        }
+    }
+
+    /// Prefix the path of the error with an array index. This should be
+    /// called when converting a member of an array fails.
+    pub fn at_index(mut self, index: usize) -> Self {
+        self.path = format!("/{}{}", index, self.path);
+        self
+    }
+
+    /// Prefix the path of the error with an object key. This should be
+    /// called when converting a member of an object fails.
+    pub fn at_key(mut self, key: &str) -> Self {
+        self.path = format!("/{}{}", key.replace('~', "~0").replace('/', "~1"), self.path);
+        self
+    }
+
+    /// JSON Pointer to the value that failed to convert. Empty string
+    /// points to the root value.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Description of the type that was expected.
+    pub fn expected(&self) -> &str {
+        &self.expected
+    }
+
+    /// JSON type of the value that was found, such as `"string"`.
+    pub fn actual(&self) -> &str {
+        self.actual
+    }
+
+    /// Stable numeric code of the error, see `json::Error::code`.
+    pub fn code(&self) -> u16 {
+        7
+    }
+
+    /// Stable string code of the error, see `json::Error::code_name`.
+    pub fn code_name(&self) -> &'static str {
+        "from_json"
+    }
+
+    /// Category of the error, always `ErrorCategory::Type`.
+    pub fn category(&self) -> ErrorCategory {
+        ErrorCategory::Type
+    }
+}
+
+impl fmt::Display for FromJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Expected {}, found {} at \"{}\"", self.expected, self.actual, self.path)
+    }
+}
+
+impl error::Error for FromJsonError {
+    fn description(&self) -> &str {
+        "Wrong type"
+    }
+}
+
+fn type_name(value: &JsonValue) -> &'static str {
+    match *value {
+        JsonValue::Null                            => "null",
+        JsonValue::Short(_) | JsonValue::String(_) => "string",
+        JsonValue::Number(_)                       => "number",
+        JsonValue::Boolean(_)                      => "boolean",
+        JsonValue::Object(_)                       => "object",
+        JsonValue::Array(_)                        => "array",
+        JsonValue::Raw(ref raw)                    => raw.value().map_or("raw", |value| type_name(&value)),
+    }
+}
+
+/// Conversion from a `JsonValue` into a Rust type, failing with
+/// a `FromJsonError` describing where and why the conversion went wrong.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// use json::FromJson;
+///
+/// let data = array![1, 2, "three"];
+///
+/// let error = Vec::<u8>::from_json(&data).unwrap_err();
+///
+/// assert_eq!(error.path(), "/2");
+/// assert_eq!(error.expected(), "u8");
+/// assert_eq!(error.actual(), "string");
+///
+/// let data = array![1, 2, 3];
+///
+/// assert_eq!(Vec::<u8>::from_json(&data), Ok(vec![1, 2, 3]));
+/// # }
+/// ```
+///
+/// The types implementing this trait in this crate, except for `Option`,
+/// can be converted with `TryFrom` as well, both from a `JsonValue` and from
+/// a reference to one:
+///
+/// ```
+/// use std::convert::TryFrom;
+///
+/// fn port(config: &json::JsonValue) -> Result<u16, json::Error> {
+///     Ok(u16::try_from(&config["port"])?)
+/// }
+///
+/// let config = json::parse(r#"{ "port": 8080, "hosts": ["a", "b"] }"#).unwrap();
+///
+/// assert_eq!(port(&config).unwrap(), 8080);
+/// assert_eq!(Vec::<String>::try_from(config["hosts"].clone()), Ok(vec!["a".to_string(), "b".to_string()]));
+/// assert_eq!(i32::try_from(&config["hosts"]).unwrap_err().to_string(), "Expected i32, found array at \"\"");
+/// ```
+pub trait FromJson: Sized {
+    /// Convert a `JsonValue` into `Self`.
+    fn from_json(value: &JsonValue) -> Result<Self, FromJsonError>;
+
+    /// Keys of an object that `from_json` reads, if it only reads some of
+    /// them. `json::parse_into` then skips over the values of all other
+    /// keys of the top level object without building them. Returns `None`
+    /// by default, in which case every value is built.
+    fn fields() -> Option<&'static [&'static str]> {
+        None
+    }
+}
+
+impl FromJson for JsonValue {
+    fn from_json(value: &JsonValue) -> Result<Self, FromJsonError> {
+        Ok(value.clone())
+    }
+}
+
+impl FromJson for bool {
+    fn from_json(value: &JsonValue) -> Result<Self, FromJsonError> {
+        value.as_bool().ok_or_else(|| FromJsonError::new("bool", value))
+    }
+}
+
+impl FromJson for String {
+    fn from_json(value: &JsonValue) -> Result<Self, FromJsonError> {
+        value.as_str()
+             .map(String::from)
+             .ok_or_else(|| FromJsonError::new("string", value))
+    }
+}
+
+impl FromJson for Number {
+    fn from_json(value: &JsonValue) -> Result<Self, FromJsonError> {
+        value.as_number().ok_or_else(|| FromJsonError::new("number", value))
+    }
+}
+
+impl FromJson for f64 {
+    fn from_json(value: &JsonValue) -> Result<Self, FromJsonError> {
+        value.as_f64().ok_or_else(|| FromJsonError::new("f64", value))
+    }
+}
+
+impl FromJson for f32 {
+    fn from_json(value: &JsonValue) -> Result<Self, FromJsonError> {
+        value.as_f32().ok_or_else(|| FromJsonError::new("f32", value))
+    }
+}
+
+macro_rules! implement_from_json_integer {
+    ($( $t:ident ),*) => ($(
+        impl FromJson for $t {
+            fn from_json(value: &JsonValue) -> Result<Self, FromJsonError> {
+                // Going through `i128` rejects numbers with a fraction or
+                // out of range, which would otherwise be silently truncated.
+                value.as_i128()
+                     .and_then(|integer| $t::try_from(integer).ok())
+                     .ok_or_else(|| FromJsonError::new(stringify!($t), value))
+            }
+        }
+    )*)
+}
+
+implement_from_json_integer!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json(value: &JsonValue) -> Result<Self, FromJsonError> {
+        match *value {
+            JsonValue::Null => Ok(None),
+            _               => T::from_json(value).map(Some),
+        }
+    }
+
+    fn fields() -> Option<&'static [&'static str]> {
+        T::fields()
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(value: &JsonValue) -> Result<Self, FromJsonError> {
+        match *value {
+            JsonValue::Array(ref vec) => {
+                vec.iter()
+                   .enumerate()
+                   .map(|(index, member)| {
+                       T::from_json(member).map_err(|error| error.at_index(index))
+                   })
+                   .collect()
+            },
+            _ => Err(FromJsonError::new("array", value)),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: FromJson> FromJson for HashMap<String, T> {
+    fn from_json(value: &JsonValue) -> Result<Self, FromJsonError> {
+        match *value {
+            JsonValue::Object(ref object) => {
+                object.iter()
+                      .map(|(key, member)| {
+                          T::from_json(member)
+                              .map(|member| (key.clone(), member))
+                              .map_err(|error| error.at_key(key))
+                      })
+                      .collect()
+            },
+            _ => Err(FromJsonError::new("object", value)),
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for BTreeMap<String, T> {
+    fn from_json(value: &JsonValue) -> Result<Self, FromJsonError> {
+        match *value {
+            JsonValue::Object(ref object) => {
+                object.iter()
+                      .map(|(key, member)| {
+                          T::from_json(member)
+                              .map(|member| (key.clone(), member))
+                              .map_err(|error| error.at_key(key))
+                      })
+                      .collect()
+            },
+            _ => Err(FromJsonError::new("object", value)),
+        }
+    }
+}
+
+// `TryFrom` conversions, using the same rules and errors as `FromJson`.
+macro_rules! implement_try_from {
+    ($( [$( $param:ident ),*] $t:ty ),*) => ($(
+        impl<'a, $( $param: FromJson ),*> TryFrom<&'a JsonValue> for $t {
+            type Error = FromJsonError;
+
+            fn try_from(value: &'a JsonValue) -> Result<Self, FromJsonError> {
+                <$t as FromJson>::from_json(value)
+            }
+        }
+
+        impl<$( $param: FromJson ),*> TryFrom<JsonValue> for $t {
+            type Error = FromJsonError;
+
+            fn try_from(value: JsonValue) -> Result<Self, FromJsonError> {
+                <$t as FromJson>::from_json(&value)
+            }
+        }
+    )*)
+}
+
+implement_try_from!(
+    [] u8, [] u16, [] u32, [] u64, [] usize,
+    [] i8, [] i16, [] i32, [] i64, [] isize,
+    [] f32, [] f64, [] bool, [] Number,
+    [T] Vec<T>, [T] BTreeMap<String, T>
+);
+
+#[cfg(feature = "std")]
+implement_try_from!([T] HashMap<String, T>);
+
+impl<'a> TryFrom<&'a JsonValue> for String {
+    type Error = FromJsonError;
+
+    fn try_from(value: &'a JsonValue) -> Result<Self, FromJsonError> {
+        String::from_json(value)
+    }
+}
+
+impl TryFrom<JsonValue> for String {
+    type Error = FromJsonError;
+
+    // Takes the string out of the value, instead of copying it.
+    fn try_from(value: JsonValue) -> Result<Self, FromJsonError> {
+        match value {
+            JsonValue::Short(short)   => Ok(short.as_str().into()),
+            JsonValue::String(string) => Ok(value::into_string(string)),
+            _                         => Err(FromJsonError::new("string", &value)),
+        }
+    }
+}
@@ -0,0 +1,332 @@
+//! Conversion between CSV and `JsonValue`, enabled with the `csv` feature.
+//! The first row of a CSV document is a header naming the columns, and every
+//! following row becomes an object keyed by those names:
+//!
+//! ```
+//! # #[macro_use] extern crate json;
+//! # fn main() {
+//! let data = json::csv::parse("name,age,admin\nJoe,30,true\n\"Doe, Jane\",,false\n").unwrap();
+//!
+//! assert_eq!(data, array![
+//!     object!{ "name" => "Joe", "age" => 30, "admin" => true },
+//!     object!{ "name" => "Doe, Jane", "age" => "", "admin" => false }
+//! ]);
+//!
+//! assert_eq!(json::csv::stringify(&data).unwrap(), "name,age,admin\nJoe,30,true\n\"Doe, Jane\",,false\n");
+//! # }
+//! ```
+//!
+//! Fields follow RFC 4180: they can be quoted with `"`, in which case they
+//! may contain delimiters, line breaks and quotes written as `""`. Rows end
+//! with either `\n` or `\r\n`, and empty lines are skipped.
+
+use { parse as parse_json, JsonValue, Error, Result };
+use object::Object;
+use prelude::*;
+
+/// Options for `json::csv::parse_with_options` and
+/// `json::csv::stringify_with_options`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// Character separating fields, `,` by default.
+    pub delimiter: char,
+
+    /// Turn unquoted fields holding a JSON number, such as `42` or `-1.5`,
+    /// into numbers. Enabled by default.
+    pub infer_numbers: bool,
+
+    /// Turn unquoted fields holding `true` or `false` into booleans. Enabled
+    /// by default.
+    pub infer_booleans: bool,
+
+    /// Turn empty unquoted fields into `null`s, instead of empty strings.
+    /// Disabled by default.
+    pub empty_as_null: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: ',',
+            infer_numbers: true,
+            infer_booleans: true,
+            empty_as_null: false,
+        }
+    }
+}
+
+/// Parse a CSV document with a header row into an array of objects, using
+/// the default `CsvOptions`.
+pub fn parse(source: &str) -> Result<JsonValue> {
+    parse_with_options(source, CsvOptions::default())
+}
+
+/// Parse a CSV document with a header row into an array of objects. Fails
+/// with `Error::InvalidCsv` if a quoted field is malformed, or a row doesn't
+/// have as many fields as the header.
+pub fn parse_with_options(source: &str, options: CsvOptions) -> Result<JsonValue> {
+    let mut reader = Reader {
+        source,
+        index: 0,
+        line: 1,
+        options,
+    };
+
+    let header = match reader.read_row()? {
+        Some(row) => row.into_iter().map(|(field, _)| field).collect::<Vec<_>>(),
+        None      => return Ok(JsonValue::new_array()),
+    };
+
+    let mut rows = Vec::new();
+
+    loop {
+        let line = reader.line;
+
+        let row = match reader.read_row()? {
+            Some(row) => row,
+            None      => break,
+        };
+
+        if row.len() != header.len() {
+            return Err(Error::InvalidCsv(format!(
+                "row at line {} has {} fields, expected {}", line, row.len(), header.len()
+            )));
+        }
+
+        let mut object = Object::with_capacity(header.len());
+
+        for (key, (field, quoted)) in header.iter().zip(row) {
+            object.insert(key, reader.options.value(field, quoted));
+        }
+
+        rows.push(JsonValue::Object(object));
+    }
+
+    Ok(JsonValue::Array(rows))
+}
+
+impl CsvOptions {
+    // Turn a field into a value, inferring its type if it wasn't quoted.
+    fn value(&self, field: String, quoted: bool) -> JsonValue {
+        if !quoted {
+            match field.as_str() {
+                "" if self.empty_as_null => return JsonValue::Null,
+                "true" if self.infer_booleans => return JsonValue::Boolean(true),
+                "false" if self.infer_booleans => return JsonValue::Boolean(false),
+                _ => {}
+            }
+
+            let numeric = field.starts_with(|ch: char| ch == '-' || ch.is_ascii_digit())
+                       && !field.ends_with(char::is_whitespace);
+
+            if self.infer_numbers && numeric {
+                if let Ok(number @ JsonValue::Number(_)) = parse_json(&field) {
+                    return number;
+                }
+            }
+        }
+
+        field.into()
+    }
+}
+
+struct Reader<'a> {
+    source: &'a str,
+    index: usize,
+    line: usize,
+    options: CsvOptions,
+}
+
+impl<'a> Reader<'a> {
+    fn peek(&self) -> Option<char> {
+        self.source[self.index..].chars().next()
+    }
+
+    fn bump(&mut self, ch: char) {
+        self.index += ch.len_utf8();
+
+        if ch == '\n' {
+            self.line += 1;
+        }
+    }
+
+    // Read the fields of the next row, along with whether they were quoted.
+    // Returns `None` at the end of the source.
+    fn read_row(&mut self) -> Result<Option<Vec<(String, bool)>>> {
+        // Skip empty lines.
+        loop {
+            match self.peek() {
+                Some('\n') => self.bump('\n'),
+                Some('\r') if self.source[self.index..].starts_with("\r\n") => {
+                    self.bump('\r');
+                    self.bump('\n');
+                },
+                Some(_) => break,
+                None    => return Ok(None),
+            }
+        }
+
+        let mut row = Vec::new();
+
+        loop {
+            row.push(self.read_field()?);
+
+            match self.peek() {
+                Some(ch) if ch == self.options.delimiter => self.bump(ch),
+                Some('\r') => {
+                    self.bump('\r');
+                    if self.peek() == Some('\n') {
+                        self.bump('\n');
+                    }
+                    return Ok(Some(row));
+                },
+                Some('\n') => {
+                    self.bump('\n');
+                    return Ok(Some(row));
+                },
+                _ => return Ok(Some(row)),
+            }
+        }
+    }
+
+    fn read_field(&mut self) -> Result<(String, bool)> {
+        let delimiter = self.options.delimiter;
+
+        if self.peek() != Some('"') {
+            let start = self.index;
+
+            while let Some(ch) = self.peek() {
+                if ch == delimiter || ch == '\n' || ch == '\r' {
+                    break;
+                }
+                self.bump(ch);
+            }
+
+            return Ok((self.source[start..self.index].into(), false));
+        }
+
+        let line = self.line;
+        let mut field = String::new();
+
+        self.bump('"');
+
+        loop {
+            match self.peek() {
+                Some('"') => {
+                    self.bump('"');
+
+                    if self.peek() == Some('"') {
+                        self.bump('"');
+                        field.push('"');
+                        continue;
+                    }
+
+                    match self.peek() {
+                        None | Some('\n') | Some('\r') => return Ok((field, true)),
+                        Some(ch) if ch == delimiter => return Ok((field, true)),
+                        Some(ch) => {
+                            return Err(Error::InvalidCsv(format!(
+                                "unexpected character {} after quoted field at line {}", ch, self.line
+                            )));
+                        }
+                    }
+                },
+                Some(ch) => {
+                    self.bump(ch);
+                    field.push(ch);
+                },
+                None => {
+                    return Err(Error::InvalidCsv(format!("unterminated quoted field at line {}", line)));
+                }
+            }
+        }
+    }
+}
+
+/// Write an array of objects as a CSV document, using the default
+/// `CsvOptions`.
+pub fn stringify(value: &JsonValue) -> Result<String> {
+    stringify_with_options(value, CsvOptions::default())
+}
+
+/// Write an array of objects as a CSV document with a header row. Columns
+/// are all the keys found in the objects, in the order they first appear.
+/// Missing keys and `null`s are written as empty fields, and nested arrays
+/// and objects are written as JSON. Fails with `Error::InvalidCsv` if
+/// `value` is not an array of objects.
+///
+/// Fields are quoted when needed, such as when they contain the delimiter.
+/// Strings that would otherwise be inferred to be numbers or booleans by
+/// `parse_with_options` with the same `options` are quoted as well, so
+/// they are read back as strings.
+pub fn stringify_with_options(value: &JsonValue, options: CsvOptions) -> Result<String> {
+    let not_objects = || Error::InvalidCsv("value that is not an array of objects".into());
+
+    let rows = match *value {
+        JsonValue::Array(ref rows) => rows,
+        _                          => return Err(not_objects()),
+    };
+
+    let mut header = Object::new();
+
+    for row in rows {
+        match *row {
+            JsonValue::Object(ref object) => {
+                for key in object.keys() {
+                    if header.get(key.as_str()).is_none() {
+                        header.insert(key, JsonValue::Null);
+                    }
+                }
+            },
+            _ => return Err(not_objects()),
+        }
+    }
+
+    let mut output = String::new();
+
+    let keys = header.keys().map(String::as_str).collect::<Vec<_>>();
+
+    write_row(&mut output, keys.iter().map(|&key| (key.into(), false)).collect(), &options);
+
+    for row in rows {
+        write_row(&mut output, keys.iter().map(|&key| match row[key] {
+            JsonValue::Null => (String::new(), false),
+            ref value if value.is_string() => {
+                let string = value.as_str().unwrap_or("");
+                let inferred = !options.value(string.into(), false).is_string();
+                (string.into(), inferred)
+            },
+            ref value => (value.dump(), false),
+        }).collect(), &options);
+    }
+
+    Ok(output)
+}
+
+// Write a row of fields, along with whether each of them must be quoted
+// regardless of its content.
+fn write_row(output: &mut String, fields: Vec<(String, bool)>, options: &CsvOptions) {
+    // A row with a single empty field would be an empty line, which is
+    // skipped when parsing.
+    let single = fields.len() == 1;
+
+    for (index, (field, quote)) in fields.into_iter().enumerate() {
+        if index > 0 {
+            output.push(options.delimiter);
+        }
+
+        let quote = quote
+                 || (single && field.is_empty())
+                 || field.contains(&[options.delimiter, '"', '\n', '\r'][..]);
+
+        if quote {
+            output.push('"');
+            output.push_str(&field.replace('"', "\"\""));
+            output.push('"');
+        } else {
+            output.push_str(&field);
+        }
+    }
+
+    output.push('\n');
+}
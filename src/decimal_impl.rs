@@ -0,0 +1,115 @@
+// Conversions between `JsonValue` and `rust_decimal::Decimal`, enabled with
+// the `rust_decimal` feature. Both `Number` and `Decimal` store a decimal
+// mantissa and exponent, so converting between them never goes through
+// binary floating point.
+//
+// `Decimal` holds up to 96 bits of mantissa, 28 to 29 significant digits,
+// while `Number` holds 19 to 20. Converting a `Decimal` with more significant
+// digits than that drops the least significant ones, the same way converting
+// a `u128` does. Converting a `Number` into a `Decimal` is always exact, and
+// fails instead if the number is too large, has more than 28 digits after
+// the decimal point, or is not finite.
+
+use rust_decimal::Decimal;
+
+use convert::{ FromJson, FromJsonError };
+use number::Number;
+use JsonValue;
+
+// Largest mantissa of a `Decimal`, 2^96 - 1.
+const MAX_MANTISSA: u128 = (1 << 96) - 1;
+
+// Largest scale of a `Decimal`, the number of digits after the decimal point.
+const MAX_SCALE: i32 = 28;
+
+impl From<Decimal> for Number {
+    fn from(val: Decimal) -> Number {
+        let mut mantissa = val.mantissa().unsigned_abs();
+        let mut exponent = -(val.scale() as i16);
+
+        while mantissa > u64::MAX as u128 {
+            mantissa /= 10;
+            exponent += 1;
+        }
+
+        Number::from_parts(!val.is_sign_negative(), mantissa as u64, exponent)
+    }
+}
+
+impl From<Decimal> for JsonValue {
+    fn from(val: Decimal) -> JsonValue {
+        JsonValue::Number(val.into())
+    }
+}
+
+impl PartialEq<Decimal> for JsonValue {
+    fn eq(&self, other: &Decimal) -> bool {
+        self.as_decimal() == Some(*other)
+    }
+}
+
+impl FromJson for Decimal {
+    fn from_json(value: &JsonValue) -> Result<Self, FromJsonError> {
+        value.as_decimal().ok_or_else(|| FromJsonError::new("decimal", value))
+    }
+}
+
+// Convert `number` into a `Decimal`, if it can be represented exactly.
+fn to_decimal(number: Number) -> Option<Decimal> {
+    if number.is_nan() || number.is_infinite() {
+        return None;
+    }
+
+    let (positive, mantissa, exponent) = number.as_parts();
+    let mut mantissa = mantissa as u128;
+    let mut exponent = exponent as i32;
+
+    if exponent > 0 {
+        mantissa = 10u128.checked_pow(exponent as u32)?.checked_mul(mantissa)?;
+        exponent = 0;
+    }
+
+    // Trailing zeros past the largest scale can be dropped without loss.
+    while exponent < -MAX_SCALE && mantissa % 10 == 0 {
+        mantissa /= 10;
+        exponent += 1;
+    }
+
+    if exponent < -MAX_SCALE || mantissa > MAX_MANTISSA {
+        return None;
+    }
+
+    let mantissa = if positive { mantissa as i128 } else { -(mantissa as i128) };
+
+    Some(Decimal::from_i128_with_scale(mantissa, -exponent as u32))
+}
+
+impl JsonValue {
+    /// Obtain the value as a `Decimal`, if it's a number that can be
+    /// represented by one exactly. Returns `None` for numbers that are too
+    /// large, have more than 28 digits after the decimal point, or are not
+    /// finite. The number of digits after the decimal point is kept, so
+    /// `1.50` parsed with `ParseOptions::preserve_number_text` becomes a
+    /// `Decimal` of `1.50`.
+    ///
+    /// Requires the `rust_decimal` feature.
+    ///
+    /// ```
+    /// # extern crate rust_decimal;
+    /// # extern crate json;
+    /// # fn main() {
+    /// use std::str::FromStr;
+    /// use rust_decimal::Decimal;
+    ///
+    /// let price = json::parse("19.99").unwrap();
+    /// let total = price.as_decimal().unwrap() * Decimal::from(3);
+    ///
+    /// assert_eq!(total, Decimal::from_str("59.97").unwrap());
+    /// assert_eq!(json::JsonValue::from(total).dump(), "59.97");
+    /// assert_eq!(json::parse("1e100").unwrap().as_decimal(), None);
+    /// # }
+    /// ```
+    pub fn as_decimal(&self) -> Option<Decimal> {
+        self.as_number().and_then(to_decimal)
+    }
+}
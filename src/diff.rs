@@ -0,0 +1,175 @@
+// Structural differences between two `JsonValue`s, see `diff_report`.
+
+use std::fmt;
+use prelude::*;
+
+use path::{ JsonPath, PathSegment };
+use JsonValue;
+
+/// Single difference between two values, as listed by `json::diff_report`.
+/// Paths point into the values the difference was found in, with array
+/// indexes being the same in both.
+///
+/// Displayed as one line, starting with the path as a JSON Pointer
+/// (RFC 6901), or `(root)` for the values themselves.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Difference {
+    /// Value only present in the new value.
+    Added {
+        path: JsonPath,
+        value: JsonValue,
+    },
+
+    /// Value only present in the old value.
+    Removed {
+        path: JsonPath,
+        value: JsonValue,
+    },
+
+    /// Value present in both, but not equal. Arrays and objects are never
+    /// reported as changed, only the values within them that differ, unless
+    /// one of them was replaced with a value of another type.
+    Changed {
+        path: JsonPath,
+        old: JsonValue,
+        new: JsonValue,
+    },
+}
+
+impl Difference {
+    /// Path to where the difference was found.
+    pub fn path(&self) -> &JsonPath {
+        match *self {
+            Difference::Added { ref path, .. }   => path,
+            Difference::Removed { ref path, .. } => path,
+            Difference::Changed { ref path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.path().is_empty() {
+            f.write_str("(root)")?;
+        } else {
+            write!(f, "{}", self.path())?;
+        }
+
+        match *self {
+            Difference::Added { ref value, .. }    => write!(f, ": added {}", value.dump()),
+            Difference::Removed { ref value, .. }  => write!(f, ": removed {}", value.dump()),
+            Difference::Changed { ref old, ref new, .. } => {
+                write!(f, ": changed {} to {}", old.dump(), new.dump())
+            },
+        }
+    }
+}
+
+/// List all differences between the `old` and the `new` value, depth-first.
+/// An empty list means the values are equal.
+///
+/// Entries of objects are compared by key, and members of arrays by index,
+/// so a member inserted in the middle of an array shows up as every member
+/// after it being changed, and the last one being added. For each object,
+/// changed and removed entries are listed in the order of `old`, followed by
+/// the added ones in the order of `new`.
+///
+/// Unlike `json::testing::diff`, which produces messages for assertions,
+/// this keeps the values, so the differences can be inspected or recorded.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// use json::Difference;
+///
+/// let old = object!{ "name" => "Joe", "roles" => array!["user"], "active" => true };
+/// let new = object!{ "name" => "Joe", "roles" => array!["user", "admin"], "team" => 7 };
+///
+/// let report = json::diff_report(&old, &new);
+///
+/// let lines: Vec<String> = report.iter().map(|difference| difference.to_string()).collect();
+///
+/// assert_eq!(lines, [
+///     r#"/roles/1: added "admin""#,
+///     "/active: removed true",
+///     "/team: added 7",
+/// ]);
+///
+/// match report[1] {
+///     Difference::Removed { ref path, ref value } => {
+///         assert_eq!(path.to_string(), "/active");
+///         assert_eq!(*value, true);
+///     },
+///     _ => unreachable!(),
+/// }
+/// # }
+/// ```
+pub fn diff_report(old: &JsonValue, new: &JsonValue) -> Vec<Difference> {
+    let mut differences = Vec::new();
+
+    compare(old, new, &mut JsonPath::new(), &mut differences);
+
+    differences
+}
+
+fn compare(old: &JsonValue, new: &JsonValue, path: &mut JsonPath, differences: &mut Vec<Difference>) {
+    match (old, new) {
+        (JsonValue::Object(old), JsonValue::Object(new)) => {
+            for (key, old) in old.iter() {
+                path.push(PathSegment::Key(key.into()));
+
+                match new.get(key) {
+                    Some(new) => compare(old, new, path, differences),
+                    None      => differences.push(Difference::Removed {
+                        path: path.clone(),
+                        value: old.clone(),
+                    }),
+                }
+
+                path.pop();
+            }
+
+            for (key, new) in new.iter() {
+                if old.get(key).is_none() {
+                    path.push(PathSegment::Key(key.into()));
+                    differences.push(Difference::Added {
+                        path: path.clone(),
+                        value: new.clone(),
+                    });
+                    path.pop();
+                }
+            }
+        },
+        (JsonValue::Array(old), JsonValue::Array(new)) => {
+            for (index, old) in old.iter().enumerate() {
+                path.push(PathSegment::Index(index));
+
+                match new.get(index) {
+                    Some(new) => compare(old, new, path, differences),
+                    None      => differences.push(Difference::Removed {
+                        path: path.clone(),
+                        value: old.clone(),
+                    }),
+                }
+
+                path.pop();
+            }
+
+            for (index, new) in new.iter().enumerate().skip(old.len()) {
+                path.push(PathSegment::Index(index));
+                differences.push(Difference::Added {
+                    path: path.clone(),
+                    value: new.clone(),
+                });
+                path.pop();
+            }
+        },
+        _ => if old != new {
+            differences.push(Difference::Changed {
+                path: path.clone(),
+                old: old.clone(),
+                new: new.clone(),
+            });
+        }
+    }
+}
@@ -1,11 +1,14 @@
-use std::{ char, error, fmt };
+use std::{ char, error, fmt, io };
+use prelude::*;
+
+use convert::FromJsonError;
 
 /// Error type of this crate.
 ///
 ///
 /// *Note:* Since `0.9.0` using `JsonError` is deprecated. Always use
 /// `json::Error` instead!
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Error {
     UnexpectedCharacter {
         ch: char,
@@ -16,12 +19,230 @@ pub enum Error {
     ExceededDepthLimit,
     FailedUtf8Parsing,
     WrongType(String),
+    Io {
+        kind: io::ErrorKind,
+        message: String,
+    },
+    FromJson(FromJsonError),
+    InvalidWrite(String),
+    PatchFailed(String),
+    ExceededSizeLimit(SizeLimit),
+    DuplicateKey(String),
+    InexactNumber(String),
+    InvalidPath(String),
+    InvalidMsgpack(String),
+    InvalidYaml(String),
+    InvalidToml(String),
+    InvalidCsv(String),
+    InvalidXml(String),
+    InvalidSchema(String),
+}
+
+/// Size limit of `json::ParseOptions` that was exceeded, reported with
+/// `Error::ExceededSizeLimit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SizeLimit {
+    /// Length of the source, in bytes.
+    Bytes,
+
+    /// Number of values, including nested ones.
+    Nodes,
+
+    /// Combined length of all strings and object keys, in bytes.
+    StringLength,
+}
+
+impl SizeLimit {
+    /// Stable, lowercase name of the limit, such as `"bytes"`.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            SizeLimit::Bytes        => "bytes",
+            SizeLimit::Nodes        => "nodes",
+            SizeLimit::StringLength => "string_length",
+        }
+    }
+}
+
+/// Broad category of an error, for handling errors without matching on
+/// every variant of `Error`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    /// Input is not valid JSON, or output would not be.
+    Syntax,
+
+    /// Input exceeded one of the limits imposed on the parser.
+    Limit,
+
+    /// Reading or writing failed.
+    Io,
+
+    /// Value is not of the expected type.
+    Type,
+
+    /// Input is not valid UTF-8, or contains invalid codepoints.
+    Utf8,
+
+    /// Value doesn't meet a requirement, such as one of a JSON Patch.
+    Value,
+}
+
+impl ErrorCategory {
+    /// Stable, lowercase name of the category, such as `"syntax"`.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            ErrorCategory::Syntax => "syntax",
+            ErrorCategory::Limit  => "limit",
+            ErrorCategory::Io     => "io",
+            ErrorCategory::Type   => "type",
+            ErrorCategory::Utf8   => "utf8",
+            ErrorCategory::Value  => "value",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 impl Error {
     pub fn wrong_type(expected: &str) -> Self {
         Error::WrongType(expected.into())
     }
+
+    /// Stable numeric code of the error. Codes are never reused or changed
+    /// between releases, new kinds of errors receive new codes.
+    ///
+    /// ```
+    /// let error = json::parse("[1,").unwrap_err();
+    ///
+    /// assert_eq!(error.code(), 2);
+    /// assert_eq!(error.code_name(), "unexpected_end_of_json");
+    /// assert_eq!(error.category(), json::ErrorCategory::Syntax);
+    /// ```
+    pub fn code(&self) -> u16 {
+        use Error::*;
+
+        match *self {
+            UnexpectedCharacter { .. } => 1,
+            UnexpectedEndOfJson        => 2,
+            ExceededDepthLimit         => 3,
+            FailedUtf8Parsing          => 4,
+            WrongType(_)               => 5,
+            Io { .. }                  => 6,
+            FromJson(_)                => 7,
+            InvalidWrite(_)            => 8,
+            PatchFailed(_)             => 9,
+            ExceededSizeLimit(_)       => 10,
+            DuplicateKey(_)            => 11,
+            InexactNumber(_)           => 12,
+            InvalidPath(_)             => 13,
+            InvalidMsgpack(_)          => 14,
+            InvalidYaml(_)             => 15,
+            InvalidToml(_)             => 16,
+            InvalidCsv(_)              => 17,
+            InvalidXml(_)              => 18,
+            InvalidSchema(_)           => 19,
+        }
+    }
+
+    /// Stable string code of the error, such as `"unexpected_character"`.
+    pub fn code_name(&self) -> &'static str {
+        use Error::*;
+
+        match *self {
+            UnexpectedCharacter { .. } => "unexpected_character",
+            UnexpectedEndOfJson        => "unexpected_end_of_json",
+            ExceededDepthLimit         => "exceeded_depth_limit",
+            FailedUtf8Parsing          => "failed_utf8_parsing",
+            WrongType(_)               => "wrong_type",
+            Io { .. }                  => "io",
+            FromJson(_)                => "from_json",
+            InvalidWrite(_)            => "invalid_write",
+            PatchFailed(_)             => "patch_failed",
+            ExceededSizeLimit(_)       => "exceeded_size_limit",
+            DuplicateKey(_)            => "duplicate_key",
+            InexactNumber(_)           => "inexact_number",
+            InvalidPath(_)             => "invalid_path",
+            InvalidMsgpack(_)          => "invalid_msgpack",
+            InvalidYaml(_)             => "invalid_yaml",
+            InvalidToml(_)             => "invalid_toml",
+            InvalidCsv(_)              => "invalid_csv",
+            InvalidXml(_)              => "invalid_xml",
+            InvalidSchema(_)           => "invalid_schema",
+        }
+    }
+
+    /// Category of the error.
+    pub fn category(&self) -> ErrorCategory {
+        use Error::*;
+
+        match *self {
+            UnexpectedCharacter { .. } => ErrorCategory::Syntax,
+            UnexpectedEndOfJson        => ErrorCategory::Syntax,
+            ExceededDepthLimit         => ErrorCategory::Limit,
+            FailedUtf8Parsing          => ErrorCategory::Utf8,
+            WrongType(_)               => ErrorCategory::Type,
+            Io { .. }                  => ErrorCategory::Io,
+            FromJson(_)                => ErrorCategory::Type,
+            InvalidWrite(_)            => ErrorCategory::Syntax,
+            PatchFailed(_)             => ErrorCategory::Value,
+            ExceededSizeLimit(_)       => ErrorCategory::Limit,
+            DuplicateKey(_)            => ErrorCategory::Value,
+            InexactNumber(_)           => ErrorCategory::Value,
+            InvalidPath(_)             => ErrorCategory::Value,
+            InvalidMsgpack(_)          => ErrorCategory::Syntax,
+            InvalidYaml(_)             => ErrorCategory::Syntax,
+            InvalidToml(_)             => ErrorCategory::Syntax,
+            InvalidCsv(_)              => ErrorCategory::Syntax,
+            InvalidXml(_)              => ErrorCategory::Syntax,
+            InvalidSchema(_)           => ErrorCategory::Value,
+        }
+    }
+
+    /// Render the error along with the line of `source` it points at, and a
+    /// caret under the offending position, for use in messages shown to
+    /// users. `source` should be the input that produced the error.
+    ///
+    /// ```
+    /// let source = "{\n  \"id\": 1,\n  \"name\": Joe\n}";
+    /// let error = json::parse(source).unwrap_err();
+    ///
+    /// assert_eq!(error.display_context(source), concat!(
+    ///     "Unexpected character: J at (3:11)\n",
+    ///     "  |\n",
+    ///     "3 |   \"name\": Joe\n",
+    ///     "  |           ^\n",
+    /// ));
+    /// ```
+    ///
+    /// `UnexpectedEndOfJson` points right after the last character of the
+    /// source. Errors without a position are rendered as with `Display`.
+    pub fn display_context(&self, source: &str) -> String {
+        let (line, column) = match *self {
+            Error::UnexpectedCharacter { line, column, .. } => (line, column),
+            Error::UnexpectedEndOfJson => {
+                let source = source.trim_end();
+                let last = &source[source.rfind('\n').map_or(0, |index| index + 1)..];
+
+                (source.matches('\n').count() + 1, last.chars().count() + 1)
+            },
+            _ => return self.to_string(),
+        };
+
+        let text = source.lines().nth(line.saturating_sub(1)).unwrap_or("");
+        let number = line.to_string();
+        let gutter = " ".repeat(number.len());
+
+        // Keep tabs in the padding so the caret lines up with the text.
+        let padding: String = text.chars()
+            .take(column.saturating_sub(1))
+            .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        format!("{}\n{} |\n{} | {}\n{} | {}^\n", self, gutter, number, text, gutter, padding)
+    }
 }
 
 impl fmt::Display for Error {
@@ -39,6 +260,20 @@ impl fmt::Display for Error {
             ExceededDepthLimit    => write!(f, "Exceeded depth limit"),
             FailedUtf8Parsing     => write!(f, "Failed to parse UTF-8 bytes"),
             WrongType(ref s)      => write!(f, "Wrong type, expected: {}", s),
+            Io { ref message, .. } => write!(f, "I/O error: {}", message),
+            FromJson(ref error)   => fmt::Display::fmt(error, f),
+            InvalidWrite(ref s)   => write!(f, "Invalid write: {}", s),
+            PatchFailed(ref s)    => write!(f, "Patch failed: {}", s),
+            ExceededSizeLimit(limit) => write!(f, "Exceeded size limit: {}", limit.as_str()),
+            DuplicateKey(ref key) => write!(f, "Duplicate key: {}", key),
+            InexactNumber(ref s)  => write!(f, "Inexact number: {}", s),
+            InvalidPath(ref s)    => write!(f, "Invalid path: {}", s),
+            InvalidMsgpack(ref s) => write!(f, "Invalid MessagePack: {}", s),
+            InvalidYaml(ref s)    => write!(f, "Invalid YAML: {}", s),
+            InvalidToml(ref s)    => write!(f, "Invalid TOML: {}", s),
+            InvalidCsv(ref s)     => write!(f, "Invalid CSV: {}", s),
+            InvalidXml(ref s)     => write!(f, "Invalid XML: {}", s),
+            InvalidSchema(ref s)  => write!(f, "Invalid schema: {}", s),
         }
     }
 }
@@ -53,6 +288,53 @@ impl error::Error for Error {
             ExceededDepthLimit         => "Exceeded depth limit",
             FailedUtf8Parsing          => "Failed to read bytes as UTF-8 from JSON",
             WrongType(_)               => "Wrong type",
+            Io { .. }                  => "I/O error",
+            FromJson(_)                => "Wrong type",
+            InvalidWrite(_)            => "Invalid write",
+            PatchFailed(_)             => "Patch failed",
+            ExceededSizeLimit(_)       => "Exceeded size limit",
+            DuplicateKey(_)            => "Duplicate key",
+            InexactNumber(_)           => "Inexact number",
+            InvalidPath(_)             => "Invalid path",
+            InvalidMsgpack(_)          => "Invalid MessagePack",
+            InvalidYaml(_)             => "Invalid YAML",
+            InvalidToml(_)             => "Invalid TOML",
+            InvalidCsv(_)              => "Invalid CSV",
+            InvalidXml(_)              => "Invalid XML",
+            InvalidSchema(_)           => "Invalid schema",
         }
     }
 }
+
+// Error for the character of `source` at byte index `at`, or the end of the
+// source if there are no characters left.
+pub(crate) fn unexpected_character_at(source: &str, at: usize) -> Error {
+    let ch = match source[at..].chars().next() {
+        Some(ch) => ch,
+        None     => return Error::UnexpectedEndOfJson,
+    };
+
+    let before = &source[..at];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+
+    Error::UnexpectedCharacter {
+        ch,
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io {
+            kind: error.kind(),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<FromJsonError> for Error {
+    fn from(error: FromJsonError) -> Self {
+        Error::FromJson(error)
+    }
+}
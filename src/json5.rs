@@ -0,0 +1,548 @@
+//! Parsing of [JSON5](https://json5.org/), enabled with the `json5` feature.
+//! JSON5 is a superset of JSON meant for documents written by hand, such as
+//! configuration files. On top of regular JSON it allows:
+//!
+//! - `// line` and `/* block */` comments,
+//! - object keys written as identifiers, without quotes,
+//! - strings in single quotes, spanning multiple lines with `\` before the
+//!   line break, and additional escapes such as `\x41` and `\v`,
+//! - trailing commas in arrays and objects,
+//! - hexadecimal numbers, a leading `+`, leading or trailing decimal points,
+//!   `Infinity` and `NaN`.
+//!
+//! Documents are parsed into the regular `JsonValue` tree.
+
+use std::char;
+use prelude::*;
+
+use number::{ Number, NAN, INFINITY };
+use object::Object;
+use error::unexpected_character_at;
+use { JsonValue, Error, Result };
+
+// How many nested Objects/Arrays are allowed to be parsed, same as with
+// `json::parse`.
+const DEPTH_LIMIT: usize = 512;
+
+/// Parse a JSON5 document.
+///
+/// ```
+/// let config = json::json5::parse("{
+///     // Hosts to connect to
+///     hosts: ['alpha', 'beta',],
+///     port: 0x1F90,
+///     timeout: .5,
+/// }").unwrap();
+///
+/// assert_eq!(config["hosts"][1], "beta");
+/// assert_eq!(config["port"], 8080);
+/// assert_eq!(config["timeout"], 0.5);
+/// ```
+///
+/// `Infinity` and `NaN` produce `number::INFINITY` and `number::NAN`, which
+/// are serialized as `null`.
+pub fn parse(source: &str) -> Result<JsonValue> {
+    let mut parser = Parser {
+        source,
+        index: 0,
+        depth: 0,
+    };
+
+    let value = parser.parse_value()?;
+
+    parser.skip_whitespace()?;
+
+    if parser.index != source.len() {
+        return parser.unexpected_character();
+    }
+
+    Ok(value)
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    index: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.source.as_bytes().get(self.index).cloned()
+    }
+
+    fn peek_char(&self) -> Option<char> {
+        self.source[self.index..].chars().next()
+    }
+
+    fn next_char(&mut self) -> Result<char> {
+        match self.peek_char() {
+            Some(ch) => {
+                self.index += ch.len_utf8();
+                Ok(ch)
+            },
+            None => Err(Error::UnexpectedEndOfJson),
+        }
+    }
+
+    fn next_digit(&mut self, radix: u32) -> Option<u32> {
+        let digit = self.peek_char().and_then(|ch| ch.to_digit(radix));
+
+        if digit.is_some() {
+            self.index += 1;
+        }
+
+        digit
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        match self.peek_char() {
+            Some(ch) if ch == expected => {
+                self.index += ch.len_utf8();
+                Ok(())
+            },
+            _ => self.unexpected_character(),
+        }
+    }
+
+    fn unexpected_at<T>(&self, at: usize) -> Result<T> {
+        Err(unexpected_character_at(self.source, at))
+    }
+
+    fn unexpected_character<T>(&self) -> Result<T> {
+        self.unexpected_at(self.index)
+    }
+
+    fn skip_whitespace(&mut self) -> Result<()> {
+        while let Some(ch) = self.peek_char() {
+            if ch.is_whitespace() || ch == '\u{FEFF}' {
+                self.index += ch.len_utf8();
+                continue;
+            }
+
+            if ch != '/' {
+                break;
+            }
+
+            self.index += 1;
+
+            match self.peek() {
+                Some(b'/') => {
+                    while let Some(ch) = self.peek_char() {
+                        if is_line_terminator(ch) {
+                            break;
+                        }
+                        self.index += ch.len_utf8();
+                    }
+                },
+                Some(b'*') => match self.source[self.index + 1..].find("*/") {
+                    Some(end) => self.index += end + 3,
+                    None      => return Err(Error::UnexpectedEndOfJson),
+                },
+                _ => return self.unexpected_character(),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn enter(&mut self) -> Result<()> {
+        if self.depth == DEPTH_LIMIT {
+            return Err(Error::ExceededDepthLimit);
+        }
+
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn parse_value(&mut self) -> Result<JsonValue> {
+        self.skip_whitespace()?;
+
+        let ch = match self.peek_char() {
+            Some(ch) => ch,
+            None     => return Err(Error::UnexpectedEndOfJson),
+        };
+
+        match ch {
+            '{' => self.parse_object(),
+            '[' => self.parse_array(),
+            '"' | '\'' => {
+                self.index += 1;
+                self.parse_string(ch).map(JsonValue::from)
+            },
+            '-' | '+' | '.' | '0' ..= '9' => self.parse_number().map(JsonValue::Number),
+            _ if is_identifier_start(ch) || ch == '\\' => {
+                let start = self.index;
+
+                match &*self.parse_identifier()? {
+                    "null"     => Ok(JsonValue::Null),
+                    "true"     => Ok(JsonValue::Boolean(true)),
+                    "false"    => Ok(JsonValue::Boolean(false)),
+                    "Infinity" => Ok(JsonValue::Number(INFINITY)),
+                    "NaN"      => Ok(JsonValue::Number(NAN)),
+                    _          => self.unexpected_at(start),
+                }
+            },
+            _ => self.unexpected_character(),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<JsonValue> {
+        self.index += 1;
+        self.enter()?;
+
+        let mut object = Object::new();
+
+        loop {
+            self.skip_whitespace()?;
+
+            let key = match self.peek_char() {
+                Some('}') => {
+                    self.index += 1;
+                    break;
+                },
+                Some(quote) if quote == '"' || quote == '\'' => {
+                    self.index += 1;
+                    self.parse_string(quote)?
+                },
+                Some(_) => self.parse_identifier()?,
+                None    => return Err(Error::UnexpectedEndOfJson),
+            };
+
+            self.skip_whitespace()?;
+            self.expect(':')?;
+
+            let value = self.parse_value()?;
+
+            object.insert_owned(key, value);
+
+            self.skip_whitespace()?;
+
+            match self.peek() {
+                Some(b',') => self.index += 1,
+                Some(b'}') => {
+                    self.index += 1;
+                    break;
+                },
+                _ => return self.unexpected_character(),
+            }
+        }
+
+        self.depth -= 1;
+
+        Ok(JsonValue::Object(object))
+    }
+
+    fn parse_array(&mut self) -> Result<JsonValue> {
+        self.index += 1;
+        self.enter()?;
+
+        let mut array = Vec::new();
+
+        loop {
+            self.skip_whitespace()?;
+
+            if self.peek() == Some(b']') {
+                self.index += 1;
+                break;
+            }
+
+            array.push(self.parse_value()?);
+
+            self.skip_whitespace()?;
+
+            match self.peek() {
+                Some(b',') => self.index += 1,
+                Some(b']') => {
+                    self.index += 1;
+                    break;
+                },
+                _ => return self.unexpected_character(),
+            }
+        }
+
+        self.depth -= 1;
+
+        Ok(JsonValue::Array(array))
+    }
+
+    // Read an identifier, as used for unquoted keys. Identifiers may contain
+    // `\uXXXX` escapes, as long as the escaped characters would be allowed
+    // in the identifier unescaped.
+    fn parse_identifier(&mut self) -> Result<String> {
+        let mut identifier = String::new();
+
+        loop {
+            let start = self.index;
+
+            let ch = match self.peek_char() {
+                Some('\\') => {
+                    self.index += 1;
+                    self.expect('u')?;
+                    self.read_unicode_escape()?
+                },
+                Some(ch) => {
+                    self.index += ch.len_utf8();
+                    ch
+                },
+                None => break,
+            };
+
+            let allowed = if identifier.is_empty() {
+                is_identifier_start(ch)
+            } else {
+                is_identifier_part(ch)
+            };
+
+            if !allowed {
+                self.index = start;
+
+                if identifier.is_empty() || self.peek() == Some(b'\\') {
+                    return self.unexpected_character();
+                }
+
+                break;
+            }
+
+            identifier.push(ch);
+        }
+
+        if identifier.is_empty() {
+            return Err(Error::UnexpectedEndOfJson);
+        }
+
+        Ok(identifier)
+    }
+
+    // Read a string after its opening `quote` has been consumed.
+    fn parse_string(&mut self, quote: char) -> Result<String> {
+        let mut string = String::new();
+
+        loop {
+            let ch = self.next_char()?;
+
+            if ch == quote {
+                return Ok(string);
+            }
+
+            match ch {
+                '\\' => {
+                    let escaped = self.next_char()?;
+
+                    match escaped {
+                        'b' => string.push('\u{8}'),
+                        'f' => string.push('\u{C}'),
+                        'n' => string.push('\n'),
+                        'r' => string.push('\r'),
+                        't' => string.push('\t'),
+                        'v' => string.push('\u{B}'),
+                        '0' => {
+                            if let Some(b'0' ..= b'9') = self.peek() {
+                                return self.unexpected_character();
+                            }
+                            string.push('\0');
+                        },
+                        '1' ..= '9' => return self.unexpected_at(self.index - 1),
+                        'x' => {
+                            let code = self.read_hex(2)?;
+                            string.push(code as u8 as char);
+                        },
+                        'u' => {
+                            let ch = self.read_unicode_escape()?;
+                            string.push(ch);
+                        },
+                        // Line continuation, the line break is skipped.
+                        '\r' => {
+                            if self.peek() == Some(b'\n') {
+                                self.index += 1;
+                            }
+                        },
+                        '\n' | '\u{2028}' | '\u{2029}' => {},
+                        _ => string.push(escaped),
+                    }
+                },
+                '\n' | '\r' => return self.unexpected_at(self.index - 1),
+                _ => string.push(ch),
+            }
+        }
+    }
+
+    fn read_hex(&mut self, digits: usize) -> Result<u32> {
+        let mut value = 0;
+
+        for _ in 0..digits {
+            match self.next_digit(16) {
+                Some(digit) => value = value * 16 + digit,
+                None        => return self.unexpected_character(),
+            }
+        }
+
+        Ok(value)
+    }
+
+    // Read the `XXXX` of a `\uXXXX` escape, combining surrogate pairs.
+    fn read_unicode_escape(&mut self) -> Result<char> {
+        let code = self.read_hex(4)?;
+
+        if let 0xD800 ..= 0xDBFF = code {
+            if self.source[self.index..].starts_with("\\u") {
+                let high = self.index;
+
+                self.index += 2;
+
+                match self.read_hex(4)? {
+                    low @ 0xDC00 ..= 0xDFFF => {
+                        let code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+
+                        return char::from_u32(code).ok_or(Error::FailedUtf8Parsing);
+                    },
+                    _ => self.index = high,
+                }
+            }
+        }
+
+        char::from_u32(code).ok_or(Error::FailedUtf8Parsing)
+    }
+
+    fn parse_number(&mut self) -> Result<Number> {
+        let positive = match self.peek() {
+            Some(b'-') => {
+                self.index += 1;
+                false
+            },
+            Some(b'+') => {
+                self.index += 1;
+                true
+            },
+            _ => true,
+        };
+
+        let number = match self.peek() {
+            Some(b'I') | Some(b'N') => {
+                let start = self.index;
+
+                match &*self.parse_identifier()? {
+                    "Infinity" => INFINITY,
+                    "NaN"      => NAN,
+                    _          => return self.unexpected_at(start),
+                }
+            },
+            Some(b'0') if self.source[self.index + 1..].starts_with(['x', 'X']) => {
+                self.index += 2;
+                self.parse_hex()?
+            },
+            _ => self.parse_decimal()?,
+        };
+
+        Ok(if positive { number } else { -number })
+    }
+
+    // Read hexadecimal digits after the `0x` prefix. Numbers that don't fit
+    // a `u64` are computed in `f64` precision.
+    fn parse_hex(&mut self) -> Result<Number> {
+        let start = self.index;
+        let mut mantissa = Some(0u64);
+        let mut float = 0f64;
+
+        while let Some(digit) = self.next_digit(16) {
+            mantissa = mantissa.and_then(|mantissa| mantissa.checked_mul(16))
+                               .map(|mantissa| mantissa + digit as u64);
+            float = float * 16.0 + digit as f64;
+        }
+
+        if self.index == start {
+            return self.unexpected_character();
+        }
+
+        Ok(match mantissa {
+            Some(mantissa) => mantissa.into(),
+            None           => float.into(),
+        })
+    }
+
+    // Read a decimal number without its sign. Digits that don't fit the
+    // mantissa are accounted for in the exponent, same as with `json::parse`.
+    fn parse_decimal(&mut self) -> Result<Number> {
+        let mut mantissa = 0u64;
+        let mut exponent = 0i32;
+        let mut digits = 0;
+
+        if self.peek() == Some(b'0') {
+            self.index += 1;
+            digits += 1;
+
+            // Leading zeroes are not allowed, same as in JSON.
+            if let Some(b'0' ..= b'9') = self.peek() {
+                return self.unexpected_character();
+            }
+        } else {
+            while let Some(digit) = self.next_digit(10) {
+                digits += 1;
+
+                match mantissa.checked_mul(10).and_then(|num| num.checked_add(digit as u64)) {
+                    Some(num) => mantissa = num,
+                    None      => exponent += 1,
+                }
+            }
+        }
+
+        if self.peek() == Some(b'.') {
+            self.index += 1;
+
+            while let Some(digit) = self.next_digit(10) {
+                digits += 1;
+
+                if let Some(num) = mantissa.checked_mul(10).and_then(|num| num.checked_add(digit as u64)) {
+                    mantissa = num;
+                    exponent -= 1;
+                }
+            }
+        }
+
+        if digits == 0 {
+            return self.unexpected_character();
+        }
+
+        if let Some(b'e') | Some(b'E') = self.peek() {
+            self.index += 1;
+
+            let sign = match self.peek() {
+                Some(b'-') => {
+                    self.index += 1;
+                    -1
+                },
+                Some(b'+') => {
+                    self.index += 1;
+                    1
+                },
+                _ => 1,
+            };
+
+            let mut e = match self.next_digit(10) {
+                Some(digit) => digit as i32,
+                None        => return self.unexpected_character(),
+            };
+
+            while let Some(digit) = self.next_digit(10) {
+                e = e.saturating_mul(10).saturating_add(digit as i32);
+            }
+
+            exponent = exponent.saturating_add(e * sign);
+        }
+
+        let exponent = exponent.max(i16::MIN as i32).min(i16::MAX as i32) as i16;
+
+        Ok(Number::from_parts(true, mantissa, exponent))
+    }
+}
+
+fn is_line_terminator(ch: char) -> bool {
+    matches!(ch, '\n' | '\r' | '\u{2028}' | '\u{2029}')
+}
+
+fn is_identifier_start(ch: char) -> bool {
+    ch == '$' || ch == '_' || ch.is_alphabetic()
+}
+
+fn is_identifier_part(ch: char) -> bool {
+    is_identifier_start(ch) || ch.is_numeric() || ch == '\u{200C}' || ch == '\u{200D}'
+}
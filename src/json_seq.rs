@@ -0,0 +1,168 @@
+//! Support for JSON text sequences (RFC 7464), served as
+//! `application/json-seq`, where every JSON document is preceded by the
+//! record separator character (`0x1E`) and followed by a line feed. See
+//! `read` for parsing them, and `Writer` for writing them.
+
+use std::io::{ self, BufRead, Write };
+use std::str;
+
+use { parse, JsonValue, Error, Result };
+
+/// Record separator, preceding every document of a sequence.
+pub const RS: u8 = 0x1E;
+
+/// Iterator over records of a JSON text sequence, returned by
+/// `json::json_seq::read`.
+#[derive(Debug)]
+pub struct Records<R: BufRead> {
+    reader: R,
+    buffer: Vec<u8>,
+    record: usize,
+}
+
+/// Parse a JSON text sequence from `reader` one record at a time. Input
+/// between two record separators is a record, and records consisting only
+/// of whitespace are skipped.
+///
+/// Every record is parsed on its own, so a malformed or truncated record
+/// produces an error for that record only, and iteration can continue with
+/// the next one, as RFC 7464 asks for. Lines and columns of errors are
+/// relative to the start of the record. Errors reading from `reader` itself
+/// end the iteration.
+///
+/// Numbers, `true`, `false` and `null` at the top level can only be told
+/// apart from truncated ones by the whitespace following them, so without
+/// it they are rejected with `Error::UnexpectedEndOfJson`.
+///
+/// ```
+/// let input = b"\x1e{\"id\":1}\n\x1e{\"id\":\n\x1e42\x1e{\"id\":3}\n";
+///
+/// let mut records = json::json_seq::read(&input[..]);
+///
+/// assert_eq!(records.next().unwrap().unwrap()["id"], 1);
+/// assert!(records.next().unwrap().is_err());
+/// assert_eq!(records.next().unwrap(), Err(json::Error::UnexpectedEndOfJson));
+/// assert_eq!(records.record(), 3);
+/// assert_eq!(records.next().unwrap().unwrap()["id"], 3);
+/// assert!(records.next().is_none());
+/// ```
+pub fn read<R: BufRead>(reader: R) -> Records<R> {
+    Records {
+        reader,
+        buffer: Vec::new(),
+        record: 0,
+    }
+}
+
+impl<R: BufRead> Records<R> {
+    /// Number, starting at `1`, of the most recently returned record,
+    /// counting the skipped ones as well.
+    pub fn record(&self) -> usize {
+        self.record
+    }
+}
+
+impl<R: BufRead> Iterator for Records<R> {
+    type Item = Result<JsonValue>;
+
+    fn next(&mut self) -> Option<Result<JsonValue>> {
+        loop {
+            self.buffer.clear();
+
+            match self.reader.read_until(RS, &mut self.buffer) {
+                Ok(0)      => return None,
+                Ok(_)      => {},
+                Err(error) => return Some(Err(error.into())),
+            }
+
+            if self.buffer.last() == Some(&RS) {
+                self.buffer.pop();
+
+                // Nothing before the first separator.
+                if self.record == 0 && self.buffer.is_empty() {
+                    continue;
+                }
+            }
+
+            self.record += 1;
+
+            match parse_record(&self.buffer) {
+                Ok(Some(record)) => return Some(Ok(record)),
+                Ok(None)         => continue,
+                Err(error)       => return Some(Err(error)),
+            }
+        }
+    }
+}
+
+// Parse a single record, returning `None` for empty ones.
+fn parse_record(bytes: &[u8]) -> Result<Option<JsonValue>> {
+    let source = str::from_utf8(bytes).map_err(|_| Error::FailedUtf8Parsing)?;
+    let text = source.trim_start();
+
+    if text.trim_end().is_empty() {
+        return Ok(None);
+    }
+
+    let truncated = match text.as_bytes()[0] {
+        b'-' | b'0' ..= b'9' | b't' | b'f' | b'n' => text.trim_end().len() == text.len(),
+        _                                         => false,
+    };
+
+    if truncated {
+        return Err(Error::UnexpectedEndOfJson);
+    }
+
+    parse(source).map(Some)
+}
+
+/// Writer of JSON text sequences, writing every document to the underlying
+/// `io::Write` preceded by the record separator and followed by a line feed,
+/// as RFC 7464 requires. Documents are written compactly, so they never
+/// contain line feeds themselves.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// use json::json_seq::Writer;
+///
+/// let mut writer = Writer::new(Vec::new());
+///
+/// writer.write(&object!{ "event" => "login" }).unwrap();
+/// writer.write(&json::from(42)).unwrap();
+///
+/// assert_eq!(writer.into_inner(), b"\x1e{\"event\":\"login\"}\n\x1e42\n");
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Writer<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(writer: W) -> Self {
+        Writer { writer }
+    }
+
+    /// Write `value` as the next record of the sequence.
+    pub fn write(&mut self, value: &JsonValue) -> io::Result<()> {
+        self.writer.write_all(&[RS])?;
+        value.write(&mut self.writer)?;
+        self.writer.write_all(b"\n")
+    }
+
+    /// Flush the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Obtain a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Obtain the underlying writer, consuming the `Writer`.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
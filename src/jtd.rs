@@ -0,0 +1,589 @@
+//! Validation with JSON Type Definition (RFC 8927) schemas, a lighter
+//! alternative to JSON Schema where every schema has exactly one form:
+//!
+//! - empty, `{}`, accepting any value,
+//! - `type`, such as `{"type": "uint8"}`, accepting a boolean, a string, an
+//!   RFC 3339 `timestamp`, any number for `float32` and `float64`, or an
+//!   integer within the range of `int8`, `uint8`, `int16`, `uint16`, `int32`
+//!   or `uint32`,
+//! - `enum`, accepting one of a list of strings,
+//! - `elements`, accepting arrays of values matching a schema,
+//! - `properties` and `optionalProperties`, accepting objects with the
+//!   listed keys, and no other keys unless `additionalProperties` is `true`,
+//! - `values`, accepting objects whose values all match a schema,
+//! - `discriminator` and `mapping`, accepting objects validated against
+//!   the schema in `mapping` named by their tag,
+//! - `ref`, referring to one of the `definitions` of the root schema.
+//!
+//! Any schema can also be `nullable`, and hold `metadata`, which is ignored.
+//!
+//! Validation reports every mismatch as a pair of JSON Pointers, one to the
+//! invalid value and one to the part of the schema it failed.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use prelude::*;
+
+use convert::ToJson;
+use path::{ JsonPath, PathSegment };
+use schema::is_date_time;
+use object::Object;
+use { JsonValue, Error, Result };
+
+/// Compiled JTD schema, ready to validate values.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// use json::jtd::Schema;
+///
+/// let schema = Schema::compile(&json::parse(r#"{
+///     "properties": {
+///         "id": { "type": "uint32" },
+///         "tags": { "elements": { "type": "string" } }
+///     },
+///     "optionalProperties": {
+///         "status": { "enum": ["active", "disabled"] }
+///     }
+/// }"#).unwrap()).unwrap();
+///
+/// assert!(schema.is_valid(&object!{ "id" => 1, "tags" => array!["a"] }));
+///
+/// let errors = schema.validate(&object!{ "id" => -1, "tags" => array!["a", 2] });
+/// let errors = errors.iter().map(|error| error.to_string()).collect::<Vec<_>>();
+///
+/// assert_eq!(errors, vec![
+///     "value at /id doesn't match schema at /properties/id/type",
+///     "value at /tags/1 doesn't match schema at /properties/tags/elements/type",
+/// ]);
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schema {
+    root: Node,
+    definitions: BTreeMap<String, Node>,
+}
+
+/// Mismatch between a value and a schema, found by `Schema::validate`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// Path to the value that doesn't match.
+    pub instance_path: JsonPath,
+
+    /// Path to the part of the schema the value doesn't match.
+    pub schema_path: JsonPath,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "value at {} doesn't match schema at {}", self.instance_path, self.schema_path)
+    }
+}
+
+/// Writes the error in the standard form of RFC 8927, such as
+/// `{"instancePath":"/id","schemaPath":"/properties/id/type"}`.
+impl ToJson for ValidationError {
+    fn into_json(self) -> JsonValue {
+        let mut object = Object::with_capacity(2);
+
+        object.insert("instancePath", self.instance_path.to_string().into());
+        object.insert("schemaPath", self.schema_path.to_string().into());
+
+        JsonValue::Object(object)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Node {
+    form: Form,
+    nullable: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Form {
+    Empty,
+    Ref(String),
+    Type(Type),
+    Enum(Vec<String>),
+    Elements(Box<Node>),
+    Properties {
+        required: Vec<(String, Node)>,
+        optional: Vec<(String, Node)>,
+        additional: bool,
+        // Whether the `properties` keyword is present, as opposed to only
+        // `optionalProperties`.
+        has_required: bool,
+    },
+    Values(Box<Node>),
+    Discriminator {
+        tag: String,
+        mapping: Vec<(String, Node)>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Type {
+    Boolean,
+    String,
+    Timestamp,
+    Float,
+    Integer(f64, f64),
+}
+
+impl Type {
+    fn from_name(name: &str) -> Option<Type> {
+        Some(match name {
+            "boolean"   => Type::Boolean,
+            "string"    => Type::String,
+            "timestamp" => Type::Timestamp,
+            "float32"   => Type::Float,
+            "float64"   => Type::Float,
+            "int8"      => Type::Integer(i8::MIN as f64, i8::MAX as f64),
+            "uint8"     => Type::Integer(0.0, u8::MAX as f64),
+            "int16"     => Type::Integer(i16::MIN as f64, i16::MAX as f64),
+            "uint16"    => Type::Integer(0.0, u16::MAX as f64),
+            "int32"     => Type::Integer(i32::MIN as f64, i32::MAX as f64),
+            "uint32"    => Type::Integer(0.0, u32::MAX as f64),
+            _           => return None,
+        })
+    }
+
+    fn accepts(&self, value: &JsonValue) -> bool {
+        match (*self, value) {
+            (Type::Boolean, &JsonValue::Boolean(_)) => true,
+            (Type::String, value)    => value.is_string(),
+            (Type::Timestamp, value) => value.as_str().is_some_and(|text| {
+                // A timestamp has to have an offset, which follows the date.
+                is_date_time(text) && text[10..].contains(&['Z', 'z', '+', '-'][..])
+            }),
+            (Type::Float, value)     => value.is_number(),
+            (Type::Integer(min, max), &JsonValue::Number(number)) => {
+                let float = f64::from(number);
+
+                float.fract() == 0.0 && float >= min && float <= max
+            },
+            _ => false,
+        }
+    }
+}
+
+// Keywords of each form, other than the empty form.
+const FORMS: [&[&str]; 7] = [
+    &["ref"],
+    &["type"],
+    &["enum"],
+    &["elements"],
+    &["properties", "optionalProperties", "additionalProperties"],
+    &["values"],
+    &["discriminator", "mapping"],
+];
+
+fn invalid(reason: &str, path: &JsonPath) -> Error {
+    match path.is_empty() {
+        true  => Error::InvalidSchema(reason.into()),
+        false => Error::InvalidSchema(format!("{} at {}", reason, path)),
+    }
+}
+
+fn push_key(path: &JsonPath, key: &str) -> JsonPath {
+    let mut path = path.clone();
+    path.push(PathSegment::Key(key.into()));
+    path
+}
+
+impl Schema {
+    /// Compile a JTD schema. Fails with `Error::InvalidSchema` if `schema`
+    /// is not a valid schema, such as one mixing keywords of different
+    /// forms, referring to a definition that doesn't exist, or having a
+    /// definition that refers only to itself.
+    pub fn compile(schema: &JsonValue) -> Result<Schema> {
+        let root_path = JsonPath::new();
+
+        let names = match schema["definitions"] {
+            JsonValue::Object(ref definitions) => definitions.keys().cloned().collect(),
+            JsonValue::Null => Vec::new(),
+            _ => return Err(invalid("definitions that are not an object", &root_path)),
+        };
+
+        let compiler = Compiler { names: &names };
+
+        let mut definitions = BTreeMap::new();
+
+        if let JsonValue::Object(ref object) = schema["definitions"] {
+            let path = push_key(&root_path, "definitions");
+
+            for (name, definition) in object.iter() {
+                definitions.insert(name.clone(), compiler.node(definition, &push_key(&path, name))?);
+            }
+        }
+
+        let root = compiler.node(schema, &root_path)?;
+
+        // A chain of definitions referring to one another would never
+        // reach a value to validate.
+        for name in &names {
+            let mut current = name;
+
+            for _ in 0..names.len() {
+                match definitions[current].form {
+                    Form::Ref(ref next) => current = next,
+                    _ => break,
+                }
+
+                if current == name {
+                    return Err(invalid("circular reference", &push_key(&push_key(&root_path, "definitions"), name)));
+                }
+            }
+        }
+
+        Ok(Schema {
+            root,
+            definitions,
+        })
+    }
+
+    /// Check whether `value` matches the schema.
+    pub fn is_valid(&self, value: &JsonValue) -> bool {
+        self.validate(value).is_empty()
+    }
+
+    /// Validate `value`, returning all the places where it doesn't match the
+    /// schema, in the order they are found. An empty `Vec` means `value` is
+    /// valid.
+    pub fn validate(&self, value: &JsonValue) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+
+        self.validate_node(&self.root, value, &mut JsonPath::new(), &mut JsonPath::new(), None, &mut errors);
+
+        errors
+    }
+
+    fn validate_node(
+        &self,
+        node: &Node,
+        value: &JsonValue,
+        instance: &mut JsonPath,
+        schema: &mut JsonPath,
+        tag: Option<&str>,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        if node.nullable && value.is_null() {
+            return;
+        }
+
+        fn fail(instance: &JsonPath, schema: &JsonPath, keyword: Option<&str>, errors: &mut Vec<ValidationError>) {
+            errors.push(ValidationError {
+                instance_path: instance.clone(),
+                schema_path: match keyword {
+                    Some(keyword) => push_key(schema, keyword),
+                    None          => schema.clone(),
+                },
+            });
+        }
+
+        match node.form {
+            Form::Empty => {},
+            Form::Ref(ref name) => {
+                let mut path = JsonPath::from(vec![
+                    PathSegment::Key("definitions".into()),
+                    PathSegment::Key(name.clone()),
+                ]);
+
+                self.validate_node(&self.definitions[name], value, instance, &mut path, None, errors);
+            },
+            Form::Type(kind) => {
+                if !kind.accepts(value) {
+                    fail(instance, schema, Some("type"), errors);
+                }
+            },
+            Form::Enum(ref variants) => {
+                if !value.as_str().is_some_and(|string| variants.iter().any(|variant| variant == string)) {
+                    fail(instance, schema, Some("enum"), errors);
+                }
+            },
+            Form::Elements(ref elements) => {
+                let vec = match *value {
+                    JsonValue::Array(ref vec) => vec,
+                    _ => return fail(instance, schema, Some("elements"), errors),
+                };
+
+                schema.push(PathSegment::Key("elements".into()));
+
+                for (index, member) in vec.iter().enumerate() {
+                    instance.push(PathSegment::Index(index));
+                    self.validate_node(elements, member, instance, schema, None, errors);
+                    instance.pop();
+                }
+
+                schema.pop();
+            },
+            Form::Properties { ref required, ref optional, additional, has_required } => {
+                let object = match *value {
+                    JsonValue::Object(ref object) => object,
+                    _ => {
+                        let keyword = if has_required { "properties" } else { "optionalProperties" };
+
+                        return fail(instance, schema, Some(keyword), errors);
+                    }
+                };
+
+                for &(keyword, properties) in &[("properties", required), ("optionalProperties", optional)] {
+                    schema.push(PathSegment::Key(keyword.into()));
+
+                    for (key, property) in properties {
+                        match object.get(key.as_str()) {
+                            Some(member) => {
+                                schema.push(PathSegment::Key(key.clone()));
+                                instance.push(PathSegment::Key(key.clone()));
+                                self.validate_node(property, member, instance, schema, None, errors);
+                                instance.pop();
+                                schema.pop();
+                            },
+                            None if keyword == "properties" => fail(instance, schema, Some(key), errors),
+                            None => {},
+                        }
+                    }
+
+                    schema.pop();
+                }
+
+                if !additional {
+                    for key in object.keys() {
+                        let known = tag == Some(key.as_str())
+                            || required.iter().chain(optional).any(|(name, _)| name == key);
+
+                        if !known {
+                            instance.push(PathSegment::Key(key.clone()));
+                            fail(instance, schema, None, errors);
+                            instance.pop();
+                        }
+                    }
+                }
+            },
+            Form::Values(ref values) => {
+                let object = match *value {
+                    JsonValue::Object(ref object) => object,
+                    _ => return fail(instance, schema, Some("values"), errors),
+                };
+
+                schema.push(PathSegment::Key("values".into()));
+
+                for (key, member) in object.iter() {
+                    instance.push(PathSegment::Key(key.clone()));
+                    self.validate_node(values, member, instance, schema, None, errors);
+                    instance.pop();
+                }
+
+                schema.pop();
+            },
+            Form::Discriminator { tag: ref name, ref mapping } => {
+                let object = match *value {
+                    JsonValue::Object(ref object) => object,
+                    _ => return fail(instance, schema, Some("discriminator"), errors),
+                };
+
+                let variant = match object.get(name.as_str()) {
+                    Some(variant) => variant,
+                    None => return fail(instance, schema, Some("discriminator"), errors),
+                };
+
+                instance.push(PathSegment::Key(name.clone()));
+
+                let variant = match variant.as_str() {
+                    Some(variant) => variant,
+                    None => {
+                        fail(instance, schema, Some("discriminator"), errors);
+                        instance.pop();
+                        return;
+                    }
+                };
+
+                let node = match mapping.iter().find(|(key, _)| key == variant) {
+                    Some((_, node)) => node,
+                    None => {
+                        fail(instance, schema, Some("mapping"), errors);
+                        instance.pop();
+                        return;
+                    }
+                };
+
+                instance.pop();
+
+                schema.push(PathSegment::Key("mapping".into()));
+                schema.push(PathSegment::Key(variant.into()));
+                self.validate_node(node, value, instance, schema, Some(name), errors);
+                schema.pop();
+                schema.pop();
+            },
+        }
+    }
+}
+
+struct Compiler<'a> {
+    names: &'a [String],
+}
+
+impl<'a> Compiler<'a> {
+    fn node(&self, schema: &JsonValue, path: &JsonPath) -> Result<Node> {
+        let object = match *schema {
+            JsonValue::Object(ref object) => object,
+            _ => return Err(invalid("schema that is not an object", path)),
+        };
+
+        let mut form = None;
+
+        for key in object.keys() {
+            let key = key.as_str();
+
+            match key {
+                "metadata" | "nullable" => continue,
+                "definitions" if path.is_empty() => continue,
+                _ => {},
+            }
+
+            let index = match FORMS.iter().position(|keywords| keywords.contains(&key)) {
+                Some(index) => index,
+                None => return Err(invalid(&format!("unknown keyword {}", key), path)),
+            };
+
+            if form.is_some_and(|form| form != index) {
+                return Err(invalid("keywords of more than one form", path));
+            }
+
+            form = Some(index);
+        }
+
+        match object.get("nullable") {
+            None | Some(&JsonValue::Boolean(_)) => {},
+            _ => return Err(invalid("nullable that is not a boolean", path)),
+        }
+
+        match object.get("metadata") {
+            None | Some(&JsonValue::Object(_)) => {},
+            _ => return Err(invalid("metadata that is not an object", path)),
+        }
+
+        let form = match form {
+            None    => Form::Empty,
+            Some(0) => self.reference(&schema["ref"], path)?,
+            Some(1) => {
+                let kind = schema["type"].as_str().and_then(Type::from_name);
+
+                Form::Type(kind.ok_or_else(|| invalid("unknown type", path))?)
+            },
+            Some(2) => self.enumeration(&schema["enum"], path)?,
+            Some(3) => Form::Elements(Box::new(self.node(&schema["elements"], &push_key(path, "elements"))?)),
+            Some(4) => self.properties(object, path)?,
+            Some(5) => Form::Values(Box::new(self.node(&schema["values"], &push_key(path, "values"))?)),
+            _       => self.discriminator(object, path)?,
+        };
+
+        Ok(Node {
+            form,
+            nullable: schema["nullable"].as_bool().unwrap_or(false),
+        })
+    }
+
+    fn reference(&self, name: &JsonValue, path: &JsonPath) -> Result<Form> {
+        match name.as_str() {
+            Some(name) if self.names.iter().any(|known| known == name) => Ok(Form::Ref(name.into())),
+            Some(name) => Err(invalid(&format!("reference to missing definition {}", name), path)),
+            None => Err(invalid("ref that is not a string", path)),
+        }
+    }
+
+    fn enumeration(&self, variants: &JsonValue, path: &JsonPath) -> Result<Form> {
+        let mut strings: Vec<String> = Vec::new();
+
+        for variant in variants.members() {
+            match variant.as_str() {
+                Some(variant) if strings.iter().any(|string| string == variant) => {
+                    return Err(invalid(&format!("repeated enum value {}", variant), path));
+                },
+                Some(variant) => strings.push(variant.into()),
+                None => return Err(invalid("enum value that is not a string", path)),
+            }
+        }
+
+        if !variants.is_array() || strings.is_empty() {
+            return Err(invalid("enum that is not a non-empty array", path));
+        }
+
+        Ok(Form::Enum(strings))
+    }
+
+    fn properties(&self, object: &Object, path: &JsonPath) -> Result<Form> {
+        let mut lists = Vec::with_capacity(2);
+
+        for &keyword in &["properties", "optionalProperties"] {
+            let path = push_key(path, keyword);
+            let mut list: Vec<(String, Node)> = Vec::new();
+
+            match object.get(keyword) {
+                Some(JsonValue::Object(properties)) => {
+                    for (key, property) in properties.iter() {
+                        list.push((key.clone(), self.node(property, &push_key(&path, key))?));
+                    }
+                },
+                Some(_) => return Err(invalid(&format!("{} that is not an object", keyword), &path)),
+                None => {},
+            }
+
+            lists.push(list);
+        }
+
+        let optional = lists.pop().unwrap_or_default();
+        let required = lists.pop().unwrap_or_default();
+
+        if let Some((key, _)) = required.iter().find(|(key, _)| optional.iter().any(|(other, _)| other == key)) {
+            return Err(invalid(&format!("property {} that is both required and optional", key), path));
+        }
+
+        let additional = match object.get("additionalProperties") {
+            Some(&JsonValue::Boolean(additional)) => additional,
+            None => false,
+            Some(_) => return Err(invalid("additionalProperties that is not a boolean", path)),
+        };
+
+        let has_required = object.get("properties").is_some();
+
+        if !has_required && object.get("optionalProperties").is_none() {
+            return Err(invalid("additionalProperties without properties", path));
+        }
+
+        Ok(Form::Properties { required, optional, additional, has_required })
+    }
+
+    fn discriminator(&self, object: &Object, path: &JsonPath) -> Result<Form> {
+        let tag = match object.get("discriminator").and_then(JsonValue::as_str) {
+            Some(tag) => tag.to_owned(),
+            None => return Err(invalid("discriminator that is not a string", path)),
+        };
+
+        let variants = match object.get("mapping") {
+            Some(JsonValue::Object(variants)) => variants,
+            _ => return Err(invalid("mapping that is not an object", path)),
+        };
+
+        let path = push_key(path, "mapping");
+        let mut mapping = Vec::with_capacity(variants.len());
+
+        for (name, variant) in variants.iter() {
+            let path = push_key(&path, name);
+            let node = self.node(variant, &path)?;
+
+            match node.form {
+                Form::Properties { ref required, ref optional, .. } if !node.nullable => {
+                    if required.iter().chain(optional).any(|(key, _)| *key == tag) {
+                        return Err(invalid(&format!("mapping defining the discriminator {}", tag), &path));
+                    }
+                },
+                _ => return Err(invalid("mapping that is not a non-nullable properties form", &path)),
+            }
+
+            mapping.push((name.clone(), node));
+        }
+
+        Ok(Form::Discriminator { tag, mapping })
+    }
+}
@@ -0,0 +1,153 @@
+//! Objects whose values are parsed on first access.
+//!
+//! `LazyObject::parse` only reads the keys of a top level object, and skips
+//! over the values, remembering where each one is in the source. A value is
+//! parsed the first time it's accessed and kept from then on. When only a
+//! few fields of a large document are needed, the rest is never parsed.
+//!
+//! ```
+//! use json::lazy::LazyObject;
+//!
+//! let source = r#"{ "id": 7, "payload": { "large": [1, 2, 3] }, "name": "Jörg" }"#;
+//! let object = LazyObject::parse(source).unwrap();
+//!
+//! assert_eq!(object.len(), 3);
+//! assert_eq!(object.raw("payload"), Some(r#"{ "large": [1, 2, 3] }"#));
+//!
+//! assert_eq!(object.get("id").unwrap().unwrap(), 7);
+//! assert_eq!(object.get("name").unwrap().unwrap(), "Jörg");
+//! assert_eq!(object.get("missing").unwrap(), None);
+//! ```
+//!
+//! Skipping a value only checks that its strings are terminated and its
+//! brackets balanced, so a malformed value is only reported once it's
+//! accessed, with the line and column relative to the start of the value.
+
+use std::borrow::Cow;
+use std::cell::OnceCell;
+use prelude::*;
+
+use object::Object;
+use { parser, JsonValue, Result };
+
+/// Top level object of a document, with its values parsed on first access.
+/// See the `lazy` module.
+///
+/// Keys appearing more than once are all kept, and lookups find the last of
+/// them, which is the value `json::parse` keeps as well.
+#[derive(Debug, Clone)]
+pub struct LazyObject<'a> {
+    entries: Vec<(Cow<'a, str>, LazyEntry<'a>)>,
+}
+
+#[derive(Debug, Clone)]
+struct LazyEntry<'a> {
+    source: &'a str,
+    value: OnceCell<JsonValue>,
+}
+
+impl<'a> LazyEntry<'a> {
+    fn value(&self) -> Result<&JsonValue> {
+        if let Some(value) = self.value.get() {
+            return Ok(value);
+        }
+
+        let value = parser::parse(self.source)?;
+
+        Ok(self.value.get_or_init(|| value))
+    }
+}
+
+impl<'a> LazyObject<'a> {
+    /// Read the keys of the object in `source`, skipping over the values.
+    /// Will return an error if the source is not a JSON object, or one of
+    /// its values has an unterminated string or unbalanced brackets.
+    pub fn parse(source: &'a str) -> Result<Self> {
+        let entries = parser::parse_lazy_entries(source)?;
+
+        Ok(LazyObject {
+            entries: entries.into_iter().map(|(key, source)| {
+                (key, LazyEntry { source, value: OnceCell::new() })
+            }).collect()
+        })
+    }
+
+    fn entry(&self, key: &str) -> Option<&LazyEntry<'a>> {
+        self.entries.iter().rev().find(|entry| entry.0 == key).map(|entry| &entry.1)
+    }
+
+    /// Get the value of `key`, parsing it if it's accessed for the first
+    /// time. Returns `Ok(None)` if there is no such key, and an error if
+    /// the value is malformed.
+    pub fn get(&self, key: &str) -> Result<Option<&JsonValue>> {
+        match self.entry(key) {
+            Some(entry) => entry.value().map(Some),
+            None        => Ok(None),
+        }
+    }
+
+    /// Get the value of `key` as another `LazyObject`, without parsing the
+    /// values within it either. Returns `Ok(None)` if there is no such key,
+    /// and an error if the value is not an object.
+    ///
+    /// ```
+    /// use json::lazy::LazyObject;
+    ///
+    /// let object = LazyObject::parse(r#"{ "user": { "id": 1, "posts": [] } }"#).unwrap();
+    /// let user = object.get_object("user").unwrap().unwrap();
+    ///
+    /// assert_eq!(user.raw("posts"), Some("[]"));
+    /// ```
+    pub fn get_object(&self, key: &str) -> Result<Option<LazyObject<'a>>> {
+        match self.entry(key) {
+            Some(entry) => LazyObject::parse(entry.source).map(Some),
+            None        => Ok(None),
+        }
+    }
+
+    /// Get the source text of the value of `key`, without parsing it.
+    pub fn raw(&self, key: &str) -> Option<&'a str> {
+        self.entry(key).map(|entry| entry.source)
+    }
+
+    /// Checks whether the object has `key`.
+    pub fn has_key(&self, key: &str) -> bool {
+        self.entry(key).is_some()
+    }
+
+    /// Whether the value of `key` has been parsed already.
+    pub fn is_parsed(&self, key: &str) -> bool {
+        self.entry(key).is_some_and(|entry| entry.value.get().is_some())
+    }
+
+    /// Number of entries, including keys appearing more than once.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over the keys, in the order of the source.
+    pub fn keys<'v>(&'v self) -> impl Iterator<Item = &'v str> + 'v {
+        self.entries.iter().map(|entry| &*entry.0)
+    }
+
+    /// Parse all values that weren't parsed yet, producing a regular
+    /// `JsonValue::Object`.
+    pub fn into_value(self) -> Result<JsonValue> {
+        let mut object = Object::with_capacity(self.entries.len());
+
+        for (key, entry) in self.entries {
+            let value = match entry.value.into_inner() {
+                Some(value) => value,
+                None        => parser::parse(entry.source)?,
+            };
+
+            object.insert(&key, value);
+        }
+
+        Ok(JsonValue::Object(object))
+    }
+}
@@ -196,17 +196,68 @@
 //! # }
 //! ```
 
-extern crate indexmap;
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
 #[macro_use]
-extern crate lazy_static;
+extern crate alloc;
+
+extern crate indexmap;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "async")]
+extern crate futures_io;
+#[cfg(feature = "chrono")]
+extern crate chrono;
+#[cfg(feature = "uuid")]
+extern crate uuid;
+#[cfg(feature = "rust_decimal")]
+extern crate rust_decimal;
+#[cfg(feature = "yaml")]
+extern crate yaml_rust;
+#[cfg(feature = "toml")]
+extern crate toml as toml_rs;
+#[cfg(feature = "xml")]
+extern crate xml as xml_rs;
+#[cfg(feature = "hash_object")]
+extern crate ahash;
+
+// Without the `std` feature, paths starting with `std` resolve to a module
+// re-exporting `core` and `alloc` instead.
+#[cfg(not(feature = "std"))]
+#[path = "no_std.rs"]
+mod std;
+
+// Items of the standard prelude that come from `alloc` without the `std`
+// feature.
+#[allow(unused_imports)]
+mod prelude {
+    pub use std::borrow::ToOwned;
+    pub use std::boxed::Box;
+    pub use std::string::{ String, ToString };
+    pub use std::vec::Vec;
+}
 
 use std::result;
+use prelude::*;
 
 pub mod codegen;
 mod parser;
 mod value;
 mod error;
 mod util;
+mod raw;
+#[cfg(feature = "simd")]
+mod simd;
+mod convert;
+#[cfg(feature = "serde")]
+mod serde_impl;
+#[cfg(feature = "chrono")]
+mod chrono_impl;
+#[cfg(feature = "uuid")]
+mod uuid_impl;
+#[cfg(feature = "rust_decimal")]
+mod decimal_impl;
 
 pub mod short;
 
@@ -216,9 +267,63 @@ pub mod object;
 
 pub mod number;
 
-pub use error::Error;
-pub use value::JsonValue;
+pub mod ops;
+
+#[cfg(feature = "std")]
+pub mod ndjson;
+
+#[cfg(feature = "std")]
+pub mod json_seq;
+
+pub mod borrowed;
+
+pub mod lazy;
+
+pub mod patch;
+
+pub mod schema;
+
+pub mod jtd;
+
+#[cfg(feature = "std")]
+pub mod stream;
+
+#[cfg(feature = "json5")]
+pub mod json5;
+
+#[cfg(feature = "msgpack")]
+pub mod msgpack;
+
+#[cfg(feature = "yaml")]
+pub mod yaml;
+
+#[cfg(feature = "toml")]
+pub mod toml;
+
+#[cfg(feature = "csv")]
+pub mod csv;
+
+#[cfg(feature = "xml")]
+pub mod xml;
+
+mod pool;
+mod recovery;
+mod path;
+mod stats;
+mod diff;
+mod content_hash;
+
+#[cfg(feature = "async")]
+mod async_read;
+
+#[macro_use]
+pub mod testing;
+
+pub use error::{ Error, ErrorCategory, SizeLimit };
+pub use value::{ JsonValue, JsonString, CompactOptions, PruneOptions, MergeStrategy, ArrayMerge, NullMerge };
 pub use value::JsonValue::Null;
+pub use raw::RawJson;
+pub use convert::{ ToJson, FromJson, FromJsonError };
 
 /// Result type used by this crate.
 ///
@@ -228,6 +333,7 @@ pub use value::JsonValue::Null;
 pub type Result<T> = result::Result<T, Error>;
 
 pub mod iterators {
+    use prelude::*;
     use indexmap::map::{
         IterMut as ObjectIterMut,
         Iter as ObjectIter
@@ -243,6 +349,8 @@ pub mod iterators {
     pub type Entries<'a> = ObjectIter<'a, String, super::JsonValue>;
 
     pub use value::EntriesMut;
+
+    pub use path::Walk;
 }
 
 #[deprecated(since="0.9.0", note="use `json::Error` instead")]
@@ -251,8 +359,40 @@ pub use Error as JsonError;
 #[deprecated(since="0.9.0", note="use `json::Result` instead")]
 pub use Result as JsonResult;
 
-pub use parser::parse;
+pub use parser::{ parse, parse_with_options, parse_bytes, ParseOptions, DuplicateKeys };
+pub use parser::{ parse_borrowed, parse_borrowed_with_options };
+pub use parser::{ validate, validate_with_options };
+pub use borrowed::JsonValueRef;
 
+pub use codegen::StringifyOptions;
+
+// Stand-in for `std::io::Write` without the `std` feature, for writing JSON
+// into custom sinks.
+#[cfg(not(feature = "std"))]
+pub use std::io;
+
+pub use recovery::parse_with_recovery;
+
+#[cfg(feature = "std")]
+pub use ndjson::shard;
+
+pub use pool::{ JsonPool, Parser };
+
+pub use path::{ JsonPath, PathSegment, Visit, Visitor };
+
+pub use stats::Stats;
+
+pub use diff::{ diff_report, Difference };
+
+#[cfg(feature = "async")]
+pub use async_read::{ parse_from_async_reader, ParseAsyncReader };
+
+/// Members of `JsonValue::Array`.
+///
+/// Arrays are always `Vec`s. Storing a few members inline, like `smallvec`
+/// does, isn't possible: the members would be part of the `JsonValue`
+/// holding the array, making it a type of infinite size. Empty arrays don't
+/// allocate, and `JsonPool` can recycle the allocations of small ones.
 pub type Array = Vec<JsonValue>;
 
 /// Convenience for `JsonValue::from(value)`
@@ -260,6 +400,63 @@ pub fn from<T>(value: T) -> JsonValue where T: Into<JsonValue> {
     value.into()
 }
 
+/// Parse `source` and convert the result into `T` in one go. Conversion
+/// failures are reported as `Error::FromJson`.
+///
+/// ```
+/// let scores: Vec<u32> = json::parse_into("[10, 20, 30]").unwrap();
+///
+/// assert_eq!(scores, [10, 20, 30]);
+///
+/// let error = json::parse_into::<Vec<u32>>("[10, -20]").unwrap_err();
+///
+/// assert_eq!(error.to_string(), r#"Expected u32, found number at "/1""#);
+/// ```
+///
+/// If `T` lists the keys it reads with `FromJson::fields`, the values of
+/// other keys of a top level object are only checked to be valid JSON,
+/// without building them:
+///
+/// ```
+/// use json::{ FromJson, FromJsonError, JsonValue };
+///
+/// struct User {
+///     id: u64,
+///     name: String,
+/// }
+///
+/// impl FromJson for User {
+///     fn from_json(value: &JsonValue) -> Result<Self, FromJsonError> {
+///         Ok(User {
+///             id: u64::from_json(&value["id"])?,
+///             name: String::from_json(&value["name"])?,
+///         })
+///     }
+///
+///     fn fields() -> Option<&'static [&'static str]> {
+///         Some(&["id", "name"])
+///     }
+/// }
+///
+/// let user: User = json::parse_into(r#"{"id":7,"posts":[{"title":"Hi"}],"name":"Ann"}"#).unwrap();
+///
+/// assert_eq!(user.id, 7);
+/// assert_eq!(user.name, "Ann");
+///
+/// assert!(json::parse_into::<User>(r#"{"id":7,"posts":[,],"name":"Ann"}"#).is_err());
+/// ```
+///
+/// Otherwise the source is parsed into a `JsonValue` in full before the
+/// conversion.
+pub fn parse_into<T: FromJson>(source: &str) -> Result<T> {
+    let value = match T::fields() {
+        Some(fields) => parser::parse_fields(source, fields)?,
+        None         => parse(source)?,
+    };
+
+    Ok(T::from_json(&value)?)
+}
+
 /// Pretty prints out the value as JSON string.
 pub fn stringify<T>(root: T) -> String where T: Into<JsonValue> {
     let root: JsonValue = root.into();
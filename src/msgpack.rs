@@ -0,0 +1,330 @@
+//! Encoding of `JsonValue`s as [MessagePack](https://msgpack.org/), enabled
+//! with the `msgpack` feature. MessagePack is a binary format with the same
+//! data model as JSON, taking less space and less time to decode.
+//!
+//! Values are mapped onto MessagePack types as follows:
+//!
+//! - numbers that are integers within the range of `i64` or `u64` use the
+//!   smallest integer type that fits them, all other numbers are encoded as
+//!   64-bit floats, which can lose precision the same way converting them
+//!   to `f64` does,
+//! - strings, arrays and objects use the smallest type that fits their
+//!   length, with object keys encoded as strings,
+//! - `null` and booleans use the nil and boolean types.
+//!
+//! Decoding accepts all the types above, as well as 32-bit floats. Binary
+//! data, extension types and objects with keys that are not strings have no
+//! JSON equivalent, and fail with `Error::InvalidMsgpack`.
+
+use std::io::{ self, Write };
+use std::str;
+use prelude::*;
+
+use number::Number;
+use object::Object;
+use { value, JsonValue, Error, Result };
+
+// How many nested Objects/Arrays are allowed to be decoded, same as with
+// `json::parse`.
+const DEPTH_LIMIT: usize = 512;
+
+/// Encode `value` as MessagePack.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// let data = object!{ "id" => 1, "tags" => array!["a"] };
+/// let bytes = json::msgpack::to_vec(&data);
+///
+/// assert_eq!(bytes, b"\x82\xa2id\x01\xa4tags\x91\xa1a");
+/// assert_eq!(json::msgpack::from_slice(&bytes).unwrap(), data);
+/// # }
+/// ```
+pub fn to_vec(value: &JsonValue) -> Vec<u8> {
+    let mut buffer = Vec::new();
+
+    // Writing into a `Vec` can't fail.
+    let _ = write(value, &mut buffer);
+
+    buffer
+}
+
+/// Encode `value` as MessagePack, writing it into `writer`.
+pub fn write<W: Write>(value: &JsonValue, writer: &mut W) -> io::Result<()> {
+    match *value {
+        JsonValue::Null               => writer.write_all(&[0xc0]),
+        JsonValue::Boolean(false)     => writer.write_all(&[0xc2]),
+        JsonValue::Boolean(true)      => writer.write_all(&[0xc3]),
+        JsonValue::Short(ref short)   => write_str(short, writer),
+        JsonValue::String(ref string) => write_str(string, writer),
+        JsonValue::Number(number)     => write_number(number, writer),
+        JsonValue::Array(ref vec)     => {
+            write_length(vec.len(), 0x90, 0xdc, writer)?;
+
+            for member in vec {
+                write(member, writer)?;
+            }
+
+            Ok(())
+        },
+        JsonValue::Object(ref object) => {
+            write_length(object.len(), 0x80, 0xde, writer)?;
+
+            for (key, member) in object.iter() {
+                write_str(key, writer)?;
+                write(member, writer)?;
+            }
+
+            Ok(())
+        },
+        JsonValue::Raw(ref raw)       => match raw.value() {
+            Some(value) => write(&value, writer),
+            None        => Err(io::Error::new(io::ErrorKind::InvalidData, "raw JSON text is invalid")),
+        },
+    }
+}
+
+// Write the header of an array or a map of `len` members, using the fixed
+// type starting at `fixed` if it fits, or the 16-bit type `marker` and the
+// 32-bit type right after it.
+fn write_length<W: Write>(len: usize, fixed: u8, marker: u8, writer: &mut W) -> io::Result<()> {
+    if len < 16 {
+        writer.write_all(&[fixed | len as u8])
+    } else if len <= u16::MAX as usize {
+        writer.write_all(&[marker])?;
+        writer.write_all(&(len as u16).to_be_bytes())
+    } else {
+        writer.write_all(&[marker + 1])?;
+        writer.write_all(&(len as u32).to_be_bytes())
+    }
+}
+
+fn write_str<W: Write>(string: &str, writer: &mut W) -> io::Result<()> {
+    let len = string.len();
+
+    if len < 32 {
+        writer.write_all(&[0xa0 | len as u8])?;
+    } else if len <= u8::MAX as usize {
+        writer.write_all(&[0xd9, len as u8])?;
+    } else if len <= u16::MAX as usize {
+        writer.write_all(&[0xda])?;
+        writer.write_all(&(len as u16).to_be_bytes())?;
+    } else {
+        writer.write_all(&[0xdb])?;
+        writer.write_all(&(len as u32).to_be_bytes())?;
+    }
+
+    writer.write_all(string.as_bytes())
+}
+
+fn write_number<W: Write>(number: Number, writer: &mut W) -> io::Result<()> {
+    match number.as_i128() {
+        Some(int @ 0..=127) => writer.write_all(&[int as u8]),
+        Some(int @ -32..=-1) => writer.write_all(&[int as i8 as u8]),
+        Some(int @ 0..=0xff) => writer.write_all(&[0xcc, int as u8]),
+        Some(int @ 0..=0xffff) => {
+            writer.write_all(&[0xcd])?;
+            writer.write_all(&(int as u16).to_be_bytes())
+        },
+        Some(int @ 0..=0xffff_ffff) => {
+            writer.write_all(&[0xce])?;
+            writer.write_all(&(int as u32).to_be_bytes())
+        },
+        Some(int @ 0..) if int <= u64::MAX as i128 => {
+            writer.write_all(&[0xcf])?;
+            writer.write_all(&(int as u64).to_be_bytes())
+        },
+        Some(int @ ..=-1) if int >= i8::MIN as i128 => writer.write_all(&[0xd0, int as i8 as u8]),
+        Some(int @ ..=-1) if int >= i16::MIN as i128 => {
+            writer.write_all(&[0xd1])?;
+            writer.write_all(&(int as i16).to_be_bytes())
+        },
+        Some(int @ ..=-1) if int >= i32::MIN as i128 => {
+            writer.write_all(&[0xd2])?;
+            writer.write_all(&(int as i32).to_be_bytes())
+        },
+        Some(int @ ..=-1) if int >= i64::MIN as i128 => {
+            writer.write_all(&[0xd3])?;
+            writer.write_all(&(int as i64).to_be_bytes())
+        },
+        _ => {
+            writer.write_all(&[0xcb])?;
+            writer.write_all(&f64::from(number).to_bits().to_be_bytes())
+        }
+    }
+}
+
+/// Decode a single MessagePack value from `bytes`. All of `bytes` must be
+/// taken by the value.
+pub fn from_slice(bytes: &[u8]) -> Result<JsonValue> {
+    let mut decoder = Decoder {
+        bytes,
+        index: 0,
+        depth: 0,
+    };
+
+    let value = decoder.decode()?;
+
+    if decoder.index != bytes.len() {
+        return Err(decoder.invalid("trailing bytes"));
+    }
+
+    Ok(value)
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    index: usize,
+    depth: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn invalid(&self, reason: &str) -> Error {
+        Error::InvalidMsgpack(format!("{} at byte {}", reason, self.index))
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.bytes.len() - self.index < len {
+            return Err(Error::UnexpectedEndOfJson);
+        }
+
+        let taken = &self.bytes[self.index..self.index + len];
+        self.index += len;
+
+        Ok(taken)
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_u64(&mut self) -> Result<u64> {
+        let high = self.read_u32()? as u64;
+        let low = self.read_u32()? as u64;
+        Ok(high << 32 | low)
+    }
+
+    fn decode(&mut self) -> Result<JsonValue> {
+        let marker = self.read_u8()?;
+
+        // Containers are handled here, while scalars are decoded separately
+        // to keep this recursive function small.
+        let len = match marker {
+            0x80 ..= 0x9f => (marker & 0x0f) as usize,
+            0xdc | 0xde   => self.read_u16()? as usize,
+            0xdd | 0xdf   => self.read_u32()? as usize,
+            _             => return self.decode_scalar(marker),
+        };
+
+        match marker {
+            0x80 ..= 0x8f | 0xde | 0xdf => self.decode_map(len),
+            _                           => self.decode_array(len),
+        }
+    }
+
+    fn decode_scalar(&mut self, marker: u8) -> Result<JsonValue> {
+        Ok(match marker {
+            0x00 ..= 0x7f => (marker as u64).into(),
+            0xa0 ..= 0xbf => self.decode_str((marker & 0x1f) as usize)?,
+            0xc0          => JsonValue::Null,
+            0xc2          => JsonValue::Boolean(false),
+            0xc3          => JsonValue::Boolean(true),
+            0xca          => f32::from_bits(self.read_u32()?).into(),
+            0xcb          => f64::from_bits(self.read_u64()?).into(),
+            0xcc          => self.read_u8()?.into(),
+            0xcd          => self.read_u16()?.into(),
+            0xce          => self.read_u32()?.into(),
+            0xcf          => self.read_u64()?.into(),
+            0xd0          => (self.read_u8()? as i8).into(),
+            0xd1          => (self.read_u16()? as i16).into(),
+            0xd2          => (self.read_u32()? as i32).into(),
+            0xd3          => (self.read_u64()? as i64).into(),
+            0xd9          => {
+                let len = self.read_u8()? as usize;
+                self.decode_str(len)?
+            },
+            0xda          => {
+                let len = self.read_u16()? as usize;
+                self.decode_str(len)?
+            },
+            0xdb          => {
+                let len = self.read_u32()? as usize;
+                self.decode_str(len)?
+            },
+            0xe0 ..= 0xff => (marker as i8).into(),
+            _             => {
+                self.index -= 1;
+                return Err(self.invalid(&format!("unsupported type 0x{:02x}", marker)));
+            }
+        })
+    }
+
+    fn decode_str(&mut self, len: usize) -> Result<JsonValue> {
+        let bytes = self.take(len)?;
+
+        str::from_utf8(bytes).map(JsonValue::from).map_err(|_| Error::FailedUtf8Parsing)
+    }
+
+    fn enter(&mut self) -> Result<()> {
+        if self.depth == DEPTH_LIMIT {
+            return Err(Error::ExceededDepthLimit);
+        }
+
+        self.depth += 1;
+
+        Ok(())
+    }
+
+    fn decode_array(&mut self, len: usize) -> Result<JsonValue> {
+        self.enter()?;
+
+        // Every member takes at least one byte, which keeps a forged length
+        // from allocating more than the input could hold.
+        let mut array = Vec::with_capacity(len.min(self.bytes.len() - self.index));
+
+        for _ in 0..len {
+            array.push(self.decode()?);
+        }
+
+        self.depth -= 1;
+
+        Ok(JsonValue::Array(array))
+    }
+
+    fn decode_map(&mut self, len: usize) -> Result<JsonValue> {
+        self.enter()?;
+
+        let mut object = Object::with_capacity(len.min(self.bytes.len() - self.index));
+
+        for _ in 0..len {
+            let at = self.index;
+
+            let key = match self.decode()? {
+                JsonValue::Short(key)  => key.as_str().to_owned(),
+                JsonValue::String(key) => value::into_string(key),
+                _ => {
+                    self.index = at;
+                    return Err(self.invalid("key that is not a string"));
+                }
+            };
+
+            let value = self.decode()?;
+
+            object.insert_owned(key, value);
+        }
+
+        self.depth -= 1;
+
+        Ok(JsonValue::Object(object))
+    }
+}
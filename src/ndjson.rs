@@ -0,0 +1,487 @@
+//! Support for [newline delimited JSON](http://ndjson.org/), also known as
+//! JSON Lines, where every line of the input is a separate JSON document.
+
+use std::io::{ self, BufRead, Write };
+use std::str;
+
+use number::Number;
+use { parse, JsonValue, Error, Result };
+
+/// An aggregate computed by `json::ndjson::aggregate`. All variants other
+/// than `Count` take a JSON Pointer (RFC 6901) that is resolved against
+/// every record, such as `"/amount"`. Records where the pointer doesn't
+/// resolve to a number are ignored by those aggregates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Agg<'a> {
+    /// Number of records.
+    Count,
+
+    /// Sum of all numbers found, computed in `f64` precision.
+    Sum(&'a str),
+
+    /// Smallest number found, or `null` if there were none.
+    Min(&'a str),
+
+    /// Largest number found, or `null` if there were none.
+    Max(&'a str),
+
+    /// Arithmetic mean of all numbers found, or `null` if there were none.
+    Mean(&'a str),
+}
+
+// Running state of a single aggregate.
+enum Accumulator {
+    Count(u64),
+    Sum(f64),
+    Min(Option<Number>),
+    Max(Option<Number>),
+    Mean(f64, u64),
+}
+
+impl Accumulator {
+    fn new(agg: &Agg) -> Self {
+        match *agg {
+            Agg::Count   => Accumulator::Count(0),
+            Agg::Sum(_)  => Accumulator::Sum(0.0),
+            Agg::Min(_)  => Accumulator::Min(None),
+            Agg::Max(_)  => Accumulator::Max(None),
+            Agg::Mean(_) => Accumulator::Mean(0.0, 0),
+        }
+    }
+
+    fn update(&mut self, agg: &Agg, record: &JsonValue) {
+        if let Accumulator::Count(ref mut count) = *self {
+            *count += 1;
+            return;
+        }
+
+        let pointer = match *agg {
+            Agg::Count => return,
+            Agg::Sum(pointer) | Agg::Min(pointer) |
+            Agg::Max(pointer) | Agg::Mean(pointer) => pointer,
+        };
+
+        let number = match record.pointer(pointer) {
+            Some(&JsonValue::Number(number)) if !number.is_nan() => number,
+            _ => return,
+        };
+
+        match *self {
+            Accumulator::Count(_) => {},
+            Accumulator::Sum(ref mut sum) => *sum += f64::from(number),
+            Accumulator::Min(ref mut min) => match *min {
+                Some(current) if f64::from(current) <= f64::from(number) => {},
+                _ => *min = Some(number),
+            },
+            Accumulator::Max(ref mut max) => match *max {
+                Some(current) if f64::from(current) >= f64::from(number) => {},
+                _ => *max = Some(number),
+            },
+            Accumulator::Mean(ref mut sum, ref mut count) => {
+                *sum += f64::from(number);
+                *count += 1;
+            }
+        }
+    }
+
+    fn finish(self) -> JsonValue {
+        match self {
+            Accumulator::Count(count) => count.into(),
+            Accumulator::Sum(sum)     => sum.into(),
+            Accumulator::Min(min)     => min.into(),
+            Accumulator::Max(max)     => max.into(),
+            Accumulator::Mean(_, 0)   => JsonValue::Null,
+            Accumulator::Mean(sum, count) => (sum / count as f64).into(),
+        }
+    }
+}
+
+/// Compute aggregates over newline delimited JSON in a single streaming
+/// pass. Only one record is held in memory at any time, so the input can be
+/// arbitrarily large. Empty lines are skipped.
+///
+/// Returns one value per requested aggregate, in the same order.
+///
+/// ```
+/// use json::ndjson::{ aggregate, Agg };
+///
+/// let input = "{\"amount\":10}\n{\"amount\":32}\n{\"other\":true}\n";
+///
+/// let result = aggregate(input.as_bytes(), &[
+///     Agg::Count,
+///     Agg::Sum("/amount"),
+///     Agg::Max("/amount"),
+/// ]).unwrap();
+///
+/// assert_eq!(result[0], 3);
+/// assert_eq!(result[1], 42);
+/// assert_eq!(result[2], 32);
+/// ```
+pub fn aggregate<R: BufRead>(reader: R, aggs: &[Agg]) -> Result<Vec<JsonValue>> {
+    let mut accumulators: Vec<Accumulator> = aggs.iter().map(Accumulator::new).collect();
+
+    for record in read(reader) {
+        let record = record?;
+
+        for (accumulator, agg) in accumulators.iter_mut().zip(aggs) {
+            accumulator.update(agg, &record);
+        }
+    }
+
+    Ok(accumulators.into_iter().map(Accumulator::finish).collect())
+}
+
+/// Iterator over records of newline delimited JSON, returned by
+/// `json::ndjson::read`.
+#[derive(Debug)]
+pub struct Records<R: BufRead> {
+    reader: R,
+    buffer: Vec<u8>,
+    line: usize,
+}
+
+/// Parse newline delimited JSON from `reader` one record at a time. Empty
+/// lines are skipped.
+///
+/// Every line is parsed on its own, so a malformed line produces an error
+/// for that record only, and iteration can continue with the next line.
+/// Errors reading from `reader` itself end the iteration.
+///
+/// ```
+/// let input = "{\"level\":\"info\"}\n\n{\"level\":}\n{\"level\":\"warn\"}\n";
+///
+/// let mut records = json::ndjson::read(input.as_bytes());
+///
+/// assert_eq!(records.next().unwrap().unwrap()["level"], "info");
+/// assert!(records.next().unwrap().is_err());
+/// assert_eq!(records.line(), 3);
+/// assert_eq!(records.next().unwrap().unwrap()["level"], "warn");
+/// assert!(records.next().is_none());
+/// ```
+pub fn read<R: BufRead>(reader: R) -> Records<R> {
+    Records {
+        reader,
+        buffer: Vec::new(),
+        line: 0,
+    }
+}
+
+impl<R: BufRead> Records<R> {
+    /// Line number, starting at `1`, of the most recently returned record.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+impl<R: BufRead> Iterator for Records<R> {
+    type Item = Result<JsonValue>;
+
+    fn next(&mut self) -> Option<Result<JsonValue>> {
+        loop {
+            self.buffer.clear();
+
+            match self.reader.read_until(b'\n', &mut self.buffer) {
+                Ok(0)      => return None,
+                Ok(_)      => {},
+                Err(error) => return Some(Err(error.into())),
+            }
+
+            self.line += 1;
+
+            match parse_line(&self.buffer, self.line) {
+                Ok(Some(record)) => return Some(Ok(record)),
+                Ok(None)         => continue,
+                Err(error)       => return Some(Err(error)),
+            }
+        }
+    }
+}
+
+// Parse a single line of input, returning `None` for empty lines. Errors
+// pointing at a character are adjusted to report the line in the input.
+fn parse_line(bytes: &[u8], line: usize) -> Result<Option<JsonValue>> {
+    let source = str::from_utf8(bytes).map_err(|_| Error::FailedUtf8Parsing)?;
+
+    if source.trim().is_empty() {
+        return Ok(None);
+    }
+
+    match parse(source) {
+        Ok(value) => Ok(Some(value)),
+        Err(Error::UnexpectedCharacter { ch, column, .. }) => {
+            Err(Error::UnexpectedCharacter { ch, line, column })
+        },
+        Err(error) => Err(error),
+    }
+}
+
+/// How records are distributed among shards by `json::ndjson::shard`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShardBy {
+    /// Distribute records evenly among a fixed number of shards, in a round
+    /// robin fashion.
+    Count(usize),
+
+    /// Fill shards one after another, starting a new shard whenever the next
+    /// record would make the current one exceed the given number of bytes.
+    /// A record larger than the limit is written to a shard of its own.
+    MaxBytes(usize),
+}
+
+/// Split a JSON array read from `reader` into multiple newline delimited
+/// JSON shards, without ever building the whole array in memory. Only a
+/// single member of the array is parsed at a time.
+///
+/// Shards are created on demand by calling `sink_factory` with the index of
+/// the shard, starting at `0`. Returns the number of records written.
+///
+/// ```
+/// use json::ndjson::{ shard, ShardBy };
+///
+/// let input = r#"[{"id":1}, {"id":2}, {"id":3}]"#;
+/// let mut shards = vec![Vec::new(), Vec::new()];
+///
+/// {
+///     let mut iter = shards.iter_mut();
+///     let count = shard(input.as_bytes(), ShardBy::Count(2), |_| {
+///         Ok(iter.next().unwrap())
+///     }).unwrap();
+///
+///     assert_eq!(count, 3);
+/// }
+///
+/// assert_eq!(shards[0], b"{\"id\":1}\n{\"id\":3}\n");
+/// assert_eq!(shards[1], b"{\"id\":2}\n");
+/// ```
+pub fn shard<R, W, F>(reader: R, by: ShardBy, mut sink_factory: F) -> Result<usize>
+where R: BufRead, W: Write, F: FnMut(usize) -> io::Result<W> {
+    let mut elements = ArrayElements::new(reader);
+    let mut element = Vec::new();
+    let mut sinks: Vec<W> = Vec::new();
+    let mut written = 0;
+    let mut count = 0;
+
+    while elements.next_element(&mut element)? {
+        let record = elements.parse_element(&element)?;
+        let mut line = record.dump();
+        line.push('\n');
+
+        let sink = match by {
+            ShardBy::Count(shards) => {
+                let index = count % shards.max(1);
+
+                if index == sinks.len() {
+                    sinks.push(sink_factory(index)?);
+                }
+
+                &mut sinks[index]
+            },
+            ShardBy::MaxBytes(max) => {
+                if sinks.is_empty() || (written > 0 && written + line.len() > max) {
+                    if let Some(sink) = sinks.last_mut() {
+                        sink.flush()?;
+                    }
+
+                    let index = sinks.len();
+                    sinks.push(sink_factory(index)?);
+                    written = 0;
+                }
+
+                written += line.len();
+                sinks.last_mut().expect("Must have a sink")
+            }
+        };
+
+        sink.write_all(line.as_bytes())?;
+        count += 1;
+    }
+
+    for sink in sinks.iter_mut() {
+        sink.flush()?;
+    }
+
+    Ok(count)
+}
+
+// Splits a JSON array into the raw bytes of its members, tracking nesting
+// and strings just enough to find the commas separating the members.
+struct ArrayElements<R: BufRead> {
+    reader: R,
+    started: bool,
+    finished: bool,
+    line: usize,
+    column: usize,
+    element_line: usize,
+    element_column: usize,
+}
+
+impl<R: BufRead> ArrayElements<R> {
+    fn new(reader: R) -> Self {
+        ArrayElements {
+            reader,
+            started: false,
+            finished: false,
+            line: 1,
+            column: 0,
+            element_line: 1,
+            element_column: 1,
+        }
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>> {
+        Ok(self.reader.fill_buf()?.first().cloned())
+    }
+
+    fn bump(&mut self, ch: u8) {
+        self.reader.consume(1);
+
+        if ch == b'\n' {
+            self.line += 1;
+            self.column = 0;
+        } else if ch & 0xC0 != 0x80 {
+            self.column += 1;
+        }
+    }
+
+    fn next_byte(&mut self) -> Result<u8> {
+        match self.peek()? {
+            Some(ch) => {
+                self.bump(ch);
+                Ok(ch)
+            },
+            None => Err(Error::UnexpectedEndOfJson),
+        }
+    }
+
+    fn skip_whitespace(&mut self) -> Result<()> {
+        while let Some(ch) = self.peek()? {
+            match ch {
+                b' ' | b'\t' | b'\n' | b'\r' => self.bump(ch),
+                _ => break,
+            }
+        }
+        Ok(())
+    }
+
+    // Report the last consumed byte as unexpected. Multi-byte characters
+    // are never reported, as the scanner only ever rejects ASCII bytes.
+    fn unexpected_character<T>(&self, ch: u8) -> Result<T> {
+        Err(Error::UnexpectedCharacter {
+            ch: ch as char,
+            line: self.line,
+            column: self.column,
+        })
+    }
+
+    fn expect_end(&mut self) -> Result<()> {
+        self.skip_whitespace()?;
+
+        match self.peek()? {
+            None     => Ok(()),
+            Some(ch) => {
+                self.bump(ch);
+                self.unexpected_character(ch)
+            }
+        }
+    }
+
+    // Read raw bytes of the next member into `buffer`, returning `false`
+    // once the end of the array is reached.
+    fn next_element(&mut self, buffer: &mut Vec<u8>) -> Result<bool> {
+        buffer.clear();
+
+        if self.finished {
+            return Ok(false);
+        }
+
+        if !self.started {
+            self.skip_whitespace()?;
+
+            match self.next_byte()? {
+                b'[' => self.started = true,
+                ch   => return self.unexpected_character(ch),
+            }
+
+            self.skip_whitespace()?;
+
+            if self.peek()? == Some(b']') {
+                self.bump(b']');
+                self.finished = true;
+                self.expect_end()?;
+                return Ok(false);
+            }
+        }
+
+        self.skip_whitespace()?;
+        self.element_line = self.line;
+        self.element_column = self.column + 1;
+
+        let mut depth = 0usize;
+        let mut in_string = false;
+        let mut escaped = false;
+
+        loop {
+            let ch = self.next_byte()?;
+
+            if in_string {
+                if escaped {
+                    escaped = false;
+                } else if ch == b'\\' {
+                    escaped = true;
+                } else if ch == b'"' {
+                    in_string = false;
+                }
+                buffer.push(ch);
+                continue;
+            }
+
+            match ch {
+                b'"'        => in_string = true,
+                b'[' | b'{' => depth += 1,
+                b',' if depth == 0 => break,
+                b']' if depth == 0 => {
+                    self.finished = true;
+                    break;
+                },
+                b']' | b'}' => {
+                    if depth == 0 {
+                        return self.unexpected_character(ch);
+                    }
+                    depth -= 1;
+                },
+                _ => {}
+            }
+
+            buffer.push(ch);
+        }
+
+        // Empty member, as in `[1,,2]` or `[1,]`
+        if buffer.iter().all(|ch| ch.is_ascii_whitespace()) {
+            let ch = if self.finished { b']' } else { b',' };
+            return self.unexpected_character(ch);
+        }
+
+        if self.finished {
+            self.expect_end()?;
+        }
+
+        Ok(true)
+    }
+
+    // Parse raw bytes of a member, adjusting errors to point at the position
+    // in the whole input.
+    fn parse_element(&self, bytes: &[u8]) -> Result<JsonValue> {
+        let source = str::from_utf8(bytes).map_err(|_| Error::FailedUtf8Parsing)?;
+
+        match parse(source) {
+            Err(Error::UnexpectedCharacter { ch, line, column }) => {
+                Err(Error::UnexpectedCharacter {
+                    ch,
+                    line: self.element_line + line - 1,
+                    column: if line == 1 { self.element_column + column - 1 } else { column },
+                })
+            },
+            result => result,
+        }
+    }
+}
@@ -0,0 +1,202 @@
+// Stand-in for the parts of `std` this crate uses, built from `core` and
+// `alloc` when the `std` feature is disabled. It's mounted as `std` at the
+// root of the crate, so paths like `std::fmt` resolve here instead.
+
+pub use core::{ arch, cell, char, cmp, convert, error, f32, f64, hash, iter, mem, num, ops, ptr, result };
+pub use core::{ i8, i16, i32, i64, isize, u8, u16, u32, u64, usize };
+pub use alloc::{ borrow, boxed, collections, fmt, slice, str, string, sync, vec };
+
+/// Minimal replacement for `std::io`, providing the `Write` trait that
+/// generators write into. Only `Vec<u8>` implements it out of the box.
+pub mod io {
+    use core::{ fmt, result };
+    use core::ops::Deref;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    /// Result of I/O operations.
+    pub type Result<T> = result::Result<T, Error>;
+
+    /// Kind of an I/O `Error`, a subset of the one in `std`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum ErrorKind {
+        InvalidData,
+        InvalidInput,
+        Interrupted,
+        WriteZero,
+        UnexpectedEof,
+        Other,
+    }
+
+    /// Error of I/O operations.
+    #[derive(Debug)]
+    pub struct Error {
+        kind: ErrorKind,
+        message: String,
+    }
+
+    impl Error {
+        pub fn new<M: Into<String>>(kind: ErrorKind, message: M) -> Self {
+            Error {
+                kind,
+                message: message.into(),
+            }
+        }
+
+        pub fn other<M: Into<String>>(message: M) -> Self {
+            Error::new(ErrorKind::Other, message)
+        }
+
+        pub fn kind(&self) -> ErrorKind {
+            self.kind
+        }
+    }
+
+    impl From<ErrorKind> for Error {
+        fn from(kind: ErrorKind) -> Self {
+            Error::new(kind, format!("{:?}", kind))
+        }
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str(&self.message)
+        }
+    }
+
+    impl ::core::error::Error for Error {}
+
+    /// Buffer passed to `Write::write_vectored`.
+    #[derive(Debug, Clone, Copy)]
+    pub struct IoSlice<'a>(&'a [u8]);
+
+    impl<'a> IoSlice<'a> {
+        pub fn new(buf: &'a [u8]) -> Self {
+            IoSlice(buf)
+        }
+
+        pub fn advance_slices(bufs: &mut &mut [IoSlice<'a>], n: usize) {
+            let mut remove = 0;
+            let mut left = n;
+
+            for buf in bufs.iter() {
+                match left.checked_sub(buf.len()) {
+                    Some(rest) => left = rest,
+                    None       => break,
+                }
+                remove += 1;
+            }
+
+            *bufs = &mut ::core::mem::take(bufs)[remove..];
+
+            if let Some(first) = bufs.first_mut() {
+                first.0 = &first.0[left..];
+            }
+        }
+    }
+
+    impl<'a> Deref for IoSlice<'a> {
+        type Target = [u8];
+
+        fn deref(&self) -> &[u8] {
+            self.0
+        }
+    }
+
+    /// Sink of bytes, with the same methods as `std::io::Write`.
+    pub trait Write {
+        fn write(&mut self, buf: &[u8]) -> Result<usize>;
+
+        fn flush(&mut self) -> Result<()>;
+
+        fn write_vectored(&mut self, bufs: &[IoSlice]) -> Result<usize> {
+            match bufs.iter().find(|buf| !buf.is_empty()) {
+                Some(buf) => self.write(buf),
+                None      => Ok(0),
+            }
+        }
+
+        fn write_all(&mut self, mut buf: &[u8]) -> Result<()> {
+            while !buf.is_empty() {
+                match self.write(buf) {
+                    Ok(0) => return Err(Error::new(ErrorKind::WriteZero, "failed to write whole buffer")),
+                    Ok(written) => buf = &buf[written..],
+                    Err(ref error) if error.kind() == ErrorKind::Interrupted => {},
+                    Err(error) => return Err(error),
+                }
+            }
+
+            Ok(())
+        }
+
+        fn write_fmt(&mut self, args: fmt::Arguments) -> Result<()> {
+            // Keeps the error of the writer, which `fmt::Write` can't carry.
+            struct Adapter<'a, W: ?Sized + 'a> {
+                writer: &'a mut W,
+                error: Option<Error>,
+            }
+
+            impl<'a, W: Write + ?Sized> fmt::Write for Adapter<'a, W> {
+                fn write_str(&mut self, string: &str) -> fmt::Result {
+                    self.writer.write_all(string.as_bytes()).map_err(|error| {
+                        self.error = Some(error);
+                        fmt::Error
+                    })
+                }
+            }
+
+            let mut adapter = Adapter { writer: self, error: None };
+
+            match fmt::write(&mut adapter, args) {
+                Ok(()) => Ok(()),
+                Err(_) => Err(adapter.error.unwrap_or_else(|| Error::other("formatter error"))),
+            }
+        }
+    }
+
+    impl<'a, W: Write + ?Sized> Write for &'a mut W {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            (**self).write(buf)
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            (**self).flush()
+        }
+
+        fn write_vectored(&mut self, bufs: &[IoSlice]) -> Result<usize> {
+            (**self).write_vectored(bufs)
+        }
+
+        fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+            (**self).write_all(buf)
+        }
+    }
+
+    impl Write for Vec<u8> {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            self.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        fn write_vectored(&mut self, bufs: &[IoSlice]) -> Result<usize> {
+            let len = bufs.iter().map(|buf| buf.len()).sum();
+
+            self.reserve(len);
+
+            for buf in bufs {
+                self.extend_from_slice(buf);
+            }
+
+            Ok(len)
+        }
+
+        fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+            self.extend_from_slice(buf);
+            Ok(())
+        }
+    }
+}
@@ -1,18 +1,42 @@
-use std::{ ops, fmt, f32, f64 };
+use std::{ ops, fmt, io, f32, f64 };
+use std::convert::TryFrom;
+use std::hash::{ Hash, Hasher };
 use std::num::FpCategory;
 use util::grisu2;
 use util::print_dec;
+use prelude::*;
+use Error;
 
 /// NaN value represented in `Number` type. NaN is equal to itself.
 pub const NAN: Number = Number {
     category: NAN_MASK,
     mantissa: 0,
+    format: PLAIN,
     exponent: 0
 };
 
+/// Positive infinity represented in `Number` type, produced only by parsing
+/// with `ParseOptions::non_finite_numbers`. Like NaN, it's serialized as
+/// `null`.
+pub const INFINITY: Number = Number {
+    category: FLOAT | POSITIVE,
+    exponent: 0,
+    mantissa: 0x7FF0_0000_0000_0000,
+    format: PLAIN,
+};
+
+/// Negative infinity represented in `Number` type, see `INFINITY`.
+pub const NEG_INFINITY: Number = Number {
+    category: FLOAT | NEGATIVE,
+    exponent: 0,
+    mantissa: 0x7FF0_0000_0000_0000,
+    format: PLAIN,
+};
+
 const NEGATIVE: u8 = 0;
 const POSITIVE: u8 = 1;
-const NAN_MASK: u8 = !1;
+const FLOAT: u8 = 2;
+const NAN_MASK: u8 = !3;
 
 /// Number representation used inside `JsonValue`. You can easily convert
 /// the `Number` type into native Rust number types and back, or use the
@@ -29,22 +53,98 @@ const NAN_MASK: u8 = !1;
 ///
 /// More often than not you will deal with `JsonValue::Number` variant that
 /// wraps around this type, instead of using the methods here directly.
+///
+/// ## Precision
+///
+/// `Number` is a small `Copy` type storing a `u64` decimal mantissa and an
+/// `i16` exponent, so it can hold up to 19 significant digits exactly.
+/// Parsing a number with more digits than that keeps as many leading digits
+/// as fit into the mantissa, and drops the rest:
+///
+/// ```
+/// # use json::number::Number;
+/// let big = json::parse("340282366920938463463374607431768211456").unwrap();
+///
+/// assert_eq!(big, Number::from_parts(true, 3402823669209384634, 20));
+/// assert_eq!(big.dump(), "3.402823669209384634e38");
+/// ```
+///
+/// Integers with up to 19 significant digits are exact, including when
+/// converted into `u128` and `i128` with `as_u128` and `as_i128`, which
+/// return `None` for numbers that had digits dropped. Parsing with
+/// `ParseOptions::big_integers_as_raw` keeps larger integers exactly, as
+/// a `JsonValue::Raw` holding their digits:
+///
+/// ```
+/// use json::{ JsonValue, ParseOptions };
+///
+/// let source = "[340282366920938463463374607431768211455]";
+/// let options = ParseOptions { big_integers_as_raw: true, ..ParseOptions::default() };
+/// let data = json::parse_with_options(source, options).unwrap();
+///
+/// assert_eq!(data.dump(), source);
+///
+/// if let JsonValue::Raw(ref raw) = data[0] {
+///     assert_eq!(raw.as_u128(), Some(u128::MAX));
+/// }
+/// ```
+///
+/// `ParseOptions::inexact_numbers_as_strings` keeps the digits of any
+/// number as a string instead, and `ParseOptions::exact_numbers` rejects
+/// such numbers.
 #[derive(Copy, Clone, Debug)]
 pub struct Number {
     // A byte describing the sign and NaN-ness of the number.
     //
     // category == 0 (NEGATIVE constant)         -> negative sign
     // category == 1 (POSITIVE constant)         -> positive sign
-    // category >  1 (matches NAN_MASK constant) -> NaN
+    // category &  2 (FLOAT constant)            -> bit-exact `f64`
+    // category >  3 (matches NAN_MASK constant) -> NaN
     category: u8,
 
     // Decimal exponent, analog to `e` notation in string form.
+    // Unused for bit-exact `f64`s.
     exponent: i16,
 
-    // Integer base before sing and exponent applied.
+    // Integer base before sing and exponent applied. For bit-exact
+    // `f64`s these are the raw bits of the absolute value.
     mantissa: u64,
+
+    // Layout of the number as it appeared in the source, for numbers parsed
+    // with `ParseOptions::preserve_number_text`. Fits in the padding.
+    format: Format,
+}
+
+// Flags of `Format`.
+const TEXT: u8 = 1;
+const EXPONENT: u8 = 2;
+const UPPERCASE: u8 = 4;
+const PLUS: u8 = 8;
+const MINUS: u8 = 16;
+const ROUNDED: u8 = 32;
+
+// Describes how the mantissa and exponent of a `Number` were written in the
+// source, which together with them is enough to reproduce the source text.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Format {
+    // Combination of `TEXT`, `EXPONENT`, `UPPERCASE`, `PLUS` and `MINUS`.
+    // Without the `TEXT` flag the number is printed as usual. `ROUNDED`
+    // marks numbers parsed with significant digits dropped.
+    flags: u8,
+
+    // Digits after the decimal point.
+    fraction_digits: u16,
+
+    // Leading zeroes of the `e` notation exponent.
+    exponent_zeroes: u8,
 }
 
+const PLAIN: Format = Format {
+    flags: 0,
+    fraction_digits: 0,
+    exponent_zeroes: 0,
+};
+
 impl Number {
     /// Construct a new `Number` from parts. This can't create a NaN value.
     ///
@@ -59,11 +159,12 @@ impl Number {
     /// THe goal of the 'unsafe' is to deter from using this method in favor of its safe equivalent
     /// `from_parts`, at least in context when the associated performance cost is negligible.
     #[inline]
-    pub unsafe fn from_parts_unchecked(positive: bool, mantissa: u64, exponent: i16) -> Self {
+    pub const unsafe fn from_parts_unchecked(positive: bool, mantissa: u64, exponent: i16) -> Self {
         Number {
             category: positive as u8,
             exponent: exponent,
             mantissa: mantissa,
+            format: PLAIN,
         }
     }
 
@@ -80,7 +181,7 @@ impl Number {
     /// assert_eq!(0, exponent);
     /// ```
     #[inline]
-    pub fn from_parts(positive: bool, mut mantissa: u64, mut exponent: i16) -> Self {
+    pub const fn from_parts(positive: bool, mut mantissa: u64, mut exponent: i16) -> Self {
         while exponent < 0 && mantissa % 10 == 0 {
             exponent += 1;
             mantissa /= 10;
@@ -88,6 +189,80 @@ impl Number {
         unsafe { Number::from_parts_unchecked(positive, mantissa, exponent) }
     }
 
+    /// Construct a new `Number` from an unsigned integer in a `const` context,
+    /// equivalent to `Number::from` otherwise. Together with `from_i64` and
+    /// `from_parts`, this allows `JsonValue::Number` to be used in `const`s
+    /// and `static`s.
+    ///
+    /// ```
+    /// # use json::JsonValue;
+    /// # use json::number::Number;
+    /// static TIMEOUT: JsonValue = JsonValue::Number(Number::from_u64(30));
+    ///
+    /// assert_eq!(TIMEOUT, 30);
+    /// ```
+    #[inline]
+    pub const fn from_u64(value: u64) -> Self {
+        Number {
+            category: POSITIVE,
+            exponent: 0,
+            mantissa: value,
+            format: PLAIN,
+        }
+    }
+
+    /// Construct a new `Number` from a signed integer in a `const` context,
+    /// see `from_u64`.
+    #[inline]
+    pub const fn from_i64(value: i64) -> Self {
+        Number {
+            category: (value >= 0) as u8,
+            exponent: 0,
+            mantissa: value.unsigned_abs(),
+            format: PLAIN,
+        }
+    }
+
+    /// Construct a new `Number` storing the bits of an `f64` as they are,
+    /// instead of converting it to a decimal mantissa and exponent. Converting
+    /// such a `Number` back to `f64` is guaranteed to produce the exact same
+    /// value, while all other operations (printing, comparing, converting to
+    /// integers) behave as if the `Number` was created with `Number::from`.
+    ///
+    /// With the `exact_f64` feature enabled, this is how all `f64` and `f32`
+    /// values are converted into `Number`s.
+    ///
+    /// ```
+    /// # use json::number::Number;
+    /// let float = 0.1 + 0.2;
+    /// let number = Number::from_f64_exact(float);
+    ///
+    /// assert_eq!(f64::from(number).to_bits(), float.to_bits());
+    /// assert_eq!(number, Number::from(float));
+    /// ```
+    ///
+    /// Infinite values and NaNs produce a NaN `Number`.
+    #[inline]
+    pub fn from_f64_exact(float: f64) -> Self {
+        if !float.is_finite() {
+            return NAN;
+        }
+
+        Number {
+            category: FLOAT | float.is_sign_positive() as u8,
+            exponent: 0,
+            mantissa: float.abs().to_bits(),
+            format: PLAIN,
+        }
+    }
+
+    /// Test if the `Number` stores the bits of an `f64`, as created by
+    /// `Number::from_f64_exact`.
+    #[inline]
+    pub fn is_exact_f64(&self) -> bool {
+        self.category & (NAN_MASK | FLOAT) == FLOAT
+    }
+
     /// Reverse to `from_parts` - obtain parts from an existing `Number`.
     ///
     /// ```
@@ -101,12 +276,24 @@ impl Number {
     /// ```
     #[inline]
     pub fn as_parts(&self) -> (bool, u64, i16) {
+        // Infinities are approximated by the largest representable exponent.
+        if self.is_infinite() {
+            return (self.is_sign_positive(), 1, i16::MAX);
+        }
+
+        if self.is_exact_f64() {
+            let (mantissa, exponent) = grisu2::convert(f64::from_bits(self.mantissa));
+            let decimal = Number::from_parts(true, mantissa, exponent);
+
+            return (self.is_sign_positive(), decimal.mantissa, decimal.exponent);
+        }
+
         (self.category == POSITIVE, self.mantissa, self.exponent)
     }
 
     #[inline]
     pub fn is_sign_positive(&self) -> bool {
-        self.category == POSITIVE
+        self.category & !FLOAT == POSITIVE
     }
 
     #[inline]
@@ -119,6 +306,160 @@ impl Number {
         self.category & NAN_MASK != 0
     }
 
+    /// Test if the number is positive or negative infinity, see `INFINITY`.
+    ///
+    /// ```
+    /// # use json::number::{ Number, NEG_INFINITY };
+    /// assert!(NEG_INFINITY.is_infinite());
+    /// assert_eq!(f64::from(NEG_INFINITY), f64::NEG_INFINITY);
+    /// assert!(!Number::from(1e300).is_infinite());
+    /// ```
+    #[inline]
+    pub fn is_infinite(&self) -> bool {
+        self.is_exact_f64() && f64::from_bits(self.mantissa).is_infinite()
+    }
+
+    /// Test if the `Number` remembers how it was written in the source, in
+    /// which case it's serialized exactly the same way. Only numbers parsed
+    /// with `ParseOptions::preserve_number_text` do.
+    ///
+    /// ```
+    /// # use json::ParseOptions;
+    /// let options = ParseOptions { preserve_number_text: true, ..ParseOptions::default() };
+    /// let data = json::parse_with_options("[1.50, 2E+01]", options).unwrap();
+    ///
+    /// assert!(data[0].as_number().unwrap().has_source_text());
+    /// assert_eq!(data.dump(), "[1.50,2E+01]");
+    /// ```
+    #[inline]
+    pub fn has_source_text(&self) -> bool {
+        self.format.flags & TEXT != 0
+    }
+
+    // Remember the layout of `text`, the source this number was parsed
+    // from, if it can be reproduced exactly. It can't for numbers with more
+    // significant digits than the mantissa can hold.
+    pub(crate) fn with_source_text(mut self, text: &str) -> Number {
+        let bytes = text.as_bytes();
+        let digits = |index: usize| bytes[index..].iter().take_while(|ch| ch.is_ascii_digit()).count();
+
+        let mut format = Format { flags: TEXT, ..PLAIN };
+        let mut index = if bytes.first() == Some(&b'-') { 1 } else { 0 };
+
+        index += digits(index);
+
+        if bytes.get(index) == Some(&b'.') {
+            let fraction = digits(index + 1);
+
+            if fraction > u16::MAX as usize {
+                return self;
+            }
+
+            format.fraction_digits = fraction as u16;
+            index += 1 + fraction;
+        }
+
+        if let Some(&e) = bytes.get(index) {
+            format.flags |= EXPONENT;
+
+            if e == b'E' {
+                format.flags |= UPPERCASE;
+            }
+
+            index += 1;
+
+            match bytes.get(index) {
+                Some(&b'+') => format.flags |= PLUS,
+                Some(&b'-') => format.flags |= MINUS,
+                _ => index -= 1,
+            }
+
+            index += 1;
+
+            let zeroes = bytes[index..].iter().take_while(|&&ch| ch == b'0').count();
+
+            format.exponent_zeroes = zeroes.min(digits(index) - 1).min(u8::MAX as usize) as u8;
+        }
+
+        let mut written = Vec::with_capacity(bytes.len());
+
+        if self.write_text(format, &mut written).is_ok() && written == bytes {
+            self.format = format;
+        }
+
+        self
+    }
+
+    // Remember if parsing `text`, the source this number was parsed from,
+    // dropped significant digits that didn't fit the mantissa.
+    pub(crate) fn with_rounding(mut self, text: &str) -> Number {
+        // The parser only drops digits once the mantissa is this large.
+        if self.mantissa < u64::MAX / 10 || self.is_exact_f64() {
+            return self;
+        }
+
+        let kept = self.mantissa.to_string().len();
+
+        let rounded = text.bytes()
+            .take_while(|&ch| ch != b'e' && ch != b'E')
+            .filter(|ch| ch.is_ascii_digit())
+            .skip_while(|&ch| ch == b'0')
+            .skip(kept)
+            .any(|ch| ch != b'0');
+
+        if rounded {
+            self.format.flags |= ROUNDED;
+        }
+
+        self
+    }
+
+    // Write the number the way it appeared in the source, if known.
+    pub(crate) fn write_source_text<W: io::Write>(&self, wr: &mut W) -> io::Result<()> {
+        self.write_text(self.format, wr)
+    }
+
+    fn write_text<W: io::Write>(&self, format: Format, wr: &mut W) -> io::Result<()> {
+        let digits = self.mantissa.to_string();
+        let fraction = format.fraction_digits as usize;
+
+        if self.category == NEGATIVE {
+            wr.write_all(b"-")?;
+        }
+
+        if digits.len() > fraction {
+            let (integer, decimal) = digits.split_at(digits.len() - fraction);
+
+            wr.write_all(integer.as_bytes())?;
+
+            if fraction > 0 {
+                write!(wr, ".{}", decimal)?;
+            }
+        } else {
+            write!(wr, "0.{:0>width$}", digits, width = fraction)?;
+        }
+
+        if format.flags & EXPONENT != 0 {
+            let exponent = self.exponent as i32 + fraction as i32;
+
+            wr.write_all(if format.flags & UPPERCASE != 0 { b"E" } else { b"e" })?;
+
+            if format.flags & PLUS != 0 {
+                wr.write_all(b"+")?;
+            } else if format.flags & MINUS != 0 {
+                wr.write_all(b"-")?;
+            }
+
+            for _ in 0..format.exponent_zeroes {
+                wr.write_all(b"0")?;
+            }
+
+            write!(wr, "{}", exponent.abs())?;
+        }
+
+        Ok(())
+    }
+
     /// Test if the number is NaN or has a zero value.
     #[inline]
     pub fn is_empty(&self) -> bool {
@@ -129,7 +470,7 @@ impl Number {
     /// converting monetary values and doing arithmetic on them without
     /// rounding errors introduced by floating point operations.
     ///
-    /// Will return `None` if `Number` is negative or a NaN.
+    /// Will return `None` if `Number` is negative, infinite or a NaN.
     ///
     /// ```
     /// # use json::number::Number;
@@ -142,23 +483,25 @@ impl Number {
     /// assert_eq!(price_c.as_fixed_point_u64(2), Some(1020));
     /// ```
     pub fn as_fixed_point_u64(&self, point: u16) -> Option<u64> {
-        if self.category != POSITIVE {
+        if !self.is_sign_positive() || self.is_infinite() {
             return None;
         }
 
-        let e_diff = point as i16 + self.exponent;
+        let (_, mantissa, exponent) = self.as_parts();
+        let e_diff = point as i16 + exponent;
 
         Some(if e_diff == 0 {
-            self.mantissa
+            mantissa
         } else if e_diff < 0 {
-            self.mantissa.wrapping_div(decimal_power(-e_diff as u16))
+            mantissa.wrapping_div(decimal_power(-e_diff as u16))
         } else {
-            self.mantissa.wrapping_mul(decimal_power(e_diff as u16))
+            mantissa.wrapping_mul(decimal_power(e_diff as u16))
         })
     }
 
     /// Analog to `as_fixed_point_u64`, except returning a signed
-    /// `i64`, properly handling negative numbers.
+    /// `i64`, properly handling negative numbers. Returns `None` for NaN
+    /// and infinities.
     ///
     /// ```
     /// # use json::number::Number;
@@ -169,17 +512,19 @@ impl Number {
     /// assert_eq!(balance_b.as_fixed_point_i64(2), Some(4200));
     /// ```
     pub fn as_fixed_point_i64(&self, point: u16) -> Option<i64> {
-        if self.is_nan() {
+        if self.is_nan() || self.is_infinite() {
             return None;
         }
 
-        let num = if self.is_sign_positive() {
-            self.mantissa as i64
+        let (positive, mantissa, exponent) = self.as_parts();
+
+        let num = if positive {
+            mantissa as i64
         } else {
-            -(self.mantissa as i64)
+            -(mantissa as i64)
         };
 
-        let e_diff = point as i16 + self.exponent;
+        let e_diff = point as i16 + exponent;
 
         Some(if e_diff == 0 {
             num
@@ -189,6 +534,140 @@ impl Number {
             num.wrapping_mul(decimal_power(e_diff as u16) as i64)
         })
     }
+
+    /// Convert the `Number` into an `u128`, if it's a non-negative integer
+    /// within range. Unlike the `From` conversions into integer types, this
+    /// never truncates or wraps around, and numbers parsed from text with
+    /// more significant digits than `Number` holds give `None`.
+    ///
+    /// ```
+    /// # use json::number::Number;
+    /// assert_eq!(Number::from(u64::MAX).as_u128(), Some(u64::MAX as u128));
+    /// assert_eq!(json::parse("340282366920938463463374607431768211455").unwrap().as_u128(), None);
+    /// assert_eq!(Number::from_parts(true, 34, 37).as_u128(), Some(340_000_000_000_000_000_000_000_000_000_000_000_000));
+    /// assert_eq!(Number::from_parts(true, 34, 38).as_u128(), None);
+    /// assert_eq!(Number::from(1.5).as_u128(), None);
+    /// assert_eq!(Number::from(-1).as_u128(), None);
+    /// ```
+    pub fn as_u128(&self) -> Option<u128> {
+        match self.integer_magnitude() {
+            Some(magnitude) if self.is_sign_positive() || magnitude == 0 => Some(magnitude),
+            _ => None,
+        }
+    }
+
+    /// Convert the `Number` into an `i128`, if it's an integer within range.
+    /// Unlike the `From` conversions into integer types, this never
+    /// truncates or wraps around, see `as_u128`.
+    ///
+    /// ```
+    /// # use json::number::Number;
+    /// assert_eq!(Number::from_parts(true, 2, 38).as_i128(), None);
+    /// assert_eq!(Number::from(-42).as_i128(), Some(-42));
+    /// assert_eq!(Number::from_parts(false, 17, 37).as_i128(), Some(-170_000_000_000_000_000_000_000_000_000_000_000_000));
+    /// ```
+    pub fn as_i128(&self) -> Option<i128> {
+        let magnitude = self.integer_magnitude()?;
+
+        if self.is_sign_positive() {
+            if magnitude <= i128::MAX as u128 {
+                Some(magnitude as i128)
+            } else {
+                None
+            }
+        } else if magnitude <= i128::MAX as u128 + 1 {
+            Some((magnitude as i128).wrapping_neg())
+        } else {
+            None
+        }
+    }
+
+    // Absolute value of the number, if it's an exact integer fitting `u128`.
+    fn integer_magnitude(&self) -> Option<u128> {
+        if self.is_nan() || self.is_infinite() || self.format.flags & ROUNDED != 0 {
+            return None;
+        }
+
+        let (_, mut mantissa, mut exponent) = self.as_parts();
+
+        while exponent < 0 {
+            if mantissa % 10 != 0 {
+                return None;
+            }
+
+            mantissa /= 10;
+            exponent += 1;
+        }
+
+        10u128.checked_pow(exponent as u32).and_then(|power| power.checked_mul(mantissa as u128))
+    }
+}
+
+/// Integers that don't fit the mantissa of `Number`, unless they end in
+/// enough zeroes, fail with `Error::InexactNumber` instead of losing digits.
+///
+/// ```
+/// # use std::convert::TryFrom;
+/// # use json::number::Number;
+/// assert_eq!(Number::try_from(12_345_000_000_000_000_000_000u128).unwrap().as_u128(), Some(12_345_000_000_000_000_000_000));
+/// assert!(Number::try_from(u128::MAX).is_err());
+/// ```
+impl TryFrom<u128> for Number {
+    type Error = Error;
+
+    fn try_from(num: u128) -> Result<Number, Error> {
+        let mut mantissa = num;
+        let mut exponent = 0;
+
+        while mantissa > u64::MAX as u128 {
+            if mantissa % 10 != 0 {
+                return Err(Error::InexactNumber(num.to_string()));
+            }
+
+            mantissa /= 10;
+            exponent += 1;
+        }
+
+        Ok(Number::from_parts(true, mantissa as u64, exponent))
+    }
+}
+
+/// Integers that don't fit the mantissa of `Number` fail, see
+/// `TryFrom<u128>`.
+impl TryFrom<i128> for Number {
+    type Error = Error;
+
+    fn try_from(num: i128) -> Result<Number, Error> {
+        match Number::try_from(num.unsigned_abs()) {
+            Ok(number) if num < 0 => Ok(-number),
+            Ok(number)            => Ok(number),
+            Err(_)                => Err(Error::InexactNumber(num.to_string())),
+        }
+    }
+}
+
+impl PartialEq<u128> for Number {
+    fn eq(&self, other: &u128) -> bool {
+        self.as_u128() == Some(*other)
+    }
+}
+
+impl PartialEq<i128> for Number {
+    fn eq(&self, other: &i128) -> bool {
+        self.as_i128() == Some(*other)
+    }
+}
+
+impl PartialEq<Number> for u128 {
+    fn eq(&self, other: &Number) -> bool {
+        other == self
+    }
+}
+
+impl PartialEq<Number> for i128 {
+    fn eq(&self, other: &Number) -> bool {
+        other == self
+    }
 }
 
 impl PartialEq for Number {
@@ -199,33 +678,79 @@ impl PartialEq for Number {
             return true;
         }
 
-        if self.category != other.category {
+        if self.is_exact_f64() && other.is_exact_f64() {
+            return self.category == other.category && self.mantissa == other.mantissa;
+        }
+
+        // Infinities only equal themselves, and their parts don't leave room
+        // for the exponent arithmetic below.
+        if self.is_infinite() || other.is_infinite() {
+            return false;
+        }
+
+        let (positive, mantissa, exponent) = self.as_parts();
+        let (other_positive, other_mantissa, other_exponent) = other.as_parts();
+
+        if positive != other_positive || self.is_nan() || other.is_nan() {
             return false;
         }
 
-        let e_diff = self.exponent - other.exponent;
+        let e_diff = exponent - other_exponent;
 
         if e_diff == 0 {
-            return self.mantissa == other.mantissa;
+            return mantissa == other_mantissa;
         } else if e_diff > 0 {
             let power = decimal_power(e_diff as u16);
 
-            self.mantissa.wrapping_mul(power) == other.mantissa
+            mantissa.wrapping_mul(power) == other_mantissa
         } else {
             let power = decimal_power(-e_diff as u16);
 
-            self.mantissa == other.mantissa.wrapping_mul(power)
+            mantissa == other_mantissa.wrapping_mul(power)
         }
 
     }
 }
 
+// Consistent with `PartialEq`: all zeros hash the same, and so do all NaNs,
+// while other numbers are hashed with trailing zeros of the mantissa removed,
+// so that `1.50` and `1.5` hash the same.
+impl Hash for Number {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        if self.is_nan() {
+            return state.write_u8(0);
+        }
+
+        if self.is_zero() {
+            return state.write_u8(1);
+        }
+
+        if self.is_infinite() {
+            return state.write_u8(if self.is_sign_positive() { 2 } else { 3 });
+        }
+
+        let (positive, mut mantissa, mut exponent) = self.as_parts();
+
+        while mantissa % 10 == 0 && exponent < i16::MAX {
+            mantissa /= 10;
+            exponent += 1;
+        }
+
+        positive.hash(state);
+        mantissa.hash(state);
+        exponent.hash(state);
+    }
+}
+
 impl fmt::Display for Number {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         unsafe {
             if self.is_nan() {
                 return f.write_str("nan")
             }
+            if self.is_infinite() {
+                return f.write_str(if self.is_sign_positive() { "inf" } else { "-inf" })
+            }
             let (positive, mantissa, exponent) = self.as_parts();
             let mut buf = Vec::new();
             print_dec::write(&mut buf, positive, mantissa, exponent).unwrap();
@@ -291,6 +816,11 @@ impl From<Number> for f64 {
     fn from(num: Number) -> f64 {
         if num.is_nan() { return f64::NAN; }
 
+        if num.is_exact_f64() {
+            let f = f64::from_bits(num.mantissa);
+            return if num.is_sign_positive() { f } else { -f };
+        }
+
         let mut n = num.mantissa as f64;
         let mut e = num.exponent;
 
@@ -308,6 +838,10 @@ impl From<Number> for f32 {
     fn from(num: Number) -> f32 {
         if num.is_nan() { return f32::NAN; }
 
+        if num.is_exact_f64() {
+            return f64::from(num) as f32;
+        }
+
         let mut n = num.mantissa as f32;
         let mut e = num.exponent;
 
@@ -321,6 +855,21 @@ impl From<Number> for f32 {
     }
 }
 
+#[cfg(feature = "exact_f64")]
+impl From<f64> for Number {
+    fn from(float: f64) -> Number {
+        Number::from_f64_exact(float)
+    }
+}
+
+#[cfg(feature = "exact_f64")]
+impl From<f32> for Number {
+    fn from(float: f32) -> Number {
+        Number::from_f64_exact(float as f64)
+    }
+}
+
+#[cfg(not(feature = "exact_f64"))]
 impl From<f64> for Number {
     fn from(float: f64) -> Number {
         match float.classify() {
@@ -340,6 +889,7 @@ impl From<f64> for Number {
     }
 }
 
+#[cfg(not(feature = "exact_f64"))]
 impl From<f32> for Number {
     fn from(float: f32) -> Number {
         match float.classify() {
@@ -392,6 +942,7 @@ macro_rules! impl_unsigned {
                     category: POSITIVE,
                     exponent: 0,
                     mantissa: num as u64,
+                    format: PLAIN,
                 }
             }
         }
@@ -409,13 +960,15 @@ macro_rules! impl_signed {
                     Number {
                         category: NEGATIVE,
                         exponent: 0,
-                        mantissa: -num as u64,
+                        mantissa: (num as i64).unsigned_abs(),
+                        format: PLAIN,
                     }
                 } else {
                     Number {
                         category: POSITIVE,
                         exponent: 0,
                         mantissa: num as u64,
+                        format: PLAIN,
                     }
                 }
             }
@@ -430,13 +983,18 @@ macro_rules! impl_integer {
     ($t:ty) => {
         impl From<Number> for $t {
             fn from(num: Number) -> $t {
+                // Saturate, same as casting an infinite `f64`.
+                if num.is_infinite() {
+                    return f64::from(num) as $t;
+                }
+
                 let (positive, mantissa, exponent) = num.as_parts();
 
                 if exponent <= 0 {
                     if positive {
                         mantissa as $t
                     } else {
-                        -(mantissa as i64) as $t
+                        (mantissa as i64).wrapping_neg() as $t
                     }
                 } else {
                     // This may overflow, which is fine
@@ -475,6 +1033,7 @@ impl ops::Neg for Number {
             category: self.category ^ POSITIVE,
             exponent: self.exponent,
             mantissa: self.mantissa,
+            format: self.format,
         }
     }
 }
@@ -1,15 +1,36 @@
 use std::{ ptr, mem, str, slice, fmt };
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::iter::FromIterator;
+use std::hash::{ Hash, Hasher };
+use std::collections::{ btree_map, BTreeMap };
+#[cfg(feature = "std")]
+use std::collections::hash_map::DefaultHasher;
+#[cfg(feature = "std")]
+use std::collections::hash_map::RandomState;
+#[cfg(not(feature = "std"))]
+use std::hash::BuildHasherDefault;
 use std::ops::{ Index, IndexMut, Deref };
+use prelude::*;
 
 use codegen::{ DumpGenerator, Generator, PrettyGenerator };
 use value::JsonValue;
+use short::Short;
+use { parser, Result };
 
 use indexmap::IndexMap;
+pub use indexmap::Equivalent;
+pub use indexmap::map::{ Entry, OccupiedEntry, VacantEntry };
 use indexmap::map::{
     Iter,
     IterMut,
     IntoIter,
-    Drain
+    Drain,
+    Keys,
+    Values,
+    ValuesMut,
+    IntoKeys,
+    IntoValues
 };
 
 use std::ops::RangeFull;
@@ -32,6 +53,9 @@ static NULL: JsonValue = JsonValue::Null;
 /// assert_eq!(data.dump(), r#"{"foo":42,"bar":false}"#);
 /// # }
 /// ```
+///
+/// Keys can be anything a `String` can be created from. Owned `String` keys
+/// are moved into the object without being copied.
 #[macro_export]
 macro_rules! object {
     // Empty object.
@@ -55,7 +79,7 @@ macro_rules! object {
         let mut object = Object::new();
 
         $(
-            object.insert($key, $value.into());
+            object.insert_owned(::std::string::String::from($key), $crate::ToJson::into_json($value));
         )*
 
         $crate::JsonValue::Object(object)
@@ -84,34 +108,227 @@ macro_rules! array {
         let mut array = Vec::new();
 
         $(
-            array.push($item.into());
+            array.push($crate::ToJson::into_json($item));
         )*
 
         $crate::JsonValue::Array(array)
     })
 }
 
+/// Types that can be used to look up entries in an `Object` without having
+/// to allocate or dereference them into a `&str` first. Implemented for
+/// `str`, `String`, `Cow<str>`, `Short`, and references to any of them.
+///
+/// Custom key types can be used by implementing `Equivalent<String>` (and
+/// `Hash`, producing the same hash as the matching `str` would) along with
+/// this trait:
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// use std::hash::{ Hash, Hasher };
+/// use json::object::{ Equivalent, Key };
+///
+/// // Field name stored as a fixed size, zero padded buffer.
+/// struct FieldName([u8; 8]);
+///
+/// impl FieldName {
+///     fn as_str(&self) -> &str {
+///         let len = self.0.iter().position(|&byte| byte == 0).unwrap_or(8);
+///         std::str::from_utf8(&self.0[..len]).unwrap()
+///     }
+/// }
+///
+/// impl Hash for FieldName {
+///     fn hash<H: Hasher>(&self, state: &mut H) {
+///         self.as_str().hash(state)
+///     }
+/// }
+///
+/// impl Equivalent<String> for FieldName {
+///     fn equivalent(&self, key: &String) -> bool {
+///         self.as_str() == key
+///     }
+/// }
+///
+/// impl Key for FieldName {
+///     type Target = FieldName;
+///
+///     fn key(&self) -> &FieldName {
+///         self
+///     }
+/// }
+///
+/// let data = object!{ "id" => 42 };
+///
+/// assert_eq!(data[&FieldName(*b"id\0\0\0\0\0\0")], 42);
+/// # }
+/// ```
+pub trait Key {
+    /// Type the lookups are performed with.
+    type Target: ?Sized + Hash + Equivalent<String>;
+
+    /// Obtain the value the lookups are performed with.
+    fn key(&self) -> &Self::Target;
+}
+
+impl Key for str {
+    type Target = str;
+
+    #[inline(always)]
+    fn key(&self) -> &str {
+        self
+    }
+}
+
+impl Key for String {
+    type Target = str;
+
+    #[inline(always)]
+    fn key(&self) -> &str {
+        self
+    }
+}
+
+impl<'a> Key for Cow<'a, str> {
+    type Target = str;
+
+    #[inline(always)]
+    fn key(&self) -> &str {
+        self
+    }
+}
+
+impl Key for Short {
+    type Target = str;
+
+    #[inline(always)]
+    fn key(&self) -> &str {
+        self
+    }
+}
+
+impl<'a, K: ?Sized + Key> Key for &'a K {
+    type Target = K::Target;
+
+    #[inline(always)]
+    fn key(&self) -> &K::Target {
+        (**self).key()
+    }
+}
+
 /// A binary tree implementation of a string -> `JsonValue` map. You normally don't
 /// have to interact with instances of `Object`, much more likely you will be
 /// using the `JsonValue::Object` variant, which wraps around this struct.
+///
+/// With the `hash_object` feature keys are hashed with `ahash` instead of
+/// SipHash, which is faster but not resistant to collisions crafted by
+/// whoever supplies the keys.
 #[derive(Debug, Clone)]
 pub struct Object {
-    inner: IndexMap<String, JsonValue>
+    inner: Inner
+}
+
+// Without the `std` feature there is no source of randomness to seed the
+// hasher of keys with, so they are hashed with FNV-1a instead.
+#[cfg(all(feature = "std", not(feature = "hash_object")))]
+type State = RandomState;
+
+#[cfg(not(feature = "std"))]
+type State = BuildHasherDefault<Fnv>;
+
+// With the `hash_object` feature keys are hashed with `ahash`, which is a
+// lot faster than the SipHash of `RandomState`.
+#[cfg(feature = "hash_object")]
+type State = ::ahash::RandomState;
+
+#[cfg(not(feature = "std"))]
+type DefaultHasher = Fnv;
+
+pub(crate) type Map = IndexMap<String, JsonValue, State>;
+
+#[cfg(not(feature = "std"))]
+pub(crate) struct Fnv(u64);
+
+#[cfg(not(feature = "std"))]
+impl Default for Fnv {
+    fn default() -> Self {
+        Fnv(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl Hasher for Fnv {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+// With the `compact_value` feature the map is boxed, making `Object`, and
+// with it `JsonValue`, a lot smaller at the cost of an extra allocation.
+#[cfg(not(feature = "compact_value"))]
+type Inner = Map;
+
+#[cfg(feature = "compact_value")]
+type Inner = Box<Map>;
+
+#[cfg(not(feature = "compact_value"))]
+#[inline(always)]
+fn inner(map: Map) -> Inner {
+    map
+}
+
+#[cfg(feature = "compact_value")]
+#[inline(always)]
+fn inner(map: Map) -> Inner {
+    Box::new(map)
+}
+
+impl From<Map> for Object {
+    fn from(val: Map) -> Self {
+        Object { inner: inner(val) }
+    }
+}
+
+// With the `hash_object` feature `Map` hashes keys with `ahash`, so maps
+// using the hasher of `std` have their keys hashed again.
+#[cfg(feature = "hash_object")]
+impl From<IndexMap<String, JsonValue, RandomState>> for Object {
+    fn from(val: IndexMap<String, JsonValue, RandomState>) -> Self {
+        Object { inner: inner(val.into_iter().collect()) }
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for Object
+where K: Into<String>, V: Into<JsonValue> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut object = Object::new();
+        object.extend(iter);
+        object
+    }
 }
 
-impl From<IndexMap<String, JsonValue>> for Object {
-    fn from(val: IndexMap<String, JsonValue>) -> Self {
-        Object { inner: val }
+impl<K, V> Extend<(K, V)> for Object
+where K: Into<String>, V: Into<JsonValue> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        self.inner.extend(iter.into_iter().map(|(key, value)| (key.into(), value.into())));
     }
 }
 
 impl Object {
     /// Create a new, empty instance of `Object`. Empty `Object` performs no
-    /// allocation until a value is inserted into it.
+    /// allocation until a value is inserted into it, unless the `compact_value`
+    /// feature is enabled.
     #[inline(always)]
     pub fn new() -> Self {
         Object {
-            inner: IndexMap::new()
+            inner: inner(Map::default())
         }
     }
 
@@ -120,7 +337,7 @@ impl Object {
     #[inline(always)]
     pub fn with_capacity(capacity: usize) -> Self {
         Object {
-            inner: IndexMap::with_capacity(capacity)
+            inner: inner(Map::with_capacity_and_hasher(capacity, State::default()))
         }
     }
 
@@ -134,18 +351,113 @@ impl Object {
         self.inner.insert(key.to_string(), value);
     }
 
-    pub fn get(&self, key: &str) -> Option<&JsonValue> {
-        self.inner.get(key)
+    /// Insert a new entry, or override an existing one, taking ownership of
+    /// the `key` instead of copying it.
+    #[inline]
+    pub fn insert_owned(&mut self, key: String, value: JsonValue) {
+        self.inner.insert(key, value);
     }
 
-    pub fn get_mut(&mut self, key: &str) -> Option<&mut JsonValue> {
-        self.inner.get_mut(key)
+    /// Get the entry for `key`, to insert or modify its value in place with
+    /// a single lookup.
+    ///
+    /// ```
+    /// # use json::object::Object;
+    /// let mut counts = Object::new();
+    ///
+    /// for word in "a b a".split(' ') {
+    ///     counts.entry(word)
+    ///         .and_modify(|count| *count = (count.as_u32().unwrap() + 1).into())
+    ///         .or_insert(1.into());
+    /// }
+    ///
+    /// assert_eq!(counts.dump(), r#"{"a":2,"b":1}"#);
+    /// ```
+    pub fn entry<K: Into<String>>(&mut self, key: K) -> Entry<'_, String, JsonValue> {
+        self.inner.entry(key.into())
+    }
+
+    pub fn get<K: ?Sized + Key>(&self, key: &K) -> Option<&JsonValue> {
+        self.inner.get(key.key())
+    }
+
+    pub fn get_mut<K: ?Sized + Key>(&mut self, key: &K) -> Option<&mut JsonValue> {
+        self.inner.get_mut(key.key())
+    }
+
+    /// Get the stored key along with the value behind `key`.
+    pub fn get_key_value<K: ?Sized + Key>(&self, key: &K) -> Option<(&String, &JsonValue)> {
+        self.inner.get_key_value(key.key())
+    }
+
+    /// Get the entry at position `index` in insertion order, if there is one.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// # use json::JsonValue;
+    /// let data = object!{ "id" => 7, "name" => "Alice" };
+    ///
+    /// if let JsonValue::Object(object) = data {
+    ///     assert_eq!(object.get_index(1), Some(("name", &"Alice".into())));
+    ///     assert_eq!(object.key_at(0), Some("id"));
+    ///     assert_eq!(object[0], 7);
+    ///     assert!(object[2].is_null());
+    /// }
+    /// # }
+    /// ```
+    pub fn get_index(&self, index: usize) -> Option<(&str, &JsonValue)> {
+        self.inner.get_index(index).map(|(key, value)| (key.as_str(), value))
+    }
+
+    /// Get the entry at position `index` with a mutable reference to the
+    /// value, if there is one.
+    pub fn get_index_mut(&mut self, index: usize) -> Option<(&str, &mut JsonValue)> {
+        self.inner.get_index_mut(index).map(|(key, value)| (key.as_str(), value))
+    }
+
+    /// Remove the entry at position `index`, moving the last entry into its
+    /// place. This is fast, but changes the order of the entries.
+    pub fn swap_remove_index(&mut self, index: usize) -> Option<(String, JsonValue)> {
+        self.inner.swap_remove_index(index)
+    }
+
+    /// Remove the entry at position `index`, shifting all entries after it.
+    /// This preserves the order of the entries, but takes linear time.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// # use json::JsonValue;
+    /// if let JsonValue::Object(mut object) = object!{ "a" => 1, "b" => 2, "c" => 3 } {
+    ///     assert_eq!(object.shift_remove_index(0), Some(("a".into(), 1.into())));
+    ///     assert_eq!(object.dump(), r#"{"b":2,"c":3}"#);
+    ///
+    ///     assert_eq!(object.swap_remove_index(0), Some(("b".into(), 2.into())));
+    ///     assert_eq!(object.dump(), r#"{"c":3}"#);
+    /// }
+    /// # }
+    /// ```
+    pub fn shift_remove_index(&mut self, index: usize) -> Option<(String, JsonValue)> {
+        self.inner.shift_remove_index(index)
+    }
+
+    /// Get the key of the entry at position `index` in insertion order,
+    /// if there is one.
+    pub fn key_at(&self, index: usize) -> Option<&str> {
+        self.inner.get_index(index).map(|(key, _)| key.as_str())
     }
 
     /// Attempts to remove the value behind `key`, if successful
     /// will return the `JsonValue` stored behind the `key`.
-    pub fn remove(&mut self, key: &str) -> Option<JsonValue> {
-        self.inner.remove(key)
+    pub fn remove<K: ?Sized + Key>(&mut self, key: &K) -> Option<JsonValue> {
+        self.inner.remove(key.key())
+    }
+
+    /// Remove the entry for `key`, returning the stored key along with the
+    /// value. Like `remove`, this moves the last entry into its place.
+    pub fn remove_entry<K: ?Sized + Key>(&mut self, key: &K) -> Option<(String, JsonValue)> {
+        self.inner.remove_entry(key.key())
     }
 
     #[inline(always)]
@@ -158,6 +470,63 @@ impl Object {
         self.inner.is_empty()
     }
 
+    /// Number of entries the `Object` can hold without reallocating.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    /// Reserve capacity for at least `additional` more entries.
+    pub fn reserve(&mut self, additional: usize) {
+        self.inner.reserve(additional);
+    }
+
+    /// Shrink the capacity as much as possible, releasing memory left over
+    /// after entries were removed.
+    pub fn shrink_to_fit(&mut self) {
+        self.inner.shrink_to_fit();
+    }
+
+    /// Retain only the entries for which the predicate returns `true`,
+    /// preserving the order of the remaining entries.
+    pub fn retain<F>(&mut self, mut keep: F)
+    where F: FnMut(&str, &mut JsonValue) -> bool {
+        self.inner.retain(|key, value| keep(key, value));
+    }
+
+    /// Sort the entries by their keys, in place.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// # use json::JsonValue;
+    /// if let JsonValue::Object(mut object) = object!{ "b" => 2, "c" => 1, "a" => 3 } {
+    ///     object.sort_keys();
+    ///     assert_eq!(object.dump(), r#"{"a":3,"b":2,"c":1}"#);
+    ///
+    ///     object.sort_by(|_, a, _, b| a.as_i32().cmp(&b.as_i32()));
+    ///     assert_eq!(object.dump(), r#"{"c":1,"b":2,"a":3}"#);
+    /// }
+    /// # }
+    /// ```
+    pub fn sort_keys(&mut self) {
+        self.inner.sort_keys();
+    }
+
+    /// Sort the entries in place with a comparator taking the key and value
+    /// of two entries. The sort is stable.
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where F: FnMut(&str, &JsonValue, &str, &JsonValue) -> Ordering {
+        self.inner.sort_by(|k1, v1, k2, v2| compare(k1, v1, k2, v2));
+    }
+
+    /// Sort the entries in place like `sort_by`, but without preserving the
+    /// order of equal entries.
+    pub fn sort_unstable_by<F>(&mut self, mut compare: F)
+    where F: FnMut(&str, &JsonValue, &str, &JsonValue) -> Ordering {
+        self.inner.sort_unstable_by(|k1, v1, k2, v2| compare(k1, v1, k2, v2));
+    }
+
     /// Wipe the `Object` clear. The capacity will remain untouched.
     pub fn clear(&mut self) {
         self.inner.clear();
@@ -173,14 +542,58 @@ impl Object {
         self.inner.iter_mut()
     }
 
+    /// Iterate over the keys, in order.
+    #[inline(always)]
+    pub fn keys(&self) -> Keys<'_, String, JsonValue> {
+        self.inner.keys()
+    }
+
+    /// Iterate over the values, in order.
+    #[inline(always)]
+    pub fn values(&self) -> Values<'_, String, JsonValue> {
+        self.inner.values()
+    }
+
+    /// Iterate over mutable references to the values, in order.
+    #[inline(always)]
+    pub fn values_mut(&mut self) -> ValuesMut<'_, String, JsonValue> {
+        self.inner.values_mut()
+    }
+
+    #[cfg(not(feature = "compact_value"))]
+    pub fn into_keys(self) -> IntoKeys<String, JsonValue> {
+        self.inner.into_keys()
+    }
+
+    #[cfg(feature = "compact_value")]
+    pub fn into_keys(self) -> IntoKeys<String, JsonValue> {
+        (*self.inner).into_keys()
+    }
+
+    #[cfg(not(feature = "compact_value"))]
+    pub fn into_values(self) -> IntoValues<String, JsonValue> {
+        self.inner.into_values()
+    }
+
+    #[cfg(feature = "compact_value")]
+    pub fn into_values(self) -> IntoValues<String, JsonValue> {
+        (*self.inner).into_values()
+    }
+
     pub fn drain(&mut self, range: RangeFull) -> Drain<String, JsonValue> {
         self.inner.drain(range)
     }
 
+    #[cfg(not(feature = "compact_value"))]
     pub fn into_iter(self) -> IntoIter<String, JsonValue> {
         self.inner.into_iter()
     }
 
+    #[cfg(feature = "compact_value")]
+    pub fn into_iter(self) -> IntoIter<String, JsonValue> {
+        (*self.inner).into_iter()
+    }
+
     /// Prints out the value as JSON string.
     pub fn dump(&self) -> String {
         let mut gen = DumpGenerator::new();
@@ -219,6 +632,23 @@ impl PartialEq for Object {
 
 impl Eq for Object {}
 
+// Like equality, hashing doesn't depend on the order of the entries. Every
+// entry is hashed on its own and the results are combined with addition.
+impl Hash for Object {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut sum = 0u64;
+
+        for entry in self.iter() {
+            let mut hasher = DefaultHasher::default();
+            entry.hash(&mut hasher);
+            sum = sum.wrapping_add(hasher.finish());
+        }
+
+        state.write_usize(self.len());
+        state.write_u64(sum);
+    }
+}
+
 /// Implements indexing by `&str` to easily access object members:
 ///
 /// ## Example
@@ -239,10 +669,10 @@ impl Eq for Object {}
 /// # }
 /// ```
 // TODO: doc
-impl<'a> Index<&'a str> for Object {
+impl<'a, K: ?Sized + Key> Index<&'a K> for Object {
     type Output = JsonValue;
 
-    fn index(&self, index: &str) -> &JsonValue {
+    fn index(&self, index: &K) -> &JsonValue {
         match self.get(index) {
             Some(value) => value,
             _ => &NULL
@@ -250,18 +680,24 @@ impl<'a> Index<&'a str> for Object {
     }
 }
 
-impl Index<String> for Object {
+/// Implements indexing by `usize` to access object members by their position
+/// in insertion order. Out of range positions yield a `null`, same as missing
+/// keys do.
+impl Index<usize> for Object {
     type Output = JsonValue;
 
-    fn index(&self, index: String) -> &JsonValue {
-        self.index(index.deref())
+    fn index(&self, index: usize) -> &JsonValue {
+        match self.inner.get_index(index) {
+            Some((_, value)) => value,
+            None             => &NULL
+        }
     }
 }
 
-impl<'a> Index<&'a String> for Object {
+impl Index<String> for Object {
     type Output = JsonValue;
 
-    fn index(&self, index: &String) -> &JsonValue {
+    fn index(&self, index: String) -> &JsonValue {
         self.index(index.deref())
     }
 }
@@ -305,3 +741,319 @@ impl<'a> IndexMut<&'a String> for Object {
         self.index_mut(index.deref())
     }
 }
+
+/// An ordered list of key/value pairs that, unlike `Object`, keeps entries
+/// with duplicate keys. Parsing JSON into an `Object` only keeps the last
+/// value for every key, which loses information when duplicate keys are
+/// meaningful to whoever consumes the JSON. `MultiObject` keeps all of them
+/// and serializes them back out as they were.
+///
+/// ```
+/// # use json::object::MultiObject;
+/// let object = MultiObject::parse(r#"{"tag":"a","id":1,"tag":"b"}"#).unwrap();
+///
+/// assert_eq!(object.len(), 3);
+/// assert_eq!(object.get("tag").unwrap(), "b");
+/// assert_eq!(object.get_all("tag").collect::<Vec<_>>(), ["a", "b"]);
+///
+/// assert_eq!(object.dump(), r#"{"tag":"a","id":1,"tag":"b"}"#);
+/// ```
+///
+/// Only the entries of the `MultiObject` itself are kept this way, objects
+/// nested inside of its values are regular `Object`s.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MultiObject {
+    entries: Vec<(String, JsonValue)>
+}
+
+impl MultiObject {
+    /// Create a new, empty instance of `MultiObject`.
+    #[inline(always)]
+    pub fn new() -> Self {
+        MultiObject {
+            entries: Vec::new()
+        }
+    }
+
+    /// Create a new `MultiObject` with memory preallocated for `capacity`
+    /// number of entries.
+    #[inline(always)]
+    pub fn with_capacity(capacity: usize) -> Self {
+        MultiObject {
+            entries: Vec::with_capacity(capacity)
+        }
+    }
+
+    /// Parse a JSON object from `source`, keeping all of its entries. Will
+    /// return an error if the source is not a valid JSON object.
+    pub fn parse(source: &str) -> Result<Self> {
+        parser::parse_multi_object(source)
+    }
+
+    /// Append a new entry at the end, regardless of whether an entry with
+    /// the same `key` already exists.
+    #[inline]
+    pub fn insert(&mut self, key: &str, value: JsonValue) {
+        self.entries.push((key.to_string(), value));
+    }
+
+    #[inline]
+    pub(crate) fn push(&mut self, key: String, value: JsonValue) {
+        self.entries.push((key, value));
+    }
+
+    /// Get the value of the last entry with `key`, which is the value
+    /// the key would have if this was an `Object`.
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        self.entries.iter().rev().find(|entry| entry.0 == key).map(|entry| &entry.1)
+    }
+
+    /// Iterate over the values of all entries with `key`, in order.
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a JsonValue> {
+        self.entries.iter().filter(move |entry| entry.0 == key).map(|entry| &entry.1)
+    }
+
+    /// Remove all entries with `key`, returning their values in order.
+    pub fn remove(&mut self, key: &str) -> Vec<JsonValue> {
+        let mut removed = Vec::new();
+
+        self.entries.retain_mut(|entry| {
+            if entry.0 != key {
+                return true;
+            }
+            removed.push(mem::replace(&mut entry.1, JsonValue::Null));
+            false
+        });
+
+        removed
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Wipe the `MultiObject` clear. The capacity will remain untouched.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Iterate over all entries in order, including duplicates.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &JsonValue)> + Clone {
+        self.entries.iter().map(|entry| (&entry.0, &entry.1))
+    }
+
+    /// Iterate mutably over all entries in order, including duplicates.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&String, &mut JsonValue)> {
+        self.entries.iter_mut().map(|entry| (&entry.0, &mut entry.1))
+    }
+
+    /// Prints out the value as JSON string, including duplicate keys.
+    pub fn dump(&self) -> String {
+        let mut gen = DumpGenerator::new();
+        gen.write_multi_object(self).expect("Can't fail");
+        gen.consume()
+    }
+
+    /// Pretty prints out the value as JSON string, including duplicate keys.
+    /// Takes an argument that's number of spaces to indent new blocks with.
+    pub fn pretty(&self, spaces: u16) -> String {
+        let mut gen = PrettyGenerator::new(spaces);
+        gen.write_multi_object(self).expect("Can't fail");
+        gen.consume()
+    }
+}
+
+impl From<Object> for MultiObject {
+    fn from(object: Object) -> Self {
+        MultiObject {
+            entries: object.into_iter().collect()
+        }
+    }
+}
+
+/// Collapses duplicate keys, keeping the position of the first entry
+/// and the value of the last one for every key.
+impl From<MultiObject> for Object {
+    fn from(object: MultiObject) -> Self {
+        let mut result = Object::with_capacity(object.len());
+
+        for (key, value) in object.entries {
+            result.insert_owned(key, value);
+        }
+
+        result
+    }
+}
+
+/// A map of key/value pairs that, unlike `Object`, keeps its entries sorted
+/// by key at all times rather than in insertion order. It's backed by a
+/// `BTreeMap`, which also takes less memory per entry than the hash map
+/// behind `Object`, at the cost of slower lookups.
+///
+/// ```
+/// # use json::object::SortedObject;
+/// let mut object = SortedObject::parse(r#"{"c":1,"a":2}"#).unwrap();
+///
+/// object.insert("b", 3.into());
+///
+/// assert_eq!(object.get("a").unwrap(), 2);
+/// assert_eq!(object.keys().collect::<Vec<_>>(), ["a", "b", "c"]);
+///
+/// assert_eq!(object.dump(), r#"{"a":2,"b":3,"c":1}"#);
+/// ```
+///
+/// Only the entries of the `SortedObject` itself are kept this way, objects
+/// nested inside of its values are regular `Object`s.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SortedObject {
+    entries: BTreeMap<String, JsonValue>
+}
+
+impl SortedObject {
+    /// Create a new, empty instance of `SortedObject`.
+    #[inline(always)]
+    pub fn new() -> Self {
+        SortedObject {
+            entries: BTreeMap::new()
+        }
+    }
+
+    /// Parse a JSON object from `source`. Will return an error if the
+    /// source is not a valid JSON object.
+    pub fn parse(source: &str) -> Result<Self> {
+        parser::parse_multi_object(source).map(|object| object.entries.into_iter().collect())
+    }
+
+    /// Insert a new entry, or replace the value of the existing entry
+    /// with the same `key`.
+    #[inline]
+    pub fn insert(&mut self, key: &str, value: JsonValue) {
+        self.entries.insert(key.to_string(), value);
+    }
+
+    #[inline]
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        self.entries.get(key)
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut JsonValue> {
+        self.entries.get_mut(key)
+    }
+
+    /// Attempts to remove the value behind `key`, if successful
+    /// will return the `JsonValue` stored behind the `key`.
+    #[inline]
+    pub fn remove(&mut self, key: &str) -> Option<JsonValue> {
+        self.entries.remove(key)
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Wipe the `SortedObject` clear.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Iterate over all keys in sorted order.
+    pub fn keys(&self) -> btree_map::Keys<'_, String, JsonValue> {
+        self.entries.keys()
+    }
+
+    /// Iterate over all entries in sorted order.
+    pub fn iter(&self) -> btree_map::Iter<'_, String, JsonValue> {
+        self.entries.iter()
+    }
+
+    /// Iterate mutably over all entries in sorted order.
+    pub fn iter_mut(&mut self) -> btree_map::IterMut<'_, String, JsonValue> {
+        self.entries.iter_mut()
+    }
+
+    /// Prints out the value as JSON string, with keys in sorted order.
+    pub fn dump(&self) -> String {
+        let mut gen = DumpGenerator::new();
+        gen.write_sorted_object(self).expect("Can't fail");
+        gen.consume()
+    }
+
+    /// Pretty prints out the value as JSON string, with keys in sorted
+    /// order. Takes an argument that's number of spaces to indent new
+    /// blocks with.
+    pub fn pretty(&self, spaces: u16) -> String {
+        let mut gen = PrettyGenerator::new(spaces);
+        gen.write_sorted_object(self).expect("Can't fail");
+        gen.consume()
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for SortedObject
+where K: Into<String>, V: Into<JsonValue> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        SortedObject {
+            entries: iter.into_iter().map(|(key, value)| (key.into(), value.into())).collect()
+        }
+    }
+}
+
+impl IntoIterator for SortedObject {
+    type Item = (String, JsonValue);
+    type IntoIter = btree_map::IntoIter<String, JsonValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a SortedObject {
+    type Item = (&'a String, &'a JsonValue);
+    type IntoIter = btree_map::Iter<'a, String, JsonValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
+
+/// Implements indexing by `&str` like `Object`, returning a reference to
+/// `Null` for missing keys.
+impl Index<&str> for SortedObject {
+    type Output = JsonValue;
+
+    fn index(&self, index: &str) -> &JsonValue {
+        self.get(index).unwrap_or(&NULL)
+    }
+}
+
+impl From<Object> for SortedObject {
+    fn from(object: Object) -> Self {
+        object.into_iter().collect()
+    }
+}
+
+/// Keeps the entries in sorted order, which is then the insertion order
+/// of the `Object`.
+impl From<SortedObject> for Object {
+    fn from(object: SortedObject) -> Self {
+        let mut result = Object::with_capacity(object.len());
+
+        for (key, value) in object.entries {
+            result.insert_owned(key, value);
+        }
+
+        result
+    }
+}
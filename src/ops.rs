@@ -0,0 +1,146 @@
+//! Operations working on whole collections of `JsonValue`s, rather than on
+//! a single value.
+
+use std::collections::BTreeMap;
+use prelude::*;
+
+use object::Object;
+use { JsonValue, Error, Result };
+
+/// The kind of join performed by `json::ops::join`, analog to the SQL
+/// joins of the same names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinKind {
+    /// Only records that have a match on both sides are produced.
+    Inner,
+
+    /// All records from the left side are produced, merged with their
+    /// matches from the right side if there are any.
+    Left,
+
+    /// All records from the right side are produced, merged with their
+    /// matches from the left side if there are any.
+    Right,
+
+    /// All records from both sides are produced, merged where a match exists.
+    Full,
+}
+
+/// Join two arrays of records on a key, producing a new array of merged
+/// records. The `key` is a JSON Pointer (RFC 6901), such as `"/user_id"`,
+/// resolved against every record on both sides.
+///
+/// Records are matched when the values behind the key are equal, the same way
+/// `JsonValue`s compare, so `1` matches `1.0` and objects match regardless of
+/// the order of their entries. Records in which the key doesn't resolve, or
+/// resolves to `null`, never match anything. When merging two objects, keys
+/// present on both sides take the value from the right record. Merging
+/// anything other than two objects yields the right record.
+///
+/// Matched records are produced in the order of the left array. For `Right`
+/// and `Full` joins, unmatched records from the right array follow at the end,
+/// in their original order.
+///
+/// Will return an error if either `left` or `right` is not an array.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// use json::ops::{ join, JoinKind };
+///
+/// let users = array![
+///     object!{ "user_id" => 1, "name" => "Alice" },
+///     object!{ "user_id" => 2, "name" => "Bob" }
+/// ];
+/// let orders = array![
+///     object!{ "user_id" => 1, "total" => 10 }
+/// ];
+///
+/// let joined = join(&users, &orders, "/user_id", JoinKind::Left).unwrap();
+///
+/// assert_eq!(joined, array![
+///     object!{ "user_id" => 1, "name" => "Alice", "total" => 10 },
+///     object!{ "user_id" => 2, "name" => "Bob" }
+/// ]);
+/// # }
+/// ```
+pub fn join(left: &JsonValue, right: &JsonValue, key: &str, kind: JoinKind) -> Result<JsonValue> {
+    let left = match *left {
+        JsonValue::Array(ref vec) => vec,
+        _                         => return Err(Error::wrong_type("Array")),
+    };
+    let right = match *right {
+        JsonValue::Array(ref vec) => vec,
+        _                         => return Err(Error::wrong_type("Array")),
+    };
+
+    // Index the right side by the content hash of the key, which is the
+    // same for all equal values, such as `1` and `1.0`, or objects with
+    // their entries in a different order. Hashes can collide, so keys are
+    // compared again when looking them up.
+    let mut index: BTreeMap<u128, Vec<usize>> = BTreeMap::new();
+
+    for (position, record) in right.iter().enumerate() {
+        if let Some(value) = join_key(record, key) {
+            index.entry(value.content_hash_128()).or_default().push(position);
+        }
+    }
+
+    let mut matched = vec![false; right.len()];
+    let mut result = Vec::with_capacity(left.len());
+
+    for record in left {
+        let mut found = false;
+
+        if let Some(value) = join_key(record, key) {
+            let positions = index.get(&value.content_hash_128()).map_or(&[][..], |positions| &positions[..]);
+
+            for &position in positions {
+                if join_key(&right[position], key) == Some(value) {
+                    found = true;
+                    matched[position] = true;
+                    result.push(merge_records(record, &right[position]));
+                }
+            }
+        }
+
+        if !found {
+            match kind {
+                JoinKind::Left | JoinKind::Full => result.push(record.clone()),
+                JoinKind::Inner | JoinKind::Right => {}
+            }
+        }
+    }
+
+    if kind == JoinKind::Right || kind == JoinKind::Full {
+        for (record, matched) in right.iter().zip(matched) {
+            if !matched {
+                result.push(record.clone());
+            }
+        }
+    }
+
+    Ok(JsonValue::Array(result))
+}
+
+fn join_key<'a>(record: &'a JsonValue, key: &str) -> Option<&'a JsonValue> {
+    match record.pointer(key) {
+        Some(&JsonValue::Null) | None => None,
+        Some(value)                   => Some(value),
+    }
+}
+
+fn merge_records(left: &JsonValue, right: &JsonValue) -> JsonValue {
+    match (left, right) {
+        (JsonValue::Object(left), JsonValue::Object(right)) => {
+            let mut merged = Object::with_capacity(left.len() + right.len());
+
+            for (key, value) in left.iter().chain(right.iter()) {
+                merged.insert(key, value.clone());
+            }
+
+            JsonValue::Object(merged)
+        },
+        _ => right.clone()
+    }
+}
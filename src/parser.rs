@@ -17,10 +17,20 @@
 // This makes for some ugly code, but it is faster. Hopefully in the future
 // with MIR support the compiler will get smarter about this.
 
-use std::{ str, slice };
-use object::Object;
-use number::Number;
+use std::{ str, slice, mem };
+use std::borrow::Cow;
+use object::{ Object, MultiObject };
+use number::{ Number, NAN, INFINITY };
+use raw::RawJson;
+#[cfg(not(feature = "shared_strings"))]
+use short::MAX_LEN;
+use pool::JsonPool;
+use borrowed::JsonValueRef;
+use error::SizeLimit;
+#[cfg(feature = "simd")]
+use simd;
 use { JsonValue, Error, Result };
+use prelude::*;
 
 // This is not actual max precision, but a threshold at which number parsing
 // kicks into checked math.
@@ -51,6 +61,130 @@ struct Parser<'a> {
 
     // Length of the source
     length: usize,
+
+    // Pool to take heap allocations of new values from, if any
+    pool: Option<&'a mut JsonPool>,
+
+    // Arrays and objects being parsed by `parse_value`, kept on the parser
+    // so that `json::Parser` can reuse the allocation
+    stack: VecDeque<StackBlock<'a>>,
+
+    // Extensions to the JSON grammar that are allowed, and size limits
+    options: ParseOptions,
+
+    // Number of values parsed so far, counted only with a node limit set
+    nodes: usize,
+
+    // Combined length of strings parsed so far, counted only with a string
+    // length limit set
+    string_length: usize,
+}
+
+/// Options for `json::parse_with_options`, enabling extensions to the JSON
+/// grammar and limiting the size of the input. By default everything is
+/// disabled, which is equivalent to `json::parse`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ParseOptions {
+    /// Allow `// line` and `/* block */` comments wherever whitespace is
+    /// allowed. Comments are skipped and don't appear in the result.
+    pub comments: bool,
+
+    /// Maximum length of the source in bytes. Checked before parsing begins.
+    pub max_bytes: Option<usize>,
+
+    /// Maximum number of values in the document, counting every array,
+    /// object and scalar, including the root.
+    pub max_nodes: Option<usize>,
+
+    /// Maximum combined length in bytes of all strings and object keys,
+    /// after unescaping.
+    pub max_string_length: Option<usize>,
+
+    /// What to do when a key appears more than once in the same object.
+    pub duplicate_keys: DuplicateKeys,
+
+    /// Make numbers remember how they were written, such as `1.50` or
+    /// `2E+01`, and serialize them exactly the same way. Numbers with more
+    /// significant digits than `Number` can hold are normalized as usual.
+    pub preserve_number_text: bool,
+
+    /// Produce a `JsonValue::String` holding the source text of numbers that
+    /// `Number` can't represent exactly, instead of rounding them. Those are
+    /// numbers with more than 19 significant digits, or exponents out of the
+    /// `i16` range.
+    pub inexact_numbers_as_strings: bool,
+
+    /// Fail with `Error::InexactNumber` for numbers that would not be
+    /// serialized exactly as they were written, instead of normalizing them.
+    /// Takes precedence over `inexact_numbers_as_strings`.
+    pub exact_numbers: bool,
+
+    /// Produce a `JsonValue::Raw` holding the source text of integers with
+    /// more than 19 significant digits, instead of rounding them. They are
+    /// then serialized exactly as they were written, and can be converted
+    /// with `RawJson::as_u128`, `RawJson::as_i128` or by parsing the text
+    /// into a big integer type of choice. Takes precedence over
+    /// `exact_numbers` and `inexact_numbers_as_strings` for such integers.
+    pub big_integers_as_raw: bool,
+
+    /// Allow the `NaN`, `Infinity` and `-Infinity` literals, producing
+    /// `number::NAN`, `number::INFINITY` and `number::NEG_INFINITY`.
+    pub non_finite_numbers: bool,
+}
+
+impl ParseOptions {
+    /// Options for proxying documents that must not be rewritten: every
+    /// number is parsed with `preserve_number_text`, and numbers that can't
+    /// be preserved fail with `Error::InexactNumber`. Numbers that are not
+    /// modified are then serialized exactly as they appeared in the source.
+    ///
+    /// ```
+    /// use json::ParseOptions;
+    ///
+    /// let source = r#"{"price":1.50,"rate":2.5E-03,"id":1e0}"#;
+    /// let data = json::parse_with_options(source, ParseOptions::lossless()).unwrap();
+    ///
+    /// assert_eq!(data.dump(), source);
+    ///
+    /// let source = "12345678901234567890123";
+    ///
+    /// assert!(json::parse_with_options(source, ParseOptions::lossless()).is_err());
+    /// ```
+    pub fn lossless() -> ParseOptions {
+        ParseOptions {
+            preserve_number_text: true,
+            exact_numbers: true,
+            ..ParseOptions::default()
+        }
+    }
+}
+
+/// Policy for keys appearing more than once in the same object, set with
+/// `ParseOptions::duplicate_keys`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateKeys {
+    /// Keep the value of the last occurrence of the key. This is what
+    /// `json::parse` does.
+    #[default]
+    KeepLast,
+
+    /// Keep the value of the first occurrence of the key.
+    KeepFirst,
+
+    /// Fail with `Error::DuplicateKey`.
+    Error,
+
+    /// Collect the values of all occurrences of the key into an array, in
+    /// order. Keys appearing only once are unaffected.
+    Collect,
+}
+
+// How the value of the current key of an object is to be stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Entry {
+    Insert,
+    Skip,
+    Collect,
 }
 
 
@@ -98,10 +232,15 @@ macro_rules! expect_byte_ignore_whitespace {
         // Don't go straight for the loop, assume we are in the clear first.
         match ch {
             // whitespace
-            9 ... 13 | 32 => {
+            9 ..= 13 | 32 => {
+                #[cfg(feature = "simd")]
+                {
+                    $parser.index = simd::skip_whitespace($parser.source.as_bytes(), $parser.index);
+                }
+
                 loop {
                     match expect_byte!($parser) {
-                        9 ... 13 | 32 => {},
+                        9 ..= 13 | 32 => {},
                         next          => {
                             ch = next;
                             break;
@@ -112,6 +251,10 @@ macro_rules! expect_byte_ignore_whitespace {
             _ => {}
         }
 
+        if ch == b'/' && $parser.options.comments {
+            ch = $parser.skip_comments()?;
+        }
+
         ch
     })
 }
@@ -121,7 +264,11 @@ macro_rules! expect_eof {
     ($parser:ident) => ({
         while !$parser.is_eof() {
             match $parser.read_byte() {
-                9 ... 13 | 32 => $parser.bump(),
+                9 ..= 13 | 32 => $parser.bump(),
+                b'/' if $parser.options.comments => {
+                    $parser.bump();
+                    $parser.skip_comment()?;
+                },
                 _             => {
                     $parser.bump();
                     return $parser.unexpected_character();
@@ -195,6 +342,11 @@ macro_rules! expect_string {
         let result: &str;
         let start = $parser.index;
 
+        #[cfg(feature = "simd")]
+        {
+            $parser.index = simd::find_string_end($parser.source.as_bytes(), start);
+        }
+
         loop {
             let ch = expect_byte!($parser);
             if ALLOWED[ch as usize] {
@@ -209,13 +361,15 @@ macro_rules! expect_string {
                 break;
             }
             if ch == b'\\' {
-                result = try!($parser.read_complex_string(start));
+                result = $parser.read_complex_string(start)?;
                 break;
             }
 
             return $parser.unexpected_character();
         }
 
+        $parser.count_string(result.len())?;
+
         result
     })
 }
@@ -232,7 +386,7 @@ macro_rules! expect_number {
         // in order to avoid an overflow.
         loop {
             if num >= MAX_PRECISION {
-                result = try!($parser.read_big_number(num));
+                result = $parser.read_big_number(num)?;
                 break;
             }
 
@@ -244,7 +398,7 @@ macro_rules! expect_number {
             let ch = $parser.read_byte();
 
             match ch {
-                b'0' ... b'9' => {
+                b'0' ..= b'9' => {
                     $parser.bump();
                     num = num * 10 + (ch - b'0') as u64;
                 },
@@ -272,7 +426,7 @@ macro_rules! allow_number_extensions {
             },
             b'e' | b'E' => {
                 $parser.bump();
-                try!($parser.expect_exponent($num, $e))
+                $parser.expect_exponent($num, $e)?
             },
             _  => $num.into()
         }
@@ -303,7 +457,7 @@ macro_rules! expect_fraction {
         let ch = expect_byte!($parser);
 
         match ch {
-            b'0' ... b'9' => {
+            b'0' ..= b'9' => {
                 if $num < MAX_PRECISION {
                     $num = $num * 10 + (ch - b'0') as u64;
                     $e -= 1;
@@ -330,7 +484,7 @@ macro_rules! expect_fraction {
             let ch = $parser.read_byte();
 
             match ch {
-                b'0' ... b'9' => {
+                b'0' ..= b'9' => {
                     $parser.bump();
                     if $num < MAX_PRECISION {
                         $num = $num * 10 + (ch - b'0') as u64;
@@ -349,7 +503,7 @@ macro_rules! expect_fraction {
                 },
                 b'e' | b'E' => {
                     $parser.bump();
-                    result = try!($parser.expect_exponent($num, $e));
+                    result = $parser.expect_exponent($num, $e)?;
                     break;
                 }
                 _ => {
@@ -365,15 +519,69 @@ macro_rules! expect_fraction {
 
 impl<'a> Parser<'a> {
     pub fn new(source: &'a str) -> Self {
+        Parser::with_buffer(source, Vec::with_capacity(30))
+    }
+
+    fn with_buffer(source: &'a str, buffer: Vec<u8>) -> Self {
         Parser {
-            buffer: Vec::with_capacity(30),
+            buffer,
             source: source,
             byte_ptr: source.as_ptr(),
             index: 0,
             length: source.len(),
+            pool: None,
+            stack: VecDeque::new(),
+            options: ParseOptions::default(),
+            nodes: 0,
+            string_length: 0,
         }
     }
 
+    fn with_options(source: &'a str, options: ParseOptions) -> Self {
+        let mut parser = Parser::new(source);
+        parser.options = options;
+        parser
+    }
+
+    fn with_pool(source: &'a str, pool: &'a mut JsonPool) -> Self {
+        let mut parser = Parser::new(source);
+        parser.pool = Some(pool);
+        parser
+    }
+
+    #[inline(always)]
+    fn new_array(&mut self) -> Vec<JsonValue> {
+        match self.pool {
+            Some(ref mut pool) => pool.take_array(),
+            None               => Vec::with_capacity(2),
+        }
+    }
+
+    #[inline(always)]
+    fn new_object(&mut self) -> Object {
+        match self.pool {
+            Some(ref mut pool) => pool.take_object(),
+            None               => Object::with_capacity(3),
+        }
+    }
+
+    #[inline(always)]
+    fn new_string(&mut self, string: &str) -> JsonValue {
+        // Shared strings are allocated anew either way.
+        #[cfg(not(feature = "shared_strings"))]
+        {
+            if let Some(ref mut pool) = self.pool {
+                if string.len() > MAX_LEN {
+                    let mut pooled = pool.take_string();
+                    pooled.push_str(string);
+                    return JsonValue::String(pooled);
+                }
+            }
+        }
+
+        string.into()
+    }
+
     // Check if we are at the end of the source.
     #[inline(always)]
     fn is_eof(&mut self) -> bool {
@@ -425,6 +633,106 @@ impl<'a> Parser<'a> {
         })
     }
 
+    // Decide how the value of `key` is to be stored in `object`, according to
+    // the duplicate key policy. New keys are inserted right away, holding
+    // `null` until their value is parsed.
+    #[inline(always)]
+    fn enter_key(&self, object: &mut Object, key: &str) -> Result<Entry> {
+        let policy = self.options.duplicate_keys;
+
+        if policy == DuplicateKeys::KeepLast || object.get(key).is_none() {
+            object.insert(key, JsonValue::Null);
+            return Ok(Entry::Insert);
+        }
+
+        match policy {
+            DuplicateKeys::KeepLast  => unreachable!(),
+            DuplicateKeys::KeepFirst => Ok(Entry::Skip),
+            DuplicateKeys::Error     => Err(Error::DuplicateKey(key.into())),
+            DuplicateKeys::Collect   => Ok(Entry::Collect),
+        }
+    }
+
+    // Account for a parsed string against the string length limit.
+    #[inline(always)]
+    fn count_string(&mut self, len: usize) -> Result<()> {
+        if let Some(max) = self.options.max_string_length {
+            self.string_length += len;
+
+            if self.string_length > max {
+                return Err(Error::ExceededSizeLimit(SizeLimit::StringLength));
+            }
+        }
+
+        Ok(())
+    }
+
+    // Account for a new value against the node limit.
+    #[inline(always)]
+    fn count_node(&mut self) -> Result<()> {
+        if let Some(max) = self.options.max_nodes {
+            self.nodes += 1;
+
+            if self.nodes > max {
+                return Err(Error::ExceededSizeLimit(SizeLimit::Nodes));
+            }
+        }
+
+        Ok(())
+    }
+
+    // Skip the rest of a comment after its leading `/` has been consumed.
+    // Line comments may be terminated by the end of the source, block
+    // comments must be closed.
+    fn skip_comment(&mut self) -> Result<()> {
+        match expect_byte!(self) {
+            b'/' => {
+                while !self.is_eof() {
+                    let ch = self.read_byte();
+                    self.bump();
+
+                    if ch == b'\n' {
+                        break;
+                    }
+                }
+            },
+            b'*' => {
+                let mut ch = expect_byte!(self);
+
+                loop {
+                    let next = expect_byte!(self);
+
+                    if ch == b'*' && next == b'/' {
+                        break;
+                    }
+
+                    ch = next;
+                }
+            },
+            _ => return self.unexpected_character(),
+        }
+
+        Ok(())
+    }
+
+    // Skip a comment after its leading `/` has been consumed, along with any
+    // whitespace and comments that follow, returning the next byte.
+    fn skip_comments(&mut self) -> Result<u8> {
+        loop {
+            self.skip_comment()?;
+
+            let mut ch = expect_byte!(self);
+
+            while let 9 ..= 13 | 32 = ch {
+                ch = expect_byte!(self);
+            }
+
+            if ch != b'/' {
+                return Ok(ch);
+            }
+        }
+    }
+
     // Boring
     fn read_hexdec_digit(&mut self) -> Result<u32> {
         let ch = expect_byte!(self);
@@ -514,6 +822,14 @@ impl<'a> Parser<'a> {
         loop {
             if ALLOWED[ch as usize] {
                 self.buffer.push(ch);
+
+                #[cfg(feature = "simd")]
+                {
+                    let end = simd::find_string_end(self.source.as_bytes(), self.index);
+                    self.buffer.extend_from_slice(&self.source.as_bytes()[self.index .. end]);
+                    self.index = end;
+                }
+
                 ch = expect_byte!(self);
                 continue;
             }
@@ -639,10 +955,535 @@ impl<'a> Parser<'a> {
 
     // Parse away!
     fn parse(&mut self) -> Result<JsonValue> {
-        let mut stack = VecDeque::new();
+        if let Some(max) = self.options.max_bytes {
+            if self.length > max {
+                return Err(Error::ExceededSizeLimit(SizeLimit::Bytes));
+            }
+        }
+
+        let ch = expect_byte_ignore_whitespace!(self);
+        let value = self.parse_value(ch)?;
+
+        expect_eof!(self);
+
+        Ok(value)
+    }
+
+    // Turn a string read with `expect_string!` into one borrowing from the
+    // source, unless it was unescaped into the helper buffer.
+    #[inline(always)]
+    fn borrow_string(&self, string: &str) -> Cow<'a, str> {
+        let offset = (string.as_ptr() as usize).wrapping_sub(self.byte_ptr as usize);
+
+        if offset <= self.length && string.len() <= self.length - offset {
+            Cow::Borrowed(&self.source[offset .. offset + string.len()])
+        } else {
+            Cow::Owned(string.into())
+        }
+    }
+
+    // Parse a document into a `JsonValueRef`, see `parse`.
+    fn parse_borrowed(&mut self) -> Result<JsonValueRef<'a>> {
+        if let Some(max) = self.options.max_bytes {
+            if self.length > max {
+                return Err(Error::ExceededSizeLimit(SizeLimit::Bytes));
+            }
+        }
+
+        let ch = expect_byte_ignore_whitespace!(self);
+        let value = self.parse_borrowed_value(ch)?;
+
+        expect_eof!(self);
+
+        Ok(value)
+    }
+
+    // Parse a single value starting with the byte `ch` into a `JsonValueRef`,
+    // the same way `parse_value` does, keeping the arrays and objects being
+    // parsed on a stack rather than recursing.
+    fn parse_borrowed_value(&mut self, mut ch: u8) -> Result<JsonValueRef<'a>> {
+        let mut stack: Vec<BorrowedBlock<'a>> = Vec::new();
+
+        'parsing: loop {
+            self.count_node()?;
+
+            let start = self.index - 1;
+
+            let mut value = match ch {
+                b'[' => {
+                    ch = expect_byte_ignore_whitespace!(self);
+
+                    if ch != b']' {
+                        if stack.len() == DEPTH_LIMIT {
+                            return Err(Error::ExceededDepthLimit);
+                        }
+
+                        stack.push(BorrowedBlock::Array(Vec::new()));
+                        continue 'parsing;
+                    }
+
+                    JsonValueRef::Array(Vec::new())
+                },
+                b'{' => {
+                    ch = expect_byte_ignore_whitespace!(self);
+
+                    if ch != b'}' {
+                        if stack.len() == DEPTH_LIMIT {
+                            return Err(Error::ExceededDepthLimit);
+                        }
+
+                        if ch != b'"' {
+                            return self.unexpected_character();
+                        }
+
+                        let key = expect_string!(self);
+                        let key = self.borrow_string(key);
+
+                        expect!(self, b':');
+
+                        stack.push(BorrowedBlock::Object(Vec::new(), key));
+
+                        ch = expect_byte_ignore_whitespace!(self);
+
+                        continue 'parsing;
+                    }
+
+                    JsonValueRef::Object(Vec::new())
+                },
+                b'"' => {
+                    let string = expect_string!(self);
+                    JsonValueRef::String(self.borrow_string(string))
+                },
+                b'0' => JsonValueRef::Number(allow_number_extensions!(self)),
+                b'1' ..= b'9' => JsonValueRef::Number(expect_number!(self, ch)),
+                b'-' => {
+                    let ch = expect_byte!(self);
+                    JsonValueRef::Number(- match ch {
+                        b'0' => allow_number_extensions!(self),
+                        b'1' ..= b'9' => expect_number!(self, ch),
+                        b'I' if self.options.non_finite_numbers => {
+                            expect_sequence!(self, b'n', b'f', b'i', b'n', b'i', b't', b'y');
+                            INFINITY
+                        },
+                        _    => return self.unexpected_character()
+                    })
+                },
+                b'N' if self.options.non_finite_numbers => {
+                    expect_sequence!(self, b'a', b'N');
+                    JsonValueRef::Number(NAN)
+                },
+                b'I' if self.options.non_finite_numbers => {
+                    expect_sequence!(self, b'n', b'f', b'i', b'n', b'i', b't', b'y');
+                    JsonValueRef::Number(INFINITY)
+                },
+                b't' => {
+                    expect_sequence!(self, b'r', b'u', b'e');
+                    JsonValueRef::Boolean(true)
+                },
+                b'f' => {
+                    expect_sequence!(self, b'a', b'l', b's', b'e');
+                    JsonValueRef::Boolean(false)
+                },
+                b'n' => {
+                    expect_sequence!(self, b'u', b'l', b'l');
+                    JsonValueRef::Null
+                },
+                _    => return self.unexpected_character()
+            };
+
+            if let JsonValueRef::Number(ref mut number) = value {
+                *number = number.with_rounding(&self.source[start..self.index]);
+            }
+
+            loop {
+                match stack.last_mut() {
+                    None => return Ok(value),
+
+                    Some(&mut BorrowedBlock::Array(ref mut array)) => {
+                        array.push(value);
+
+                        match expect_byte_ignore_whitespace!(self) {
+                            b',' => {
+                                ch = expect_byte_ignore_whitespace!(self);
+
+                                continue 'parsing;
+                            },
+                            b']' => {},
+                            _    => return self.unexpected_character()
+                        }
+                    },
+
+                    Some(&mut BorrowedBlock::Object(ref mut object, ref mut key)) => {
+                        object.push((mem::replace(key, Cow::Borrowed("")), value));
+
+                        match expect_byte_ignore_whitespace!(self) {
+                            b',' => {
+                                expect!(self, b'"');
+                                let next = expect_string!(self);
+                                *key = self.borrow_string(next);
+
+                                expect!(self, b':');
+
+                                ch = expect_byte_ignore_whitespace!(self);
+
+                                continue 'parsing;
+                            },
+                            b'}' => {},
+                            _    => return self.unexpected_character()
+                        }
+                    },
+                }
+
+                value = match stack.pop() {
+                    Some(BorrowedBlock::Array(array))      => JsonValueRef::Array(array),
+                    Some(BorrowedBlock::Object(object, _)) => JsonValueRef::Object(object),
+                    None                                   => unreachable!(),
+                };
+            }
+        }
+    }
+
+    // Check that the source is a single valid JSON value, the same way
+    // `parse` would, without building any values.
+    fn validate(&mut self) -> Result<()> {
+        if let Some(max) = self.options.max_bytes {
+            if self.length > max {
+                return Err(Error::ExceededSizeLimit(SizeLimit::Bytes));
+            }
+        }
+
+        let ch = expect_byte_ignore_whitespace!(self);
+
+        self.validate_value(ch)?;
+
+        expect_eof!(self);
+
+        Ok(())
+    }
+
+    // Check a single value starting with the byte `ch`, leaving the parser
+    // right after the value. The stack only keeps the byte closing each
+    // array or object being checked.
+    fn validate_value(&mut self, mut ch: u8) -> Result<()> {
+        let mut stack: Vec<u8> = Vec::new();
+
+        'validating: loop {
+            self.count_node()?;
+
+            match ch {
+                b'[' => {
+                    ch = expect_byte_ignore_whitespace!(self);
+
+                    if ch != b']' {
+                        if stack.len() == DEPTH_LIMIT {
+                            return Err(Error::ExceededDepthLimit);
+                        }
+
+                        stack.push(b']');
+                        continue 'validating;
+                    }
+                },
+                b'{' => {
+                    ch = expect_byte_ignore_whitespace!(self);
+
+                    if ch != b'}' {
+                        if stack.len() == DEPTH_LIMIT {
+                            return Err(Error::ExceededDepthLimit);
+                        }
+
+                        if ch != b'"' {
+                            return self.unexpected_character();
+                        }
+
+                        expect_string!(self);
+                        expect!(self, b':');
+
+                        stack.push(b'}');
+
+                        ch = expect_byte_ignore_whitespace!(self);
+
+                        continue 'validating;
+                    }
+                },
+                b'"' => {
+                    expect_string!(self);
+                },
+                b'0' => {
+                    allow_number_extensions!(self);
+                },
+                b'1' ..= b'9' => {
+                    expect_number!(self, ch);
+                },
+                b'-' => match expect_byte!(self) {
+                    b'0' => {
+                        allow_number_extensions!(self);
+                    },
+                    ch @ b'1' ..= b'9' => {
+                        expect_number!(self, ch);
+                    },
+                    b'I' if self.options.non_finite_numbers => {
+                        expect_sequence!(self, b'n', b'f', b'i', b'n', b'i', b't', b'y');
+                    },
+                    _ => return self.unexpected_character()
+                },
+                b'N' if self.options.non_finite_numbers => {
+                    expect_sequence!(self, b'a', b'N');
+                },
+                b'I' if self.options.non_finite_numbers => {
+                    expect_sequence!(self, b'n', b'f', b'i', b'n', b'i', b't', b'y');
+                },
+                b't' => {
+                    expect_sequence!(self, b'r', b'u', b'e');
+                },
+                b'f' => {
+                    expect_sequence!(self, b'a', b'l', b's', b'e');
+                },
+                b'n' => {
+                    expect_sequence!(self, b'u', b'l', b'l');
+                },
+                _    => return self.unexpected_character()
+            }
+
+            while let Some(&close) = stack.last() {
+                match expect_byte_ignore_whitespace!(self) {
+                    b',' if close == b']' => {
+                        ch = expect_byte_ignore_whitespace!(self);
+
+                        continue 'validating;
+                    },
+                    b',' => {
+                        expect!(self, b'"');
+                        expect_string!(self);
+                        expect!(self, b':');
+
+                        ch = expect_byte_ignore_whitespace!(self);
+
+                        continue 'validating;
+                    },
+                    end if end == close => {
+                        stack.pop();
+                    },
+                    _ => return self.unexpected_character()
+                }
+            }
+
+            return Ok(());
+        }
+    }
+
+    // Parse a single value like `parse`, except that for an object only the
+    // values of `fields` are built. The values of other keys are checked
+    // without building them.
+    fn parse_fields(&mut self, fields: &[&str]) -> Result<JsonValue> {
+        let mut ch = expect_byte_ignore_whitespace!(self);
+
+        if ch != b'{' {
+            let value = self.parse_value(ch)?;
+
+            expect_eof!(self);
+
+            return Ok(value);
+        }
+
+        self.count_node()?;
+
+        let mut object = Object::with_capacity(fields.len());
+
+        ch = expect_byte_ignore_whitespace!(self);
+
+        if ch != b'}' {
+            loop {
+                if ch != b'"' {
+                    return self.unexpected_character();
+                }
+
+                // Keys read from the helper buffer are only valid until the
+                // next string is parsed, copy it out before reading the value.
+                let key = expect_string!(self);
+                let key = if fields.contains(&key) { Some(String::from(key)) } else { None };
+
+                expect!(self, b':');
+
+                ch = expect_byte_ignore_whitespace!(self);
+
+                match key {
+                    Some(key) => {
+                        let value = self.parse_value(ch)?;
+
+                        object.insert_owned(key, value);
+                    },
+                    None => self.validate_value(ch)?,
+                }
+
+                ch = expect_byte_ignore_whitespace!(self);
+
+                match ch {
+                    b',' => ch = expect_byte_ignore_whitespace!(self),
+                    b'}' => break,
+                    _    => return self.unexpected_character()
+                }
+            }
+        }
+
+        expect_eof!(self);
+
+        Ok(JsonValue::Object(object))
+    }
+
+    // Read the entries of a single top level object without parsing their
+    // values, returning the source text of each value instead.
+    fn parse_lazy_entries(&mut self) -> Result<Vec<(Cow<'a, str>, &'a str)>> {
+        let source = self.source;
+        let mut entries = Vec::new();
+
+        expect!(self, b'{');
+
         let mut ch = expect_byte_ignore_whitespace!(self);
 
+        if ch != b'}' {
+            loop {
+                if ch != b'"' {
+                    return self.unexpected_character();
+                }
+
+                let key = expect_string!(self);
+                let key = self.borrow_string(key);
+
+                expect!(self, b':');
+
+                ch = expect_byte_ignore_whitespace!(self);
+
+                let start = self.index - 1;
+
+                self.skip_value(ch)?;
+
+                entries.push((key, &source[start .. self.index]));
+
+                match expect_byte_ignore_whitespace!(self) {
+                    b',' => ch = expect_byte_ignore_whitespace!(self),
+                    b'}' => break,
+                    _    => return self.unexpected_character()
+                }
+            }
+        }
+
+        expect_eof!(self);
+
+        Ok(entries)
+    }
+
+    // Skip over the value starting with the byte `ch`, only making sure that
+    // strings are terminated and brackets are balanced. Anything else is
+    // checked once the value is parsed.
+    fn skip_value(&mut self, mut ch: u8) -> Result<()> {
+        let mut depth = 0usize;
+
+        loop {
+            match ch {
+                b'"' => self.skip_string()?,
+                b'[' | b'{' => depth += 1,
+                b']' | b'}' if depth > 0 => depth -= 1,
+                b'-' | b'0' ..= b'9' | b'a' ..= b'z' | b'A' ..= b'Z' if depth == 0 => {
+                    while !self.is_eof() {
+                        match self.read_byte() {
+                            b',' | b']' | b'}' | 9 ..= 13 | 32 => break,
+                            _                                  => self.bump(),
+                        }
+                    }
+                },
+                _ if depth == 0 => return self.unexpected_character(),
+                _ => {}
+            }
+
+            if depth == 0 {
+                return Ok(());
+            }
+
+            ch = expect_byte!(self);
+        }
+    }
+
+    // Skip the rest of a string after its opening quote has been consumed,
+    // without unescaping it.
+    fn skip_string(&mut self) -> Result<()> {
+        loop {
+            #[cfg(feature = "simd")]
+            {
+                self.index = simd::find_string_end(self.source.as_bytes(), self.index);
+            }
+
+            match expect_byte!(self) {
+                b'"'  => return Ok(()),
+                b'\\' => {
+                    expect_byte!(self);
+                },
+                _     => {}
+            }
+        }
+    }
+
+    // Parse a single top level object, keeping all of its entries in order,
+    // including the ones with duplicate keys.
+    fn parse_multi_object(&mut self) -> Result<MultiObject> {
+        let mut object = MultiObject::new();
+
+        expect!(self, b'{');
+
+        let mut ch = expect_byte_ignore_whitespace!(self);
+
+        if ch != b'}' {
+            loop {
+                if ch != b'"' {
+                    return self.unexpected_character();
+                }
+
+                // Keys read from the helper buffer are only valid until the
+                // next string is parsed, copy it out before reading the value.
+                let key = String::from(expect_string!(self));
+
+                expect!(self, b':');
+
+                ch = expect_byte_ignore_whitespace!(self);
+
+                let value = self.parse_value(ch)?;
+
+                object.push(key, value);
+
+                ch = expect_byte_ignore_whitespace!(self);
+
+                match ch {
+                    b',' => ch = expect_byte_ignore_whitespace!(self),
+                    b'}' => break,
+                    _    => return self.unexpected_character()
+                }
+            }
+        }
+
+        expect_eof!(self);
+
+        Ok(object)
+    }
+
+    // Parse a single value starting with the byte `ch`, leaving the parser
+    // right after the value.
+    fn parse_value(&mut self, ch: u8) -> Result<JsonValue> {
+        // The stack is put back whether parsing succeeds or not, so that its
+        // allocation is kept for the next value.
+        let mut stack = mem::take(&mut self.stack);
+        let result = self.parse_value_on(ch, &mut stack);
+
+        stack.clear();
+        self.stack = stack;
+
+        result
+    }
+
+    fn parse_value_on(&mut self, mut ch: u8, stack: &mut VecDeque<StackBlock<'a>>) -> Result<JsonValue> {
         'parsing: loop {
+            self.count_node()?;
+
+            let start = self.index - 1;
+
             let mut value = match ch {
                 b'[' => {
                     ch = expect_byte_ignore_whitespace!(self);
@@ -652,7 +1493,9 @@ impl<'a> Parser<'a> {
                             return Err(Error::ExceededDepthLimit);
                         }
 
-                        stack.push_back(StackBlock(JsonValue::Array(Vec::with_capacity(2)), ""));
+                        let array = self.new_array();
+
+                        stack.push_back(StackBlock::new(JsonValue::Array(array), Cow::Borrowed("")));
                         continue 'parsing;
                     }
 
@@ -666,7 +1509,7 @@ impl<'a> Parser<'a> {
                             return Err(Error::ExceededDepthLimit);
                         }
 
-                        let mut object = Object::with_capacity(3);
+                        let mut object = self.new_object();
 
                         if ch != b'"' {
                             return self.unexpected_character()
@@ -677,7 +1520,9 @@ impl<'a> Parser<'a> {
                         //let index = object.insert_index(expect_string!(self), JsonValue::Null);
                         expect!(self, b':');
 
-                        stack.push_back(StackBlock(JsonValue::Object(object), key));
+                        let key = self.borrow_string(key);
+
+                        stack.push_back(StackBlock::new(JsonValue::Object(object), key));
 
                         ch = expect_byte_ignore_whitespace!(self);
 
@@ -686,7 +1531,10 @@ impl<'a> Parser<'a> {
 
                     JsonValue::Object(Object::new())
                 },
-                b'"' => expect_string!(self).into(),
+                b'"' => {
+                    let string = expect_string!(self);
+                    self.new_string(string)
+                },
                 b'0' => JsonValue::Number(allow_number_extensions!(self)),
                 b'1' ... b'9' => {
                     JsonValue::Number(expect_number!(self, ch))
@@ -696,9 +1544,21 @@ impl<'a> Parser<'a> {
                     JsonValue::Number(- match ch {
                         b'0' => allow_number_extensions!(self),
                         b'1' ... b'9' => expect_number!(self, ch),
+                        b'I' if self.options.non_finite_numbers => {
+                            expect_sequence!(self, b'n', b'f', b'i', b'n', b'i', b't', b'y');
+                            INFINITY
+                        },
                         _    => return self.unexpected_character()
                     })
                 }
+                b'N' if self.options.non_finite_numbers => {
+                    expect_sequence!(self, b'a', b'N');
+                    JsonValue::Number(NAN)
+                },
+                b'I' if self.options.non_finite_numbers => {
+                    expect_sequence!(self, b'n', b'f', b'i', b'n', b'i', b't', b'y');
+                    JsonValue::Number(INFINITY)
+                },
                 b't' => {
                     expect_sequence!(self, b'r', b'u', b'e');
                     JsonValue::Boolean(true)
@@ -714,15 +1574,43 @@ impl<'a> Parser<'a> {
                 _    => return self.unexpected_character()
             };
 
+            if let JsonValue::Number(ref mut number) = value {
+                *number = number.with_rounding(&self.source[start..self.index]);
+            }
+
+            if self.options.preserve_number_text
+                || self.options.inexact_numbers_as_strings
+                || self.options.exact_numbers
+                || self.options.big_integers_as_raw {
+                // Non-finite numbers have no digits to preserve, unless
+                // they are integers too large for `Number`.
+                if let JsonValue::Number(number) = value {
+                    let text = &self.source[start..self.index];
+
+                    if self.options.big_integers_as_raw
+                        && text.bytes().all(|ch| ch.is_ascii_digit() || ch == b'-')
+                        && (number.is_infinite() || !number.with_source_text(text).has_source_text()) {
+                        value = JsonValue::Raw(RawJson::with_value(text.into(), value));
+                    } else if !number.is_nan() && !number.is_infinite() {
+                        // Numbers that can't be preserved lost some precision.
+                        let preserved = number.with_source_text(text);
+
+                        if self.options.exact_numbers && !preserved.has_source_text() {
+                            return Err(Error::InexactNumber(text.into()));
+                        } else if self.options.inexact_numbers_as_strings && !preserved.has_source_text() {
+                            value = self.new_string(text);
+                        } else if self.options.preserve_number_text {
+                            value = JsonValue::Number(preserved);
+                        }
+                    }
+                }
+            }
+
             'popping: loop {
                 match stack.back_mut() {
-                    None => {
-                        expect_eof!(self);
+                    None => return Ok(value),
 
-                        return Ok(value);
-                    },
-
-                    Some(&mut StackBlock(JsonValue::Array(ref mut array), _)) => {
+                    Some(&mut StackBlock { value: JsonValue::Array(ref mut array), .. }) => {
                         array.push(value);
 
                         ch = expect_byte_ignore_whitespace!(self);
@@ -738,8 +1626,17 @@ impl<'a> Parser<'a> {
                         }
                     },
 
-                    Some(&mut StackBlock(JsonValue::Object(ref mut object), ref mut key )) => {
-                        object.insert(key, value);
+                    Some(&mut StackBlock {
+                        value: JsonValue::Object(ref mut object),
+                        ref mut key,
+                        ref mut entry,
+                        ref mut collected,
+                    }) => {
+                        match *entry {
+                            Entry::Insert  => object.insert(key, value),
+                            Entry::Skip    => {},
+                            Entry::Collect => collect(object, key, value, collected),
+                        }
 
                         ch = expect_byte_ignore_whitespace!(self);
 
@@ -747,8 +1644,8 @@ impl<'a> Parser<'a> {
                             b',' => {
                                 expect!(self, b'"');
                                 let _key = expect_string!(self);
-                                object.insert(_key, JsonValue::Null);
-                                *key = _key;
+                                *entry = self.enter_key(object, _key)?;
+                                *key = self.borrow_string(_key);
 
                                 //*index = object.insert_index(expect_string!(self), JsonValue::Null);
                                 expect!(self, b':');
@@ -766,8 +1663,8 @@ impl<'a> Parser<'a> {
                 }
 
                 value = match stack.pop_back() {
-                    Some(StackBlock(value, _)) => value,
-                    None                       => break 'popping
+                    Some(StackBlock { value, .. }) => value,
+                    None                           => break 'popping
                 }
             }
         }
@@ -775,7 +1672,55 @@ impl<'a> Parser<'a> {
 }
 
 #[derive(Debug)]
-struct StackBlock<'a>(JsonValue, &'a str);
+struct StackBlock<'a> {
+    value: JsonValue,
+
+    // Current key, for objects. Keys unescaped into the helper buffer are
+    // copied, the buffer may be reallocated while the value is parsed.
+    key: Cow<'a, str>,
+
+    // How the value of the current key is to be stored, for objects
+    entry: Entry,
+
+    // Keys whose values were collected into arrays with
+    // `DuplicateKeys::Collect`, for objects
+    collected: Vec<String>,
+}
+
+impl<'a> StackBlock<'a> {
+    fn new(value: JsonValue, key: Cow<'a, str>) -> Self {
+        StackBlock {
+            value,
+            key,
+            entry: Entry::Insert,
+            collected: Vec::new(),
+        }
+    }
+}
+
+// An array or object being parsed by `parse_borrowed_value`, along with
+// the key of the value being parsed, for objects.
+enum BorrowedBlock<'a> {
+    Array(Vec<JsonValueRef<'a>>),
+    Object(Vec<(Cow<'a, str>, JsonValueRef<'a>)>, Cow<'a, str>),
+}
+
+// Store the value of a duplicate key in an array, along with the values of
+// all of its previous occurrences.
+fn collect(object: &mut Object, key: &str, value: JsonValue, collected: &mut Vec<String>) {
+    let existing = object.get_mut(key).expect("Duplicate key must exist");
+
+    if collected.iter().any(|collected| collected == key) {
+        if let JsonValue::Array(ref mut array) = *existing {
+            array.push(value);
+        }
+    } else {
+        let first = existing.take();
+
+        *existing = JsonValue::Array(vec![first, value]);
+        collected.push(key.into());
+    }
+}
 
 // All that hard work, and in the end it's just a single function in the API.
 #[inline]
@@ -783,6 +1728,194 @@ pub fn parse(source: &str) -> Result<JsonValue> {
     Parser::new(source).parse()
 }
 
+/// Parse `source` with extensions to the JSON grammar and size limits set
+/// by `options`.
+///
+/// ```
+/// use json::ParseOptions;
+///
+/// let source = r#"{
+///     // Port to listen on
+///     "port": 8080 /* default */
+/// }"#;
+///
+/// assert!(json::parse(source).is_err());
+///
+/// let config = json::parse_with_options(source, ParseOptions {
+///     comments: true,
+///     ..ParseOptions::default()
+/// }).unwrap();
+///
+/// assert_eq!(config["port"], 8080);
+/// ```
+///
+/// Inputs exceeding one of the size limits are rejected with
+/// `Error::ExceededSizeLimit`, before the whole document is parsed:
+///
+/// ```
+/// use json::{ Error, ParseOptions, SizeLimit };
+///
+/// let options = ParseOptions {
+///     max_nodes: Some(3),
+///     ..ParseOptions::default()
+/// };
+///
+/// assert!(json::parse_with_options("[1, 2]", options).is_ok());
+/// assert_eq!(
+///     json::parse_with_options("[1, 2, 3]", options),
+///     Err(Error::ExceededSizeLimit(SizeLimit::Nodes))
+/// );
+/// ```
+#[inline]
+pub fn parse_with_options(source: &str, options: ParseOptions) -> Result<JsonValue> {
+    Parser::with_options(source, options).parse()
+}
+
+/// Parse `source` like `parse`, after checking that the bytes are valid
+/// UTF-8. Fails with `Error::FailedUtf8Parsing` if they aren't.
+///
+/// With the `simd` feature enabled, runs of ASCII are checked a block of
+/// bytes at a time, and the parser scans strings and whitespace the same
+/// way, which pays off for large documents.
+///
+/// ```
+/// let data = json::parse_bytes(br#"{ "name": "J\u00f6rg" }"#).unwrap();
+///
+/// assert_eq!(data["name"], "Jörg");
+///
+/// assert_eq!(json::parse_bytes(b"\"\xff\""), Err(json::Error::FailedUtf8Parsing));
+/// ```
+pub fn parse_bytes(source: &[u8]) -> Result<JsonValue> {
+    #[cfg(feature = "simd")]
+    let source = simd::validate_utf8(source).ok_or(Error::FailedUtf8Parsing)?;
+    #[cfg(not(feature = "simd"))]
+    let source = str::from_utf8(source).map_err(|_| Error::FailedUtf8Parsing)?;
+
+    parse(source)
+}
+
+/// Parse `source` into a `JsonValueRef`, borrowing strings and object keys
+/// without escape sequences from `source` instead of copying them. See the
+/// `borrowed` module for details.
+///
+/// ```
+/// use json::JsonValueRef;
+///
+/// let source = String::from(r#"["plain", "tab\t"]"#);
+/// let data = json::parse_borrowed(&source).unwrap();
+///
+/// assert!(data[0].is_borrowed());
+/// assert!(!data[1].is_borrowed());
+/// assert_eq!(data[1], "tab\t");
+///
+/// // Detach the document from the source to keep it around longer.
+/// let data: JsonValueRef<'static> = data.into_owned();
+/// drop(source);
+///
+/// assert_eq!(data[0], "plain");
+/// ```
+#[inline]
+pub fn parse_borrowed<'a>(source: &'a str) -> Result<JsonValueRef<'a>> {
+    Parser::new(source).parse_borrowed()
+}
+
+/// Parse `source` into a `JsonValueRef` like `parse_borrowed`, with size
+/// limits and extensions to the JSON grammar set by `options`. Options
+/// concerning number text and duplicate keys don't apply, as numbers are
+/// never rewritten and duplicate keys are all kept.
+#[inline]
+pub fn parse_borrowed_with_options<'a>(source: &'a str, options: ParseOptions) -> Result<JsonValueRef<'a>> {
+    Parser::with_options(source, options).parse_borrowed()
+}
+
+/// Check that `source` is valid JSON, without building a `JsonValue`. Fails
+/// with the same error `parse` would, but takes far less memory and time,
+/// which makes it suitable for screening documents that are passed on as
+/// they are. See `json::stream::validate_reader` for checking documents
+/// as they are read.
+///
+/// ```
+/// assert!(json::validate(r#"{ "id": 1, "tags": ["a", "b"] }"#).is_ok());
+///
+/// assert_eq!(
+///     json::validate("[1, 2,]"),
+///     Err(json::Error::UnexpectedCharacter { ch: ']', line: 1, column: 7 })
+/// );
+/// ```
+#[inline]
+pub fn validate(source: &str) -> Result<()> {
+    Parser::new(source).validate()
+}
+
+/// Check that `source` is valid JSON like `validate`, with size limits and
+/// extensions to the JSON grammar set by `options`. Options concerning
+/// number text and duplicate keys don't apply.
+#[inline]
+pub fn validate_with_options(source: &str, options: ParseOptions) -> Result<()> {
+    Parser::with_options(source, options).validate()
+}
+
+#[inline]
+pub(crate) fn parse_with_pool(source: &str, pool: &mut JsonPool) -> Result<JsonValue> {
+    Parser::with_pool(source, pool).parse()
+}
+
+/// Buffers kept by `json::Parser` between parses.
+#[derive(Debug, Default)]
+pub(crate) struct Scratch {
+    buffer: Vec<u8>,
+    stack: VecDeque<StackBlock<'static>>,
+}
+
+impl Scratch {
+    pub(crate) fn capacity(&self) -> usize {
+        self.buffer.capacity() + self.stack.capacity()
+    }
+}
+
+// Hand the allocation of a stack over to a parser of another source. Keys
+// borrow from the source, but the stack is emptied first, so none of them
+// outlives it.
+fn recycle_stack<'a, 'b>(mut stack: VecDeque<StackBlock<'a>>) -> VecDeque<StackBlock<'b>> {
+    stack.clear();
+
+    // SAFETY: `StackBlock<'a>` and `StackBlock<'b>` only differ in the
+    // lifetime of the `Cow<str>` key, so the two stacks have the same
+    // layout. The stack is empty, so no key borrowed for `'a` can be read
+    // as borrowed for `'b`, and only the allocation is handed over.
+    unsafe { mem::transmute(stack) }
+}
+
+pub(crate) fn parse_with_scratch(source: &str, options: ParseOptions, scratch: &mut Scratch) -> Result<JsonValue> {
+    let mut parser = Parser::with_buffer(source, mem::take(&mut scratch.buffer));
+
+    parser.options = options;
+    parser.buffer.clear();
+    parser.stack = recycle_stack(mem::take(&mut scratch.stack));
+
+    let result = parser.parse();
+
+    scratch.buffer = mem::take(&mut parser.buffer);
+    scratch.stack = recycle_stack(mem::take(&mut parser.stack));
+
+    result
+}
+
+#[inline]
+pub(crate) fn parse_lazy_entries<'a>(source: &'a str) -> Result<Vec<(Cow<'a, str>, &'a str)>> {
+    Parser::new(source).parse_lazy_entries()
+}
+
+#[inline]
+pub(crate) fn parse_multi_object(source: &str) -> Result<MultiObject> {
+    Parser::new(source).parse_multi_object()
+}
+
+#[inline]
+pub(crate) fn parse_fields(source: &str, fields: &[&str]) -> Result<JsonValue> {
+    Parser::new(source).parse_fields(fields)
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -922,4 +2055,20 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn it_should_recycle_stack_allocations() {
+        let key = String::from("key");
+        let mut stack = VecDeque::with_capacity(8);
+
+        stack.push_back(StackBlock::new(JsonValue::new_array(), Cow::Borrowed(key.as_str())));
+
+        let capacity = stack.capacity();
+        let recycled: VecDeque<StackBlock<'static>> = recycle_stack(stack);
+
+        drop(key);
+
+        assert!(recycled.is_empty());
+        assert_eq!(recycled.capacity(), capacity);
+    }
+
 }
\ No newline at end of file
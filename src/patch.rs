@@ -0,0 +1,201 @@
+//! Support for JSON Patch (RFC 6902), describing changes to a document as
+//! a sequence of operations.
+
+use convert::{ FromJson, FromJsonError };
+use value::{ pointer_index, unescape_pointer_token };
+use { JsonValue, Error, Result };
+use prelude::*;
+
+/// A single JSON Patch operation. Paths are JSON Pointers (RFC 6901).
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchOp {
+    /// Add the value at the path, replacing an existing member of an
+    /// object, or inserting into an array. A path ending with `-` appends
+    /// to an array.
+    Add { path: String, value: JsonValue },
+
+    /// Remove the value at the path, which has to exist.
+    Remove { path: String },
+
+    /// Replace the value at the path, which has to exist.
+    Replace { path: String, value: JsonValue },
+
+    /// Remove the value at `from` and add it at `path`.
+    Move { from: String, path: String },
+
+    /// Add a copy of the value at `from` at `path`.
+    Copy { from: String, path: String },
+
+    /// Check that the value at the path is equal to the given value.
+    Test { path: String, value: JsonValue },
+}
+
+/// Reads an operation in its JSON form, such as
+/// `{"op":"add","path":"/a","value":1}`.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// use json::FromJson;
+/// use json::patch::PatchOp;
+///
+/// let patch = json::parse(r#"[
+///     { "op": "replace", "path": "/name", "value": "Bob" },
+///     { "op": "remove", "path": "/tags/0" }
+/// ]"#).unwrap();
+///
+/// let patch = Vec::<PatchOp>::from_json(&patch).unwrap();
+/// let mut data = object!{ "name" => "Alice", "tags" => array!["a", "b"] };
+///
+/// data.apply_patch(&patch).unwrap();
+///
+/// assert_eq!(data, object!{ "name" => "Bob", "tags" => array!["b"] });
+/// # }
+/// ```
+impl FromJson for PatchOp {
+    fn from_json(value: &JsonValue) -> ::std::result::Result<Self, FromJsonError> {
+        if !value.is_object() {
+            return Err(FromJsonError::new("JSON Patch operation", value));
+        }
+
+        let member = |key: &str| -> ::std::result::Result<String, FromJsonError> {
+            String::from_json(&value[key]).map_err(|error| error.at_key(key))
+        };
+
+        let op = member("op")?;
+
+        // `null` is a valid value to add, so check for presence of the key.
+        let operand = || -> ::std::result::Result<JsonValue, FromJsonError> {
+            if value.has_key("value") {
+                Ok(value["value"].clone())
+            } else {
+                Err(FromJsonError::new("any value", &JsonValue::Null).at_key("value"))
+            }
+        };
+
+        Ok(match op.as_str() {
+            "add"     => PatchOp::Add { path: member("path")?, value: operand()? },
+            "remove"  => PatchOp::Remove { path: member("path")? },
+            "replace" => PatchOp::Replace { path: member("path")?, value: operand()? },
+            "move"    => PatchOp::Move { from: member("from")?, path: member("path")? },
+            "copy"    => PatchOp::Copy { from: member("from")?, path: member("path")? },
+            "test"    => PatchOp::Test { path: member("path")?, value: operand()? },
+            _         => return Err(FromJsonError::new("JSON Patch operation name", &value["op"]).at_key("op")),
+        })
+    }
+}
+
+/// Apply all operations in order. The target is only modified if all
+/// operations succeed, otherwise an `Error::PatchFailed` is returned and
+/// the target is left untouched.
+pub fn apply(target: &mut JsonValue, patch: &[PatchOp]) -> Result<()> {
+    let mut result = target.clone();
+
+    for op in patch {
+        apply_op(&mut result, op)?;
+    }
+
+    *target = result;
+
+    Ok(())
+}
+
+fn apply_op(target: &mut JsonValue, op: &PatchOp) -> Result<()> {
+    match *op {
+        PatchOp::Add { ref path, ref value } => add(target, path, value.clone()),
+        PatchOp::Remove { ref path } => remove(target, path).map(|_| ()),
+        PatchOp::Replace { ref path, ref value } => {
+            *get_mut(target, path)? = value.clone();
+            Ok(())
+        },
+        PatchOp::Move { ref from, ref path } => {
+            if from == path {
+                return get_mut(target, from).map(|_| ());
+            }
+
+            if path.starts_with(from.as_str()) && path[from.len()..].starts_with('/') {
+                return failed(format!("can't move \"{}\" into one of its children", from));
+            }
+
+            let value = remove(target, from)?;
+            add(target, path, value)
+        },
+        PatchOp::Copy { ref from, ref path } => {
+            let value = get_mut(target, from)?.clone();
+            add(target, path, value)
+        },
+        PatchOp::Test { ref path, ref value } => {
+            if get_mut(target, path)? == value {
+                Ok(())
+            } else {
+                failed(format!("value at \"{}\" is not {}", path, value.dump()))
+            }
+        },
+    }
+}
+
+fn failed<T>(message: String) -> Result<T> {
+    Err(Error::PatchFailed(message))
+}
+
+fn get_mut<'a>(target: &'a mut JsonValue, path: &str) -> Result<&'a mut JsonValue> {
+    match target.pointer_mut(path) {
+        Some(value) => Ok(value),
+        None        => failed(format!("path \"{}\" does not exist", path)),
+    }
+}
+
+// Split the path into the pointer to the parent and the last, unescaped,
+// token. Fails for the empty path, which has no parent.
+fn split(path: &str) -> Result<(&str, String)> {
+    match path.rfind('/') {
+        Some(index) => Ok((&path[..index], unescape_pointer_token(&path[index + 1..]).into_owned())),
+        None        => failed(format!("path \"{}\" is not a valid JSON Pointer", path)),
+    }
+}
+
+fn add(target: &mut JsonValue, path: &str, value: JsonValue) -> Result<()> {
+    if path.is_empty() {
+        *target = value;
+        return Ok(());
+    }
+
+    let (parent, token) = split(path)?;
+
+    match *get_mut(target, parent)? {
+        JsonValue::Object(ref mut object) => object.insert(&token, value),
+        JsonValue::Array(ref mut vec) => {
+            let index = if token == "-" { Some(vec.len()) } else { pointer_index(&token) };
+
+            match index {
+                Some(index) if index <= vec.len() => vec.insert(index, value),
+                _ => return failed(format!("path \"{}\" is not a valid array index", path)),
+            }
+        },
+        _ => return failed(format!("path \"{}\" does not point into an object or an array", parent)),
+    }
+
+    Ok(())
+}
+
+fn remove(target: &mut JsonValue, path: &str) -> Result<JsonValue> {
+    if path.is_empty() {
+        return failed("can't remove the whole document".into());
+    }
+
+    let (parent, token) = split(path)?;
+
+    let removed = match *get_mut(target, parent)? {
+        JsonValue::Object(ref mut object) => object.remove(&token),
+        JsonValue::Array(ref mut vec) => match pointer_index(&token) {
+            Some(index) if index < vec.len() => Some(vec.remove(index)),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    match removed {
+        Some(value) => Ok(value),
+        None        => failed(format!("path \"{}\" does not exist", path)),
+    }
+}
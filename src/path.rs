@@ -0,0 +1,380 @@
+// Paths to values within a `JsonValue`, see `JsonPath`, and traversals
+// reporting them, see `JsonValue::walk` and `JsonValue::transform`. Paths
+// can also be written with dots and brackets, such as `a.b[0].c`, which is
+// used by `JsonValue::flatten` and `JsonValue::unflatten`.
+
+use std::fmt::{ self, Write };
+use prelude::*;
+
+use object::Object;
+use JsonValue;
+
+/// Single step of a `JsonPath`, either a key of an object or an index of
+/// an array.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Path from a `JsonValue` to one of the values nested within it, as
+/// produced by `JsonValue::walk`. Displayed as a JSON Pointer (RFC 6901).
+///
+/// ```
+/// use json::{ JsonPath, PathSegment };
+///
+/// let mut path = JsonPath::new();
+/// path.push(PathSegment::Key("a/b".into()));
+/// path.push(PathSegment::Index(0));
+///
+/// assert_eq!(path.to_string(), "/a~1b/0");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct JsonPath {
+    segments: Vec<PathSegment>,
+}
+
+impl JsonPath {
+    /// Create an empty path, referring to the root value.
+    pub fn new() -> Self {
+        JsonPath::default()
+    }
+
+    /// Segments of the path, from the root down.
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// Last segment of the path, if it is not empty.
+    pub fn last(&self) -> Option<&PathSegment> {
+        self.segments.last()
+    }
+
+    /// Number of segments in the path.
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Whether the path is empty, referring to the root value.
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Append a segment to the path.
+    pub fn push(&mut self, segment: PathSegment) {
+        self.segments.push(segment);
+    }
+
+    /// Remove the last segment of the path.
+    pub fn pop(&mut self) -> Option<PathSegment> {
+        self.segments.pop()
+    }
+}
+
+impl From<Vec<PathSegment>> for JsonPath {
+    fn from(segments: Vec<PathSegment>) -> Self {
+        JsonPath { segments }
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for segment in &self.segments {
+            match *segment {
+                PathSegment::Key(ref key) => write!(f, "/{}", key.replace('~', "~0").replace('/', "~1"))?,
+                PathSegment::Index(index) => write!(f, "/{}", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Depth-first iterator over a `JsonValue` and all the values nested within
+/// it, along with their paths, see `JsonValue::walk`.
+pub struct Walk<'a> {
+    stack: Vec<(JsonPath, &'a JsonValue)>,
+}
+
+impl<'a> Walk<'a> {
+    pub(crate) fn new(value: &'a JsonValue) -> Self {
+        Walk {
+            stack: vec![(JsonPath::new(), value)],
+        }
+    }
+}
+
+impl<'a> Iterator for Walk<'a> {
+    type Item = (JsonPath, &'a JsonValue);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, value) = self.stack.pop()?;
+
+        // Children are pushed in reverse, so they are visited in order.
+        match *value {
+            JsonValue::Object(ref object) => {
+                for (key, child) in object.iter().rev() {
+                    let mut path = path.clone();
+                    path.push(PathSegment::Key(key.clone()));
+                    self.stack.push((path, child));
+                }
+            },
+            JsonValue::Array(ref vec) => {
+                for (index, child) in vec.iter().enumerate().rev() {
+                    let mut path = path.clone();
+                    path.push(PathSegment::Index(index));
+                    self.stack.push((path, child));
+                }
+            },
+            _ => {}
+        }
+
+        Some((path, value))
+    }
+}
+
+// Call `visit` on `value` and then on everything nested within it,
+// depth-first, see `JsonValue::walk_mut`.
+pub(crate) fn walk_mut<F>(value: &mut JsonValue, path: &mut JsonPath, visit: &mut F)
+where F: FnMut(&JsonPath, &mut JsonValue) {
+    visit(path, value);
+
+    match *value {
+        JsonValue::Object(ref mut object) => {
+            for (key, child) in object.iter_mut() {
+                path.push(PathSegment::Key(key.clone()));
+                walk_mut(child, path, visit);
+                path.pop();
+            }
+        },
+        JsonValue::Array(ref mut vec) => {
+            for (index, child) in vec.iter_mut().enumerate() {
+                path.push(PathSegment::Index(index));
+                walk_mut(child, path, visit);
+                path.pop();
+            }
+        },
+        _ => {}
+    }
+}
+
+/// What to do with a value after it has been visited by a `Visitor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visit {
+    /// Keep the value and go on to visit the values nested within it.
+    Continue,
+    /// Keep the value, but don't visit the values nested within it.
+    Skip,
+    /// Remove the value from its parent array or object. Removing the value
+    /// `transform` was called on replaces it with `null`.
+    Remove,
+}
+
+/// Visitor used by `JsonValue::transform`. Implemented for closures taking
+/// the path and the value being visited.
+pub trait Visitor {
+    /// Visit a value, which can be changed in place.
+    fn visit(&mut self, path: &JsonPath, value: &mut JsonValue) -> Visit;
+}
+
+impl<F> Visitor for F
+where F: FnMut(&JsonPath, &mut JsonValue) -> Visit {
+    fn visit(&mut self, path: &JsonPath, value: &mut JsonValue) -> Visit {
+        self(path, value)
+    }
+}
+
+// Visit `value` and then everything nested within it, depth-first, see
+// `JsonValue::transform`. Returns `false` if `value` should be removed.
+pub(crate) fn transform<V>(value: &mut JsonValue, path: &mut JsonPath, visitor: &mut V) -> bool
+where V: Visitor + ?Sized {
+    match visitor.visit(path, value) {
+        Visit::Continue => {},
+        Visit::Skip => return true,
+        Visit::Remove => return false,
+    }
+
+    match *value {
+        JsonValue::Object(ref mut object) => {
+            object.retain(|key, child| {
+                path.push(PathSegment::Key(key.into()));
+                let keep = transform(child, path, visitor);
+                path.pop();
+                keep
+            });
+        },
+        JsonValue::Array(ref mut vec) => {
+            // Paths hold the index from before any removals.
+            let mut index = 0;
+
+            vec.retain_mut(|child| {
+                path.push(PathSegment::Index(index));
+                let keep = transform(child, path, visitor);
+                path.pop();
+                index += 1;
+                keep
+            });
+        },
+        _ => {}
+    }
+
+    true
+}
+
+// Split a path such as `a.b[0].c` into segments. Returns `None` if the
+// brackets don't hold an index, or dots and brackets are out of place.
+// The empty path has no segments and refers to the root.
+pub(crate) fn parse_dotted(path: &str) -> Option<Vec<PathSegment>> {
+    let mut segments = Vec::new();
+    let mut rest = path;
+
+    // Only the first key may be absent, for paths starting with an index.
+    if !rest.is_empty() && !rest.starts_with('[') {
+        rest = parse_key(rest, &mut segments)?;
+    }
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            rest = parse_key(after, &mut segments)?;
+        } else {
+            let after = rest.strip_prefix('[')?;
+            let end = after.find(']')?;
+            let index = &after[..end];
+
+            if index.is_empty() || !index.bytes().all(|ch| ch.is_ascii_digit()) {
+                return None;
+            }
+
+            segments.push(PathSegment::Index(index.parse().ok()?));
+            rest = &after[end + 1..];
+        }
+    }
+
+    Some(segments)
+}
+
+fn parse_key<'a>(path: &'a str, segments: &mut Vec<PathSegment>) -> Option<&'a str> {
+    let end = path.find(['.', '[']).unwrap_or(path.len());
+
+    if end == 0 {
+        return None;
+    }
+
+    segments.push(PathSegment::Key(path[..end].into()));
+
+    Some(&path[end..])
+}
+
+// Append a key to a path being built.
+fn push_key(path: &mut String, key: &str) {
+    if !path.is_empty() {
+        path.push('.');
+    }
+    path.push_str(key);
+}
+
+// Append an index to a path being built.
+fn push_index(path: &mut String, index: usize) {
+    let _ = write!(path, "[{}]", index);
+}
+
+// Store `value` at the path made of `segments`, creating objects and arrays
+// along the way. An index can be at most the length of the array, appending
+// to it. Anything else in the way, including a value already at the path,
+// is replaced if `replace` is set. Returns `false` if the index is out of
+// bounds, or something is in the way and `replace` isn't set, leaving the
+// containers created so far in place.
+pub(crate) fn set(target: &mut JsonValue, segments: &[PathSegment], value: JsonValue, replace: bool) -> bool {
+    let mut target = target;
+
+    // Whether `target` was created for this path, and isn't in the way.
+    let mut created = false;
+
+    for segment in segments {
+        target = match *segment {
+            PathSegment::Key(ref key) => {
+                if !target.is_object() {
+                    if !replace && !created {
+                        return false;
+                    }
+                    *target = JsonValue::new_object();
+                }
+
+                match *target {
+                    JsonValue::Object(ref mut object) => {
+                        created = object.get(key).is_none();
+                        object.entry(key.as_str()).or_insert(JsonValue::Null)
+                    },
+                    _ => unreachable!(),
+                }
+            },
+            PathSegment::Index(index) => {
+                if !target.is_array() {
+                    if !replace && !created {
+                        return false;
+                    }
+                    *target = JsonValue::new_array();
+                }
+
+                match *target {
+                    JsonValue::Array(ref mut vec) => {
+                        if index > vec.len() {
+                            return false;
+                        }
+                        created = index == vec.len();
+                        if created {
+                            vec.push(JsonValue::Null);
+                        }
+                        &mut vec[index]
+                    },
+                    _ => unreachable!(),
+                }
+            }
+        };
+    }
+
+    if !replace && !created {
+        return false;
+    }
+
+    *target = value;
+
+    true
+}
+
+// Write `segments` as a path with dots and brackets.
+pub(crate) fn to_dotted(segments: &[PathSegment]) -> String {
+    let mut path = String::new();
+
+    for segment in segments {
+        match *segment {
+            PathSegment::Key(ref key) => push_key(&mut path, key),
+            PathSegment::Index(index) => push_index(&mut path, index),
+        }
+    }
+
+    path
+}
+
+// Collect every leaf of `value` into `result`, keyed by its dotted path.
+// Empty arrays and objects are leaves as well.
+pub(crate) fn flatten(value: &JsonValue, path: &mut String, result: &mut Object) {
+    let len = path.len();
+
+    match *value {
+        JsonValue::Object(ref object) if !object.is_empty() => {
+            for (key, value) in object.iter() {
+                push_key(path, key);
+                flatten(value, path, result);
+                path.truncate(len);
+            }
+        },
+        JsonValue::Array(ref vec) if !vec.is_empty() => {
+            for (index, value) in vec.iter().enumerate() {
+                push_index(path, index);
+                flatten(value, path, result);
+                path.truncate(len);
+            }
+        },
+        _ => result.insert(path, value.clone()),
+    }
+}
@@ -0,0 +1,196 @@
+//! Recycling of heap allocations between parses.
+
+use object::Object;
+use parser::{ self, ParseOptions, Scratch };
+use { JsonValue, Result };
+use prelude::*;
+
+// How many allocations of each kind are kept by default.
+const DEFAULT_LIMIT: usize = 1024;
+
+/// A pool of heap allocations taken from dropped `JsonValue`s, reused by
+/// subsequent parses. For services parsing many documents of similar shape,
+/// recycling the previous documents means most parses don't have to touch
+/// the allocator at all.
+///
+/// ```
+/// use json::JsonPool;
+///
+/// let mut pool = JsonPool::new();
+///
+/// for _ in 0..3 {
+///     let data = pool.parse(r#"{"items":[1,2,3],"name":"a string longer than thirty bytes"}"#).unwrap();
+///
+///     assert_eq!(data["items"][1], 2);
+///
+///     // Give the allocations back to the pool once done with the value.
+///     pool.recycle(data);
+/// }
+/// ```
+///
+/// Pooled are `String`s of string values (`Short`s don't allocate), arrays,
+/// and objects. Keys of objects are allocated by the map as usual, and with
+/// the `shared_strings` feature so are string values. Each kind of
+/// allocation is kept up to a limit, anything past that is dropped.
+#[derive(Debug)]
+pub struct JsonPool {
+    strings: Vec<String>,
+    arrays: Vec<Vec<JsonValue>>,
+    objects: Vec<Object>,
+    limit: usize,
+}
+
+impl JsonPool {
+    /// Create a new, empty pool keeping up to 1024 allocations of each kind.
+    pub fn new() -> Self {
+        JsonPool::with_limit(DEFAULT_LIMIT)
+    }
+
+    /// Create a new, empty pool keeping up to `limit` allocations of each kind.
+    pub fn with_limit(limit: usize) -> Self {
+        JsonPool {
+            strings: Vec::new(),
+            arrays: Vec::new(),
+            objects: Vec::new(),
+            limit,
+        }
+    }
+
+    /// Parse the `source` just like `json::parse`, taking allocations
+    /// from the pool whenever it has them.
+    pub fn parse(&mut self, source: &str) -> Result<JsonValue> {
+        parser::parse_with_pool(source, self)
+    }
+
+    /// Take apart the value, putting all of its allocations into the pool.
+    pub fn recycle(&mut self, value: JsonValue) {
+        // Walk the tree with an explicit stack, values built manually can
+        // be nested deeper than the parser would allow.
+        let mut stack = vec![value];
+
+        while let Some(value) = stack.pop() {
+            match value {
+                #[cfg(not(feature = "shared_strings"))]
+                JsonValue::String(mut string) if self.strings.len() < self.limit => {
+                    string.clear();
+                    self.strings.push(string);
+                },
+                JsonValue::Array(mut vec) => {
+                    stack.append(&mut vec);
+
+                    if vec.capacity() > 0 && self.arrays.len() < self.limit {
+                        self.arrays.push(vec);
+                    }
+                },
+                JsonValue::Object(mut object) => {
+                    stack.extend(object.drain(..).map(|(_, value)| value));
+
+                    if object.capacity() > 0 && self.objects.len() < self.limit {
+                        self.objects.push(object);
+                    }
+                },
+                _ => {}
+            }
+        }
+    }
+
+    /// Number of allocations currently held by the pool.
+    pub fn len(&self) -> usize {
+        self.strings.len() + self.arrays.len() + self.objects.len()
+    }
+
+    /// Test if the pool holds no allocations.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drop all allocations held by the pool.
+    pub fn clear(&mut self) {
+        self.strings.clear();
+        self.arrays.clear();
+        self.objects.clear();
+    }
+
+    #[cfg(not(feature = "shared_strings"))]
+    #[inline]
+    pub(crate) fn take_string(&mut self) -> String {
+        self.strings.pop().unwrap_or_default()
+    }
+
+    #[inline]
+    pub(crate) fn take_array(&mut self) -> Vec<JsonValue> {
+        self.arrays.pop().unwrap_or_else(|| Vec::with_capacity(2))
+    }
+
+    #[inline]
+    pub(crate) fn take_object(&mut self) -> Object {
+        self.objects.pop().unwrap_or_else(|| Object::with_capacity(3))
+    }
+}
+
+impl Default for JsonPool {
+    fn default() -> Self {
+        JsonPool::new()
+    }
+}
+
+/// A parser that can be used for any number of documents, keeping the
+/// buffers it needs while parsing between them: the buffer strings with
+/// escape sequences are unescaped into, and the stack of arrays and objects
+/// being parsed. `json::parse` allocates both anew for every document.
+///
+/// ```
+/// use json::Parser;
+///
+/// let mut parser = Parser::new();
+///
+/// for source in &[r#"{"name":"tab\t"}"#, r#"{"name":"new\nline"}"#] {
+///     let data = parser.parse(source).unwrap();
+///
+///     assert!(data["name"].is_string());
+/// }
+/// ```
+///
+/// Values themselves are allocated as usual, see `JsonPool` to recycle
+/// those as well.
+#[derive(Debug, Default)]
+pub struct Parser {
+    scratch: Scratch,
+    options: ParseOptions,
+}
+
+impl Parser {
+    /// Create a new parser, equivalent to `json::parse`.
+    pub fn new() -> Self {
+        Parser::default()
+    }
+
+    /// Create a new parser, equivalent to `json::parse_with_options`.
+    pub fn with_options(options: ParseOptions) -> Self {
+        Parser {
+            scratch: Scratch::default(),
+            options,
+        }
+    }
+
+    /// Options the parser was created with.
+    pub fn options(&self) -> &ParseOptions {
+        &self.options
+    }
+
+    /// Parse the `source`, reusing the buffers from previous parses.
+    pub fn parse(&mut self, source: &str) -> Result<JsonValue> {
+        parser::parse_with_scratch(source, self.options, &mut self.scratch)
+    }
+
+    /// Combined capacity of the buffers, in elements rather than bytes.
+    pub fn capacity(&self) -> usize {
+        self.scratch.capacity()
+    }
+
+    /// Drop the buffers, for instance after parsing an unusually large
+    /// document.
+    pub fn clear(&mut self) {
+        self.scratch = Scratch::default();
+    }
+}
@@ -0,0 +1,147 @@
+use std::fmt;
+use std::borrow::Cow;
+use prelude::*;
+
+use { parser, JsonValue, Result };
+
+/// JSON text that is known to be valid, held by `JsonValue::Raw`. Generators
+/// write it out verbatim, so fragments serialized earlier can be spliced
+/// into larger documents without parsing and serializing them again.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// use json::{ JsonValue, RawJson };
+///
+/// let cached = RawJson::new(r#"{"id":1,"tags":["a","b"]}"#).unwrap();
+///
+/// let response = object!{
+///     "status" => "ok",
+///     "user" => JsonValue::Raw(cached)
+/// };
+///
+/// assert_eq!(response.dump(), r#"{"status":"ok","user":{"id":1,"tags":["a","b"]}}"#);
+/// # }
+/// ```
+///
+/// Pretty printing writes the text as it is as well, without indenting it.
+/// Canonical JSON (`JsonValue::canonical`) is the exception, as it has to
+/// be written in one particular way.
+///
+/// Comparing, hashing and converting into other formats treat
+/// a `JsonValue::Raw` like the value its text parses into. That value is
+/// kept alongside the text, so it's only parsed once. Accessors such as
+/// `as_str` or indexing don't look into it, use `to_value` for that.
+#[derive(Debug, Clone)]
+pub struct RawJson(Box<Inner>);
+
+// Boxed, so that `JsonValue::Raw` takes no more room than a `String`.
+#[derive(Debug, Clone)]
+struct Inner {
+    text: String,
+    // The value the text parses into, `None` for text wrapped with
+    // `new_unchecked`, which is parsed anew whenever it's needed.
+    value: Option<JsonValue>,
+}
+
+impl RawJson {
+    /// Wrap `text`, checking that it is a single valid JSON value first.
+    /// Whitespace around the value is removed.
+    pub fn new<T: Into<String>>(text: T) -> Result<RawJson> {
+        let mut text = text.into();
+
+        let value = parser::parse(&text)?;
+
+        let end = text.trim_end().len();
+        text.truncate(end);
+
+        let start = text.len() - text.trim_start().len();
+        text.drain(..start);
+
+        Ok(RawJson::with_value(text, value))
+    }
+
+    /// Wrap `text` without checking it. Writing out a `JsonValue` holding
+    /// invalid text produces invalid JSON. As the text isn't parsed up
+    /// front, comparing, hashing and converting it parse it every time.
+    pub fn new_unchecked<T: Into<String>>(text: T) -> RawJson {
+        RawJson(Box::new(Inner { text: text.into(), value: None }))
+    }
+
+    /// Serialize `value` once, to be written out as it is from now on.
+    pub fn from_value(value: &JsonValue) -> RawJson {
+        RawJson::with_value(value.dump(), value.clone())
+    }
+
+    // Wrap `text` known to parse into `value`.
+    pub(crate) fn with_value(text: String, value: JsonValue) -> RawJson {
+        RawJson(Box::new(Inner { text, value: Some(value) }))
+    }
+
+    /// Obtain the JSON text.
+    pub fn as_str(&self) -> &str {
+        &self.0.text
+    }
+
+    /// Obtain the JSON text, consuming the `RawJson`.
+    pub fn into_string(self) -> String {
+        self.0.text
+    }
+
+    /// Obtain the integer the text holds, if it is one that fits into
+    /// `u128`. Unlike `JsonValue::as_u128`, this is exact for integers of
+    /// any length, such as those produced by
+    /// `ParseOptions::big_integers_as_raw`.
+    pub fn as_u128(&self) -> Option<u128> {
+        self.0.text.parse().ok()
+    }
+
+    /// Obtain the integer the text holds, if it is one that fits into
+    /// `i128`. See `as_u128`.
+    pub fn as_i128(&self) -> Option<i128> {
+        self.0.text.parse().ok()
+    }
+
+    /// Obtain the value the JSON text parses into. Fails only for invalid
+    /// text wrapped with `new_unchecked`.
+    pub fn to_value(&self) -> Result<JsonValue> {
+        match self.0.value {
+            Some(ref value) => Ok(value.clone()),
+            None            => parser::parse(&self.0.text),
+        }
+    }
+
+    // Borrow the value the JSON text parses into, parsing it only if it
+    // isn't kept already. `None` for invalid text.
+    pub(crate) fn value(&self) -> Option<Cow<'_, JsonValue>> {
+        match self.0.value {
+            Some(ref value) => Some(Cow::Borrowed(value)),
+            None            => parser::parse(&self.0.text).ok().map(Cow::Owned),
+        }
+    }
+}
+
+impl PartialEq for RawJson {
+    fn eq(&self, other: &RawJson) -> bool {
+        if self.0.text == other.0.text {
+            return true;
+        }
+
+        match (self.value(), other.value()) {
+            (Some(a), Some(b)) => a == b,
+            _                  => false,
+        }
+    }
+}
+
+impl fmt::Display for RawJson {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0.text)
+    }
+}
+
+impl From<RawJson> for JsonValue {
+    fn from(raw: RawJson) -> JsonValue {
+        JsonValue::Raw(raw)
+    }
+}
@@ -0,0 +1,319 @@
+// Lenient parsing that keeps going after errors, see `parse_with_recovery`.
+//
+// The structure of the document (brackets, commas, colons) is scanned here,
+// while every scalar is handed to the regular parser, so values are decoded
+// exactly as `json::parse` would decode them.
+
+use error::unexpected_character_at;
+use object::Object;
+use { parse, value, JsonValue, Error };
+use prelude::*;
+
+// How many nested Objects/Arrays are allowed to be parsed, same as with
+// `json::parse`.
+const DEPTH_LIMIT: usize = 512;
+
+/// Parse `source`, continuing past errors instead of stopping at the first
+/// one. Returns a best-effort value along with every error found, in the
+/// order they appear in the source. The list of errors is empty only if
+/// `source` is valid JSON, in which case the value is the same as the one
+/// produced by `json::parse`.
+///
+/// After an error, the parser skips ahead to the next comma or closing
+/// bracket of the enclosing array or object. Members that couldn't be
+/// parsed are left out of the result, while everything around them is kept.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// let (value, errors) = json::parse_with_recovery(r#"{
+///     "id": 42,
+///     "name": Joe,
+///     "tags": ["a", tru, "c"]
+/// }"#);
+///
+/// assert_eq!(value, object!{ "id" => 42, "tags" => array!["a", "c"] });
+/// assert_eq!(errors.len(), 2);
+/// assert_eq!(errors[0].to_string(), "Unexpected character: J at (3:13)");
+/// assert_eq!(errors[1].to_string(), "Unexpected character: , at (4:22)");
+/// # }
+/// ```
+pub fn parse_with_recovery(source: &str) -> (JsonValue, Vec<Error>) {
+    let mut parser = Recovery {
+        source,
+        index: 0,
+        depth: 0,
+        errors: Vec::new(),
+    };
+
+    let value = parser.parse_value().unwrap_or(JsonValue::Null);
+
+    parser.skip_whitespace();
+
+    if parser.index < source.len() {
+        parser.unexpected_character();
+    }
+
+    (value, parser.errors)
+}
+
+struct Recovery<'a> {
+    source: &'a str,
+    index: usize,
+    depth: usize,
+    errors: Vec<Error>,
+}
+
+impl<'a> Recovery<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.source.as_bytes().get(self.index).cloned()
+    }
+
+    fn error(&mut self, error: Error) {
+        // The same error can be found more than once while recovering, such
+        // as the end of the source by every container left open.
+        if self.errors.last() == Some(&error) {
+            return;
+        }
+
+        self.errors.push(error);
+    }
+
+    fn unexpected_character(&mut self) {
+        let error = unexpected_character_at(self.source, self.index);
+        self.error(error);
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') = self.peek() {
+            self.index += 1;
+        }
+    }
+
+    // Skip past a string, starting at its opening quote. Strings that are
+    // not closed before the end of the line end there.
+    fn skip_string(&mut self) {
+        self.index += 1;
+
+        while let Some(ch) = self.peek() {
+            self.index += 1;
+
+            match ch {
+                b'\\' if self.index < self.source.len() => self.index += 1,
+                b'"' | b'\n' => break,
+                _ => {}
+            }
+        }
+    }
+
+    // Skip ahead to the next comma or closing bracket that is not nested
+    // within another array or object, without consuming it.
+    fn skip_to_separator(&mut self) {
+        let mut depth = 0usize;
+
+        while let Some(ch) = self.peek() {
+            match ch {
+                b'"' => {
+                    self.skip_string();
+                    continue;
+                },
+                b'[' | b'{' => depth += 1,
+                b']' | b'}' if depth > 0 => depth -= 1,
+                b',' | b']' | b'}' => return,
+                _ => {}
+            }
+
+            self.index += 1;
+        }
+    }
+
+    // Parse a scalar spanning from `start` to the current index with the
+    // regular parser, adjusting the position of any error it reports.
+    fn parse_token(&mut self, start: usize) -> Option<JsonValue> {
+        let token = &self.source[start..self.index];
+
+        let at = match parse(token) {
+            Ok(value) => return Some(value),
+            Err(Error::UnexpectedCharacter { column, .. }) => {
+                start + token.char_indices().nth(column - 1).map_or(token.len(), |(index, _)| index)
+            },
+            Err(Error::UnexpectedEndOfJson) => self.index,
+            Err(error) => {
+                self.error(error);
+                return None;
+            }
+        };
+
+        let error = unexpected_character_at(self.source, at);
+        self.error(error);
+
+        None
+    }
+
+    // Parse a value, returning `None` if it had to be skipped. The index is
+    // then left at the point of the error.
+    fn parse_value(&mut self) -> Option<JsonValue> {
+        self.skip_whitespace();
+
+        let start = self.index;
+
+        match self.peek() {
+            None => {
+                self.error(Error::UnexpectedEndOfJson);
+                None
+            },
+            Some(b'[') | Some(b'{') if self.depth == DEPTH_LIMIT => {
+                self.error(Error::ExceededDepthLimit);
+                None
+            },
+            Some(b'[') => Some(self.parse_array()),
+            Some(b'{') => Some(self.parse_object()),
+            Some(b'"') => {
+                self.skip_string();
+                self.parse_token(start)
+            },
+            Some(b'-') | Some(b'0' ..= b'9') | Some(b't') | Some(b'f') | Some(b'n') => {
+                while let Some(ch) = self.peek() {
+                    match ch {
+                        b' ' | b'\t' | b'\n' | b'\r' | b',' | b':' |
+                        b'[' | b']' | b'{' | b'}' | b'"' => break,
+                        _ => self.index += 1,
+                    }
+                }
+
+                self.parse_token(start)
+            },
+            Some(_) => {
+                self.unexpected_character();
+                None
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> JsonValue {
+        self.index += 1;
+        self.depth += 1;
+
+        let mut array = Vec::new();
+
+        self.skip_whitespace();
+
+        if self.peek() == Some(b']') {
+            self.index += 1;
+        } else {
+            loop {
+                match self.parse_value() {
+                    Some(value) => array.push(value),
+                    None        => self.skip_to_separator(),
+                }
+
+                if self.next_member(b']') {
+                    break;
+                }
+            }
+        }
+
+        self.depth -= 1;
+
+        JsonValue::Array(array)
+    }
+
+    fn parse_object(&mut self) -> JsonValue {
+        self.index += 1;
+        self.depth += 1;
+
+        let mut object = Object::new();
+
+        self.skip_whitespace();
+
+        if self.peek() == Some(b'}') {
+            self.index += 1;
+        } else {
+            loop {
+                if !self.parse_entry(&mut object) {
+                    self.skip_to_separator();
+                }
+
+                if self.next_member(b'}') {
+                    break;
+                }
+            }
+        }
+
+        self.depth -= 1;
+
+        JsonValue::Object(object)
+    }
+
+    // Parse a key and value pair into `object`, returning `false` if it had
+    // to be skipped.
+    fn parse_entry(&mut self, object: &mut Object) -> bool {
+        self.skip_whitespace();
+
+        let key = match self.peek() {
+            Some(b'"') => match self.parse_value() {
+                Some(JsonValue::Short(key)) => key.as_str().to_owned(),
+                Some(JsonValue::String(key)) => value::into_string(key),
+                _ => return false,
+            },
+            Some(_) => {
+                self.unexpected_character();
+                return false;
+            },
+            None => {
+                self.error(Error::UnexpectedEndOfJson);
+                return false;
+            }
+        };
+
+        self.skip_whitespace();
+
+        if self.peek() != Some(b':') {
+            self.unexpected_character();
+            return false;
+        }
+
+        self.index += 1;
+
+        match self.parse_value() {
+            Some(value) => {
+                object.insert_owned(key, value);
+                true
+            },
+            None => false,
+        }
+    }
+
+    // Handle what follows a member of a container closed by `close`,
+    // returning `true` once the container has been closed.
+    fn next_member(&mut self, close: u8) -> bool {
+        loop {
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(b',') => {
+                    self.index += 1;
+                    return false;
+                },
+                Some(ch) if ch == close => {
+                    self.index += 1;
+                    return true;
+                },
+                // Mismatched bracket, treat it as closing the container.
+                Some(b']') | Some(b'}') => {
+                    self.unexpected_character();
+                    self.index += 1;
+                    return true;
+                },
+                Some(_) => {
+                    self.unexpected_character();
+                    self.skip_to_separator();
+                },
+                None => {
+                    self.error(Error::UnexpectedEndOfJson);
+                    return true;
+                }
+            }
+        }
+    }
+}
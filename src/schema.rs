@@ -0,0 +1,305 @@
+//! Inference of [JSON Schema](https://json-schema.org/) documents from
+//! sample values, to describe APIs that come without one.
+//!
+//! The inferred schema accepts every sample, and is only as strict as the
+//! samples allow:
+//!
+//! - `type` lists all the types that were seen, as a single string if
+//!   there is only one. Numbers are `"integer"` if none of them has a
+//!   fractional part,
+//! - `format` is set for strings if all of them are in the same format, out
+//!   of `date-time`, `date`, `time`, `uuid`, `email` and `uri`,
+//! - `properties` describes every key seen in objects, in the order they
+//!   first appear, and `required` lists the keys found in all of them,
+//! - `items` describes all the members of arrays together.
+
+use number::Number;
+use object::Object;
+use JsonValue;
+use prelude::*;
+
+/// Version of JSON Schema the inferred schemas use.
+pub const SCHEMA_VERSION: &str = "https://json-schema.org/draft/2020-12/schema";
+
+// Types in the order they are listed in `type`.
+const TYPES: [&str; 7] = ["null", "boolean", "integer", "number", "string", "array", "object"];
+
+// Function recognizing a string format.
+type Matcher = fn(&str) -> bool;
+
+// Formats detected in strings, with the functions recognizing them.
+const FORMATS: [(&str, Matcher); 6] = [
+    ("date-time", is_date_time),
+    ("date", is_date),
+    ("time", is_time),
+    ("uuid", is_uuid),
+    ("email", is_email),
+    ("uri", is_uri),
+];
+
+/// Infer a schema describing all of the `samples`, such as responses
+/// captured from the same endpoint.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// let first = object!{ "id" => 1, "email" => "joe@example.com", "tags" => array!["a"] };
+/// let second = object!{ "id" => 2, "email" => "jane@example.com", "score" => 4.5 };
+///
+/// let schema = json::schema::infer(&[&first, &second]);
+///
+/// assert_eq!(schema, object!{
+///     "$schema" => "https://json-schema.org/draft/2020-12/schema",
+///     "type" => "object",
+///     "properties" => object!{
+///         "id" => object!{ "type" => "integer" },
+///         "email" => object!{ "type" => "string", "format" => "email" },
+///         "tags" => object!{ "type" => "array", "items" => object!{ "type" => "string" } },
+///         "score" => object!{ "type" => "number" }
+///     },
+///     "required" => array!["id", "email"]
+/// });
+/// # }
+/// ```
+pub fn infer(samples: &[&JsonValue]) -> JsonValue {
+    let schema = infer_values(samples);
+
+    let mut object = Object::with_capacity(schema.len() + 1);
+
+    object.insert("$schema", SCHEMA_VERSION.into());
+
+    if let JsonValue::Object(schema) = schema {
+        for (key, value) in schema.into_iter() {
+            object.insert_owned(key, value);
+        }
+    }
+
+    JsonValue::Object(object)
+}
+
+fn infer_values(values: &[&JsonValue]) -> JsonValue {
+    // Raw JSON is inferred from the value it parses into.
+    if values.iter().any(|value| matches!(**value, JsonValue::Raw(_))) {
+        let parsed: Vec<JsonValue> = values.iter().map(|value| match **value {
+            // Invalid text is inferred like `null`.
+            JsonValue::Raw(ref raw) => raw.to_value().unwrap_or(JsonValue::Null),
+            ref value               => value.clone(),
+        }).collect();
+
+        return infer_values(&parsed.iter().collect::<Vec<_>>());
+    }
+
+    let mut seen = [false; 7];
+    let mut strings = Vec::new();
+    let mut members = Vec::new();
+    let mut objects = Vec::new();
+
+    for &value in values {
+        let index = match *value {
+            JsonValue::Null                => 0,
+            JsonValue::Boolean(_)          => 1,
+            JsonValue::Number(number)      => if is_integer(number) { 2 } else { 3 },
+            JsonValue::Short(ref value)    => {
+                strings.push(value.as_str());
+                4
+            },
+            JsonValue::String(ref value)   => {
+                strings.push(&**value);
+                4
+            },
+            JsonValue::Array(ref vec)      => {
+                members.extend(vec.iter());
+                5
+            },
+            JsonValue::Object(ref object)  => {
+                objects.push(object);
+                6
+            },
+            JsonValue::Raw(_)              => unreachable!(),
+        };
+
+        seen[index] = true;
+    }
+
+    // Integers are numbers as well.
+    if seen[3] {
+        seen[2] = false;
+    }
+
+    let mut schema = Object::new();
+
+    let mut types = TYPES.iter().zip(seen.iter())
+        .filter(|&(_, &seen)| seen)
+        .map(|(&name, _)| JsonValue::from(name))
+        .collect::<Vec<_>>();
+
+    match types.len() {
+        0 => return JsonValue::Object(schema),
+        1 => schema.insert("type", types.remove(0)),
+        _ => schema.insert("type", JsonValue::Array(types)),
+    }
+
+    if !strings.is_empty() {
+        let format = FORMATS.iter().find(|&&(_, matches)| strings.iter().all(|string| matches(string)));
+
+        if let Some(&(name, _)) = format {
+            schema.insert("format", name.into());
+        }
+    }
+
+    if seen[5] {
+        let items = infer_values(&members);
+
+        if !items.is_empty() {
+            schema.insert("items", items);
+        }
+    }
+
+    if !objects.is_empty() {
+        let mut properties: Vec<(&str, Vec<&JsonValue>)> = Vec::new();
+
+        for object in &objects {
+            for (key, value) in object.iter() {
+                match properties.iter().position(|&(name, _)| name == key) {
+                    Some(index) => properties[index].1.push(value),
+                    None        => properties.push((key, vec![value])),
+                }
+            }
+        }
+
+        let required = properties.iter()
+            .filter(|(_, values)| values.len() == objects.len())
+            .map(|&(key, _)| JsonValue::from(key))
+            .collect::<Vec<_>>();
+
+        let mut described = Object::with_capacity(properties.len());
+
+        for (key, values) in properties {
+            described.insert(key, infer_values(&values));
+        }
+
+        schema.insert("properties", JsonValue::Object(described));
+
+        if !required.is_empty() {
+            schema.insert("required", JsonValue::Array(required));
+        }
+    }
+
+    JsonValue::Object(schema)
+}
+
+fn is_integer(number: Number) -> bool {
+    if number.is_nan() || number.is_infinite() {
+        return false;
+    }
+
+    let (_, mantissa, exponent) = number.as_parts();
+
+    exponent >= 0 || (exponent > -20 && mantissa % 10u64.pow(-exponent as u32) == 0)
+}
+
+// Check that `text` is made of ASCII digits.
+fn digits(text: &str) -> bool {
+    !text.is_empty() && text.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+// Check for `text` being `2020-01-31`.
+fn is_date(text: &str) -> bool {
+    let parts = text.split('-').collect::<Vec<_>>();
+
+    match parts[..] {
+        [year, month, day] if year.len() == 4 && month.len() == 2 && day.len() == 2 => {
+            digits(year) && digits(month) && digits(day)
+                && (1..=12).contains(&month.parse::<u8>().unwrap_or(0))
+                && (1..=31).contains(&day.parse::<u8>().unwrap_or(0))
+        },
+        _ => false,
+    }
+}
+
+// Check for `text` being `12:30:00`, optionally with a fraction of seconds
+// and an offset such as `Z` or `+02:00`.
+fn is_time(text: &str) -> bool {
+    let (time, offset) = match text.find(&['Z', 'z', '+', '-'][..]) {
+        Some(index) => text.split_at(index),
+        None        => (text, ""),
+    };
+
+    let time = match time.find('.') {
+        Some(index) if digits(&time[index + 1..]) => &time[..index],
+        Some(_)     => return false,
+        None        => time,
+    };
+
+    let offset_valid = match offset {
+        "" | "Z" | "z" => true,
+        _ => offset.len() == 6 && is_clock(&offset[1..], false),
+    };
+
+    is_clock(time, true) && offset_valid
+}
+
+// Check for `hh:mm`, or `hh:mm:ss` if `seconds` is set.
+fn is_clock(text: &str, seconds: bool) -> bool {
+    let parts = text.split(':').collect::<Vec<_>>();
+
+    if parts.len() != if seconds { 3 } else { 2 } {
+        return false;
+    }
+
+    let limits = [23, 59, 60];
+
+    parts.iter().zip(limits.iter()).all(|(part, &limit)| {
+        part.len() == 2 && digits(part) && part.parse::<u8>().unwrap_or(u8::MAX) <= limit
+    })
+}
+
+// Check for `text` being an RFC 3339 date and time, such as
+// `2020-01-31T12:30:00Z`.
+pub(crate) fn is_date_time(text: &str) -> bool {
+    match text.find(&['T', 't', ' '][..]) {
+        Some(index) => is_date(&text[..index]) && is_time(&text[index + 1..]),
+        None        => false,
+    }
+}
+
+// Check for `text` being a hyphenated UUID.
+fn is_uuid(text: &str) -> bool {
+    let lengths = [8, 4, 4, 4, 12];
+    let parts = text.split('-').collect::<Vec<_>>();
+
+    parts.len() == lengths.len() && parts.iter().zip(lengths.iter()).all(|(part, &len)| {
+        part.len() == len && part.bytes().all(|byte| byte.is_ascii_hexdigit())
+    })
+}
+
+fn is_email(text: &str) -> bool {
+    let mut parts = text.split('@');
+
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(local), Some(domain), None) => {
+            !local.is_empty()
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !text.contains(char::is_whitespace)
+        },
+        _ => false,
+    }
+}
+
+// Check for `text` being an absolute URI with an authority, such as
+// `https://example.com/path`.
+fn is_uri(text: &str) -> bool {
+    match text.find("://") {
+        Some(index) if index > 0 => {
+            let scheme = &text[..index];
+
+            scheme.starts_with(|ch: char| ch.is_ascii_alphabetic())
+                && scheme.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '+' || ch == '-' || ch == '.')
+                && text.len() > index + 3
+                && !text.contains(char::is_whitespace)
+        },
+        _ => false,
+    }
+}
@@ -0,0 +1,201 @@
+// Implementations of `serde::Serialize` and `serde::Deserialize`, enabled
+// with the `serde` feature. This makes it possible to embed `JsonValue`s in
+// types (de)serialized by any serde format.
+
+use std::fmt;
+use prelude::*;
+
+use serde::de::{ self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor };
+use serde::ser::{ self, Serialize, Serializer, SerializeMap, SerializeSeq };
+
+use number::Number;
+use object::Object;
+use JsonValue;
+
+impl Serialize for JsonValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            JsonValue::Null               => serializer.serialize_unit(),
+            JsonValue::Short(ref short)   => serializer.serialize_str(short),
+            JsonValue::String(ref string) => serializer.serialize_str(string),
+            JsonValue::Number(ref number) => number.serialize(serializer),
+            JsonValue::Boolean(value)     => serializer.serialize_bool(value),
+            JsonValue::Object(ref object) => object.serialize(serializer),
+            JsonValue::Array(ref vec)     => {
+                let mut seq = serializer.serialize_seq(Some(vec.len()))?;
+
+                for member in vec {
+                    seq.serialize_element(member)?;
+                }
+
+                seq.end()
+            },
+            JsonValue::Raw(ref raw)       => match raw.value() {
+                Some(value) => value.serialize(serializer),
+                None        => Err(ser::Error::custom("raw JSON text is invalid")),
+            },
+        }
+    }
+}
+
+impl Serialize for Object {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+
+        for (key, value) in self.iter() {
+            map.serialize_entry(key, value)?;
+        }
+
+        map.end()
+    }
+}
+
+/// Integers are serialized as `u64` or `i64` where they fit, everything
+/// else as `f64`. NaN is serialized as a unit, same as `null`.
+impl Serialize for Number {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.is_nan() {
+            return serializer.serialize_unit();
+        }
+
+        let (positive, mantissa, exponent) = self.as_parts();
+
+        let integer = if exponent >= 0 {
+            10u64.checked_pow(exponent as u32).and_then(|power| mantissa.checked_mul(power))
+        } else {
+            None
+        };
+
+        match integer {
+            Some(integer) if positive => serializer.serialize_u64(integer),
+            Some(integer) if integer <= i64::MAX as u64 + 1 => {
+                serializer.serialize_i64((integer as i64).wrapping_neg())
+            },
+            _ => serializer.serialize_f64(f64::from(*self)),
+        }
+    }
+}
+
+struct JsonValueVisitor;
+
+impl<'de> Visitor<'de> for JsonValueVisitor {
+    type Value = JsonValue;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any valid JSON value")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<JsonValue, E> {
+        Ok(value.into())
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<JsonValue, E> {
+        Ok(JsonValue::Number(Number::from_i64(value)))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<JsonValue, E> {
+        Ok(value.into())
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<JsonValue, E> {
+        Ok(value.into())
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<JsonValue, E> {
+        Ok(value.into())
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<JsonValue, E> {
+        Ok(value.into())
+    }
+
+    fn visit_unit<E>(self) -> Result<JsonValue, E> {
+        Ok(JsonValue::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<JsonValue, E> {
+        Ok(JsonValue::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<JsonValue, D::Error> {
+        Deserialize::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<JsonValue, A::Error> {
+        let mut vec = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+
+        while let Some(member) = seq.next_element()? {
+            vec.push(member);
+        }
+
+        Ok(JsonValue::Array(vec))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<JsonValue, A::Error> {
+        ObjectVisitor.visit_map(map).map(JsonValue::Object)
+    }
+}
+
+impl<'de> Deserialize<'de> for JsonValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(JsonValueVisitor)
+    }
+}
+
+struct ObjectVisitor;
+
+impl<'de> Visitor<'de> for ObjectVisitor {
+    type Value = Object;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a JSON object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Object, A::Error> {
+        let mut object = Object::with_capacity(map.size_hint().unwrap_or(0));
+
+        while let Some((key, value)) = map.next_entry::<String, JsonValue>()? {
+            object.insert_owned(key, value);
+        }
+
+        Ok(object)
+    }
+}
+
+impl<'de> Deserialize<'de> for Object {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(ObjectVisitor)
+    }
+}
+
+struct NumberVisitor;
+
+impl<'de> Visitor<'de> for NumberVisitor {
+    type Value = Number;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a number")
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Number, E> {
+        Ok(Number::from_i64(value))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Number, E> {
+        Ok(value.into())
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Number, E> {
+        if !value.is_finite() {
+            return Err(E::invalid_value(de::Unexpected::Float(value), &self));
+        }
+
+        Ok(value.into())
+    }
+}
+
+impl<'de> Deserialize<'de> for Number {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(NumberVisitor)
+    }
+}
@@ -1,8 +1,28 @@
 use std::{ ptr, str, slice, fmt };
 use std::ops::Deref;
+use prelude::*;
 
+/// Length of the longest string stored in a `Short`, in bytes. Raised to 46
+/// by the `short_46` feature and to 62 by the `short_62` feature. Objects
+/// take more space than that, so this only makes `JsonValue` larger with
+/// the `compact_value` feature, growing it to 48 or 64 bytes respectively.
+#[cfg(not(any(feature = "short_46", feature = "short_62")))]
 pub const MAX_LEN: usize = 30;
 
+/// Length of the longest string stored in a `Short`, in bytes. Raised to 46
+/// by the `short_46` feature and to 62 by the `short_62` feature. Objects
+/// take more space than that, so this only makes `JsonValue` larger with
+/// the `compact_value` feature, growing it to 48 or 64 bytes respectively.
+#[cfg(all(feature = "short_46", not(feature = "short_62")))]
+pub const MAX_LEN: usize = 46;
+
+/// Length of the longest string stored in a `Short`, in bytes. Raised to 46
+/// by the `short_46` feature and to 62 by the `short_62` feature. Objects
+/// take more space than that, so this only makes `JsonValue` larger with
+/// the `compact_value` feature, growing it to 48 or 64 bytes respectively.
+#[cfg(feature = "short_62")]
+pub const MAX_LEN: usize = 62;
+
 #[derive(Clone, Copy)]
 pub struct Short {
     len: u8,
@@ -32,6 +52,52 @@ impl Short {
         short
     }
 
+    /// Creates a `Short` from a `&str` slice in a `const` context, allowing
+    /// `JsonValue::Short` to be used in `const`s and `static`s. Panics if the
+    /// length of the slice is larger than `MAX_LEN`, which for constants
+    /// means a compile time error.
+    ///
+    /// ```
+    /// # use json::JsonValue;
+    /// # use json::short::Short;
+    /// static GREETING: JsonValue = JsonValue::Short(Short::new("hello"));
+    ///
+    /// assert_eq!(GREETING, "hello");
+    /// ```
+    pub const fn new(slice: &str) -> Self {
+        let bytes = slice.as_bytes();
+
+        assert!(bytes.len() <= MAX_LEN, "Slice is too long to fit in a Short");
+
+        let mut value = [0; MAX_LEN];
+        let mut index = 0;
+
+        while index < bytes.len() {
+            value[index] = bytes[index];
+            index += 1;
+        }
+
+        Short {
+            len: bytes.len() as u8,
+            value,
+        }
+    }
+
+    /// Length of the longest string a `Short` can hold, in bytes, which is
+    /// `MAX_LEN`. Strings up to this length are stored without allocating.
+    ///
+    /// ```
+    /// # use json::JsonValue;
+    /// # use json::short::Short;
+    /// let inline = "x".repeat(Short::capacity());
+    ///
+    /// assert!(matches!(JsonValue::from(&*inline), JsonValue::Short(_)));
+    /// ```
+    #[inline(always)]
+    pub const fn capacity() -> usize {
+        MAX_LEN
+    }
+
     /// Cheaply obtain a `&str` slice out of the `Short`.
     #[inline]
     pub fn as_str(&self) -> &str {
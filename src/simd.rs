@@ -0,0 +1,360 @@
+// Block-wise scanning of the source, used by the parser when the `simd`
+// feature is enabled. Instead of looking at one byte at a time, these look
+// at 16 bytes at once to find the next byte of interest: the end of a run
+// of plain string characters, the end of a run of whitespace, or the first
+// byte that isn't ASCII.
+//
+// On x86_64 this uses SSE2, which every x86_64 CPU has, so no runtime
+// detection is needed. Other targets use a portable fallback working on
+// 8 byte words, which is slower but still beats the byte by byte loop.
+//
+// All functions take the `bytes` and the `index` to start at, and return
+// the index of the first byte of interest, or `bytes.len()` if there isn't
+// any. Indexes past the end are returned as they are.
+
+use std::str;
+
+/// Find the first `"`, `\` or control character at or after `index`.
+#[inline]
+pub fn find_string_end(bytes: &[u8], index: usize) -> usize {
+    imp::find_string_end(bytes, index)
+}
+
+/// Find the first byte that is not whitespace at or after `index`, treating
+/// the same bytes as whitespace as the parser.
+#[inline]
+pub fn skip_whitespace(bytes: &[u8], index: usize) -> usize {
+    imp::skip_whitespace(bytes, index)
+}
+
+/// Find the first byte that is not ASCII at or after `index`.
+#[inline]
+pub fn skip_ascii(bytes: &[u8], index: usize) -> usize {
+    imp::skip_ascii(bytes, index)
+}
+
+/// Check that `bytes` are valid UTF-8 and turn them into a `str`. Runs of
+/// ASCII are skipped block by block, and only the multibyte sequences in
+/// between are decoded.
+pub fn validate_utf8(bytes: &[u8]) -> Option<&str> {
+    let mut index = 0;
+
+    loop {
+        index = skip_ascii(bytes, index);
+
+        if index >= bytes.len() {
+            break;
+        }
+
+        index += sequence_len(&bytes[index..])?;
+    }
+
+    // Validated above.
+    Some(unsafe { str::from_utf8_unchecked(bytes) })
+}
+
+// Length of the UTF-8 sequence at the start of `bytes`, if it's valid.
+// Follows table 3-7 of the Unicode Standard, which rules out overlong
+// encodings, surrogates and code points past U+10FFFF.
+#[inline]
+fn sequence_len(bytes: &[u8]) -> Option<usize> {
+    let (len, min, max) = match bytes[0] {
+        0xC2 ..= 0xDF               => (2, 0x80, 0xBF),
+        0xE0                        => (3, 0xA0, 0xBF),
+        0xE1 ..= 0xEC | 0xEE | 0xEF => (3, 0x80, 0xBF),
+        0xED                        => (3, 0x80, 0x9F),
+        0xF0                        => (4, 0x90, 0xBF),
+        0xF1 ..= 0xF3               => (4, 0x80, 0xBF),
+        0xF4                        => (4, 0x80, 0x8F),
+        _                           => return None,
+    };
+
+    if bytes.len() < len || bytes[1] < min || bytes[1] > max {
+        return None;
+    }
+
+    if bytes[2..len].iter().any(|&byte| byte & 0xC0 != 0x80) {
+        return None;
+    }
+
+    Some(len)
+}
+
+#[inline(always)]
+fn is_string_end(byte: u8) -> bool {
+    byte == b'"' || byte == b'\\' || byte < 0x20
+}
+
+#[inline(always)]
+fn is_whitespace(byte: u8) -> bool {
+    matches!(byte, 9 ..= 13 | 32)
+}
+
+#[cfg(target_arch = "x86_64")]
+mod imp {
+    use std::arch::x86_64::*;
+
+    use super::{ is_string_end, is_whitespace };
+
+    const BLOCK: usize = 16;
+
+    // Bytes that are at most `max`, compared as unsigned.
+    #[inline(always)]
+    unsafe fn at_most(block: __m128i, max: __m128i) -> __m128i {
+        _mm_cmpeq_epi8(_mm_min_epu8(block, max), block)
+    }
+
+    #[inline(always)]
+    unsafe fn load(bytes: &[u8], index: usize) -> __m128i {
+        _mm_loadu_si128(bytes.as_ptr().add(index) as *const __m128i)
+    }
+
+    #[inline]
+    pub fn find_string_end(bytes: &[u8], mut index: usize) -> usize {
+        unsafe {
+            let quote = _mm_set1_epi8(b'"' as i8);
+            let backslash = _mm_set1_epi8(b'\\' as i8);
+            let control = _mm_set1_epi8(0x1F);
+
+            while index + BLOCK <= bytes.len() {
+                let block = load(bytes, index);
+                let found = _mm_or_si128(
+                    _mm_or_si128(_mm_cmpeq_epi8(block, quote), _mm_cmpeq_epi8(block, backslash)),
+                    at_most(block, control)
+                );
+                let mask = _mm_movemask_epi8(found);
+
+                if mask != 0 {
+                    return index + mask.trailing_zeros() as usize;
+                }
+
+                index += BLOCK;
+            }
+        }
+
+        while index < bytes.len() && !is_string_end(bytes[index]) {
+            index += 1;
+        }
+
+        index
+    }
+
+    #[inline]
+    pub fn skip_whitespace(bytes: &[u8], mut index: usize) -> usize {
+        unsafe {
+            let space = _mm_set1_epi8(b' ' as i8);
+            let tab = _mm_set1_epi8(9);
+            let range = _mm_set1_epi8(4);
+
+            while index + BLOCK <= bytes.len() {
+                let block = load(bytes, index);
+                let found = _mm_or_si128(
+                    _mm_cmpeq_epi8(block, space),
+                    at_most(_mm_sub_epi8(block, tab), range)
+                );
+                let mask = !_mm_movemask_epi8(found) & 0xFFFF;
+
+                if mask != 0 {
+                    return index + mask.trailing_zeros() as usize;
+                }
+
+                index += BLOCK;
+            }
+        }
+
+        while index < bytes.len() && is_whitespace(bytes[index]) {
+            index += 1;
+        }
+
+        index
+    }
+
+    #[inline]
+    pub fn skip_ascii(bytes: &[u8], mut index: usize) -> usize {
+        unsafe {
+            while index + BLOCK <= bytes.len() {
+                // The top bit of every byte, set for bytes outside of ASCII.
+                let mask = _mm_movemask_epi8(load(bytes, index));
+
+                if mask != 0 {
+                    return index + mask.trailing_zeros() as usize;
+                }
+
+                index += BLOCK;
+            }
+        }
+
+        while index < bytes.len() && bytes[index] < 0x80 {
+            index += 1;
+        }
+
+        index
+    }
+}
+
+#[cfg_attr(target_arch = "x86_64", allow(dead_code))]
+mod swar {
+    use super::{ is_string_end, is_whitespace };
+
+    const BLOCK: usize = 8;
+    const ONES: u64 = u64::MAX / 0xFF;
+    const HIGH: u64 = ONES * 0x80;
+
+    #[inline(always)]
+    fn load(bytes: &[u8], index: usize) -> u64 {
+        let mut word = [0; BLOCK];
+        word.copy_from_slice(&bytes[index .. index + BLOCK]);
+        u64::from_le_bytes(word)
+    }
+
+    // Sets the top bit of every byte that is zero. Bits above the first
+    // zero byte may be set spuriously, which is fine for finding the first.
+    #[inline(always)]
+    fn zero_bytes(word: u64) -> u64 {
+        word.wrapping_sub(ONES) & !word & HIGH
+    }
+
+    // Sets the top bit of every byte that is below `n`, for `n <= 0x80`,
+    // with the same caveat as `zero_bytes`.
+    #[inline(always)]
+    fn bytes_below(word: u64, n: u8) -> u64 {
+        word.wrapping_sub(ONES * n as u64) & !word & HIGH
+    }
+
+    #[inline(always)]
+    fn first(mask: u64) -> usize {
+        mask.trailing_zeros() as usize / 8
+    }
+
+    #[inline]
+    pub fn find_string_end(bytes: &[u8], mut index: usize) -> usize {
+        while index + BLOCK <= bytes.len() {
+            let word = load(bytes, index);
+            let mask = zero_bytes(word ^ (ONES * b'"' as u64))
+                | zero_bytes(word ^ (ONES * b'\\' as u64))
+                | bytes_below(word, 0x20);
+
+            if mask != 0 {
+                return index + first(mask);
+            }
+
+            index += BLOCK;
+        }
+
+        while index < bytes.len() && !is_string_end(bytes[index]) {
+            index += 1;
+        }
+
+        index
+    }
+
+    #[inline]
+    pub fn skip_whitespace(bytes: &[u8], mut index: usize) -> usize {
+        // Whitespace rarely comes in runs long enough for words to pay off
+        // without SIMD, unless it's all spaces.
+        while index + BLOCK <= bytes.len() && load(bytes, index) == ONES * b' ' as u64 {
+            index += BLOCK;
+        }
+
+        while index < bytes.len() && is_whitespace(bytes[index]) {
+            index += 1;
+        }
+
+        index
+    }
+
+    #[inline]
+    pub fn skip_ascii(bytes: &[u8], mut index: usize) -> usize {
+        while index + BLOCK <= bytes.len() {
+            let mask = load(bytes, index) & HIGH;
+
+            if mask != 0 {
+                return index + first(mask);
+            }
+
+            index += BLOCK;
+        }
+
+        while index < bytes.len() && bytes[index] < 0x80 {
+            index += 1;
+        }
+
+        index
+    }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+use self::swar as imp;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::{ imp, swar };
+
+    // Every byte at every offset, so each one lands in every lane of
+    // a block, as well as in the tail handled a byte at a time. The other
+    // bytes are the lowest and the highest ones that aren't of interest.
+    fn check<F, G>(found: F, expected: G) where F: Fn(&[u8], usize) -> usize, G: Fn(u8) -> bool {
+        let low = (0 ..= 255u8).find(|&byte| !expected(byte)).unwrap();
+        let high = (0 ..= 255u8).rev().find(|&byte| !expected(byte)).unwrap();
+
+        for &filler in &[low, high] {
+            for byte in 0 ..= 255u8 {
+                for len in 1 .. 40 {
+                    for at in 0 .. len {
+                        let mut bytes = vec![filler; len];
+                        bytes[at] = byte;
+
+                        let want = if expected(byte) { at } else { len };
+
+                        assert_eq!(found(&bytes, 0), want, "{:#x} at {} of {}", byte, at, len);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn find_string_end_matches_bytes() {
+        check(imp::find_string_end, is_string_end);
+        check(swar::find_string_end, is_string_end);
+    }
+
+    #[test]
+    fn skip_whitespace_matches_bytes() {
+        check(imp::skip_whitespace, |byte| !is_whitespace(byte));
+        check(swar::skip_whitespace, |byte| !is_whitespace(byte));
+    }
+
+    #[test]
+    fn skip_ascii_matches_bytes() {
+        check(imp::skip_ascii, |byte| byte >= 0x80);
+        check(swar::skip_ascii, |byte| byte >= 0x80);
+    }
+
+    #[test]
+    fn start_past_the_end() {
+        assert_eq!(find_string_end(b"abc", 5), 5);
+        assert_eq!(skip_whitespace(b"   ", 3), 3);
+    }
+
+    #[test]
+    fn validate_utf8_matches_std() {
+        let samples: &[&[u8]] = &[
+            b"plain ascii that is longer than a single block",
+            "zażółć gęślą jaźń, 日本語, 🦀🦀🦀".as_bytes(),
+            b"\xC0\x80",
+            b"\xED\xA0\x80",
+            b"\xF4\x90\x80\x80",
+            b"\xE2\x82",
+            b"aaaaaaaaaaaaaaaaaaaaaaa\xE2\x82",
+            b"aaaaaaaaaaaaaaa\xF0\x9F\xA6\x80aaaaaaaaaaaaaaaaaaaa",
+            b"\x80",
+            b"\xFF",
+        ];
+
+        for sample in samples {
+            assert_eq!(validate_utf8(sample), str::from_utf8(sample).ok(), "{:?}", sample);
+        }
+    }
+}
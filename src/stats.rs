@@ -0,0 +1,91 @@
+// Statistics about the shape of a `JsonValue`, see `JsonValue::stats`.
+
+use prelude::*;
+
+use JsonValue;
+
+/// Counts and sizes describing a `JsonValue` and everything nested within
+/// it, as computed by `JsonValue::stats`.
+///
+/// `JsonValue::Raw` is counted as the value its text parses into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Stats {
+    /// Number of `null`s.
+    pub nulls: usize,
+
+    /// Number of strings, not including object keys.
+    pub strings: usize,
+
+    /// Number of numbers.
+    pub numbers: usize,
+
+    /// Number of booleans.
+    pub booleans: usize,
+
+    /// Number of objects.
+    pub objects: usize,
+
+    /// Number of arrays.
+    pub arrays: usize,
+
+    /// Greatest number of arrays and objects any value is nested within,
+    /// counting the value itself. Zero for a value without any, one for
+    /// an array of numbers.
+    pub max_depth: usize,
+
+    /// Length in bytes of all strings added together, not including object
+    /// keys.
+    pub string_bytes: usize,
+
+    /// Number of members of the largest array.
+    pub largest_array: usize,
+
+    /// Number of entries of the largest object.
+    pub largest_object: usize,
+}
+
+impl Stats {
+    /// Total number of values, including the value itself.
+    pub fn nodes(&self) -> usize {
+        self.nulls + self.strings + self.numbers + self.booleans + self.objects + self.arrays
+    }
+
+    // Values are visited with an explicit stack rather than by recursion, so
+    // deeply nested documents can't overflow the call stack.
+    pub(crate) fn collect(&mut self, value: &JsonValue, depth: usize) {
+        let mut stack = Vec::new();
+        stack.push((value, depth));
+
+        while let Some((value, depth)) = stack.pop() {
+            match *value {
+                JsonValue::Null              => self.nulls += 1,
+                JsonValue::Number(_)         => self.numbers += 1,
+                JsonValue::Boolean(_)        => self.booleans += 1,
+                JsonValue::Short(ref value)  => self.string(value.len()),
+                JsonValue::String(ref value) => self.string(value.len()),
+                JsonValue::Raw(ref raw)      => if let Some(value) = raw.value() {
+                    self.collect(&value, depth)
+                },
+                JsonValue::Array(ref members) => {
+                    self.arrays += 1;
+                    self.max_depth = self.max_depth.max(depth + 1);
+                    self.largest_array = self.largest_array.max(members.len());
+
+                    stack.extend(members.iter().map(|member| (member, depth + 1)));
+                },
+                JsonValue::Object(ref object) => {
+                    self.objects += 1;
+                    self.max_depth = self.max_depth.max(depth + 1);
+                    self.largest_object = self.largest_object.max(object.len());
+
+                    stack.extend(object.iter().map(|(_, value)| (value, depth + 1)));
+                },
+            }
+        }
+    }
+
+    fn string(&mut self, len: usize) {
+        self.strings += 1;
+        self.string_bytes += len;
+    }
+}
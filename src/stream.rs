@@ -0,0 +1,990 @@
+//! Streaming parsing and serialization of JSON. Instead of building a
+//! `JsonValue` tree, the `Parser` in this module produces a sequence of
+//! `Event`s, and the `Writer` serializes values piece by piece. This allows
+//! processing documents far larger than available memory.
+//!
+//! The `FeedParser` does build a `JsonValue`, but is fed the input in chunks
+//! of any size as they arrive, rather than reading it itself, so it can be
+//! used where reads must not block.
+
+use std::io::{ BufRead, BufReader, Read };
+use std::{ mem, str };
+
+use codegen::Generator;
+use number::Number;
+use object::Object;
+use { parse, value, JsonValue, Error, Result };
+
+// How many nested arrays and objects `FeedParser` allows, the same as
+// `json::parse`.
+const DEPTH_LIMIT: usize = 512;
+
+/// A single step of the document produced by `json::stream::Parser`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// Start of an object, followed by pairs of `Key` and value events.
+    StartObject,
+
+    /// End of the most recently started object.
+    EndObject,
+
+    /// Start of an array, followed by events of its members.
+    StartArray,
+
+    /// End of the most recently started array.
+    EndArray,
+
+    /// Key of an object entry, followed by the events of its value.
+    Key(String),
+
+    String(String),
+    Number(Number),
+    Boolean(bool),
+    Null,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Container {
+    Object,
+    Array,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    // Expecting any value.
+    Value,
+
+    // Right after `[`, expecting a value or `]`.
+    FirstValue,
+
+    // Right after `{`, expecting a key or `}`.
+    FirstKey,
+
+    // After a comma within an object, expecting a key.
+    Key,
+
+    // After a value, expecting a comma, the end of the current container,
+    // or the end of input at the top level.
+    Next,
+
+    // After a key, expecting a colon. Only used by `FeedParser`, as
+    // `Parser` reads the colon along with the key.
+    Colon,
+
+    // Finished parsing, or failed to.
+    Done,
+}
+
+/// Pull parser reading JSON from an implementor of `io::Read`, producing
+/// `Event`s as an iterator. Strings and numbers are validated and decoded
+/// the same way `json::parse` does it, and errors carry the line and column
+/// within the whole input.
+///
+/// ```
+/// use json::stream::{ Parser, Event };
+/// use json::number::Number;
+///
+/// let parser = Parser::from(r#"{"ids":[1,2],"ok":true}"#);
+/// let events: Vec<Event> = parser.collect::<json::Result<_>>().unwrap();
+///
+/// assert_eq!(events, vec![
+///     Event::StartObject,
+///     Event::Key("ids".into()),
+///     Event::StartArray,
+///     Event::Number(Number::from(1)),
+///     Event::Number(Number::from(2)),
+///     Event::EndArray,
+///     Event::Key("ok".into()),
+///     Event::Boolean(true),
+///     Event::EndObject,
+/// ]);
+/// ```
+///
+/// After an error is produced the iterator is exhausted.
+pub struct Parser<R: Read> {
+    reader: BufReader<R>,
+    stack: Vec<Container>,
+    state: State,
+    scratch: Vec<u8>,
+    line: usize,
+    column: usize,
+}
+
+impl<R: Read> Parser<R> {
+    /// Create a new parser reading from `reader`. Reads are buffered,
+    /// there is no need to wrap the reader in a `BufReader`.
+    pub fn new(reader: R) -> Self {
+        Parser {
+            reader: BufReader::new(reader),
+            stack: Vec::new(),
+            state: State::Value,
+            scratch: Vec::new(),
+            line: 1,
+            column: 0,
+        }
+    }
+
+    /// Number of containers the parser is currently within.
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>> {
+        Ok(self.reader.fill_buf()?.first().cloned())
+    }
+
+    fn bump(&mut self, ch: u8) {
+        self.reader.consume(1);
+
+        if ch == b'\n' {
+            self.line += 1;
+            self.column = 0;
+        } else if ch & 0xC0 != 0x80 {
+            self.column += 1;
+        }
+    }
+
+    fn next_byte(&mut self) -> Result<u8> {
+        match self.peek()? {
+            Some(ch) => {
+                self.bump(ch);
+                Ok(ch)
+            },
+            None => Err(Error::UnexpectedEndOfJson),
+        }
+    }
+
+    // Skip whitespace, returning the next byte without consuming it.
+    fn peek_token(&mut self) -> Result<Option<u8>> {
+        while let Some(ch) = self.peek()? {
+            match ch {
+                b' ' | b'\t' | b'\n' | b'\r' => self.bump(ch),
+                _ => return Ok(Some(ch)),
+            }
+        }
+
+        Ok(None)
+    }
+
+    // Consume the byte that was just peeked and report it as unexpected.
+    // Multi-byte characters are decoded for the error, as with `json::parse`.
+    fn unexpected_character<T>(&mut self, ch: u8) -> Result<T> {
+        self.bump(ch);
+
+        let mut bytes = vec![ch];
+
+        while bytes.len() < 4 && str::from_utf8(&bytes).is_err() {
+            match self.peek()? {
+                Some(next) if next & 0xC0 == 0x80 => {
+                    self.bump(next);
+                    bytes.push(next);
+                },
+                _ => break,
+            }
+        }
+
+        Err(Error::UnexpectedCharacter {
+            ch: str::from_utf8(&bytes).ok().and_then(|s| s.chars().next()).unwrap_or(ch as char),
+            line: self.line,
+            column: self.column,
+        })
+    }
+
+    // Read the raw bytes of a string, including the quotes, into scratch.
+    fn read_string_token(&mut self) -> Result<()> {
+        let mut escaped = false;
+
+        let quote = self.next_byte()?;
+
+        self.scratch.clear();
+        self.scratch.push(quote);
+
+        loop {
+            let ch = self.next_byte()?;
+
+            self.scratch.push(ch);
+
+            if escaped {
+                escaped = false;
+            } else if ch == b'\\' {
+                escaped = true;
+            } else if ch == b'"' {
+                return Ok(());
+            }
+        }
+    }
+
+    // Read the raw bytes of a number or a literal into scratch.
+    fn read_scalar_token(&mut self) -> Result<()> {
+        self.scratch.clear();
+
+        while let Some(ch) = self.peek()? {
+            match ch {
+                ch if is_scalar_byte(ch) => {
+                    self.bump(ch);
+                    self.scratch.push(ch);
+                },
+                _ => break,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn read_string(&mut self) -> Result<String> {
+        let (line, column) = (self.line, self.column + 1);
+
+        self.read_string_token()?;
+
+        parse_token(&self.scratch, line, column).map(into_key)
+    }
+
+    fn read_value(&mut self, ch: u8) -> Result<Event> {
+        match ch {
+            b'[' => {
+                self.bump(ch);
+                self.stack.push(Container::Array);
+                self.state = State::FirstValue;
+
+                return Ok(Event::StartArray);
+            },
+            b'{' => {
+                self.bump(ch);
+                self.stack.push(Container::Object);
+                self.state = State::FirstKey;
+
+                return Ok(Event::StartObject);
+            },
+            _ => {}
+        }
+
+        let event = match ch {
+            b'"' => Event::String(self.read_string()?),
+            b'-' | b'0' ..= b'9' | b't' | b'f' | b'n' => {
+                let (line, column) = (self.line, self.column + 1);
+
+                self.read_scalar_token()?;
+
+                match parse_token(&self.scratch, line, column)? {
+                    JsonValue::Number(number) => Event::Number(number),
+                    JsonValue::Boolean(value) => Event::Boolean(value),
+                    JsonValue::Null           => Event::Null,
+                    _                         => unreachable!(),
+                }
+            },
+            _ => return self.unexpected_character(ch),
+        };
+
+        self.state = State::Next;
+
+        Ok(event)
+    }
+
+    fn read_key(&mut self) -> Result<Event> {
+        let key = self.read_string()?;
+
+        match self.peek_token()? {
+            Some(b':') => self.bump(b':'),
+            Some(ch)   => return self.unexpected_character(ch),
+            None       => return Err(Error::UnexpectedEndOfJson),
+        }
+
+        self.state = State::Value;
+
+        Ok(Event::Key(key))
+    }
+
+    fn end_container(&mut self, ch: u8, event: Event) -> Result<Event> {
+        self.bump(ch);
+        self.stack.pop();
+        self.state = State::Next;
+
+        Ok(event)
+    }
+
+    fn next_event(&mut self) -> Result<Option<Event>> {
+        loop {
+            if self.state == State::Done {
+                return Ok(None);
+            }
+
+            let ch = match self.peek_token()? {
+                Some(ch) => ch,
+                None if self.state == State::Next && self.stack.is_empty() => {
+                    self.state = State::Done;
+                    return Ok(None);
+                },
+                None => return Err(Error::UnexpectedEndOfJson),
+            };
+
+            let event = match (self.state, ch) {
+                (State::FirstValue, b']') => self.end_container(ch, Event::EndArray),
+                (State::Value, _) |
+                (State::FirstValue, _)    => self.read_value(ch),
+
+                (State::FirstKey, b'}')   => self.end_container(ch, Event::EndObject),
+                (State::FirstKey, b'"') |
+                (State::Key, b'"')        => self.read_key(),
+
+                (State::Next, _) => match (self.stack.last().cloned(), ch) {
+                    (Some(Container::Array), b',') => {
+                        self.bump(ch);
+                        self.state = State::Value;
+                        continue;
+                    },
+                    (Some(Container::Object), b',') => {
+                        self.bump(ch);
+                        self.state = State::Key;
+                        continue;
+                    },
+                    (Some(Container::Array), b']')  => self.end_container(ch, Event::EndArray),
+                    (Some(Container::Object), b'}') => self.end_container(ch, Event::EndObject),
+                    _ => self.unexpected_character(ch),
+                },
+
+                _ => self.unexpected_character(ch),
+            };
+
+            return event.map(Some);
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Parser<&'a [u8]> {
+    fn from(source: &'a str) -> Self {
+        Parser::new(source.as_bytes())
+    }
+}
+
+impl<R: Read> Iterator for Parser<R> {
+    type Item = Result<Event>;
+
+    fn next(&mut self) -> Option<Result<Event>> {
+        match self.next_event() {
+            Ok(event) => event.map(Ok),
+            Err(error) => {
+                self.state = State::Done;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+// Whether `ch` can be part of a number or a literal.
+fn is_scalar_byte(ch: u8) -> bool {
+    matches!(ch, b'0' ..= b'9' | b'a' ..= b'z' | b'A' ..= b'Z' | b'+' | b'-' | b'.')
+}
+
+// Parse a token with `json::parse`, adjusting errors to point at the
+// position in the whole input, given the position the token starts at.
+fn parse_token(token: &[u8], line: usize, column: usize) -> Result<JsonValue> {
+    let source = str::from_utf8(token).map_err(|_| Error::FailedUtf8Parsing)?;
+
+    match parse(source) {
+        Err(Error::UnexpectedCharacter { ch, line: token_line, column: token_column }) => {
+            Err(Error::UnexpectedCharacter {
+                ch,
+                line: line + token_line - 1,
+                column: if token_line == 1 { column + token_column - 1 } else { token_column },
+            })
+        },
+        result => result,
+    }
+}
+
+// Turn a string token parsed with `parse_token` into a key.
+fn into_key(value: JsonValue) -> String {
+    match value {
+        JsonValue::Short(short)   => short.into(),
+        JsonValue::String(string) => value::into_string(string),
+        _                         => unreachable!(),
+    }
+}
+
+/// Whether a `FeedParser` has read a complete value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// The value isn't complete yet, more input is needed.
+    NeedMore,
+
+    /// A complete value was read. Any more input has to be whitespace.
+    Complete,
+}
+
+// Token of a `FeedParser` that was started, but may continue in the next
+// chunk of input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    None,
+    String { escaped: bool },
+    Scalar,
+
+    // Multi-byte character that isn't allowed where it is, read completely
+    // before it's reported.
+    Unexpected,
+}
+
+// Array or object a `FeedParser` is within, along with the key awaiting its
+// value within objects.
+#[derive(Debug)]
+enum Partial {
+    Array(Vec<JsonValue>),
+    Object(Object, String),
+}
+
+/// Push parser, building a `JsonValue` from input fed to it in chunks of
+/// any size, such as the ones arriving from a socket. Only the arrays and
+/// objects being built, and the string or number being read, are kept, so
+/// the input doesn't have to be buffered as a whole.
+///
+/// Strings and numbers are validated and decoded the same way `json::parse`
+/// does it, and errors carry the line and column within the whole input.
+///
+/// ```
+/// use json::stream::{ FeedParser, Status };
+///
+/// let mut parser = FeedParser::new();
+///
+/// assert_eq!(parser.feed(br#"{"name":"J\u00f6"#).unwrap(), Status::NeedMore);
+/// assert_eq!(parser.feed(br#"rg","ids":[1,"#).unwrap(), Status::NeedMore);
+/// assert_eq!(parser.feed(b"2]}\n").unwrap(), Status::Complete);
+///
+/// let data = parser.finish().unwrap();
+///
+/// assert_eq!(data["name"], "Jörg");
+/// assert_eq!(data["ids"][1], 2);
+/// ```
+///
+/// A number at the top level can't be known to be complete until the end of
+/// the input, which is what `finish` is for. After an error the same error
+/// is returned for any more input.
+#[derive(Debug)]
+pub struct FeedParser {
+    stack: Vec<Partial>,
+    root: Option<JsonValue>,
+    state: State,
+    token: Token,
+    scratch: Vec<u8>,
+
+    // Position of the first byte of the token in scratch.
+    token_line: usize,
+    token_column: usize,
+
+    line: usize,
+    column: usize,
+    error: Option<Error>,
+}
+
+impl FeedParser {
+    /// Create a parser that wasn't fed anything yet.
+    pub fn new() -> Self {
+        FeedParser {
+            stack: Vec::new(),
+            root: None,
+            state: State::Value,
+            token: Token::None,
+            scratch: Vec::new(),
+            token_line: 0,
+            token_column: 0,
+            line: 1,
+            column: 0,
+            error: None,
+        }
+    }
+
+    /// Number of containers the parser is currently within.
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Parse the next `chunk` of input, reporting whether a complete value
+    /// was read. Chunks can end anywhere, even within a string or a UTF-8
+    /// sequence.
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<Status> {
+        if let Some(ref error) = self.error {
+            return Err(error.clone());
+        }
+
+        for &ch in chunk {
+            if let Err(error) = self.feed_byte(ch) {
+                self.error = Some(error.clone());
+                return Err(error);
+            }
+        }
+
+        Ok(match self.root {
+            Some(_) => Status::Complete,
+            None    => Status::NeedMore,
+        })
+    }
+
+    /// Signal the end of input, obtaining the value that was read. Fails
+    /// with `Error::UnexpectedEndOfJson` if the value isn't complete.
+    pub fn finish(mut self) -> Result<JsonValue> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        match self.token {
+            Token::None       => {},
+            Token::Scalar     => {
+                let value = parse_token(&self.scratch, self.token_line, self.token_column)?;
+                self.push_value(value);
+            },
+            Token::String{..} => return Err(Error::UnexpectedEndOfJson),
+            Token::Unexpected => return Err(self.unexpected_token()),
+        }
+
+        self.root.ok_or(Error::UnexpectedEndOfJson)
+    }
+
+    fn feed_byte(&mut self, ch: u8) -> Result<()> {
+        if ch == b'\n' {
+            self.line += 1;
+            self.column = 0;
+        } else if ch & 0xC0 != 0x80 {
+            self.column += 1;
+        }
+
+        match self.token {
+            Token::None => {},
+            Token::String { escaped } => {
+                self.scratch.push(ch);
+
+                if escaped {
+                    self.token = Token::String { escaped: false };
+                } else if ch == b'\\' {
+                    self.token = Token::String { escaped: true };
+                } else if ch == b'"' {
+                    self.token = Token::None;
+                    return self.end_string();
+                }
+
+                return Ok(());
+            },
+            Token::Scalar => {
+                if is_scalar_byte(ch) {
+                    self.scratch.push(ch);
+                    return Ok(());
+                }
+
+                self.token = Token::None;
+
+                // A token cut short is reported at the byte ending it, as
+                // `json::parse` would.
+                let value = match parse_token(&self.scratch, self.token_line, self.token_column) {
+                    Err(Error::UnexpectedEndOfJson) => return self.unexpected_character(ch),
+                    result                          => result?,
+                };
+
+                self.push_value(value);
+            },
+            Token::Unexpected => {
+                if ch & 0xC0 == 0x80 {
+                    self.scratch.push(ch);
+
+                    if str::from_utf8(&self.scratch).is_err() && self.scratch.len() < 4 {
+                        return Ok(());
+                    }
+                }
+
+                return Err(self.unexpected_token());
+            },
+        }
+
+        if let b' ' | b'\t' | b'\n' | b'\r' = ch {
+            return Ok(());
+        }
+
+        let container = match self.stack.last() {
+            Some(&Partial::Array(_))     => Some(Container::Array),
+            Some(&Partial::Object(_, _)) => Some(Container::Object),
+            None                         => None,
+        };
+
+        match (self.state, ch) {
+            (State::FirstValue, b']') => self.end_container(),
+            (State::Value, _) |
+            (State::FirstValue, _)    => self.start_value(ch),
+
+            (State::FirstKey, b'}')   => self.end_container(),
+            (State::FirstKey, b'"') |
+            (State::Key, b'"')        => self.start_token(Token::String { escaped: false }, ch),
+
+            (State::Colon, b':')      => {
+                self.state = State::Value;
+                Ok(())
+            },
+
+            (State::Next, _) => match (container, ch) {
+                (Some(Container::Array), b',')  => {
+                    self.state = State::Value;
+                    Ok(())
+                },
+                (Some(Container::Object), b',') => {
+                    self.state = State::Key;
+                    Ok(())
+                },
+                (Some(Container::Array), b']')  |
+                (Some(Container::Object), b'}') => self.end_container(),
+                _ => self.unexpected_character(ch),
+            },
+
+            _ => self.unexpected_character(ch),
+        }
+    }
+
+    fn start_token(&mut self, token: Token, ch: u8) -> Result<()> {
+        self.token = token;
+        self.token_line = self.line;
+        self.token_column = self.column;
+        self.scratch.clear();
+        self.scratch.push(ch);
+
+        Ok(())
+    }
+
+    fn start_value(&mut self, ch: u8) -> Result<()> {
+        match ch {
+            b'[' | b'{' => {
+                if self.stack.len() == DEPTH_LIMIT {
+                    return Err(Error::ExceededDepthLimit);
+                }
+
+                if ch == b'[' {
+                    self.stack.push(Partial::Array(Vec::new()));
+                    self.state = State::FirstValue;
+                } else {
+                    self.stack.push(Partial::Object(Object::new(), String::new()));
+                    self.state = State::FirstKey;
+                }
+
+                Ok(())
+            },
+            b'"' => self.start_token(Token::String { escaped: false }, ch),
+            b'-' | b'0' ..= b'9' | b't' | b'f' | b'n' => self.start_token(Token::Scalar, ch),
+            _ => self.unexpected_character(ch),
+        }
+    }
+
+    fn end_string(&mut self) -> Result<()> {
+        let value = parse_token(&self.scratch, self.token_line, self.token_column)?;
+
+        match (self.state, self.stack.last_mut()) {
+            (State::FirstKey, Some(&mut Partial::Object(_, ref mut key))) |
+            (State::Key, Some(&mut Partial::Object(_, ref mut key))) => {
+                *key = into_key(value);
+                self.state = State::Colon;
+            },
+            _ => self.push_value(value),
+        }
+
+        Ok(())
+    }
+
+    fn end_container(&mut self) -> Result<()> {
+        let value = match self.stack.pop() {
+            Some(Partial::Array(array))       => JsonValue::Array(array),
+            Some(Partial::Object(object, _))  => JsonValue::Object(object),
+            None                              => unreachable!(),
+        };
+
+        self.push_value(value);
+
+        Ok(())
+    }
+
+    fn push_value(&mut self, value: JsonValue) {
+        self.state = State::Next;
+
+        match self.stack.last_mut() {
+            Some(&mut Partial::Array(ref mut array))         => array.push(value),
+            Some(&mut Partial::Object(ref mut object, ref mut key)) => {
+                object.insert_owned(mem::take(key), value);
+            },
+            None => {
+                self.root = Some(value);
+                self.state = State::Done;
+            },
+        }
+    }
+
+    // Report the byte just fed as unexpected. Multi-byte characters are
+    // reported once they were fed completely, as with `json::parse`.
+    fn unexpected_character(&mut self, ch: u8) -> Result<()> {
+        self.start_token(Token::Unexpected, ch)?;
+
+        if ch & 0xC0 == 0xC0 {
+            return Ok(());
+        }
+
+        Err(self.unexpected_token())
+    }
+
+    fn unexpected_token(&self) -> Error {
+        Error::UnexpectedCharacter {
+            ch: str::from_utf8(&self.scratch).ok().and_then(|s| s.chars().next()).unwrap_or(self.scratch[0] as char),
+            line: self.token_line,
+            column: self.token_column,
+        }
+    }
+}
+
+impl Default for FeedParser {
+    fn default() -> Self {
+        FeedParser::new()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Frame {
+    container: Container,
+
+    // Whether any members were written into the container yet.
+    empty: bool,
+
+    // Within objects, whether a key was written that awaits its value.
+    has_key: bool,
+}
+
+/// Incremental JSON serializer, writing values piece by piece through
+/// a `Generator`, without having to build a `JsonValue` tree first.
+/// Well-formedness is checked as the values are written: calls that would
+/// produce invalid JSON fail with `Error::InvalidWrite`.
+///
+/// ```
+/// use json::stream::Writer;
+/// use json::codegen::DumpGenerator;
+///
+/// let mut writer = Writer::new(DumpGenerator::new());
+///
+/// writer.begin_object().unwrap();
+/// writer.key("ids").unwrap();
+/// writer.begin_array().unwrap();
+///
+/// for id in 1..4 {
+///     writer.number(id).unwrap();
+/// }
+///
+/// writer.end_array().unwrap();
+/// writer.key("done").unwrap();
+/// writer.boolean(true).unwrap();
+/// writer.end_object().unwrap();
+///
+/// let output = writer.finish().unwrap().consume();
+///
+/// assert_eq!(output, r#"{"ids":[1,2,3],"done":true}"#);
+/// ```
+///
+/// The output is formatted by the generator, a `PrettyGenerator` produces
+/// the same output as `JsonValue::pretty` would.
+pub struct Writer<G: Generator> {
+    gen: G,
+    stack: Vec<Frame>,
+    written: bool,
+}
+
+impl<G: Generator> Writer<G> {
+    /// Create a new writer writing through `gen`.
+    pub fn new(gen: G) -> Self {
+        Writer {
+            gen,
+            stack: Vec::new(),
+            written: false,
+        }
+    }
+
+    /// Number of containers that are currently open.
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    // Check that a value can be written at this point, and write out
+    // the separator preceding it.
+    fn before_value(&mut self) -> Result<()> {
+        let frame = match self.stack.last_mut() {
+            Some(frame) => frame,
+            None if self.written => return invalid("only one value can be written at the top level"),
+            None => {
+                self.written = true;
+                return Ok(());
+            }
+        };
+
+        match frame.container {
+            Container::Object => {
+                if !frame.has_key {
+                    return invalid("expected a key within an object");
+                }
+                frame.has_key = false;
+            },
+            Container::Array => {
+                if frame.empty {
+                    self.gen.indent();
+                } else {
+                    self.gen.write_item_separator()?;
+                }
+                frame.empty = false;
+                self.gen.new_line()?;
+            },
+        }
+
+        Ok(())
+    }
+
+    fn begin(&mut self, container: Container) -> Result<()> {
+        self.before_value()?;
+        self.gen.write_char(if container == Container::Object { b'{' } else { b'[' })?;
+        self.stack.push(Frame {
+            container,
+            empty: true,
+            has_key: false,
+        });
+
+        Ok(())
+    }
+
+    fn end(&mut self, container: Container) -> Result<()> {
+        match self.stack.last() {
+            Some(frame) if frame.container == container && !frame.has_key => {},
+            Some(frame) if frame.has_key => return invalid("expected a value for the key"),
+            _ if container == Container::Object => return invalid("no object to end"),
+            _ => return invalid("no array to end"),
+        }
+
+        let frame = self.stack.pop().expect("Checked above");
+
+        if !frame.empty {
+            self.gen.dedent();
+            self.gen.new_line()?;
+        }
+
+        self.gen.write_char(if container == Container::Object { b'}' } else { b']' })?;
+
+        Ok(())
+    }
+
+    /// Start a new object.
+    pub fn begin_object(&mut self) -> Result<()> {
+        self.begin(Container::Object)
+    }
+
+    /// End the most recently started object.
+    pub fn end_object(&mut self) -> Result<()> {
+        self.end(Container::Object)
+    }
+
+    /// Start a new array.
+    pub fn begin_array(&mut self) -> Result<()> {
+        self.begin(Container::Array)
+    }
+
+    /// End the most recently started array.
+    pub fn end_array(&mut self) -> Result<()> {
+        self.end(Container::Array)
+    }
+
+    /// Write the key of the next entry of the current object.
+    pub fn key(&mut self, key: &str) -> Result<()> {
+        let frame = match self.stack.last_mut() {
+            Some(frame) if frame.container == Container::Object => frame,
+            _ => return invalid("keys can only be written within an object"),
+        };
+
+        if frame.has_key {
+            return invalid("expected a value for the key");
+        }
+
+        if frame.empty {
+            self.gen.indent();
+        } else {
+            self.gen.write_item_separator()?;
+        }
+
+        frame.empty = false;
+        frame.has_key = true;
+
+        self.gen.new_line()?;
+        self.gen.write_string(key)?;
+        self.gen.write_key_separator()?;
+
+        Ok(())
+    }
+
+    /// Write a complete value.
+    pub fn value(&mut self, value: &JsonValue) -> Result<()> {
+        self.before_value()?;
+        self.gen.write_json(value)?;
+
+        Ok(())
+    }
+
+    /// Write a string value.
+    pub fn string(&mut self, value: &str) -> Result<()> {
+        self.before_value()?;
+        self.gen.write_string(value)?;
+
+        Ok(())
+    }
+
+    /// Write a number value.
+    pub fn number<N: Into<Number>>(&mut self, value: N) -> Result<()> {
+        self.before_value()?;
+        self.gen.write_number(&value.into())?;
+
+        Ok(())
+    }
+
+    /// Write a boolean value.
+    pub fn boolean(&mut self, value: bool) -> Result<()> {
+        self.before_value()?;
+        self.gen.write_bool(value)?;
+
+        Ok(())
+    }
+
+    /// Write a `null`.
+    pub fn null(&mut self) -> Result<()> {
+        self.before_value()?;
+        self.gen.write_null()?;
+
+        Ok(())
+    }
+
+    /// Check that a complete value was written and return the generator.
+    pub fn finish(self) -> Result<G> {
+        if !self.stack.is_empty() {
+            return invalid("not all containers were ended");
+        }
+
+        if !self.written {
+            return invalid("no value was written");
+        }
+
+        Ok(self.gen)
+    }
+}
+
+/// Check that the JSON read from `reader` is valid, without building
+/// a `JsonValue`, by running a `Parser` over it. Only the containers the
+/// parser is within and the string being read are kept in memory, so
+/// documents of any size can be checked. See `json::validate` for
+/// documents already in memory.
+///
+/// ```
+/// use json::stream::validate_reader;
+///
+/// assert!(validate_reader(&br#"{"ids":[1,2]}"#[..]).is_ok());
+/// assert_eq!(validate_reader(&b"[1, 2"[..]), Err(json::Error::UnexpectedEndOfJson));
+/// ```
+pub fn validate_reader<R: Read>(reader: R) -> Result<()> {
+    for event in Parser::new(reader) {
+        event?;
+    }
+
+    Ok(())
+}
+
+fn invalid<T>(message: &str) -> Result<T> {
+    Err(Error::InvalidWrite(message.into()))
+}
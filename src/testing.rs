@@ -0,0 +1,176 @@
+//! Helpers for comparing `JsonValue`s in tests. On failure, the assertion
+//! macros in this module print every difference found, along with a JSON
+//! Pointer (RFC 6901) to where in the document it occurred.
+//!
+//! ```
+//! # #[macro_use] extern crate json;
+//! # fn main() {
+//! let response = json::parse(r#"{"id":7,"tags":["a","b"],"extra":null}"#).unwrap();
+//!
+//! assert_json_eq!(response, object!{
+//!     "id" => 7,
+//!     "tags" => array!["a", "b"],
+//!     "extra" => json::Null
+//! });
+//!
+//! assert_json_contains!(response, object!{ "tags" => array!["a"] });
+//! # }
+//! ```
+
+use JsonValue;
+use prelude::*;
+
+/// Assert that two values are structurally equal, printing all differences
+/// between them on failure. Both arguments can be anything implementing
+/// `ToJson` and `Clone`, and are taken by reference.
+///
+/// ```should_panic
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// // Panics with:
+/// //
+/// // JSON values are not equal:
+/// //   /name: expected "Alice", found "Bob"
+/// //   /admin: missing, expected true
+/// assert_json_eq!(object!{ "name" => "Bob" }, object!{ "name" => "Alice", "admin" => true });
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assert_json_eq {
+    ($actual:expr, $expected:expr) => ({
+        let differences = $crate::testing::diff(
+            &$crate::ToJson::to_json(&$actual),
+            &$crate::ToJson::to_json(&$expected),
+        );
+
+        if !differences.is_empty() {
+            panic!("JSON values are not equal:\n  {}", differences.join("\n  "));
+        }
+    })
+}
+
+/// Assert that the first value contains the second one, printing all
+/// differences on failure. See `json::testing::contains_diff` for the exact
+/// rules. Both arguments can be anything implementing `ToJson` and `Clone`.
+#[macro_export]
+macro_rules! assert_json_contains {
+    ($superset:expr, $subset:expr) => ({
+        let differences = $crate::testing::contains_diff(
+            &$crate::ToJson::to_json(&$superset),
+            &$crate::ToJson::to_json(&$subset),
+        );
+
+        if !differences.is_empty() {
+            panic!("JSON value does not contain the expected subset:\n  {}", differences.join("\n  "));
+        }
+    })
+}
+
+/// List all differences between the `actual` and the `expected` value, one
+/// line per difference. An empty list means the values are equal.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// use json::testing::diff;
+///
+/// let actual = array![1, object!{ "a" => 2 }];
+/// let expected = array![1, object!{ "a" => 3 }, 4];
+///
+/// assert_eq!(diff(&actual, &expected), [
+///     "/1/a: expected 3, found 2",
+///     "/2: missing, expected 4",
+/// ]);
+/// # }
+/// ```
+pub fn diff(actual: &JsonValue, expected: &JsonValue) -> Vec<String> {
+    let mut differences = Vec::new();
+
+    compare(actual, expected, false, &mut String::new(), &mut differences);
+
+    differences
+}
+
+/// List all the ways in which `superset` fails to contain `subset`, one line
+/// per difference. An empty list means `superset` contains `subset`.
+///
+/// An object contains another object if it has all of its keys, with values
+/// containing the respective values of the other object. An array contains
+/// another array if every member at a given index contains the member of the
+/// other array at the same index; extra members at the end are allowed. Any
+/// other values have to be equal.
+pub fn contains_diff(superset: &JsonValue, subset: &JsonValue) -> Vec<String> {
+    let mut differences = Vec::new();
+
+    compare(superset, subset, true, &mut String::new(), &mut differences);
+
+    differences
+}
+
+fn compare(
+    actual: &JsonValue,
+    expected: &JsonValue,
+    subset: bool,
+    path: &mut String,
+    differences: &mut Vec<String>,
+) {
+    match (actual, expected) {
+        (JsonValue::Object(actual), JsonValue::Object(expected)) => {
+            for (key, expected) in expected.iter() {
+                let length = path.len();
+
+                path.push('/');
+                path.push_str(&key.replace('~', "~0").replace('/', "~1"));
+
+                match actual.get(key) {
+                    Some(actual) => compare(actual, expected, subset, path, differences),
+                    None => differences.push(format!("{}: missing, expected {}", path, expected.dump())),
+                }
+
+                path.truncate(length);
+            }
+
+            if !subset {
+                for (key, actual) in actual.iter() {
+                    if expected.get(key).is_none() {
+                        differences.push(format!(
+                            "{}/{}: unexpected {}",
+                            path,
+                            key.replace('~', "~0").replace('/', "~1"),
+                            actual.dump()
+                        ));
+                    }
+                }
+            }
+        },
+        (JsonValue::Array(actual), JsonValue::Array(expected)) => {
+            for (index, expected) in expected.iter().enumerate() {
+                match actual.get(index) {
+                    Some(actual) => {
+                        let length = path.len();
+
+                        path.push('/');
+                        path.push_str(&index.to_string());
+
+                        compare(actual, expected, subset, path, differences);
+
+                        path.truncate(length);
+                    },
+                    None => differences.push(format!("{}/{}: missing, expected {}", path, index, expected.dump())),
+                }
+            }
+
+            if !subset {
+                for (index, actual) in actual.iter().enumerate().skip(expected.len()) {
+                    differences.push(format!("{}/{}: unexpected {}", path, index, actual.dump()));
+                }
+            }
+        },
+        _ => if actual != expected {
+            // Empty pointer refers to the root value, spell it out.
+            let location = if path.is_empty() { "(root)" } else { path.as_str() };
+
+            differences.push(format!("{}: expected {}, found {}", location, expected.dump(), actual.dump()));
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Conversion between [TOML](https://toml.io/) and `JsonValue`, enabled with
+//! the `toml` feature. TOML documents are parsed into the regular `JsonValue`
+//! tree, following these rules:
+//!
+//! - tables, including inline tables and arrays of tables, become objects,
+//!   keeping the order of their keys,
+//! - integers and floats become numbers, with `inf` and `nan` producing
+//!   `number::INFINITY` and `number::NAN`,
+//! - dates and times become strings in the RFC 3339 format they are written
+//!   in, such as `"1979-05-27T07:32:00Z"` or `"1979-05-27"`.
+//!
+//! TOML has no `null`, and a document is always a table, so only objects
+//! without any `null`s in them can be written as TOML.
+
+use toml_rs::{ self, Value };
+use toml_rs::value::Table;
+
+use number::{ NAN, INFINITY, NEG_INFINITY };
+use object::Object;
+use { JsonValue, Error, Result };
+
+/// Parse a TOML document.
+///
+/// ```
+/// let manifest = json::toml::parse(r#"
+/// [package]
+/// name = "json"
+/// version = "0.11.19"
+/// published = 2020-01-31
+///
+/// [[bin]]
+/// name = "pretty"
+/// "#).unwrap();
+///
+/// assert_eq!(manifest["package"]["name"], "json");
+/// assert_eq!(manifest["package"]["published"], "2020-01-31");
+/// assert_eq!(manifest["bin"][0]["name"], "pretty");
+/// ```
+pub fn parse(source: &str) -> Result<JsonValue> {
+    let table = source.parse::<Value>().map_err(|error| Error::InvalidToml(error.to_string()))?;
+
+    Ok(from_toml(table))
+}
+
+fn from_toml(value: Value) -> JsonValue {
+    match value {
+        Value::String(value)   => value.into(),
+        Value::Integer(value)  => value.into(),
+        Value::Float(value)    => JsonValue::Number(match value {
+            value if value.is_nan() => NAN,
+            value if value == f64::INFINITY => INFINITY,
+            value if value == f64::NEG_INFINITY => NEG_INFINITY,
+            value => value.into(),
+        }),
+        Value::Boolean(value)  => value.into(),
+        Value::Datetime(value) => value.to_string().into(),
+        Value::Array(vec)      => JsonValue::Array(vec.into_iter().map(from_toml).collect()),
+        Value::Table(table)    => {
+            let mut object = Object::with_capacity(table.len());
+
+            for (key, value) in table {
+                object.insert_owned(key, from_toml(value));
+            }
+
+            JsonValue::Object(object)
+        }
+    }
+}
+
+/// Serialize `value` as a TOML document. Fails with `Error::InvalidToml` if
+/// `value` is not an object, or if it contains a `null`, which TOML has no
+/// representation for. Strings are always written as strings, including
+/// ones holding dates and times.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// let data = object!{ "name" => "json", "server" => object!{ "port" => 8080 } };
+///
+/// assert_eq!(json::toml::to_toml_string(&data).unwrap(), "name = \"json\"\n\n[server]\nport = 8080\n");
+/// assert!(json::toml::to_toml_string(&array![1]).is_err());
+/// # }
+/// ```
+pub fn to_toml_string(value: &JsonValue) -> Result<String> {
+    let table = match to_toml(value)? {
+        table @ Value::Table(_) => table,
+        _ => return Err(Error::InvalidToml("document that is not an object".into())),
+    };
+
+    toml_rs::to_string(&table).map_err(|error| Error::InvalidToml(error.to_string()))
+}
+
+fn to_toml(value: &JsonValue) -> Result<Value> {
+    Ok(match *value {
+        JsonValue::Null               => return Err(Error::InvalidToml("null value".into())),
+        JsonValue::Boolean(value)     => Value::Boolean(value),
+        JsonValue::Short(ref value)   => Value::String(value.as_str().into()),
+        JsonValue::String(ref value)  => Value::String(String::from(&**value)),
+        JsonValue::Number(number)     => {
+            match number.as_i128() {
+                Some(int) if int as i64 as i128 == int => Value::Integer(int as i64),
+                _ => Value::Float(number.into()),
+            }
+        },
+        JsonValue::Array(ref vec)     => Value::Array(vec.iter().map(to_toml).collect::<Result<_>>()?),
+        JsonValue::Object(ref object) => {
+            let mut table = Table::new();
+
+            for (key, value) in object.iter() {
+                table.insert(key.clone(), to_toml(value)?);
+            }
+
+            Value::Table(table)
+        },
+        JsonValue::Raw(ref raw)       => to_toml(&raw.to_value()?)?,
+    })
+}
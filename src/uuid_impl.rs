@@ -0,0 +1,51 @@
+// Conversions between `JsonValue` and `uuid::Uuid`, enabled with the `uuid`
+// feature. UUIDs are stored as lowercase, hyphenated strings.
+
+use uuid::Uuid;
+
+use convert::{ FromJson, FromJsonError };
+use JsonValue;
+
+// Length of a hyphenated UUID, such as `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+const HYPHENATED_LEN: usize = 36;
+
+impl From<Uuid> for JsonValue {
+    fn from(val: Uuid) -> JsonValue {
+        val.hyphenated().to_string().into()
+    }
+}
+
+impl FromJson for Uuid {
+    fn from_json(value: &JsonValue) -> Result<Self, FromJsonError> {
+        value.as_uuid().ok_or_else(|| FromJsonError::new("uuid", value))
+    }
+}
+
+impl JsonValue {
+    /// Parse a string holding a hyphenated UUID, such as
+    /// `"67e55044-10b1-426f-9247-bb680e5fe0c8"`, in either case. Returns
+    /// `None` if the value is not a string, or the string is not a valid
+    /// hyphenated UUID. Other forms, such as UUIDs without hyphens or within
+    /// braces, are rejected.
+    ///
+    /// Requires the `uuid` feature.
+    ///
+    /// ```
+    /// # extern crate uuid;
+    /// # extern crate json;
+    /// # fn main() {
+    /// use json::JsonValue;
+    ///
+    /// let id = JsonValue::from("67e55044-10b1-426f-9247-bb680e5fe0c8").as_uuid().unwrap();
+    ///
+    /// assert_eq!(JsonValue::from(id), "67e55044-10b1-426f-9247-bb680e5fe0c8");
+    /// assert_eq!(JsonValue::from("67e5504410b1426f9247bb680e5fe0c8").as_uuid(), None);
+    /// # }
+    /// ```
+    pub fn as_uuid(&self) -> Option<Uuid> {
+        match self.as_str() {
+            Some(string) if string.len() == HYPHENATED_LEN => Uuid::parse_str(string).ok(),
+            _ => None,
+        }
+    }
+}
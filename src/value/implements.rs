@@ -1,16 +1,17 @@
 // This is a private module that contains `PartialEq` and `From` trait
 // implementations for `JsonValue`.
 
-use std::collections::{ BTreeMap, HashMap };
-use std::mem;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+use prelude::*;
 
 use short::{ self, Short };
 use number::Number;
-use object::Object;
+use object::{ Object, SortedObject, Map };
 
-use indexmap::IndexMap;
-
-use { JsonValue, Null };
+use { JsonValue, Error };
 
 macro_rules! implement_eq {
     ($to:ident, $from:ty) => {
@@ -44,6 +45,17 @@ macro_rules! implement_eq {
 }
 
 macro_rules! implement {
+    ($to:ident, $from:ty as try) => {
+        impl TryFrom<$from> for JsonValue {
+            type Error = Error;
+
+            fn try_from(val: $from) -> Result<JsonValue, Error> {
+                Number::try_from(val).map(JsonValue::$to)
+            }
+        }
+
+        implement_eq!($to, $from);
+    };
     ($to:ident, $from:ty as num) => {
         impl From<$from> for JsonValue {
             fn from(val: $from) -> JsonValue {
@@ -76,7 +88,7 @@ impl<'a> From<&'a str> for JsonValue {
 
 impl<'a> From<&'a String> for JsonValue {
     fn from(val: &'a String) -> JsonValue {
-        JsonValue::String(val.to_owned())
+        JsonValue::String(val.as_str().into())
     }
 }
 
@@ -101,36 +113,46 @@ impl<T: Into<JsonValue>> From<Vec<T>> for JsonValue {
     }
 }
 
-impl From<HashMap<String, JsonValue>> for JsonValue {
-    fn from(mut val: HashMap<String, JsonValue>) -> JsonValue {
+#[cfg(feature = "std")]
+impl<T: Into<JsonValue>> From<HashMap<String, T>> for JsonValue {
+    fn from(val: HashMap<String, T>) -> JsonValue {
         let mut object = Object::with_capacity(val.len());
 
-        for (key, value) in val.drain() {
-            object.insert(&key, value);
+        for (key, value) in val {
+            object.insert_owned(key, value.into());
         }
 
         JsonValue::Object(object)
     }
 }
 
-impl From<BTreeMap<String, JsonValue>> for JsonValue {
-    fn from(mut val: BTreeMap<String, JsonValue>) -> JsonValue {
+impl<T: Into<JsonValue>> From<BTreeMap<String, T>> for JsonValue {
+    fn from(val: BTreeMap<String, T>) -> JsonValue {
         let mut object = Object::with_capacity(val.len());
 
-        for (key, value) in val.iter_mut() {
-            // Since BTreeMap has no `drain` available, we can use
-            // the mutable iterator and replace all values by nulls,
-            // taking ownership and transferring it to the new `Object`.
-            let value = mem::replace(value, Null);
-            object.insert(key, value);
+        for (key, value) in val {
+            object.insert_owned(key, value.into());
         }
 
         JsonValue::Object(object)
     }
 }
 
-impl From<IndexMap<String, JsonValue>> for JsonValue {
-    fn from(mut val: IndexMap<String, JsonValue>) -> JsonValue {
+impl From<Map> for JsonValue {
+    fn from(val: Map) -> JsonValue {
+        JsonValue::Object(val.into())
+    }
+}
+
+#[cfg(feature = "hash_object")]
+impl From<::indexmap::IndexMap<String, JsonValue, ::std::collections::hash_map::RandomState>> for JsonValue {
+    fn from(val: ::indexmap::IndexMap<String, JsonValue, ::std::collections::hash_map::RandomState>) -> JsonValue {
+        JsonValue::Object(val.into())
+    }
+}
+
+impl From<SortedObject> for JsonValue {
+    fn from(val: SortedObject) -> JsonValue {
         JsonValue::Object(val.into())
     }
 }
@@ -140,7 +162,7 @@ impl<'a> PartialEq<&'a str> for JsonValue {
     fn eq(&self, other: &&str) -> bool {
         match *self {
             JsonValue::Short(ref value)  => value == *other,
-            JsonValue::String(ref value) => value == *other,
+            JsonValue::String(ref value) => &**value == *other,
             _ => false
         }
     }
@@ -150,7 +172,7 @@ impl<'a> PartialEq<JsonValue> for &'a str {
     fn eq(&self, other: &JsonValue) -> bool {
         match *other {
             JsonValue::Short(ref value)  => value == *self,
-            JsonValue::String(ref value) => value == *self,
+            JsonValue::String(ref value) => &**value == *self,
             _ => false
         }
     }
@@ -160,7 +182,7 @@ impl PartialEq<str> for JsonValue {
     fn eq(&self, other: &str) -> bool {
         match *self {
             JsonValue::Short(ref value)  => value == other,
-            JsonValue::String(ref value) => value == other,
+            JsonValue::String(ref value) => &**value == other,
             _ => false
         }
     }
@@ -170,13 +192,48 @@ impl<'a> PartialEq<JsonValue> for str {
     fn eq(&self, other: &JsonValue) -> bool {
         match *other {
             JsonValue::Short(ref value)  => value == self,
-            JsonValue::String(ref value) => value == self,
+            JsonValue::String(ref value) => &**value == self,
             _ => false
         }
     }
 }
 
-implement!(String, String);
+impl From<String> for JsonValue {
+    fn from(val: String) -> JsonValue {
+        #[cfg(feature = "shared_strings")]
+        let val = val.into();
+
+        JsonValue::String(val)
+    }
+}
+
+impl PartialEq<String> for JsonValue {
+    fn eq(&self, other: &String) -> bool {
+        match *self {
+            JsonValue::String(ref value) => **value == **other,
+            _                            => false
+        }
+    }
+}
+
+impl PartialEq<String> for &JsonValue {
+    fn eq(&self, other: &String) -> bool {
+        match **self {
+            JsonValue::String(ref value) => **value == **other,
+            _                            => false
+        }
+    }
+}
+
+impl PartialEq<JsonValue> for String {
+    fn eq(&self, other: &JsonValue) -> bool {
+        match *other {
+            JsonValue::String(ref value) => **value == **self,
+            _                            => false
+        }
+    }
+}
+
 implement!(Number, isize as num);
 implement!(Number, usize as num);
 implement!(Number, i8 as num);
@@ -187,6 +244,8 @@ implement!(Number, u8 as num);
 implement!(Number, u16 as num);
 implement!(Number, u32 as num);
 implement!(Number, u64 as num);
+implement!(Number, i128 as try);
+implement!(Number, u128 as try);
 implement!(Number, f32 as num);
 implement!(Number, f64 as num);
 implement!(Number, Number);
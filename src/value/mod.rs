@@ -1,25 +1,36 @@
 use { Result, Error };
+use prelude::*;
 
 use std::ops::{ Index, IndexMut, Deref };
-use std::{ fmt, mem, usize, u8, u16, u32, u64, isize, i8, i16, i32, i64, f32 };
+use std::{ fmt, mem, result, usize, u8, u16, u32, u64, isize, i8, i16, i32, i64, f32 };
 use std::io::{ self, Write };
+use std::borrow::Cow;
+use std::hash::{ Hash, Hasher };
+#[cfg(feature = "shared_strings")]
+use std::sync::Arc;
 
 use short::Short;
+use raw::RawJson;
+use convert::{ ToJson, FromJson, FromJsonError };
 use number::Number;
-use object::Object;
+use patch::{ self, PatchOp };
+use path::{ self, JsonPath, PathSegment, Walk, Visit, Visitor };
+use object::{ Object, Key };
+use stats::Stats;
+use content_hash;
 use iterators::{ Members, MembersMut, Entries };
-use codegen::{ Generator, PrettyGenerator, DumpGenerator, WriterGenerator, PrettyWriterGenerator };
+use codegen::{ Generator, PrettyGenerator, DumpGenerator, WriterGenerator, PrettyWriterGenerator, CanonicalGenerator };
+use codegen::{ OptionsGenerator, StringifyOptions, FmtGenerator };
 
 mod implements;
 
-lazy_static! {
-
-    static ref JSON_OBJECT: Object = Object::new();
-
-}
-
+use indexmap::IndexMap;
 use indexmap::map::IterMut;
 
+// Map without entries, iterated over by `JsonValue::entries` for values
+// that are not objects. It's never hashed into, so it needs no hasher.
+static NO_ENTRIES: IndexMap<String, JsonValue, ()> = IndexMap::with_hasher(());
+
 /// Mutable iterator over key value pairs of `JsonValue::Object`.
 pub enum EntriesMut<'a> {
     Object(IterMut<'a, String, JsonValue>),
@@ -92,15 +103,173 @@ macro_rules! number_to_signed {
     }
 }
 
+/// Options for `JsonValue::compact`, describing which members should be
+/// removed from arrays and objects. By default everything except for
+/// `drop_empty_strings` is enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactOptions {
+    /// Remove `null` members.
+    pub drop_nulls: bool,
+
+    /// Remove empty string members.
+    pub drop_empty_strings: bool,
+
+    /// Remove empty object members.
+    pub drop_empty_objects: bool,
+
+    /// Remove empty array members.
+    pub drop_empty_arrays: bool,
+
+    /// Compact nested arrays and objects as well. Containers that become
+    /// empty after being compacted are then removed too.
+    pub recursive: bool,
+}
+
+impl Default for CompactOptions {
+    fn default() -> Self {
+        CompactOptions {
+            drop_nulls: true,
+            drop_empty_strings: false,
+            drop_empty_objects: true,
+            drop_empty_arrays: true,
+            recursive: true,
+        }
+    }
+}
+
+impl CompactOptions {
+    fn drops(&self, value: &JsonValue) -> bool {
+        match *value {
+            JsonValue::Null              => self.drop_nulls,
+            JsonValue::Short(ref value)  => self.drop_empty_strings && value.is_empty(),
+            JsonValue::String(ref value) => self.drop_empty_strings && value.is_empty(),
+            JsonValue::Object(ref value) => self.drop_empty_objects && value.is_empty(),
+            JsonValue::Array(ref value)  => self.drop_empty_arrays && value.is_empty(),
+            _                            => false,
+        }
+    }
+}
+
+/// Options for `JsonValue::prune`, describing which members should be
+/// removed from arrays and objects. By default everything is enabled. Same
+/// as `CompactOptions` with `recursive` always enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PruneOptions {
+    /// Remove `null` members.
+    pub drop_nulls: bool,
+
+    /// Remove empty string members.
+    pub drop_empty_strings: bool,
+
+    /// Remove empty array members.
+    pub drop_empty_arrays: bool,
+
+    /// Remove empty object members.
+    pub drop_empty_objects: bool,
+}
+
+impl Default for PruneOptions {
+    fn default() -> Self {
+        PruneOptions {
+            drop_nulls: true,
+            drop_empty_strings: true,
+            drop_empty_arrays: true,
+            drop_empty_objects: true,
+        }
+    }
+}
+
+impl From<PruneOptions> for CompactOptions {
+    fn from(options: PruneOptions) -> Self {
+        CompactOptions {
+            drop_nulls: options.drop_nulls,
+            drop_empty_strings: options.drop_empty_strings,
+            drop_empty_objects: options.drop_empty_objects,
+            drop_empty_arrays: options.drop_empty_arrays,
+            recursive: true,
+        }
+    }
+}
+
+/// Options for `JsonValue::merge`, describing how arrays and `null`s of the
+/// merged value are combined with the existing ones. Objects are always
+/// merged recursively, and anything else replaces the existing value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MergeStrategy {
+    /// How to merge an array into an existing array.
+    pub arrays: ArrayMerge,
+
+    /// What to do with `null` members of merged objects.
+    pub nulls: NullMerge,
+}
+
+/// How `JsonValue::merge` combines two arrays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArrayMerge {
+    /// The merged array replaces the existing one.
+    #[default]
+    Replace,
+
+    /// Members of the merged array are appended to the existing ones.
+    Concat,
+
+    /// Members at the same index are merged, members past the end of the
+    /// existing array are appended.
+    ByIndex,
+}
+
+/// What `JsonValue::merge` does with `null` members of merged objects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NullMerge {
+    /// `null` replaces the existing value, like any other value.
+    #[default]
+    Replace,
+
+    /// `null` is skipped, keeping the existing value.
+    Ignore,
+
+    /// `null` removes the key, as with `JsonValue::merge_patch`.
+    Remove,
+}
+
+/// Storage of `JsonValue::String`. With the `shared_strings` feature this
+/// is an `Arc<str>`, so that cloning a value shares its strings instead of
+/// copying them, which pays off when a large document is handed to several
+/// consumers. Strings can't be modified in place then.
+#[cfg(not(feature = "shared_strings"))]
+pub type JsonString = String;
+
+/// Storage of `JsonValue::String`. With the `shared_strings` feature this
+/// is an `Arc<str>`, so that cloning a value shares its strings instead of
+/// copying them, which pays off when a large document is handed to several
+/// consumers. Strings can't be modified in place then.
+#[cfg(feature = "shared_strings")]
+pub type JsonString = Arc<str>;
+
+// Turn the contents of a `JsonValue::String` into a `String`, which is
+// a copy only with the `shared_strings` feature.
+#[cfg(not(feature = "shared_strings"))]
+#[inline(always)]
+pub(crate) fn into_string(string: JsonString) -> String {
+    string
+}
+
+#[cfg(feature = "shared_strings")]
+#[inline(always)]
+pub(crate) fn into_string(string: JsonString) -> String {
+    String::from(&*string)
+}
+
 #[derive(Debug, Clone)]
 pub enum JsonValue {
     Null,
     Short(Short),
-    String(String),
+    String(JsonString),
     Number(Number),
     Boolean(bool),
     Object(Object),
     Array(Vec<JsonValue>),
+    Raw(RawJson),
 }
 
 impl PartialEq for JsonValue {
@@ -111,11 +280,13 @@ impl PartialEq for JsonValue {
             (&Short(ref a), &Short(ref b)) => a == b,
             (&String(ref a), &String(ref b)) => a == b,
             (&Short(ref a), &String(ref b))
-            | (&String(ref b), &Short(ref a)) => a.as_str() == b.as_str(),
+            | (&String(ref b), &Short(ref a)) => a.as_str() == &**b,
             (&Number(ref a), &Number(ref b)) => a == b,
             (&Boolean(ref a), &Boolean(ref b)) => a == b,
             (&Object(ref a), &Object(ref b)) => a == b,
             (&Array(ref a), &Array(ref b)) => a == b,
+            (Raw(a), Raw(b)) => a == b,
+            (Raw(a), b) | (b, Raw(a)) => a.value().is_some_and(|a| *a == *b),
             _ => false,
         }
     }
@@ -123,6 +294,42 @@ impl PartialEq for JsonValue {
 
 impl Eq for JsonValue {}
 
+/// Hashing is consistent with equality: `Short` and `String` values with
+/// the same content hash the same, numbers hash by their value rather than
+/// representation, and objects hash the same regardless of the order of
+/// their entries.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// use std::collections::HashSet;
+///
+/// let mut set = HashSet::new();
+///
+/// set.insert(object!{ "a" => 1, "b" => 2.50 });
+/// set.insert(object!{ "b" => 2.5, "a" => 1 });
+///
+/// assert_eq!(set.len(), 1);
+/// # }
+/// ```
+impl Hash for JsonValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match *self {
+            JsonValue::Null               => state.write_u8(0),
+            JsonValue::Short(ref value)   => { state.write_u8(1); value.as_str().hash(state) },
+            JsonValue::String(ref value)  => { state.write_u8(1); (**value).hash(state) },
+            JsonValue::Number(ref value)  => { state.write_u8(2); value.hash(state) },
+            JsonValue::Boolean(value)     => { state.write_u8(3); value.hash(state) },
+            JsonValue::Object(ref value)  => { state.write_u8(4); value.hash(state) },
+            JsonValue::Array(ref value)   => { state.write_u8(5); value.hash(state) },
+            JsonValue::Raw(ref value)     => match value.value() {
+                Some(parsed) => parsed.hash(state),
+                None         => { state.write_u8(6); value.as_str().hash(state) },
+            },
+        }
+    }
+}
+
 /// Implements formatting
 ///
 /// ```
@@ -134,7 +341,9 @@ impl Eq for JsonValue {}
 impl fmt::Display for JsonValue {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         if f.alternate() {
-            f.write_str(&self.pretty(4))
+            let mut gen = FmtGenerator::pretty(f, 4);
+            gen.write_json(self).map_err(|_| fmt::Error)?;
+            gen.finish()
         } else {
             match *self {
                 JsonValue::Short(ref value)   => value.fmt(f),
@@ -142,7 +351,7 @@ impl fmt::Display for JsonValue {
                 JsonValue::Number(ref value)  => value.fmt(f),
                 JsonValue::Boolean(ref value) => value.fmt(f),
                 JsonValue::Null               => f.write_str("null"),
-                _                             => f.write_str(&self.dump())
+                _                             => self.dump_fmt(f)
             }
         }
     }
@@ -171,6 +380,75 @@ impl JsonValue {
         gen.consume()
     }
 
+    /// Prints out the value as canonical JSON (RFC 8785), with keys sorted
+    /// and numbers in a fixed format, so that equal values always produce
+    /// the same bytes. Fails with `Error::InvalidWrite` for NaN and infinite
+    /// numbers, which have no JSON representation.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let data = object!{ "b" => 1e21, "a" => array![4.50, 2e-3, -0.0] };
+    ///
+    /// assert_eq!(data.canonical().unwrap(), r#"{"a":[4.5,0.002,0],"b":1e+21}"#);
+    /// # }
+    /// ```
+    pub fn canonical(&self) -> Result<String> {
+        let mut gen = CanonicalGenerator::new();
+        gen.write_json(self).map_err(|error| Error::InvalidWrite(error.to_string()))?;
+        Ok(gen.consume())
+    }
+
+    /// Prints out the value as JSON string, appending it to `buffer`. Reusing
+    /// the same buffer saves an allocation per value when dumping many values.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let mut buffer = String::new();
+    ///
+    /// for id in 1..3 {
+    ///     buffer.clear();
+    ///     object!{ "id" => id }.dump_into(&mut buffer);
+    ///
+    ///     assert_eq!(buffer, format!("{{\"id\":{}}}", id));
+    /// }
+    /// # }
+    /// ```
+    pub fn dump_into(&self, buffer: &mut String) {
+        let mut gen = DumpGenerator::with_buffer(mem::take(buffer).into_bytes());
+        gen.write_json(self).expect("Can't fail");
+        *buffer = gen.consume();
+    }
+
+    /// Prints out the value as JSON, appending the bytes to `buffer`, see
+    /// `dump_into`.
+    pub fn dump_into_vec(&self, buffer: &mut Vec<u8>) {
+        let mut gen = DumpGenerator::with_buffer(mem::take(buffer));
+        gen.write_json(self).expect("Can't fail");
+        *buffer = gen.into_bytes();
+    }
+
+    /// Prints out the value as JSON into an implementor of `fmt::Write`,
+    /// such as a `String` or a `fmt::Formatter`. Unlike `write`, this doesn't
+    /// need `std::io`, and only fails if the writer does.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let mut code = String::from("data: ");
+    ///
+    /// array![1, "two"].dump_fmt(&mut code).unwrap();
+    ///
+    /// assert_eq!(code, r#"data: [1,"two"]"#);
+    /// # }
+    /// ```
+    pub fn dump_fmt<W: fmt::Write + ?Sized>(&self, writer: &mut W) -> fmt::Result {
+        let mut gen = FmtGenerator::new(writer);
+        gen.write_json(self).map_err(|_| fmt::Error)?;
+        gen.finish()
+    }
+
     /// Pretty prints out the value as JSON string. Takes an argument that's
     /// number of spaces to indent new blocks with.
     pub fn pretty(&self, spaces: u16) -> String {
@@ -179,6 +457,28 @@ impl JsonValue {
         gen.consume()
     }
 
+    /// Pretty prints out the value as JSON string, vertically aligning values
+    /// within objects by padding them to the length of the longest key.
+    /// Takes an argument that's number of spaces to indent new blocks with.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let data = object!{
+    ///     "name" => "json",
+    ///     "version" => 11
+    /// };
+    ///
+    /// assert_eq!(data.pretty_aligned(2), "{\n  \"name\":    \"json\",\n  \"version\": 11\n}");
+    /// # }
+    /// ```
+    pub fn pretty_aligned(&self, spaces: u16) -> String {
+        let mut gen = PrettyGenerator::new(spaces);
+        gen.align_values(true);
+        gen.write_json(self).expect("Can't fail");
+        gen.consume()
+    }
+
     /// Writes the JSON as byte stream into an implementor of `std::io::Write`.
     ///
     /// This method is deprecated as it will panic on io errors, use `write` instead.
@@ -186,18 +486,48 @@ impl JsonValue {
     pub fn to_writer<W: Write>(&self, writer: &mut W) {
         let mut gen = WriterGenerator::new(writer);
         gen.write_json(self).expect("Deprecated");
+        gen.finish().expect("Deprecated");
     }
 
     /// Writes the JSON as byte stream into an implementor of `std::io::Write`.
     pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
         let mut gen = WriterGenerator::new(writer);
-        gen.write_json(self)
+        gen.write_json(self)?;
+        gen.finish()
     }
 
     /// Writes the JSON as byte stream into an implementor of `std::io::Write`.
     pub fn write_pretty<W: Write>(&self, writer: &mut W, spaces: u16) -> io::Result<()> {
         let mut gen = PrettyWriterGenerator::new(writer, spaces);
-        gen.write_json(self)
+        gen.write_json(self)?;
+        gen.finish()
+    }
+
+    /// Writes the JSON as byte stream into an implementor of `std::io::Write`,
+    /// vertically aligning values within objects like `pretty_aligned`.
+    pub fn write_pretty_aligned<W: Write>(&self, writer: &mut W, spaces: u16) -> io::Result<()> {
+        let mut gen = PrettyWriterGenerator::new(writer, spaces);
+        gen.align_values(true);
+        gen.write_json(self)?;
+        gen.finish()
+    }
+
+    /// Prints out the value as JSON string, formatted according to
+    /// `options`. See `StringifyOptions` for what can be configured.
+    pub fn dump_with(&self, options: &StringifyOptions) -> String {
+        let mut code = Vec::new();
+        self.write_with(options, &mut code).expect("Can't fail");
+        // Original strings, indentation and line breaks were unicode,
+        // numbers are all ASCII, therefore this is safe.
+        unsafe { String::from_utf8_unchecked(code) }
+    }
+
+    /// Writes the JSON as byte stream into an implementor of `std::io::Write`,
+    /// formatted according to `options`.
+    pub fn write_with<W: Write>(&self, options: &StringifyOptions, writer: &mut W) -> io::Result<()> {
+        let mut gen = OptionsGenerator::new(writer, options);
+        gen.write_json(self)?;
+        gen.finish()
     }
 
     pub fn is_string(&self) -> bool {
@@ -260,6 +590,7 @@ impl JsonValue {
             JsonValue::Boolean(ref value) => !value,
             JsonValue::Array(ref value)   => value.is_empty(),
             JsonValue::Object(ref value)  => value.is_empty(),
+            JsonValue::Raw(ref value)     => value.value().is_some_and(|value| value.is_empty()),
         }
     }
 
@@ -332,6 +663,18 @@ impl JsonValue {
         self.as_i64().and_then(|value| number_to_signed!(isize, value, i64))
     }
 
+    /// Obtain the value as an `u128`, if it's a non-negative integer within
+    /// range, see `Number::as_u128`.
+    pub fn as_u128(&self) -> Option<u128> {
+        self.as_number().and_then(|value| value.as_u128())
+    }
+
+    /// Obtain the value as an `i128`, if it's an integer within range, see
+    /// `Number::as_i128`.
+    pub fn as_i128(&self) -> Option<i128> {
+        self.as_number().and_then(|value| value.as_i128())
+    }
+
     pub fn as_bool(&self) -> Option<bool> {
         match *self {
             JsonValue::Boolean(ref value) => Some(*value),
@@ -381,6 +724,63 @@ impl JsonValue {
         }
     }
 
+    /// Convert the value into any type implementing `FromJson`, failing with
+    /// an error that names the expected and the actual type.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let data = object!{ "ids" => array![1, 2, 3], "name" => "json" };
+    ///
+    /// assert_eq!(data["ids"].expect_type::<Vec<u32>>(), Ok(vec![1, 2, 3]));
+    ///
+    /// let error = data["name"].expect_type::<u32>().unwrap_err();
+    ///
+    /// assert_eq!(error.to_string(), "Expected u32, found string at \"\"");
+    /// # }
+    /// ```
+    pub fn expect_type<T: FromJson>(&self) -> result::Result<T, FromJsonError> {
+        T::from_json(self)
+    }
+
+    /// Analog to `as_str`, failing with an error naming the actual type
+    /// instead of returning `None`.
+    pub fn as_str_or_err(&self) -> result::Result<&str, FromJsonError> {
+        self.as_str().ok_or_else(|| FromJsonError::new("string", self))
+    }
+
+    /// Analog to `as_number`, failing with an error naming the actual type
+    /// instead of returning `None`.
+    pub fn as_number_or_err(&self) -> result::Result<Number, FromJsonError> {
+        self.expect_type()
+    }
+
+    /// Analog to `as_f64`, failing with an error naming the actual type
+    /// instead of returning `None`.
+    pub fn as_f64_or_err(&self) -> result::Result<f64, FromJsonError> {
+        self.expect_type()
+    }
+
+    /// Analog to `as_u64`, failing with an error naming the actual type
+    /// instead of returning `None`. Unlike `as_u64`, numbers with a fraction
+    /// or out of range are rejected rather than truncated.
+    pub fn as_u64_or_err(&self) -> result::Result<u64, FromJsonError> {
+        self.expect_type()
+    }
+
+    /// Analog to `as_i64`, failing with an error naming the actual type
+    /// instead of returning `None`. Unlike `as_i64`, numbers with a fraction
+    /// or out of range are rejected rather than truncated.
+    pub fn as_i64_or_err(&self) -> result::Result<i64, FromJsonError> {
+        self.expect_type()
+    }
+
+    /// Analog to `as_bool`, failing with an error naming the actual type
+    /// instead of returning `None`.
+    pub fn as_bool_or_err(&self) -> result::Result<bool, FromJsonError> {
+        self.expect_type()
+    }
+
     /// Take over the ownership of the value, leaving `Null` in it's place.
     ///
     /// ## Example
@@ -404,6 +804,25 @@ impl JsonValue {
         mem::replace(self, JsonValue::Null)
     }
 
+    /// Replace the value with `value`, returning the old one, so it can be
+    /// moved out of the tree without cloning.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let mut data = object!{ "status" => "pending" };
+    ///
+    /// let old = data["status"].replace("done");
+    ///
+    /// assert_eq!(old, "pending");
+    /// assert_eq!(data["status"], "done");
+    /// # }
+    /// ```
+    pub fn replace<T>(&mut self, value: T) -> JsonValue
+    where T: Into<JsonValue> {
+        mem::replace(self, value.into())
+    }
+
     /// Checks that self is a string, returns an owned Rust `String`, leaving
     /// `Null` in it's place.
     ///
@@ -433,7 +852,7 @@ impl JsonValue {
 
         match placeholder {
             JsonValue::Short(short)   => return Some(short.into()),
-            JsonValue::String(string) => return Some(string),
+            JsonValue::String(string) => return Some(into_string(string)),
 
             // Not a string? Swap the original value back in place!
             _ => mem::swap(self, &mut placeholder)
@@ -454,6 +873,19 @@ impl JsonValue {
         }
     }
 
+    /// Works on `JsonValue::Object` - inserts a new entry, or overrides an
+    /// existing one. Accepts any value implementing `ToJson`.
+    pub fn insert<T>(&mut self, key: &str, value: T) -> Result<()>
+    where T: ToJson {
+        match *self {
+            JsonValue::Object(ref mut object) => {
+                object.insert(key, value.into_json());
+                Ok(())
+            },
+            _ => Err(Error::wrong_type("Object"))
+        }
+    }
+
     /// Works on `JsonValue::Array` - remove and return last element from
     /// an array. On failure returns a null.
     pub fn pop(&mut self) -> JsonValue {
@@ -524,7 +956,7 @@ impl JsonValue {
             JsonValue::Object(ref object) => {
                 object.iter()
             },
-            _ => JSON_OBJECT.iter()
+            _ => NO_ENTRIES.iter()
         }
     }
 
@@ -540,10 +972,576 @@ impl JsonValue {
         }
     }
 
+    /// Resolve a JSON Pointer (RFC 6901), such as `"/servers/0/host"`, against
+    /// this value. The empty pointer refers to the value itself. Returns `None`
+    /// if the pointer is malformed or doesn't lead to any value.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let config = object!{
+    ///     "servers" => array![object!{ "host" => "localhost" }],
+    ///     "a/b" => object!{ "~c" => 1 }
+    /// };
+    ///
+    /// assert_eq!(config.pointer("/servers/0/host").unwrap(), "localhost");
+    /// assert_eq!(config.pointer("/a~1b/~0c").unwrap(), 1);
+    /// assert_eq!(config.pointer("/servers/1"), None);
+    /// # }
+    /// ```
+    pub fn pointer(&self, pointer: &str) -> Option<&JsonValue> {
+        let mut target = self;
+
+        for token in pointer_tokens(pointer)? {
+            target = match *target {
+                JsonValue::Object(ref object) => object.get(&token)?,
+                JsonValue::Array(ref vec)     => vec.get(pointer_index(&token)?)?,
+                _                             => return None,
+            };
+        }
+
+        Some(target)
+    }
+
+    /// Mutable analog to `pointer`.
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut JsonValue> {
+        let mut target = self;
+
+        for token in pointer_tokens(pointer)? {
+            target = match *target {
+                JsonValue::Object(ref mut object) => object.get_mut(&token)?,
+                JsonValue::Array(ref mut vec)     => vec.get_mut(pointer_index(&token)?)?,
+                _                                 => return None,
+            };
+        }
+
+        Some(target)
+    }
+
+    /// Get the value at the path made of `segments`, with keys looking up
+    /// members of objects and indices looking up members of arrays. Unlike
+    /// chained indexing, returns `None` instead of `null` if the path doesn't
+    /// lead to any value.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// use json::PathSegment::{ Key, Index };
+    ///
+    /// let data = object!{ "users" => array![object!{ "name" => "Joe" }] };
+    ///
+    /// assert_eq!(data.get_path(&[Key("users".into()), Index(0), Key("name".into())]).unwrap(), "Joe");
+    /// assert_eq!(data.get_path(&[Key("users".into()), Key("name".into())]), None);
+    /// # }
+    /// ```
+    pub fn get_path(&self, segments: &[PathSegment]) -> Option<&JsonValue> {
+        let mut target = self;
+
+        for segment in segments {
+            target = match (target, segment) {
+                (JsonValue::Object(object), PathSegment::Key(key)) => object.get(key)?,
+                (JsonValue::Array(vec), PathSegment::Index(index)) => vec.get(*index)?,
+                _                                                  => return None,
+            };
+        }
+
+        Some(target)
+    }
+
+    /// Mutable analog to `get_path`.
+    pub fn get_path_mut(&mut self, segments: &[PathSegment]) -> Option<&mut JsonValue> {
+        let mut target = self;
+
+        for segment in segments {
+            target = match (target, segment) {
+                (JsonValue::Object(object), PathSegment::Key(key)) => object.get_mut(key)?,
+                (JsonValue::Array(vec), PathSegment::Index(index)) => vec.get_mut(*index)?,
+                _                                                  => return None,
+            };
+        }
+
+        Some(target)
+    }
+
+    /// Store `value` at the path made of `segments`, creating objects for
+    /// keys and arrays for indices along the way. An index equal to the
+    /// length of an array appends to it, and anything else in the way of
+    /// the path, such as a string where an object is expected, is replaced.
+    /// Fails with `Error::InvalidPath` if an index is past the end of an
+    /// array.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// use json::PathSegment::{ Key, Index };
+    ///
+    /// let mut data = json::JsonValue::new_object();
+    ///
+    /// data.set_path(&[Key("users".into()), Index(0), Key("name".into())], "Joe").unwrap();
+    ///
+    /// assert_eq!(data, object!{ "users" => array![object!{ "name" => "Joe" }] });
+    /// assert!(data.set_path(&[Key("users".into()), Index(2)], "Jane").is_err());
+    /// # }
+    /// ```
+    pub fn set_path<T>(&mut self, segments: &[PathSegment], value: T) -> Result<()>
+    where T: Into<JsonValue> {
+        if !path::set(self, segments, value.into(), true) {
+            return Err(Error::InvalidPath(path::to_dotted(segments)));
+        }
+
+        Ok(())
+    }
+
+    /// Get the value at a path written with dots and brackets, such as
+    /// `"a.b[2].c"`, where names after dots look up members of objects and
+    /// numbers within brackets look up members of arrays. The empty path
+    /// refers to the value itself. Returns `None` if the path is malformed or
+    /// doesn't lead to any value.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let config = object!{ "servers" => array![object!{ "host" => "localhost" }] };
+    ///
+    /// assert_eq!(config.dot_get("servers[0].host").unwrap(), "localhost");
+    /// assert_eq!(config.dot_get("servers[1].host"), None);
+    /// assert_eq!(config.dot_get("servers.[0]"), None);
+    /// # }
+    /// ```
+    pub fn dot_get(&self, path: &str) -> Option<&JsonValue> {
+        self.get_path(&path::parse_dotted(path)?)
+    }
+
+    /// Mutable analog to `dot_get`.
+    pub fn dot_get_mut(&mut self, path: &str) -> Option<&mut JsonValue> {
+        self.get_path_mut(&path::parse_dotted(path)?)
+    }
+
+    /// Store `value` at a path written with dots and brackets, such as
+    /// `"a.b[2].c"`, creating objects and arrays along the way the same way
+    /// `set_path` does. Fails with `Error::InvalidPath` if the path is
+    /// malformed, leaving the value unchanged, or if an index is past the
+    /// end of an array.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let mut config = json::JsonValue::new_object();
+    ///
+    /// config.dot_set("servers[0].host", "localhost").unwrap();
+    /// config.dot_set("servers[0].port", 8080).unwrap();
+    ///
+    /// assert_eq!(config, object!{
+    ///     "servers" => array![object!{ "host" => "localhost", "port" => 8080 }]
+    /// });
+    /// assert!(config.dot_set("servers[x]", 1).is_err());
+    /// assert!(config.dot_set("servers[2].host", "example.com").is_err());
+    /// # }
+    /// ```
+    pub fn dot_set<T>(&mut self, path: &str, value: T) -> Result<()>
+    where T: Into<JsonValue> {
+        match path::parse_dotted(path) {
+            Some(ref segments) if path::set(self, segments, value.into(), true) => Ok(()),
+            _ => Err(Error::InvalidPath(path.into())),
+        }
+    }
+
+    /// Apply a JSON Merge Patch (RFC 7386) to this value. Objects in the patch
+    /// are merged into the value recursively, with `null`s removing keys.
+    /// Anything else in the patch replaces the value entirely.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let mut user = object!{
+    ///     "name" => "Alice",
+    ///     "email" => "alice@example.com",
+    ///     "address" => object!{ "city" => "Oslo", "zip" => "0150" }
+    /// };
+    ///
+    /// user.merge_patch(&object!{
+    ///     "email" => json::Null,
+    ///     "address" => object!{ "zip" => "0151" },
+    ///     "tags" => array!["admin"]
+    /// });
+    ///
+    /// assert_eq!(user, object!{
+    ///     "name" => "Alice",
+    ///     "address" => object!{ "city" => "Oslo", "zip" => "0151" },
+    ///     "tags" => array!["admin"]
+    /// });
+    /// # }
+    /// ```
+    pub fn merge_patch(&mut self, patch: &JsonValue) {
+        let patch = match *patch {
+            JsonValue::Object(ref patch) => patch,
+            _ => {
+                *self = patch.clone();
+                return;
+            }
+        };
+
+        if !self.is_object() {
+            *self = JsonValue::new_object();
+        }
+
+        if let JsonValue::Object(ref mut object) = *self {
+            for (key, value) in patch.iter() {
+                if value.is_null() {
+                    object.remove(key);
+                } else {
+                    object[key].merge_patch(value);
+                }
+            }
+        }
+    }
+
+    /// Merge `other` into this value recursively, as configured by
+    /// `strategy`. Useful for layering configuration, such as defaults
+    /// overridden by the environment.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// use json::{ MergeStrategy, ArrayMerge, NullMerge };
+    ///
+    /// let mut config = object!{
+    ///     "plugins" => array!["core"],
+    ///     "server" => object!{ "host" => "localhost", "port" => 8080 }
+    /// };
+    ///
+    /// config.merge(object!{
+    ///     "plugins" => array!["auth"],
+    ///     "server" => object!{ "port" => 9090, "host" => json::Null }
+    /// }, MergeStrategy { arrays: ArrayMerge::Concat, nulls: NullMerge::Ignore });
+    ///
+    /// assert_eq!(config, object!{
+    ///     "plugins" => array!["core", "auth"],
+    ///     "server" => object!{ "host" => "localhost", "port" => 9090 }
+    /// });
+    /// # }
+    /// ```
+    pub fn merge(&mut self, other: JsonValue, strategy: MergeStrategy) {
+        // Objects are merged into an empty one, so that `null`s are handled
+        // the same way regardless of what they replace.
+        if other.is_object() && !self.is_object() {
+            *self = JsonValue::new_object();
+        }
+
+        match (self, other) {
+            (&mut JsonValue::Object(ref mut object), JsonValue::Object(other)) => {
+                for (key, value) in other.into_iter() {
+                    if value.is_null() {
+                        match strategy.nulls {
+                            NullMerge::Replace => {},
+                            NullMerge::Ignore  => continue,
+                            NullMerge::Remove  => {
+                                object.remove(&key);
+                                continue;
+                            },
+                        }
+                    }
+
+                    match object.get_mut(&key) {
+                        Some(existing) => existing.merge(value, strategy),
+                        None           => object.insert_owned(key, value),
+                    }
+                }
+            },
+            (&mut JsonValue::Array(ref mut vec), JsonValue::Array(other)) => {
+                match strategy.arrays {
+                    ArrayMerge::Replace => *vec = other,
+                    ArrayMerge::Concat  => vec.extend(other),
+                    ArrayMerge::ByIndex => {
+                        for (index, value) in other.into_iter().enumerate() {
+                            match vec.get_mut(index) {
+                                Some(existing) => existing.merge(value, strategy),
+                                None           => vec.push(value),
+                            }
+                        }
+                    },
+                }
+            },
+            (this, other) => *this = other,
+        }
+    }
+
+    /// Iterate over this value and every value nested within it, depth-first,
+    /// along with their paths. The value itself comes first, with an empty
+    /// path, followed by the members of arrays and objects in order.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// use json::PathSegment;
+    ///
+    /// let data = object!{
+    ///     "user" => object!{ "email" => "joe@example.com" },
+    ///     "contacts" => array![object!{ "email" => "ann@example.com" }]
+    /// };
+    ///
+    /// let emails: Vec<String> = data.walk()
+    ///     .filter(|(path, _)| path.last() == Some(&PathSegment::Key("email".into())))
+    ///     .map(|(path, _)| path.to_string())
+    ///     .collect();
+    ///
+    /// assert_eq!(emails, vec!["/user/email", "/contacts/0/email"]);
+    /// # }
+    /// ```
+    pub fn walk(&self) -> Walk<'_> {
+        Walk::new(self)
+    }
+
+    /// Count the values of each type within this value, and measure how
+    /// deeply nested and how large it is, in a single pass. See `Stats`.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let data = object!{
+    ///     "name" => "Joe",
+    ///     "tags" => array!["a", "b", json::Null],
+    ///     "address" => object!{ "city" => "Berlin", "zip" => 10115 }
+    /// };
+    ///
+    /// let stats = data.stats();
+    ///
+    /// assert_eq!(stats.strings, 4);
+    /// assert_eq!(stats.objects, 2);
+    /// assert_eq!(stats.nodes(), 9);
+    /// assert_eq!(stats.max_depth, 2);
+    /// assert_eq!(stats.string_bytes, 11);
+    /// assert_eq!(stats.largest_array, 3);
+    /// assert_eq!(stats.largest_object, 3);
+    /// # }
+    /// ```
+    pub fn stats(&self) -> Stats {
+        let mut stats = Stats::default();
+        stats.collect(self, 0);
+        stats
+    }
+
+    /// Digest of the content of this value, which stays the same across
+    /// platforms and versions of this crate, making it usable as a cache
+    /// key or for finding duplicates. Values that are equal have the same
+    /// digest, regardless of the order of the entries of objects, or of how
+    /// numbers are written.
+    ///
+    /// The digest is the 128 bit FNV-1a hash of an encoding of the value,
+    /// starting with a tag byte for its type. Lengths and numbers are
+    /// encoded in little endian:
+    ///
+    /// - `null` is just the tag `0`.
+    /// - Booleans are the tag `1`, followed by `0` or `1`.
+    /// - Numbers are the tag `2`, followed by `0` for NaN, `1` for zero, or
+    ///   otherwise `2`, the sign (`1` if positive), and the shortest decimal
+    ///   mantissa and exponent representing the number, as a `u64` and `i16`.
+    ///   For example `1.50` is `15` and `-1`, and `1e3` is `1` and `3`.
+    /// - Strings are the tag `3`, followed by their length in bytes as
+    ///   a `u64`, and their UTF-8 bytes.
+    /// - Arrays are the tag `4`, followed by their length as a `u64`, and
+    ///   the digests of their members in order.
+    /// - Objects are the tag `5`, followed by their length as a `u64`, and
+    ///   the digests of their entries, sorted from smallest to largest. The
+    ///   digest of an entry is the hash of the key, encoded like a string
+    ///   without the tag, followed by the digest of the value.
+    ///
+    /// Digests of nested values are written as `u128`s. `JsonValue::Raw` is
+    /// digested like the value its text parses into.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let a = json::parse(r#"{ "id": 1.0, "tags": ["x"] }"#).unwrap();
+    /// let b = object!{ "tags" => array!["x"], "id" => 1 };
+    ///
+    /// assert_eq!(a.content_hash_128(), b.content_hash_128());
+    /// assert_ne!(a.content_hash_128(), object!{ "id" => 1 }.content_hash_128());
+    /// # }
+    /// ```
+    pub fn content_hash_128(&self) -> u128 {
+        content_hash::digest(self)
+    }
+
+    /// 64 bit digest of the content of this value, being the two halves of
+    /// `content_hash_128` combined with XOR.
+    pub fn content_hash(&self) -> u64 {
+        let digest = self.content_hash_128();
+
+        (digest >> 64) as u64 ^ digest as u64
+    }
+
+    /// Mutable analog to `walk`, calling `visit` on this value and every
+    /// value nested within it, depth-first. Values are visited before the
+    /// values nested within them, so changes made by `visit` to arrays and
+    /// objects affect which of their members are visited next.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let mut data = object!{ "password" => "hunter2", "nested" => object!{ "password" => "1234" } };
+    ///
+    /// data.walk_mut(|path, value| {
+    ///     if path.to_string().ends_with("/password") {
+    ///         *value = "***".into();
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(data, object!{ "password" => "***", "nested" => object!{ "password" => "***" } });
+    /// # }
+    /// ```
+    pub fn walk_mut<F>(&mut self, mut visit: F)
+    where F: FnMut(&JsonPath, &mut JsonValue) {
+        path::walk_mut(self, &mut JsonPath::new(), &mut visit);
+    }
+
+    /// Rewrite this value in place, calling `visitor` on it and on every
+    /// value nested within it, depth-first. The visitor can change each
+    /// value it is given, and decides whether the values nested within it
+    /// are visited next, or whether it is removed from its parent, see
+    /// `Visit`. Values are visited after any changes made to their parents,
+    /// and array indices in paths are the ones from before any removals.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// use json::{ PathSegment, Visit };
+    ///
+    /// let mut data = object!{
+    ///     "id" => "42",
+    ///     "token" => "secret",
+    ///     "items" => array![object!{ "id" => "7", "token" => "secret" }]
+    /// };
+    ///
+    /// data.transform(|path, value| {
+    ///     match path.last() {
+    ///         Some(PathSegment::Key(key)) if key == "token" => return Visit::Remove,
+    ///         Some(PathSegment::Key(key)) if key == "id" => {
+    ///             let id = value.as_str().and_then(|id| id.parse::<u32>().ok());
+    ///             if let Some(id) = id {
+    ///                 *value = id.into();
+    ///             }
+    ///         },
+    ///         _ => {}
+    ///     }
+    ///     Visit::Continue
+    /// });
+    ///
+    /// assert_eq!(data, object!{ "id" => 42, "items" => array![object!{ "id" => 7 }] });
+    /// # }
+    /// ```
+    pub fn transform<F>(&mut self, mut visit: F)
+    where F: FnMut(&JsonPath, &mut JsonValue) -> Visit {
+        self.transform_with(&mut visit);
+    }
+
+    /// Same as `transform`, using a `Visitor` implemented by a type that can
+    /// keep state across the traversal.
+    pub fn transform_with<V: Visitor + ?Sized>(&mut self, visitor: &mut V) {
+        if !path::transform(self, &mut JsonPath::new(), visitor) {
+            *self = JsonValue::Null;
+        }
+    }
+
+    /// Flatten nested arrays and objects into a single object, keyed by the
+    /// path to every value, such as `a.b[0]`. Empty arrays and objects are
+    /// kept as values. A value that isn't an array or an object is stored
+    /// under the empty key.
+    ///
+    /// Keys are not escaped, keys containing `.`, `[` or `]` can't be
+    /// restored by `unflatten`.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let data = object!{
+    ///     "user" => object!{ "name" => "Joe", "tags" => array!["a", "b"] },
+    ///     "empty" => array![]
+    /// };
+    ///
+    /// let flat = data.flatten();
+    ///
+    /// assert_eq!(flat, object!{
+    ///     "user.name" => "Joe",
+    ///     "user.tags[0]" => "a",
+    ///     "user.tags[1]" => "b",
+    ///     "empty" => array![]
+    /// });
+    ///
+    /// assert_eq!(flat.unflatten().unwrap(), data);
+    /// # }
+    /// ```
+    pub fn flatten(&self) -> JsonValue {
+        let mut result = Object::new();
+        path::flatten(self, &mut String::new(), &mut result);
+        JsonValue::Object(result)
+    }
+
+    /// Works on `JsonValue::Object` - inverse of `flatten`, rebuilding
+    /// nested arrays and objects from keys such as `a.b[0]`. Keys that are
+    /// not valid paths are kept as they are. Anything other than an object
+    /// is returned unchanged.
+    ///
+    /// Indices of an array have to appear in order, without gaps, the way
+    /// `flatten` writes them. Fails with `Error::InvalidPath` for a key with
+    /// an index past the end of the array built so far, or a key that
+    /// conflicts with another one, such as `a` and `a.b`.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// assert!(object!{ "list[0]" => 1, "list[2]" => 3 }.unflatten().is_err());
+    /// assert!(object!{ "a" => 1, "a.b" => 2 }.unflatten().is_err());
+    /// # }
+    /// ```
+    pub fn unflatten(&self) -> Result<JsonValue> {
+        let object = match *self {
+            JsonValue::Object(ref object) => object,
+            _ => return Ok(self.clone()),
+        };
+
+        let mut result = JsonValue::new_object();
+
+        for (index, (key, value)) in object.iter().enumerate() {
+            let segments = path::parse_dotted(key).unwrap_or_else(|| vec![PathSegment::Key(key.clone())]);
+
+            // The first key may replace the empty object at the root, for
+            // flattened values other than objects.
+            if !path::set(&mut result, &segments, value.clone(), index == 0) {
+                return Err(Error::InvalidPath(key.clone()));
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Apply a JSON Patch (RFC 6902), see `json::patch`. Either all of the
+    /// operations are applied, or, if any of them fails, none are.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// use json::patch::PatchOp;
+    ///
+    /// let mut data = object!{ "a" => 1 };
+    ///
+    /// let result = data.apply_patch(&[
+    ///     PatchOp::Add { path: "/b".into(), value: 2.into() },
+    ///     PatchOp::Test { path: "/a".into(), value: 2.into() },
+    /// ]);
+    ///
+    /// assert!(result.is_err());
+    /// assert_eq!(data, object!{ "a" => 1 });
+    /// # }
+    /// ```
+    pub fn apply_patch(&mut self, patch: &[PatchOp]) -> Result<()> {
+        patch::apply(self, patch)
+    }
+
     /// Works on `JsonValue::Object` - remove a key and return the value it held.
     /// If the key was not present, the method is called on anything but an
     /// object, it will return a null.
-    pub fn remove(&mut self, key: &str) -> JsonValue {
+    pub fn remove<K: ?Sized + Key>(&mut self, key: &K) -> JsonValue {
         match *self {
             JsonValue::Object(ref mut object) => {
                 object.remove(key).unwrap_or(JsonValue::Null)
@@ -568,11 +1566,83 @@ impl JsonValue {
         }
     }
 
+    /// Works on `JsonValue::Array` and `JsonValue::Object` - removes members
+    /// that are `null`, empty strings, empty arrays or empty objects in place,
+    /// as configured by `options`. The value `compact` is called on is never
+    /// removed itself.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// use json::CompactOptions;
+    ///
+    /// let mut data = object!{
+    ///     "name" => "json",
+    ///     "tags" => array![],
+    ///     "meta" => object!{ "owner" => json::Null },
+    ///     "list" => array![1, json::Null, 2]
+    /// };
+    ///
+    /// data.compact(CompactOptions::default());
+    ///
+    /// assert_eq!(data, object!{ "name" => "json", "list" => array![1, 2] });
+    /// # }
+    /// ```
+    pub fn compact(&mut self, options: CompactOptions) {
+        match *self {
+            JsonValue::Array(ref mut vec) => {
+                if options.recursive {
+                    for member in vec.iter_mut() {
+                        member.compact(options);
+                    }
+                }
+                vec.retain(|member| !options.drops(member));
+            },
+            JsonValue::Object(ref mut object) => {
+                object.retain(|_, member| {
+                    if options.recursive {
+                        member.compact(options);
+                    }
+                    !options.drops(member)
+                });
+            },
+            _ => {}
+        }
+    }
+
+    /// Recursive `compact`, removing `null`s, empty strings, empty arrays
+    /// and empty objects by default. Arrays and objects that become empty
+    /// once pruned are removed as well.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// use json::PruneOptions;
+    ///
+    /// let mut data = object!{
+    ///     "name" => "json",
+    ///     "nickname" => "",
+    ///     "address" => object!{ "street" => "", "zip" => json::Null },
+    ///     "tags" => array!["", "parser"]
+    /// };
+    ///
+    /// data.prune(PruneOptions::default());
+    ///
+    /// assert_eq!(data, object!{ "name" => "json", "tags" => array!["parser"] });
+    /// # }
+    /// ```
+    pub fn prune(&mut self, options: PruneOptions) {
+        self.compact(options.into());
+    }
+
     /// When called on an array or an object, will wipe them clean. When called
     /// on a string will clear the string. Numbers and booleans become null.
     pub fn clear(&mut self) {
         match *self {
+            #[cfg(not(feature = "shared_strings"))]
             JsonValue::String(ref mut string) => string.clear(),
+            #[cfg(feature = "shared_strings")]
+            JsonValue::String(ref mut string) => *string = "".into(),
             JsonValue::Object(ref mut object) => object.clear(),
             JsonValue::Array(ref mut vec)     => vec.clear(),
             _                                 => *self = JsonValue::Null,
@@ -580,6 +1650,39 @@ impl JsonValue {
     }
 }
 
+// Split a JSON Pointer into unescaped reference tokens. Returns `None` for
+// malformed pointers, which have to be empty or start with a slash.
+fn pointer_tokens<'a>(pointer: &'a str) -> Option<impl Iterator<Item = Cow<'a, str>>> {
+    let rest = match pointer {
+        "" => None,
+        _  => Some(pointer.strip_prefix('/')?),
+    };
+
+    Some(rest.into_iter().flat_map(|rest| rest.split('/')).map(unescape_pointer_token))
+}
+
+pub(crate) fn unescape_pointer_token(token: &str) -> Cow<'_, str> {
+    if token.contains('~') {
+        Cow::Owned(token.replace("~1", "/").replace("~0", "~"))
+    } else {
+        Cow::Borrowed(token)
+    }
+}
+
+// Parse a reference token as an array index, which must be a plain decimal
+// number without leading zeroes.
+pub(crate) fn pointer_index(token: &str) -> Option<usize> {
+    if token.is_empty() || (token.len() > 1 && token.starts_with('0')) {
+        return None;
+    }
+
+    if !token.bytes().all(|ch| ch.is_ascii_digit()) {
+        return None;
+    }
+
+    token.parse().ok()
+}
+
 /// Implements indexing by `usize` to easily access array members:
 ///
 /// ## Example
@@ -657,10 +1760,10 @@ impl IndexMut<usize> for JsonValue {
 /// assert!(object["foo"] == "bar");
 /// # }
 /// ```
-impl<'a> Index<&'a str> for JsonValue {
+impl<'a, K: ?Sized + Key> Index<&'a K> for JsonValue {
     type Output = JsonValue;
 
-    fn index(&self, index: &str) -> &JsonValue {
+    fn index(&self, index: &K) -> &JsonValue {
         match *self {
             JsonValue::Object(ref object) => &object[index],
             _ => &NULL
@@ -676,14 +1779,6 @@ impl Index<String> for JsonValue {
     }
 }
 
-impl<'a> Index<&'a String> for JsonValue {
-    type Output = JsonValue;
-
-    fn index(&self, index: &String) -> &JsonValue {
-        self.index(index.deref())
-    }
-}
-
 /// Implements mutable indexing by `&str` to easily modify object members:
 ///
 /// ## Example
@@ -0,0 +1,303 @@
+//! Conversion between XML and `JsonValue`, enabled with the `xml` feature.
+//! A document becomes an object with a single key, the name of its root
+//! element, and elements are converted following these rules:
+//!
+//! - attributes become keys prefixed with `@`, such as `"@id"`, with string
+//!   values. Namespace declarations are kept as `"@xmlns"` or
+//!   `"@xmlns:prefix"` attributes on the elements that declare them,
+//! - child elements become keys named after them, including their namespace
+//!   prefix, such as `"soap:Body"`. Elements appearing more than once in the
+//!   same parent are collected into an array,
+//! - text, including CDATA sections, becomes a string. An element with only
+//!   text in it is converted to that string, otherwise its text is stored
+//!   under the `"#text"` key. Whitespace around text is trimmed,
+//! - an element with no attributes, children or text becomes `null`.
+//!
+//! Comments and processing instructions are skipped. XML has no other
+//! types, so all values read from a document are strings.
+//!
+//! ```
+//! # #[macro_use] extern crate json;
+//! # fn main() {
+//! let data = json::xml::parse(r#"
+//! <order id="42">
+//!     <item sku="A1">Hammer</item>
+//!     <item sku="B2">Nail</item>
+//!     <note/>
+//! </order>
+//! "#).unwrap();
+//!
+//! assert_eq!(data, object!{
+//!     "order" => object!{
+//!         "@id" => "42",
+//!         "item" => array![
+//!             object!{ "@sku" => "A1", "#text" => "Hammer" },
+//!             object!{ "@sku" => "B2", "#text" => "Nail" }
+//!         ],
+//!         "note" => json::Null
+//!     }
+//! });
+//! # }
+//! ```
+
+use xml_rs::reader::{ EventReader, XmlEvent };
+use xml_rs::namespace::Namespace;
+
+use object::Object;
+use { JsonValue, Error, Result };
+
+/// Key holding the text of elements that also have attributes or children.
+pub const TEXT_KEY: &str = "#text";
+
+/// Prefix of keys holding attributes.
+pub const ATTRIBUTE_PREFIX: char = '@';
+
+// Element being read, until its end tag is found.
+struct Element {
+    name: String,
+    object: Object,
+    text: String,
+    namespace: Namespace,
+}
+
+impl Element {
+    fn into_value(self) -> JsonValue {
+        let mut object = self.object;
+        let text = self.text.trim();
+
+        if object.is_empty() {
+            return match text {
+                "" => JsonValue::Null,
+                _  => text.into(),
+            };
+        }
+
+        if !text.is_empty() {
+            object.insert(TEXT_KEY, text.into());
+        }
+
+        JsonValue::Object(object)
+    }
+}
+
+/// Parse an XML document.
+pub fn parse(source: &str) -> Result<JsonValue> {
+    let mut stack: Vec<Element> = Vec::new();
+    let mut root = None;
+
+    for event in EventReader::from_str(source) {
+        match event.map_err(|error| Error::InvalidXml(error.to_string()))? {
+            XmlEvent::StartElement { name, attributes, namespace } => {
+                let mut object = Object::with_capacity(attributes.len());
+
+                for (prefix, uri) in &namespace {
+                    if prefix == "xml" || prefix == "xmlns" || (prefix.is_empty() && uri.is_empty()) {
+                        continue;
+                    }
+
+                    let declared = match stack.last() {
+                        Some(parent) => parent.namespace.get(prefix) != Some(uri),
+                        None         => true,
+                    };
+
+                    if declared {
+                        let key = match prefix {
+                            "" => "@xmlns".to_owned(),
+                            _  => format!("@xmlns:{}", prefix),
+                        };
+
+                        object.insert_owned(key, uri.into());
+                    }
+                }
+
+                for attribute in attributes {
+                    let key = format!("{}{}", ATTRIBUTE_PREFIX, attribute.name.borrow().to_repr());
+
+                    object.insert_owned(key, attribute.value.into());
+                }
+
+                stack.push(Element {
+                    name: name.borrow().to_repr(),
+                    object,
+                    text: String::new(),
+                    namespace,
+                });
+            },
+            XmlEvent::Characters(text) | XmlEvent::CData(text) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&text);
+                }
+            },
+            XmlEvent::EndElement { .. } => {
+                // The reader makes sure tags are balanced.
+                let element = match stack.pop() {
+                    Some(element) => element,
+                    None          => continue,
+                };
+
+                let name = element.name.clone();
+                let value = element.into_value();
+
+                let parent = match stack.last_mut() {
+                    Some(parent) => &mut parent.object,
+                    None => {
+                        let mut object = Object::with_capacity(1);
+                        object.insert_owned(name, value);
+                        root = Some(JsonValue::Object(object));
+                        continue;
+                    }
+                };
+
+                // Element values are never arrays, so an array can only
+                // come from a repeated element.
+                if let Some(existing) = parent.get_mut(&name) {
+                    match *existing {
+                        JsonValue::Array(ref mut vec) => vec.push(value),
+                        ref mut existing => {
+                            let first = existing.take();
+                            *existing = JsonValue::Array(vec![first, value]);
+                        }
+                    }
+                    continue;
+                }
+
+                parent.insert_owned(name, value);
+            },
+            _ => {}
+        }
+    }
+
+    root.ok_or_else(|| Error::InvalidXml("document without a root element".into()))
+}
+
+/// Serialize `value` as an XML document, following the same rules as
+/// `parse`. Numbers and booleans are written as text, and `null` as an
+/// empty element. Fails with `Error::InvalidXml` if `value` is not an
+/// object with a single key, if an attribute or a `"#text"` key is an
+/// array or an object, or if an array is nested directly in another array.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// let data = object!{
+///     "order" => object!{ "@id" => 42, "item" => array!["Hammer", "Nail"], "note" => json::Null }
+/// };
+///
+/// assert_eq!(
+///     json::xml::to_xml_string(&data).unwrap(),
+///     r#"<order id="42"><item>Hammer</item><item>Nail</item><note/></order>"#
+/// );
+/// # }
+/// ```
+pub fn to_xml_string(value: &JsonValue) -> Result<String> {
+    let mut output = String::new();
+
+    match *value {
+        JsonValue::Object(ref object) if object.len() == 1 => {
+            for (name, value) in object.iter() {
+                if let JsonValue::Array(_) = *value {
+                    return Err(Error::InvalidXml("more than one root element".into()));
+                }
+
+                write_element(&mut output, name, value)?;
+            }
+        },
+        _ => return Err(Error::InvalidXml("document that is not an object with a single key".into())),
+    }
+
+    Ok(output)
+}
+
+fn write_element(output: &mut String, name: &str, value: &JsonValue) -> Result<()> {
+    if let JsonValue::Array(ref vec) = *value {
+        for member in vec {
+            if let JsonValue::Array(_) = *member {
+                return Err(Error::InvalidXml(format!("nested array in element {}", name)));
+            }
+
+            write_element(output, name, member)?;
+        }
+
+        return Ok(());
+    }
+
+    output.push('<');
+    output.push_str(name);
+
+    let object = match *value {
+        JsonValue::Null => {
+            output.push_str("/>");
+            return Ok(());
+        },
+        JsonValue::Object(ref object) => object,
+        ref value => {
+            output.push('>');
+            escape(output, &value.to_string(), false);
+            write_end(output, name);
+            return Ok(());
+        }
+    };
+
+    for (key, value) in object.iter() {
+        if !key.starts_with(ATTRIBUTE_PREFIX) {
+            continue;
+        }
+
+        if value.is_array() || value.is_object() {
+            return Err(Error::InvalidXml(format!("attribute {} that is not a scalar", key)));
+        }
+
+        output.push(' ');
+        output.push_str(&key[1..]);
+        output.push_str("=\"");
+        if !value.is_null() {
+            escape(output, &value.to_string(), true);
+        }
+        output.push('"');
+    }
+
+    let mut content = object.iter().filter(|&(key, _)| !key.starts_with(ATTRIBUTE_PREFIX)).peekable();
+
+    if content.peek().is_none() {
+        output.push_str("/>");
+        return Ok(());
+    }
+
+    output.push('>');
+
+    for (key, value) in content {
+        if key == TEXT_KEY {
+            if value.is_array() || value.is_object() {
+                return Err(Error::InvalidXml(format!("text of element {} that is not a scalar", name)));
+            }
+
+            if !value.is_null() {
+                escape(output, &value.to_string(), false);
+            }
+        } else {
+            write_element(output, key, value)?;
+        }
+    }
+
+    write_end(output, name);
+
+    Ok(())
+}
+
+fn write_end(output: &mut String, name: &str) {
+    output.push_str("</");
+    output.push_str(name);
+    output.push('>');
+}
+
+fn escape(output: &mut String, text: &str, attribute: bool) {
+    for ch in text.chars() {
+        match ch {
+            '<'             => output.push_str("&lt;"),
+            '>'             => output.push_str("&gt;"),
+            '&'             => output.push_str("&amp;"),
+            '"' if attribute => output.push_str("&quot;"),
+            _               => output.push(ch),
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! Conversion between [YAML](https://yaml.org/) and `JsonValue`, enabled
+//! with the `yaml` feature. YAML documents are parsed into the regular
+//! `JsonValue` tree, following these rules:
+//!
+//! - integers and floats become numbers, with `.inf` and `.nan` producing
+//!   `number::INFINITY` and `number::NAN`, and `true`, `false` and `null`
+//!   (or `~`) become their JSON counterparts. Anything else, including
+//!   quoted scalars, becomes a string,
+//! - aliases are replaced by a copy of the value of their anchor, and merge
+//!   keys (`<<`) copy the entries of the mapping, or list of mappings, they
+//!   refer to into the mapping they appear in, without overwriting existing
+//!   keys,
+//! - mapping keys that are numbers, booleans or `null` are turned into
+//!   strings, such as `"1"` or `"true"`. Keys that are sequences or mappings
+//!   fail with `Error::InvalidYaml`.
+//!
+//! Only a single YAML document is accepted, an empty source parses to
+//! `null`.
+
+use yaml_rust::{ Yaml, YamlEmitter, YamlLoader };
+
+use number::{ Number, NAN, INFINITY, NEG_INFINITY };
+use object::Object;
+use { JsonValue, Error, Result };
+
+/// Parse a YAML document.
+///
+/// ```
+/// let config = json::yaml::parse("
+/// defaults: &defaults
+///   timeout: 30
+///   retries: 3
+/// production:
+///   <<: *defaults
+///   retries: 5
+///   hosts: [alpha, beta]
+/// 404: not found
+/// ").unwrap();
+///
+/// assert_eq!(config["production"]["timeout"], 30);
+/// assert_eq!(config["production"]["retries"], 5);
+/// assert_eq!(config["production"]["hosts"][1], "beta");
+/// assert_eq!(config["404"], "not found");
+/// ```
+pub fn parse(source: &str) -> Result<JsonValue> {
+    let mut documents = YamlLoader::load_from_str(source)
+        .map_err(|error| Error::InvalidYaml(error.to_string()))?;
+
+    if documents.len() > 1 {
+        return Err(Error::InvalidYaml("more than one document".into()));
+    }
+
+    match documents.pop() {
+        Some(document) => from_yaml(document),
+        None           => Ok(JsonValue::Null),
+    }
+}
+
+fn from_yaml(yaml: Yaml) -> Result<JsonValue> {
+    Ok(match yaml {
+        Yaml::Null           => JsonValue::Null,
+        Yaml::Boolean(value) => JsonValue::Boolean(value),
+        Yaml::Integer(value) => value.into(),
+        Yaml::Real(text)     => JsonValue::Number(real(&text)?),
+        Yaml::String(value)  => value.into(),
+        Yaml::Array(vec)     => {
+            JsonValue::Array(vec.into_iter().map(from_yaml).collect::<Result<_>>()?)
+        },
+        Yaml::Hash(hash)     => {
+            let mut object = Object::with_capacity(hash.len());
+            let mut merged = Vec::new();
+
+            for (key, value) in hash {
+                if key.as_str() == Some("<<") {
+                    merged.push(value);
+                    continue;
+                }
+
+                object.insert_owned(key_string(key)?, from_yaml(value)?);
+            }
+
+            for value in merged {
+                merge(&mut object, value)?;
+            }
+
+            JsonValue::Object(object)
+        },
+        // Aliases are resolved by the loader, so only values that don't
+        // match their tag, such as `!!int abc`, are left here.
+        Yaml::Alias(_) | Yaml::BadValue => {
+            return Err(Error::InvalidYaml("value that doesn't match its tag".into()));
+        }
+    })
+}
+
+// Convert the text of a YAML float into a `Number`.
+fn real(text: &str) -> Result<Number> {
+    let text = text.trim_start_matches('+');
+
+    if let Ok(JsonValue::Number(number)) = ::parse(text) {
+        return Ok(number);
+    }
+
+    Ok(match text {
+        ".inf" | ".Inf" | ".INF"    => INFINITY,
+        "-.inf" | "-.Inf" | "-.INF" => NEG_INFINITY,
+        ".nan" | ".NaN" | ".NAN"    => NAN,
+        _ => match text.parse::<f64>() {
+            Ok(float) => float.into(),
+            Err(_)    => return Err(Error::InvalidYaml(format!("invalid float {}", text))),
+        }
+    })
+}
+
+fn key_string(key: Yaml) -> Result<String> {
+    Ok(match key {
+        Yaml::String(key)  => key,
+        Yaml::Integer(key) => key.to_string(),
+        Yaml::Real(key)    => key,
+        Yaml::Boolean(key) => key.to_string(),
+        Yaml::Null         => "null".into(),
+        _ => return Err(Error::InvalidYaml("key that is not a scalar".into())),
+    })
+}
+
+// Apply the merge key `<<` with `value`, a mapping or a list of mappings.
+// Earlier mappings in the list take precedence over later ones.
+fn merge(object: &mut Object, value: Yaml) -> Result<()> {
+    let sources = match value {
+        Yaml::Array(vec) => vec,
+        value            => vec![value],
+    };
+
+    for source in sources {
+        match from_yaml(source)? {
+            JsonValue::Object(source) => {
+                for (key, value) in source.into_iter() {
+                    object.entry(key).or_insert(value);
+                }
+            },
+            _ => return Err(Error::InvalidYaml("merge of a value that is not a mapping".into())),
+        }
+    }
+
+    Ok(())
+}
+
+/// Serialize `value` as a YAML document.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// let data = object!{ "name" => "json", "tags" => array!["a", "b"], "version" => 1.5 };
+///
+/// assert_eq!(json::yaml::to_yaml_string(&data), "---\nname: json\ntags:\n  - a\n  - b\nversion: 1.5");
+/// # }
+/// ```
+pub fn to_yaml_string(value: &JsonValue) -> String {
+    let mut output = String::new();
+
+    // Writing into a `String` can't fail.
+    let _ = YamlEmitter::new(&mut output).dump(&to_yaml(value));
+
+    output
+}
+
+fn to_yaml(value: &JsonValue) -> Yaml {
+    match *value {
+        JsonValue::Null               => Yaml::Null,
+        JsonValue::Boolean(value)     => Yaml::Boolean(value),
+        JsonValue::Short(ref value)   => Yaml::String(value.as_str().into()),
+        JsonValue::String(ref value)  => Yaml::String(String::from(&**value)),
+        JsonValue::Number(number)     => {
+            if let Some(int) = number.as_i128().filter(|&int| int as i64 as i128 == int) {
+                Yaml::Integer(int as i64)
+            } else if number.is_nan() {
+                Yaml::Real(".nan".into())
+            } else if number.is_infinite() {
+                Yaml::Real(if number.is_sign_positive() { ".inf" } else { "-.inf" }.into())
+            } else {
+                Yaml::Real(number.to_string())
+            }
+        },
+        JsonValue::Array(ref vec)     => Yaml::Array(vec.iter().map(to_yaml).collect()),
+        JsonValue::Object(ref object) => {
+            Yaml::Hash(object.iter().map(|(key, value)| (Yaml::String(key.clone()), to_yaml(value))).collect())
+        },
+        JsonValue::Raw(ref raw)       => match raw.value() {
+            Some(value) => to_yaml(&value),
+            None        => Yaml::BadValue,
+        },
+    }
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "async")]
+
+extern crate futures;
+#[macro_use]
+extern crate json;
+
+use std::io;
+use std::pin::Pin;
+use std::task::{ Context, Poll };
+
+use futures::executor::block_on;
+use futures::io::{ AsyncRead, Cursor };
+use json::{ parse_from_async_reader, Error };
+
+// Yields a few bytes at a time, returning `Pending` between reads.
+struct Trickle {
+    data: Vec<u8>,
+    position: usize,
+    ready: bool,
+}
+
+impl AsyncRead for Trickle {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        if !self.ready {
+            self.ready = true;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        self.ready = false;
+
+        let start = self.position;
+        let end = (start + 3).min(self.data.len()).min(start + buf.len());
+
+        buf[..end - start].copy_from_slice(&self.data[start..end]);
+        self.position = end;
+
+        Poll::Ready(Ok(end - start))
+    }
+}
+
+#[test]
+fn parse_async_reader() {
+    let data = block_on(parse_from_async_reader(Cursor::new(b"[1, true, null]".to_vec()))).unwrap();
+
+    assert_eq!(data, array![1, true, json::Null]);
+}
+
+#[test]
+fn parse_async_reader_pending() {
+    let reader = Trickle {
+        data: br#"{"name":"Joe","tags":["a","b"]}"#.to_vec(),
+        position: 0,
+        ready: false,
+    };
+
+    let data = block_on(parse_from_async_reader(reader)).unwrap();
+
+    assert_eq!(data["name"], "Joe");
+    assert_eq!(data["tags"][1], "b");
+}
+
+#[test]
+fn parse_async_reader_errors() {
+    let error = block_on(parse_from_async_reader(Cursor::new(b"[1,".to_vec()))).unwrap_err();
+
+    assert_eq!(error, Error::UnexpectedEndOfJson);
+
+    let error = block_on(parse_from_async_reader(Cursor::new(vec![b'"', 0xFF, b'"']))).unwrap_err();
+
+    assert_eq!(error, Error::FailedUtf8Parsing);
+}
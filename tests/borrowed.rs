@@ -0,0 +1,81 @@
+#[macro_use]
+extern crate json;
+
+use json::{ parse, parse_borrowed, parse_borrowed_with_options, Error, JsonValue, JsonValueRef, ParseOptions };
+
+#[test]
+fn borrowed_matches_parse() {
+    let sources = [
+        r#"{ "a": [1, -2.5, 3e10, true, false, null], "b": { "c": "dé", "": [] }, "e": {} }"#,
+        r#"  [ "x\n", "🦀", 0, -0, 12345678901234567890 ] "#,
+        r#""just a string""#,
+        "123",
+    ];
+
+    for source in &sources {
+        let borrowed = parse_borrowed(source).unwrap();
+
+        assert_eq!(JsonValue::from(&borrowed), parse(source).unwrap(), "{}", source);
+        assert_eq!(borrowed, parse(source).unwrap());
+    }
+}
+
+#[test]
+fn borrowed_strings_and_keys() {
+    let data = parse_borrowed(r#"{ "plain": "value", "esc\"aped": "tab\t" }"#).unwrap();
+
+    let keys: Vec<bool> = data.entries().map(|entry| match entry.0 {
+        std::borrow::Cow::Borrowed(_) => true,
+        std::borrow::Cow::Owned(_)    => false,
+    }).collect();
+
+    assert_eq!(keys, vec![true, false]);
+    assert!(data["plain"].is_borrowed());
+    assert!(!data["esc\"aped"].is_borrowed());
+    assert_eq!(data["esc\"aped"], "tab\t");
+    assert_eq!(data.dump(), r#"{"plain":"value","esc\"aped":"tab\t"}"#);
+}
+
+#[test]
+fn borrowed_duplicate_keys() {
+    let data = parse_borrowed(r#"{ "a": 1, "b": 2, "a": 3 }"#).unwrap();
+
+    assert_eq!(data.len(), 3);
+    assert_eq!(data["a"].as_u64(), Some(3));
+    assert_eq!(data.dump(), r#"{"a":1,"b":2,"a":3}"#);
+    assert_eq!(JsonValue::from(data), object!{ "a" => 3, "b" => 2 });
+}
+
+#[test]
+fn borrowed_accessors() {
+    let data = parse_borrowed(r#"[{ "n": -7 }, 1.5, true, "s"]"#).unwrap();
+
+    assert_eq!(data.members().count(), 4);
+    assert_eq!(data[0]["n"].as_i64(), Some(-7));
+    assert_eq!(data[0]["n"].as_u64(), None);
+    assert_eq!(data[1].as_f64(), Some(1.5));
+    assert_eq!(data[2].as_bool(), Some(true));
+    assert_eq!(data[3].as_str(), Some("s"));
+    assert!(data[4].is_null());
+    assert!(data["key"].is_null());
+    assert!(data[0].has_key("n"));
+    assert_eq!(format!("{}", data[3]), "s");
+    assert_eq!(format!("{}", data[0]), r#"{"n":-7}"#);
+}
+
+#[test]
+fn borrowed_errors() {
+    for source in &["[1,]", "{\"a\" 1}", "[\"a\u{1}\"]", "tru", "[1] x", ""] {
+        assert_eq!(parse_borrowed(source).map(JsonValueRef::into_owned), parse(source).map(|_| JsonValueRef::Null), "{}", source);
+    }
+
+    let deep = format!("{}{}", "[".repeat(600), "]".repeat(600));
+
+    assert_eq!(parse_borrowed(&deep), Err(Error::ExceededDepthLimit));
+
+    let options = ParseOptions { comments: true, non_finite_numbers: true, ..ParseOptions::default() };
+    let data = parse_borrowed_with_options("[NaN, /* x */ -Infinity] // end", options).unwrap();
+
+    assert_eq!(data.len(), 2);
+    assert!(data[0].as_number().unwrap().is_nan());
+}
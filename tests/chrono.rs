@@ -0,0 +1,51 @@
+#![cfg(feature = "chrono")]
+
+extern crate chrono;
+#[macro_use]
+extern crate json;
+
+use chrono::{ DateTime, FixedOffset, NaiveDate, TimeZone, Utc };
+use json::JsonValue;
+
+#[test]
+fn chrono_to_json() {
+    let utc = Utc.with_ymd_and_hms(2020, 1, 31, 12, 30, 0).unwrap();
+    let offset = FixedOffset::east_opt(2 * 3600).unwrap().with_ymd_and_hms(2020, 1, 31, 12, 30, 0).unwrap();
+    let date = NaiveDate::from_ymd_opt(2020, 1, 31).unwrap();
+
+    assert_eq!(JsonValue::from(utc), "2020-01-31T12:30:00Z");
+    assert_eq!(JsonValue::from(offset), "2020-01-31T12:30:00+02:00");
+    assert_eq!(JsonValue::from(date), "2020-01-31");
+
+    let data = object!{ "created" => utc };
+
+    assert_eq!(data.dump(), r#"{"created":"2020-01-31T12:30:00Z"}"#);
+}
+
+#[test]
+fn chrono_from_json() {
+    let data = object!{
+        "utc" => "2020-01-31T12:30:00.5Z",
+        "offset" => "2020-01-31T12:30:00-05:00",
+        "date" => "2020-01-31",
+        "bad" => "31/01/2020",
+        "number" => 5
+    };
+
+    let utc = Utc.with_ymd_and_hms(2020, 1, 31, 12, 30, 0).unwrap() + chrono::Duration::milliseconds(500);
+
+    assert_eq!(data["utc"].as_datetime_utc(), Some(utc));
+    assert_eq!(data["offset"].as_datetime().unwrap().offset().local_minus_utc(), -5 * 3600);
+    assert_eq!(data["offset"].as_datetime_utc(), Some(Utc.with_ymd_and_hms(2020, 1, 31, 17, 30, 0).unwrap()));
+    assert_eq!(data["date"].as_naive_date(), NaiveDate::from_ymd_opt(2020, 1, 31));
+    assert_eq!(data["date"].as_datetime(), None);
+    assert_eq!(data["bad"].as_datetime(), None);
+    assert_eq!(data["number"].as_naive_date(), None);
+
+    assert_eq!(data["utc"].expect_type::<DateTime<Utc>>(), Ok(utc));
+
+    let error = data["bad"].expect_type::<DateTime<FixedOffset>>().unwrap_err();
+
+    assert_eq!(error.expected(), "datetime");
+    assert_eq!(error.actual(), "string");
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "csv")]
+
+#[macro_use]
+extern crate json;
+
+use json::{ csv, Error, Null };
+use json::csv::CsvOptions;
+
+#[test]
+fn csv_parse() {
+    let data = csv::parse("id,name,score,active\r\n1,\"Doe, \"\"JD\"\" Jane\",-1.5e2,true\r\n\r\n2,\"multi\nline\",01,\"false\"").unwrap();
+
+    assert_eq!(data, array![
+        object!{ "id" => 1, "name" => "Doe, \"JD\" Jane", "score" => -150, "active" => true },
+        object!{ "id" => 2, "name" => "multi\nline", "score" => "01", "active" => "false" }
+    ]);
+}
+
+#[test]
+fn csv_parse_empty() {
+    assert_eq!(csv::parse("").unwrap(), array![]);
+    assert_eq!(csv::parse("a,b\n").unwrap(), array![]);
+}
+
+#[test]
+fn csv_parse_with_options() {
+    let options = CsvOptions {
+        delimiter: ';',
+        infer_numbers: false,
+        infer_booleans: false,
+        empty_as_null: true,
+    };
+
+    let data = csv::parse_with_options("a;b;c\n1;true;\n", options).unwrap();
+
+    assert_eq!(data, array![object!{ "a" => "1", "b" => "true", "c" => Null }]);
+}
+
+#[test]
+fn csv_errors() {
+    assert_eq!(csv::parse("a,b\n1,2\n3\n"), Err(Error::InvalidCsv("row at line 3 has 1 fields, expected 2".into())));
+    assert_eq!(csv::parse("a\n\"open\n"), Err(Error::InvalidCsv("unterminated quoted field at line 2".into())));
+    assert_eq!(csv::parse("a\n\"x\"y\n"), Err(Error::InvalidCsv("unexpected character y after quoted field at line 2".into())));
+}
+
+#[test]
+fn csv_stringify() {
+    let data = array![
+        object!{ "id" => 1, "name" => "a,b", "tags" => array!["x"] },
+        object!{ "id" => 2, "extra" => Null, "name" => "42" }
+    ];
+
+    let output = csv::stringify(&data).unwrap();
+
+    assert_eq!(output, "id,name,tags,extra\n1,\"a,b\",\"[\"\"x\"\"]\",\n2,\"42\",,\n");
+    assert_eq!(csv::parse(&output).unwrap()[1]["name"], "42");
+
+    assert_eq!(csv::stringify(&array![1]), Err(Error::InvalidCsv("value that is not an array of objects".into())));
+    assert_eq!(csv::stringify(&object!{}), Err(Error::InvalidCsv("value that is not an array of objects".into())));
+}
+
+#[test]
+fn csv_round_trip_with_options() {
+    let options = CsvOptions {
+        empty_as_null: true,
+        ..CsvOptions::default()
+    };
+
+    let data = array![object!{ "a" => "", "b" => Null, "c" => false }];
+    let output = csv::stringify_with_options(&data, options).unwrap();
+
+    assert_eq!(output, "a,b,c\n\"\",,false\n");
+    assert_eq!(csv::parse_with_options(&output, options).unwrap(), data);
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "rust_decimal")]
+
+extern crate rust_decimal;
+#[macro_use]
+extern crate json;
+
+use std::str::FromStr;
+use rust_decimal::Decimal;
+use json::number::Number;
+use json::{ JsonValue, ParseOptions };
+
+fn decimal(string: &str) -> Decimal {
+    Decimal::from_str(string).unwrap()
+}
+
+#[test]
+fn decimal_to_json() {
+    assert_eq!(JsonValue::from(decimal("0.1")).dump(), "0.1");
+    assert_eq!(JsonValue::from(decimal("-12.340")).dump(), "-12.34");
+    assert_eq!(JsonValue::from(decimal("100")).dump(), "100");
+    assert_eq!(object!{ "price" => decimal("19.99") }.dump(), r#"{"price":19.99}"#);
+
+    // Too many significant digits for `Number`, least significant are dropped.
+    let precise = decimal("1.2345678901234567890123456789");
+
+    assert_eq!(Number::from(precise), Number::from_parts(true, 12345678901234567890, -19));
+}
+
+#[test]
+fn decimal_from_json() {
+    let data = json::parse(r#"[0.1, -5, 1.5e3, 1e-28, 1e-29, 1e30, 79228162514264337593543950335]"#).unwrap();
+
+    assert_eq!(data[0].as_decimal(), Some(decimal("0.1")));
+    assert_eq!(data[1].as_decimal(), Some(decimal("-5")));
+    assert_eq!(data[2].as_decimal(), Some(decimal("1500")));
+    assert_eq!(data[3].as_decimal(), Some(decimal("0.0000000000000000000000000001")));
+    assert_eq!(data[4].as_decimal(), None);
+    assert_eq!(data[5].as_decimal(), None);
+    assert_eq!(data[6].as_decimal(), Some(decimal("79228162514264337590000000000")));
+    assert_eq!(JsonValue::from("1.5").as_decimal(), None);
+    assert_eq!(JsonValue::from(::std::f64::NAN).as_decimal(), None);
+
+    assert_eq!(data[0], decimal("0.1"));
+    assert_eq!(data[0].expect_type::<Decimal>(), Ok(decimal("0.1")));
+
+    let options = ParseOptions { preserve_number_text: true, ..ParseOptions::default() };
+    let price = json::parse_with_options("1.50", options).unwrap();
+
+    assert_eq!(price.as_decimal().unwrap().to_string(), "1.50");
+}
+
+#[test]
+fn decimal_round_trip() {
+    for string in &["0", "0.01", "-0.5", "123456789.123456789", "9999999999999999999"] {
+        let value = decimal(string);
+
+        assert_eq!(JsonValue::from(value).as_decimal(), Some(value));
+    }
+}
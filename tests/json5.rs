@@ -0,0 +1,73 @@
+#![cfg(feature = "json5")]
+
+#[macro_use]
+extern crate json;
+
+use json::json5::parse;
+use json::number::Number;
+use json::Error;
+
+#[test]
+fn json5_example() {
+    let source = r#"
+// This file is written in JSON5 syntax, naturally, but npm needs a regular
+// JSON file, so compile via `npm run build`. Be sure to keep both in sync!
+{
+    name: 'json5',
+    version: '0.5.0',
+    description: 'JSON for the ES5 era.',
+    keywords: ['json', 'es5',],
+    $schema: "x",
+    _private: true,
+    /* block */ 'quoted key': 1,
+}
+"#;
+
+    assert_eq!(parse(source).unwrap(), object!{
+        "name" => "json5",
+        "version" => "0.5.0",
+        "description" => "JSON for the ES5 era.",
+        "keywords" => array!["json", "es5"],
+        "$schema" => "x",
+        "_private" => true,
+        "quoted key" => 1
+    });
+}
+
+#[test]
+fn json5_strings() {
+    assert_eq!(parse(r#"'single "quoted"'"#).unwrap(), "single \"quoted\"");
+    assert_eq!(parse(r#""double 'quoted'""#).unwrap(), "double 'quoted'");
+    assert_eq!(parse("'multi\\\nline\\\r\nstring'").unwrap(), "multilinestring");
+    assert_eq!(parse(r#"'\x41B\v\0\'\q😀'"#).unwrap(), "AB\u{B}\0'q\u{1F600}");
+    assert_eq!(parse("{ \\u0061b: 1, ünïcødé: 2 }").unwrap(), object!{ "ab" => 1, "ünïcødé" => 2 });
+}
+
+#[test]
+fn json5_numbers() {
+    assert_eq!(parse("0x1F").unwrap(), 31);
+    assert_eq!(parse("-0XFF").unwrap(), -255);
+    assert_eq!(parse("+1").unwrap(), 1);
+    assert_eq!(parse(".5").unwrap(), 0.5);
+    assert_eq!(parse("5.").unwrap(), 5);
+    assert_eq!(parse("-1.25e+2").unwrap(), -125);
+    assert_eq!(parse("0").unwrap(), 0);
+    assert_eq!(parse("18446744073709551616").unwrap(), Number::from_parts(true, 1844674407370955161, 1));
+    assert!(parse("NaN").unwrap().as_number().unwrap().is_nan());
+    assert_eq!(parse("-Infinity").unwrap(), json::number::NEG_INFINITY);
+    assert_eq!(parse("[1, 2, ]").unwrap(), array![1, 2]);
+}
+
+#[test]
+fn json5_errors() {
+    assert_eq!(parse("[1, 2"), Err(Error::UnexpectedEndOfJson));
+    assert_eq!(parse("/* open"), Err(Error::UnexpectedEndOfJson));
+    assert_eq!(parse("{\n  a: 01 }"), Err(Error::UnexpectedCharacter { ch: '1', line: 2, column: 7 }));
+    assert_eq!(parse("'line\nbreak'"), Err(Error::UnexpectedCharacter { ch: '\n', line: 1, column: 6 }));
+    assert_eq!(parse("[,]"), Err(Error::UnexpectedCharacter { ch: ',', line: 1, column: 2 }));
+    assert_eq!(parse("{ a-b: 1 }"), Err(Error::UnexpectedCharacter { ch: '-', line: 1, column: 4 }));
+    assert_eq!(parse("undefined"), Err(Error::UnexpectedCharacter { ch: 'u', line: 1, column: 1 }));
+    assert_eq!(parse("0x"), Err(Error::UnexpectedEndOfJson));
+    assert_eq!(parse("1 2"), Err(Error::UnexpectedCharacter { ch: '2', line: 1, column: 3 }));
+    assert_eq!(parse(&"[".repeat(1000)), Err(Error::ExceededDepthLimit));
+}
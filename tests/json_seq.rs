@@ -0,0 +1,73 @@
+#[macro_use]
+extern crate json;
+
+use json::json_seq::{ read, Writer };
+use json::Error;
+
+fn records(input: &[u8]) -> Vec<json::Result<json::JsonValue>> {
+    read(input).collect()
+}
+
+#[test]
+fn read_sequence() {
+    let input = b"\x1e{\"a\":1}\n\x1e[1,\n 2]\n\x1e\"x\"\n\x1etrue\n\x1e\x1e  \n\x1e-1.5 \n";
+
+    assert_eq!(records(input), vec![
+        Ok(object!{ "a" => 1 }),
+        Ok(array![1, 2]),
+        Ok("x".into()),
+        Ok(true.into()),
+        Ok((-1.5).into()),
+    ]);
+
+    // Separators are all that's required, line feeds are optional.
+    assert_eq!(records(b"\x1e{}\x1e[]\x1e"), vec![Ok(object!{}), Ok(array![])]);
+    assert!(records(b"").is_empty());
+    assert!(records(b"\x1e\n").is_empty());
+}
+
+#[test]
+fn read_truncated_records() {
+    let input = b"\x1e{\"a\":[1,\x1e123\x1enul\x1e{\"b\":2}\n\x1e12";
+
+    let mut records = read(&input[..]);
+
+    assert_eq!(records.next(), Some(Err(Error::UnexpectedEndOfJson)));
+    assert_eq!(records.next(), Some(Err(Error::UnexpectedEndOfJson)));
+    assert_eq!(records.record(), 2);
+    assert_eq!(records.next(), Some(Err(Error::UnexpectedEndOfJson)));
+    assert_eq!(records.next(), Some(Ok(object!{ "b" => 2 })));
+    assert_eq!(records.next(), Some(Err(Error::UnexpectedEndOfJson)));
+    assert_eq!(records.record(), 5);
+    assert_eq!(records.next(), None);
+}
+
+#[test]
+fn read_malformed_records() {
+    let input = b"\x1e{\"a\" 1}\n\x1e\xff\n\x1e[1]\n";
+
+    assert_eq!(records(input), vec![
+        Err(Error::UnexpectedCharacter { ch: '1', line: 1, column: 6 }),
+        Err(Error::FailedUtf8Parsing),
+        Ok(array![1]),
+    ]);
+}
+
+#[test]
+fn write_sequence() {
+    let values = vec![object!{ "a" => "line\nbreak" }, array![1, 2], 3.into(), json::Null];
+    let mut writer = Writer::new(Vec::new());
+
+    for value in &values {
+        writer.write(value).unwrap();
+    }
+
+    writer.flush().unwrap();
+
+    assert_eq!(writer.get_ref().len(), 37);
+
+    let output = writer.into_inner();
+
+    assert_eq!(&output[..24], &b"\x1e{\"a\":\"line\\nbreak\"}\n\x1e[1"[..]);
+    assert_eq!(records(&output), values.into_iter().map(Ok).collect::<Vec<_>>());
+}
@@ -0,0 +1,174 @@
+#[macro_use]
+extern crate json;
+
+use json::jtd::Schema;
+use json::{ Error, JsonValue, Null, ToJson };
+
+fn compile(source: &str) -> Schema {
+    Schema::compile(&json::parse(source).unwrap()).unwrap()
+}
+
+fn errors(schema: &Schema, value: &JsonValue) -> Vec<(String, String)> {
+    schema.validate(value).iter()
+        .map(|error| (error.instance_path.to_string(), error.schema_path.to_string()))
+        .collect()
+}
+
+fn pair(instance: &str, schema: &str) -> (String, String) {
+    (instance.into(), schema.into())
+}
+
+#[test]
+fn jtd_empty_and_nullable() {
+    let schema = compile("{}");
+
+    assert!(schema.is_valid(&Null));
+    assert!(schema.is_valid(&object!{ "a" => array![1] }));
+
+    let schema = compile(r#"{ "type": "string", "nullable": true, "metadata": { "description": "x" } }"#);
+
+    assert!(schema.is_valid(&Null));
+    assert!(schema.is_valid(&"a".into()));
+    assert_eq!(errors(&schema, &1.into()), vec![pair("", "/type")]);
+}
+
+#[test]
+fn jtd_types() {
+    let cases: &[(&str, JsonValue, bool)] = &[
+        ("boolean", true.into(), true),
+        ("boolean", "true".into(), false),
+        ("string", "a".into(), true),
+        ("timestamp", "1985-04-12T23:20:50.52Z".into(), true),
+        ("timestamp", "1990-12-31T15:59:60-08:00".into(), true),
+        ("timestamp", "1985-04-12T23:20:50".into(), false),
+        ("timestamp", "1985-04-12".into(), false),
+        ("float32", 1.5.into(), true),
+        ("float64", "1".into(), false),
+        ("int8", (-128).into(), true),
+        ("int8", 128.into(), false),
+        ("uint8", 255.into(), true),
+        ("uint8", (-1).into(), false),
+        ("int16", 1.5.into(), false),
+        ("uint16", json::parse("3.0").unwrap(), true),
+        ("int32", 2147483647.into(), true),
+        ("uint32", 4294967296u64.into(), false),
+    ];
+
+    for &(kind, ref value, valid) in cases {
+        let schema = compile(&object!{ "type" => kind }.dump());
+
+        assert_eq!(schema.is_valid(value), valid, "{} {}", kind, value);
+    }
+}
+
+#[test]
+fn jtd_enum_elements_values() {
+    let schema = compile(r#"{ "elements": { "enum": ["a", "b"] } }"#);
+
+    assert!(schema.is_valid(&array!["a", "b", "a"]));
+    assert_eq!(errors(&schema, &array!["a", "c", 1]), vec![pair("/1", "/elements/enum"), pair("/2", "/elements/enum")]);
+    assert_eq!(errors(&schema, &object!{}), vec![pair("", "/elements")]);
+
+    let schema = compile(r#"{ "values": { "type": "uint8" } }"#);
+
+    assert!(schema.is_valid(&object!{ "a" => 1, "b" => 2 }));
+    assert_eq!(errors(&schema, &object!{ "a" => 1, "b" => Null }), vec![pair("/b", "/values/type")]);
+    assert_eq!(errors(&schema, &array![]), vec![pair("", "/values")]);
+}
+
+#[test]
+fn jtd_properties() {
+    let schema = compile(r#"{
+        "properties": { "id": { "type": "string" } },
+        "optionalProperties": { "age": { "type": "uint8" } }
+    }"#);
+
+    assert!(schema.is_valid(&object!{ "id" => "a" }));
+    assert!(schema.is_valid(&object!{ "id" => "a", "age" => 3 }));
+
+    assert_eq!(errors(&schema, &object!{ "age" => "old", "extra" => 1 }), vec![
+        pair("", "/properties/id"),
+        pair("/age", "/optionalProperties/age/type"),
+        pair("/extra", ""),
+    ]);
+    assert_eq!(errors(&schema, &"a".into()), vec![pair("", "/properties")]);
+
+    let schema = compile(r#"{ "optionalProperties": {}, "additionalProperties": true }"#);
+
+    assert!(schema.is_valid(&object!{ "anything" => 1 }));
+    assert_eq!(errors(&schema, &1.into()), vec![pair("", "/optionalProperties")]);
+}
+
+#[test]
+fn jtd_discriminator() {
+    let schema = compile(r#"{
+        "discriminator": "kind",
+        "mapping": {
+            "circle": { "properties": { "radius": { "type": "float64" } } },
+            "square": { "properties": { "side": { "type": "float64" } } }
+        }
+    }"#);
+
+    assert!(schema.is_valid(&object!{ "kind" => "circle", "radius" => 1.5 }));
+
+    assert_eq!(errors(&schema, &object!{ "kind" => "square", "radius" => 1 }), vec![
+        pair("", "/mapping/square/properties/side"),
+        pair("/radius", "/mapping/square"),
+    ]);
+    assert_eq!(errors(&schema, &object!{ "radius" => 1 }), vec![pair("", "/discriminator")]);
+    assert_eq!(errors(&schema, &object!{ "kind" => 1 }), vec![pair("/kind", "/discriminator")]);
+    assert_eq!(errors(&schema, &object!{ "kind" => "oval" }), vec![pair("/kind", "/mapping")]);
+    assert_eq!(errors(&schema, &array![]), vec![pair("", "/discriminator")]);
+}
+
+#[test]
+fn jtd_ref() {
+    let schema = compile(r#"{
+        "definitions": {
+            "node": {
+                "properties": { "value": { "type": "int32" } },
+                "optionalProperties": { "next": { "ref": "node" } }
+            },
+            "alias": { "ref": "node" }
+        },
+        "ref": "alias"
+    }"#);
+
+    assert!(schema.is_valid(&object!{ "value" => 1, "next" => object!{ "value" => 2 } }));
+    assert_eq!(
+        errors(&schema, &object!{ "value" => 1, "next" => object!{ "value" => "2" } }),
+        vec![pair("/next/value", "/definitions/node/properties/value/type")]
+    );
+}
+
+#[test]
+fn jtd_error_to_json() {
+    let schema = compile(r#"{ "elements": { "type": "string" } }"#);
+    let errors = schema.validate(&array![1]);
+
+    assert_eq!(errors[0].to_json(), object!{ "instancePath" => "/0", "schemaPath" => "/elements/type" });
+}
+
+#[test]
+fn jtd_invalid_schemas() {
+    let cases = [
+        (r#"[]"#, "schema that is not an object"),
+        (r#"{ "type": "int64" }"#, "unknown type"),
+        (r#"{ "type": "string", "enum": ["a"] }"#, "keywords of more than one form"),
+        (r#"{ "elements": { "foo": 1 } }"#, "unknown keyword foo at /elements"),
+        (r#"{ "enum": [] }"#, "enum that is not a non-empty array"),
+        (r#"{ "enum": ["a", "a"] }"#, "repeated enum value a"),
+        (r#"{ "ref": "missing" }"#, "reference to missing definition missing"),
+        (r#"{ "values": { "definitions": {} } }"#, "unknown keyword definitions at /values"),
+        (r#"{ "nullable": 1 }"#, "nullable that is not a boolean"),
+        (r#"{ "additionalProperties": true }"#, "additionalProperties without properties"),
+        (r#"{ "properties": { "a": {} }, "optionalProperties": { "a": {} } }"#, "property a that is both required and optional"),
+        (r#"{ "discriminator": "k", "mapping": { "x": { "type": "string" } } }"#, "mapping that is not a non-nullable properties form at /mapping/x"),
+        (r#"{ "discriminator": "k", "mapping": { "x": { "properties": { "k": {} } } } }"#, "mapping defining the discriminator k at /mapping/x"),
+        (r#"{ "definitions": { "a": { "ref": "b" }, "b": { "ref": "a" } } }"#, "circular reference at /definitions/a"),
+    ];
+
+    for &(source, reason) in &cases {
+        assert_eq!(Schema::compile(&json::parse(source).unwrap()), Err(Error::InvalidSchema(reason.into())), "{}", source);
+    }
+}
@@ -0,0 +1,62 @@
+#[macro_use]
+extern crate json;
+
+use json::lazy::LazyObject;
+use json::{ parse, Error };
+
+#[test]
+fn lazy_parses_on_access() {
+    let source = r#"{
+        "id": 1,
+        "tags": ["a", "]", "\"}"],
+        "nested": { "deep": [{ "x": null }] },
+        "neg": -1.5e3,
+        "flag": false
+    }"#;
+
+    let object = LazyObject::parse(source).unwrap();
+
+    assert_eq!(object.keys().collect::<Vec<_>>(), vec!["id", "tags", "nested", "neg", "flag"]);
+    assert_eq!(object.raw("tags"), Some(r#"["a", "]", "\"}"]"#));
+    assert_eq!(object.raw("neg"), Some("-1.5e3"));
+    assert!(!object.is_parsed("tags"));
+
+    assert_eq!(object.get("tags").unwrap().unwrap(), &array!["a", "]", "\"}"]);
+    assert!(object.is_parsed("tags"));
+    assert!(!object.is_parsed("id"));
+
+    let nested = object.get_object("nested").unwrap().unwrap();
+
+    assert_eq!(nested.raw("deep"), Some(r#"[{ "x": null }]"#));
+    assert!(object.get_object("flag").is_err());
+
+    assert_eq!(object.into_value().unwrap(), parse(source).unwrap());
+}
+
+#[test]
+fn lazy_duplicate_and_escaped_keys() {
+    let object = LazyObject::parse(r#"{ "a": 1, "b\n": 2, "a": 3 }"#).unwrap();
+
+    assert_eq!(object.len(), 3);
+    assert_eq!(object.get("a").unwrap().unwrap(), 3);
+    assert!(object.has_key("b\n"));
+    assert_eq!(object.into_value().unwrap(), object!{ "a" => 3, "b\n" => 2 });
+}
+
+#[test]
+fn lazy_errors() {
+    assert!(LazyObject::parse("[]").is_err());
+    assert!(LazyObject::parse(r#"{ "a": "unterminated }"#).is_err());
+    assert!(LazyObject::parse(r#"{ "a": [1, 2 }"#).is_err());
+    assert!(LazyObject::parse(r#"{ "a": }"#).is_err());
+    assert!(LazyObject::parse(r#"{ "a": 1 } x"#).is_err());
+
+    // Malformed values are only reported once accessed.
+    let object = LazyObject::parse(r#"{ "good": true, "bad": [1 2] }"#).unwrap();
+
+    assert_eq!(object.get("good").unwrap().unwrap(), true);
+    assert_eq!(
+        object.get("bad"),
+        Err(Error::UnexpectedCharacter { ch: '2', line: 1, column: 4 })
+    );
+}
@@ -0,0 +1,92 @@
+#![cfg(feature = "msgpack")]
+
+#[macro_use]
+extern crate json;
+
+use json::{ msgpack, Error, JsonValue, Null };
+use json::number::Number;
+
+#[test]
+fn msgpack_scalars() {
+    assert_eq!(msgpack::to_vec(&Null), [0xc0]);
+    assert_eq!(msgpack::to_vec(&false.into()), [0xc2]);
+    assert_eq!(msgpack::to_vec(&true.into()), [0xc3]);
+    assert_eq!(msgpack::to_vec(&5.into()), [0x05]);
+    assert_eq!(msgpack::to_vec(&(-5).into()), [0xfb]);
+    assert_eq!(msgpack::to_vec(&200.into()), [0xcc, 200]);
+    assert_eq!(msgpack::to_vec(&(-200).into()), [0xd1, 0xff, 0x38]);
+    assert_eq!(msgpack::to_vec(&70000.into()), [0xce, 0x00, 0x01, 0x11, 0x70]);
+    assert_eq!(msgpack::to_vec(&u64::MAX.into()), [0xcf, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]);
+    assert_eq!(msgpack::to_vec(&i64::MIN.into()), [0xd3, 0x80, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(msgpack::to_vec(&1.5.into()), [0xcb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(msgpack::to_vec(&"hi".into()), [0xa2, b'h', b'i']);
+
+    // Integers out of range of `u64` are encoded as floats.
+    let big = JsonValue::Number(Number::from_parts(true, 1, 20));
+
+    assert_eq!(msgpack::to_vec(&big)[0], 0xcb);
+    assert_eq!(msgpack::from_slice(&msgpack::to_vec(&big)).unwrap(), 1e20);
+}
+
+#[test]
+fn msgpack_round_trip() {
+    let long = "x".repeat(300);
+    let data = object!{
+        "null" => Null,
+        "bools" => array![true, false],
+        "ints" => array![0, 127, 128, -32, -33, 65535, 65536, -2147483648i64, 4294967296u64],
+        "floats" => array![0.1, -2.5e-10],
+        "short" => "abc",
+        "long" => long.as_str(),
+        "unicode" => "zażółć",
+        "nested" => object!{ "list" => (0..20).collect::<Vec<i32>>(), "empty" => object!{} }
+    };
+
+    let bytes = msgpack::to_vec(&data);
+
+    assert!(bytes.len() < data.dump().len());
+    assert_eq!(msgpack::from_slice(&bytes).unwrap(), data);
+
+    let mut written = Vec::new();
+    msgpack::write(&data, &mut written).unwrap();
+
+    assert_eq!(written, bytes);
+}
+
+#[test]
+fn msgpack_decode_other_types() {
+    // float 32
+    assert_eq!(msgpack::from_slice(&[0xca, 0x3f, 0xc0, 0, 0]).unwrap(), 1.5);
+    // str 16 and array 16
+    assert_eq!(msgpack::from_slice(&[0xda, 0, 1, b'a']).unwrap(), "a");
+    assert_eq!(msgpack::from_slice(&[0xdc, 0, 2, 1, 2]).unwrap(), array![1, 2]);
+    // map 32
+    assert_eq!(msgpack::from_slice(&[0xdf, 0, 0, 0, 1, 0xa1, b'k', 0xc0]).unwrap(), object!{ "k" => Null });
+}
+
+#[test]
+fn msgpack_decode_errors() {
+    assert_eq!(msgpack::from_slice(&[]), Err(Error::UnexpectedEndOfJson));
+    assert_eq!(msgpack::from_slice(&[0x92, 0x01]), Err(Error::UnexpectedEndOfJson));
+    assert_eq!(msgpack::from_slice(&[0xdd, 0xff, 0xff, 0xff, 0xff]), Err(Error::UnexpectedEndOfJson));
+    assert_eq!(msgpack::from_slice(&[0xa2, 0xff, 0xfe]), Err(Error::FailedUtf8Parsing));
+    assert_eq!(
+        msgpack::from_slice(&[0x91, 0xc4, 0x01, 0x00]),
+        Err(Error::InvalidMsgpack("unsupported type 0xc4 at byte 1".into()))
+    );
+    assert_eq!(
+        msgpack::from_slice(&[0x81, 0x01, 0x02]),
+        Err(Error::InvalidMsgpack("key that is not a string at byte 1".into()))
+    );
+
+    let error = msgpack::from_slice(&[0x01, 0x02]).unwrap_err();
+
+    assert_eq!(error.to_string(), "Invalid MessagePack: trailing bytes at byte 1");
+    assert_eq!(error.code(), 14);
+    assert_eq!(error.code_name(), "invalid_msgpack");
+
+    let mut nested = vec![0x91; 600];
+    nested.push(0xc0);
+
+    assert_eq!(msgpack::from_slice(&nested), Err(Error::ExceededDepthLimit));
+}
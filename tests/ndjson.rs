@@ -0,0 +1,136 @@
+extern crate json;
+
+use json::ndjson::{ aggregate, read, shard, Agg, ShardBy };
+use json::{ Error, Null };
+
+const LOG: &'static str = r#"{"amount":10,"ts":1500}
+{"amount":2.5,"ts":1700}
+
+{"amount":-4,"ts":1600}
+{"ts":1800,"amount":"n/a"}
+"#;
+
+#[test]
+fn aggregate_all() {
+    let result = aggregate(LOG.as_bytes(), &[
+        Agg::Count,
+        Agg::Sum("/amount"),
+        Agg::Min("/amount"),
+        Agg::Max("/ts"),
+        Agg::Mean("/amount"),
+    ]).unwrap();
+
+    assert_eq!(result.len(), 5);
+    assert_eq!(result[0], 4);
+    assert_eq!(result[1], 8.5);
+    assert_eq!(result[2], -4);
+    assert_eq!(result[3], 1800);
+    assert_eq!(result[4], 8.5 / 3.0);
+}
+
+#[test]
+fn aggregate_no_matches() {
+    let result = aggregate(LOG.as_bytes(), &[
+        Agg::Max("/missing"),
+        Agg::Mean("/missing"),
+        Agg::Sum("/missing"),
+    ]).unwrap();
+
+    assert_eq!(result[0], Null);
+    assert_eq!(result[1], Null);
+    assert_eq!(result[2], 0);
+}
+
+#[test]
+fn aggregate_reports_line() {
+    let input = "{\"a\":1}\n{\"a\":2}\n{\"a\":x}\n";
+
+    assert_eq!(aggregate(input.as_bytes(), &[Agg::Count]), Err(Error::UnexpectedCharacter {
+        ch: 'x',
+        line: 3,
+        column: 6,
+    }));
+}
+
+#[test]
+fn read_records() {
+    let amounts: Vec<_> = read(LOG.as_bytes())
+        .map(|record| record.unwrap()["amount"].clone())
+        .collect();
+
+    assert_eq!(amounts, vec![json::from(10), json::from(2.5), json::from(-4), json::from("n/a")]);
+}
+
+#[test]
+fn read_reports_line_and_continues() {
+    let input = "{\"a\":1}\n{\"a\":x}\n{\"a\":\n{\"a\":4}";
+    let mut records = read(input.as_bytes());
+
+    assert_eq!(records.next(), Some(Ok(json::parse("{\"a\":1}").unwrap())));
+    assert_eq!(records.next(), Some(Err(Error::UnexpectedCharacter {
+        ch: 'x',
+        line: 2,
+        column: 6,
+    })));
+    assert_eq!(records.next(), Some(Err(Error::UnexpectedEndOfJson)));
+    assert_eq!(records.line(), 3);
+    assert_eq!(records.next().unwrap().unwrap()["a"], 4);
+    assert_eq!(records.next(), None);
+}
+
+#[test]
+fn shard_by_count() {
+    let input = "[\n  {\"id\": 1},\n  {\"id\": 2, \"tags\": [\"a,b\", \"]\"]},\n  3,\n  \"four\"\n]\n";
+    let mut shards = vec![Vec::new(), Vec::new(), Vec::new()];
+
+    let count = {
+        let mut sinks = shards.iter_mut();
+
+        shard(input.as_bytes(), ShardBy::Count(3), |_| Ok(sinks.next().unwrap())).unwrap()
+    };
+
+    assert_eq!(count, 4);
+    assert_eq!(shards[0], b"{\"id\":1}\n\"four\"\n".to_vec());
+    assert_eq!(shards[1], b"{\"id\":2,\"tags\":[\"a,b\",\"]\"]}\n".to_vec());
+    assert_eq!(shards[2], b"3\n".to_vec());
+}
+
+#[test]
+fn shard_by_max_bytes() {
+    let input = "[1, 2, 3, 4444, 5]";
+    let mut shards = Vec::new();
+
+    let count = shard(input.as_bytes(), ShardBy::MaxBytes(4), |index| {
+        assert_eq!(index, shards.len());
+        shards.push(());
+        Ok(Vec::new())
+    }).unwrap();
+
+    assert_eq!(count, 5);
+    // "1\n2\n" | "3\n" | "4444\n" | "5\n"
+    assert_eq!(shards.len(), 4);
+}
+
+#[test]
+fn shard_empty_array() {
+    let count = shard(" [ ] ".as_bytes(), ShardBy::Count(2), |_| -> std::io::Result<Vec<u8>> {
+        panic!("No shards should be created");
+    }).unwrap();
+
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn shard_errors() {
+    let sink = |_| Ok(std::io::sink());
+
+    assert!(shard("{}".as_bytes(), ShardBy::Count(1), sink).is_err());
+    assert_eq!(shard("[1,2".as_bytes(), ShardBy::Count(1), sink), Err(Error::UnexpectedEndOfJson));
+    assert!(shard("[1,,2]".as_bytes(), ShardBy::Count(1), sink).is_err());
+    assert!(shard("[1,2] x".as_bytes(), ShardBy::Count(1), sink).is_err());
+    assert_eq!(shard("[1,\n  trux]".as_bytes(), ShardBy::Count(1), sink), Err(Error::UnexpectedCharacter {
+        ch: 'x',
+        line: 2,
+        column: 6,
+    }));
+}
@@ -126,3 +126,102 @@ fn as_fixed_point_i64() {
 fn convert_f64_precision() {
     assert_eq!(unsafe { Number::from_parts_unchecked(true, 4750000000000001, -18) }, 0.004750000000000001);
 }
+
+#[test]
+fn from_f64_exact_round_trip() {
+    let floats = [0.1 + 0.2, 0.004750000000000001, 1.7976931348623157e308, 5e-324, -2.5, -0.0, 123456789.12345678];
+
+    for &float in floats.iter() {
+        let number = Number::from_f64_exact(float);
+
+        assert!(number.is_exact_f64());
+        assert_eq!(f64::from(number).to_bits(), float.to_bits());
+        assert_eq!((-number).is_sign_positive(), float.is_sign_negative());
+        assert_eq!(f64::from(-number).to_bits(), (-float).to_bits());
+    }
+
+    assert!(Number::from_f64_exact(f64::NAN).is_nan());
+    assert!(Number::from_f64_exact(f64::INFINITY).is_nan());
+}
+
+#[test]
+fn from_f64_exact_behaves_as_decimal() {
+    let number = Number::from_f64_exact(-1.2345);
+
+    assert_eq!(number, Number::from_parts(false, 12345, -4));
+    assert_eq!(number.as_parts(), (false, 12345, -4));
+    assert_eq!(number.as_fixed_point_i64(2), Some(-123));
+    assert_eq!(number.as_fixed_point_u64(2), None);
+    assert_eq!(number.to_string(), "-1.2345");
+    assert_eq!(Number::from_f64_exact(5.99).as_fixed_point_u64(2), Some(599));
+    assert_eq!(Number::from_f64_exact(0.0), 0);
+    assert!(Number::from_f64_exact(0.0).is_zero());
+    assert!(Number::from_f64_exact(-0.0).is_zero());
+    assert_ne!(Number::from_f64_exact(1.5), Number::from_f64_exact(-1.5));
+}
+
+#[test]
+fn number_128_bit_conversions() {
+    use std::convert::TryFrom;
+
+    let id: u128 = 12_345_678_901_234_567_890_000_000;
+
+    let value = json::JsonValue::try_from(id).unwrap();
+
+    assert!(id > u64::MAX as u128);
+    assert_eq!(value.as_u128(), Some(id));
+    assert_eq!(value.as_i128(), Some(id as i128));
+    assert_eq!(value, id);
+    assert_eq!(json::parse(&value.dump()).unwrap().as_u128(), Some(id));
+
+    assert_eq!(json::JsonValue::try_from(-7i128).unwrap().as_i128(), Some(-7));
+    assert_eq!(json::JsonValue::try_from(-7i128).unwrap().as_u128(), None);
+    assert_eq!(json::JsonValue::from("7").as_u128(), None);
+
+    // Identifiers using all 128 bits have too many significant digits.
+    let id: u128 = 0x1234_5678_9abc_def0_1234_5678_9abc_def0;
+
+    assert_eq!(
+        json::JsonValue::try_from(id).unwrap_err(),
+        json::Error::InexactNumber(id.to_string())
+    );
+    assert!(Number::try_from(u128::MAX).is_err());
+    assert!(Number::try_from(i128::MIN).is_err());
+    assert_eq!(json::parse(&id.to_string()).unwrap().as_u128(), None);
+
+    let max = json::parse("170141183460469231731687303715884105727").unwrap();
+
+    assert_eq!(max.as_i128(), None);
+    assert_eq!(json::parse("-170141183460469231731687303715884105728").unwrap().as_i128(), None);
+    assert_eq!(json::parse("18446744073709551615.5").unwrap().as_u128(), None);
+    assert_eq!(json::parse("184467440737095516150").unwrap().as_u128(), Some(184467440737095516150));
+    assert_eq!(json::parse("-0").unwrap().as_u128(), Some(0));
+    assert_eq!(json::parse("1.0").unwrap().as_u128(), Some(1));
+    assert_eq!(json::parse("1e40").unwrap().as_u128(), None);
+}
+
+#[test]
+fn number_signed_minimums() {
+    assert_eq!(Number::from(i64::MIN), Number::from_parts(false, 9223372036854775808, 0));
+    assert_eq!(i64::from(Number::from(i64::MIN)), i64::MIN);
+    assert_eq!(i32::from(Number::from(i32::MIN)), i32::MIN);
+    assert_eq!(i8::from(Number::from(i8::MIN)), i8::MIN);
+}
+
+#[test]
+fn infinities_compare_with_finite_numbers() {
+    use json::number::{ INFINITY, NEG_INFINITY };
+
+    assert_ne!(INFINITY, Number::from_parts(true, 15, -1));
+    assert_ne!(NEG_INFINITY, Number::from_parts(false, 15, -1));
+    assert_ne!(Number::from_parts(true, 1, i16::MAX), INFINITY);
+    assert_ne!(INFINITY, NEG_INFINITY);
+    assert_eq!(INFINITY, INFINITY);
+    assert_ne!(INFINITY, 1);
+
+    assert_eq!(INFINITY.as_fixed_point_u64(2), None);
+    assert_eq!(NEG_INFINITY.as_fixed_point_i64(2), None);
+    assert_eq!(INFINITY.as_u128(), None);
+    assert_eq!(i64::from(NEG_INFINITY), i64::MIN);
+    assert_eq!(u8::from(INFINITY), u8::MAX);
+}
@@ -0,0 +1,99 @@
+#[macro_use]
+extern crate json;
+
+use json::ops::{ join, JoinKind };
+
+fn users() -> json::JsonValue {
+    array![
+        object!{ "id" => 1, "name" => "Alice" },
+        object!{ "id" => 2, "name" => "Bob" },
+        object!{ "name" => "Nobody" }
+    ]
+}
+
+fn orders() -> json::JsonValue {
+    array![
+        object!{ "id" => 1, "total" => 10 },
+        object!{ "id" => 1, "total" => 20 },
+        object!{ "id" => 3, "total" => 30 }
+    ]
+}
+
+#[test]
+fn join_inner() {
+    let joined = join(&users(), &orders(), "/id", JoinKind::Inner).unwrap();
+
+    assert_eq!(joined, array![
+        object!{ "id" => 1, "name" => "Alice", "total" => 10 },
+        object!{ "id" => 1, "name" => "Alice", "total" => 20 }
+    ]);
+}
+
+#[test]
+fn join_left() {
+    let joined = join(&users(), &orders(), "/id", JoinKind::Left).unwrap();
+
+    assert_eq!(joined.len(), 4);
+    assert_eq!(joined[2], object!{ "id" => 2, "name" => "Bob" });
+    assert_eq!(joined[3], object!{ "name" => "Nobody" });
+}
+
+#[test]
+fn join_right_and_full() {
+    let right = join(&users(), &orders(), "/id", JoinKind::Right).unwrap();
+
+    assert_eq!(right.len(), 3);
+    assert_eq!(right[2], object!{ "id" => 3, "total" => 30 });
+
+    let full = join(&users(), &orders(), "/id", JoinKind::Full).unwrap();
+
+    assert_eq!(full.len(), 5);
+    assert_eq!(full[4], object!{ "id" => 3, "total" => 30 });
+}
+
+#[test]
+fn join_nested_key() {
+    let left = array![object!{ "user" => object!{ "id" => "a" }, "x" => 1 }];
+    let right = array![object!{ "user" => object!{ "id" => "a" }, "y" => 2 }];
+
+    let joined = join(&left, &right, "/user/id", JoinKind::Inner).unwrap();
+
+    assert_eq!(joined, array![object!{ "user" => object!{ "id" => "a" }, "x" => 1, "y" => 2 }]);
+}
+
+#[test]
+fn join_null_keys_dont_match() {
+    let left = array![object!{ "id" => json::Null, "x" => 1 }];
+    let right = array![object!{ "id" => json::Null, "y" => 2 }];
+
+    let joined = join(&left, &right, "/id", JoinKind::Inner).unwrap();
+
+    assert!(joined.is_empty());
+}
+
+#[test]
+fn join_requires_arrays() {
+    assert!(join(&object!{}, &array![], "/id", JoinKind::Inner).is_err());
+    assert!(join(&array![], &json::Null, "/id", JoinKind::Inner).is_err());
+}
+
+#[test]
+fn join_equal_keys_written_differently() {
+    let left = array![
+        object!{ "id" => 1, "x" => 1 },
+        object!{ "id" => object!{ "a" => 1, "b" => 2 }, "x" => 2 }
+    ];
+    let right = json::parse(r#"[
+        {"id": 10e-1, "y": 1},
+        {"id": 1.0, "y": 2},
+        {"id": {"b": 2, "a": 1}, "y": 3}
+    ]"#).unwrap();
+
+    let joined = join(&left, &right, "/id", JoinKind::Inner).unwrap();
+
+    assert_eq!(joined.len(), 3);
+    assert_eq!(joined[0]["y"], 1);
+    assert_eq!(joined[1]["y"], 2);
+    assert_eq!(joined[2]["x"], 2);
+    assert_eq!(joined[2]["y"], 3);
+}
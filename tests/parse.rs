@@ -2,7 +2,7 @@
 extern crate json;
 
 use json::number::Number;
-use json::{ parse, JsonValue, Null };
+use json::{ parse, parse_with_options, parse_with_recovery, Error, JsonValue, Null, ParseOptions, SizeLimit };
 
 #[test]
 fn parse_true() {
@@ -287,6 +287,20 @@ fn parse_escaped_characters() {
     assert_eq!(data, "\r\n\t\u{8}\u{c}\\/\"");
 }
 
+#[test]
+fn parse_escaped_keys_of_nested_values() {
+    // Escaped strings in the nested value grow the buffer the outer key
+    // was unescaped into.
+    let data = parse(r#"{
+        "outer\tkey": { "inner\nkey": ["escaped\nstring that is longer than the buffer", 1] },
+        "next\tkey": "\u00e9scaped"
+    }"#).unwrap();
+
+    assert_eq!(data["outer\tkey"]["inner\nkey"][0], "escaped\nstring that is longer than the buffer");
+    assert_eq!(data["next\tkey"], "éscaped");
+    assert_eq!(data.len(), 2);
+}
+
 #[test]
 fn parse_escaped_unicode() {
     let data = parse(r#"
@@ -378,3 +392,375 @@ fn does_not_panic_on_huge_numbers() {
 
     let _ = json::parse(&string);
 }
+
+#[test]
+fn parse_multi_object_keeps_duplicates() {
+    use json::object::{ MultiObject, Object };
+
+    let source = r#" { "a" : 1, "b\n" : [1, {"c": 2}], "a": "two", "b\n": null } "#;
+    let mut object = MultiObject::parse(source).unwrap();
+
+    assert_eq!(object.len(), 4);
+    assert_eq!(object.get("a").unwrap(), "two");
+    assert_eq!(object.get_all("a").collect::<Vec<_>>(), [&JsonValue::from(1), &JsonValue::from("two")]);
+    assert_eq!(object.get_all("b\n").count(), 2);
+    assert_eq!(object.dump(), r#"{"a":1,"b\n":[1,{"c":2}],"a":"two","b\n":null}"#);
+
+    let collapsed = Object::from(object.clone());
+
+    assert_eq!(JsonValue::Object(collapsed), parse(source).unwrap());
+
+    assert_eq!(object.remove("a"), vec![JsonValue::from(1), JsonValue::from("two")]);
+    assert_eq!(object.dump(), r#"{"b\n":[1,{"c":2}],"b\n":null}"#);
+}
+
+#[test]
+fn parse_multi_object_errors() {
+    use json::object::MultiObject;
+
+    assert_eq!(MultiObject::parse("{}").unwrap(), MultiObject::new());
+    assert!(MultiObject::parse("[]").is_err());
+    assert!(MultiObject::parse(r#"{"a":1,}"#).is_err());
+    assert!(MultiObject::parse(r#"{"a":1} 2"#).is_err());
+    assert!(MultiObject::parse(r#"{"a":1"#).is_err());
+}
+
+#[test]
+fn parse_sorted_object() {
+    use json::object::{ SortedObject, Object };
+
+    let source = r#"{ "b": [2, {"z": 1, "y": 2}], "a": 1, "c": null, "a": "two" }"#;
+    let mut object = SortedObject::parse(source).unwrap();
+
+    assert_eq!(object.len(), 3);
+    assert_eq!(object["a"], "two");
+    assert_eq!(object.dump(), r#"{"a":"two","b":[2,{"z":1,"y":2}],"c":null}"#);
+
+    object.insert("aa", 3.into());
+
+    assert_eq!(object.remove("c"), Some(JsonValue::Null));
+    assert_eq!(object.keys().collect::<Vec<_>>(), ["a", "aa", "b"]);
+    assert_eq!(JsonValue::from(object.clone()).dump(), object.dump());
+
+    let unsorted = parse(source).unwrap();
+
+    if let JsonValue::Object(unsorted) = unsorted {
+        assert_eq!(Object::from(SortedObject::from(unsorted)).dump(), r#"{"a":"two","b":[2,{"z":1,"y":2}],"c":null}"#);
+    }
+
+    assert!(SortedObject::parse("[]").is_err());
+}
+
+#[test]
+fn parse_with_comments() {
+    let options = ParseOptions { comments: true, ..ParseOptions::default() };
+
+    let source = "// leading\n{ /* a */ \"a\" /**/ : 1, // trailing\n \"b\": [ 2 /* ** */, 3 ] } // end";
+
+    assert_eq!(parse_with_options(source, options).unwrap(), object!{
+        "a" => 1,
+        "b" => array![2, 3]
+    });
+    assert_eq!(parse_with_options("10// end", options).unwrap(), 10);
+    assert_eq!(parse_with_options("\"// not a comment\"", options).unwrap(), "// not a comment");
+}
+
+#[test]
+fn parse_with_comments_errors() {
+    let options = ParseOptions { comments: true, ..ParseOptions::default() };
+
+    assert!(parse("[1 /* a */]").is_err());
+    assert!(parse_with_options("[1 /* a */]", ParseOptions::default()).is_err());
+
+    assert_eq!(parse_with_options("[1 /* open", options), Err(json::Error::UnexpectedEndOfJson));
+    assert_eq!(parse_with_options("// only", options), Err(json::Error::UnexpectedEndOfJson));
+    assert_eq!(parse_with_options("[1 / 2]", options), Err(json::Error::UnexpectedCharacter {
+        ch: ' ',
+        line: 1,
+        column: 5,
+    }));
+}
+
+#[test]
+fn parse_with_size_limits() {
+    let source = r#"{"name":"Joe","tags":["ab",""]}"#;
+
+    let limit = |options: ParseOptions| parse_with_options(source, options);
+
+    assert!(limit(ParseOptions { max_bytes: Some(source.len()), ..ParseOptions::default() }).is_ok());
+    assert_eq!(
+        limit(ParseOptions { max_bytes: Some(source.len() - 1), ..ParseOptions::default() }),
+        Err(Error::ExceededSizeLimit(SizeLimit::Bytes))
+    );
+
+    assert!(limit(ParseOptions { max_nodes: Some(5), ..ParseOptions::default() }).is_ok());
+    assert_eq!(
+        limit(ParseOptions { max_nodes: Some(4), ..ParseOptions::default() }),
+        Err(Error::ExceededSizeLimit(SizeLimit::Nodes))
+    );
+
+    // "name" + "Joe" + "tags" + "ab" + ""
+    assert!(limit(ParseOptions { max_string_length: Some(13), ..ParseOptions::default() }).is_ok());
+    assert_eq!(
+        limit(ParseOptions { max_string_length: Some(12), ..ParseOptions::default() }),
+        Err(Error::ExceededSizeLimit(SizeLimit::StringLength))
+    );
+}
+
+#[test]
+fn parse_with_recovery_valid() {
+    let source = r#"{"a":[1,2.5,-3e2,"x\ny",true,false,null],"b":{},"c":[]}"#;
+
+    let (value, errors) = parse_with_recovery(source);
+
+    assert_eq!(value, parse(source).unwrap());
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn parse_with_recovery_collects_errors() {
+    let (value, errors) = parse_with_recovery("[1,,2, \"ok\", \"bad\\q\", 01, [3,}, {\"k\" 1, \"v\": 4}]");
+
+    assert_eq!(value, array![1, 2, "ok", array![3], object!{ "v" => 4 }]);
+    assert_eq!(errors, vec![
+        Error::UnexpectedCharacter { ch: ',', line: 1, column: 4 },
+        Error::UnexpectedCharacter { ch: 'q', line: 1, column: 19 },
+        Error::UnexpectedCharacter { ch: '1', line: 1, column: 24 },
+        Error::UnexpectedCharacter { ch: '}', line: 1, column: 30 },
+        Error::UnexpectedCharacter { ch: '1', line: 1, column: 38 },
+    ]);
+}
+
+#[test]
+fn parse_with_recovery_unterminated() {
+    let (value, errors) = parse_with_recovery("{\"a\": [1, {\"b\": 2");
+
+    assert_eq!(value, object!{ "a" => array![1, object!{ "b" => 2 }] });
+    assert_eq!(errors, vec![Error::UnexpectedEndOfJson]);
+
+    let (value, errors) = parse_with_recovery("nope 1");
+
+    assert_eq!(value, Null);
+    assert_eq!(errors, vec![
+        Error::UnexpectedCharacter { ch: 'o', line: 1, column: 2 },
+        Error::UnexpectedCharacter { ch: '1', line: 1, column: 6 },
+    ]);
+}
+
+#[test]
+fn parse_with_duplicate_keys() {
+    use json::DuplicateKeys;
+
+    let source = r#"{"a": 1, "b": [true], "a": {"x": 2}, "b": 3, "a": [4], "c": {"d": 5, "d": 6}}"#;
+
+    let policy = |duplicate_keys| parse_with_options(source, ParseOptions {
+        duplicate_keys,
+        ..ParseOptions::default()
+    });
+
+    assert_eq!(policy(DuplicateKeys::KeepLast).unwrap(), parse(source).unwrap());
+    assert_eq!(policy(DuplicateKeys::KeepLast).unwrap(), object!{
+        "a" => array![4],
+        "b" => 3,
+        "c" => object!{ "d" => 6 }
+    });
+    assert_eq!(policy(DuplicateKeys::KeepFirst).unwrap(), object!{
+        "a" => 1,
+        "b" => array![true],
+        "c" => object!{ "d" => 5 }
+    });
+    assert_eq!(policy(DuplicateKeys::Collect).unwrap(), object!{
+        "a" => array![1, object!{ "x" => 2 }, array![4]],
+        "b" => array![array![true], 3],
+        "c" => object!{ "d" => array![5, 6] }
+    });
+    assert_eq!(policy(DuplicateKeys::Error), Err(Error::DuplicateKey("a".into())));
+    assert_eq!(Error::DuplicateKey("a".into()).to_string(), "Duplicate key: a");
+}
+
+#[test]
+fn parse_preserving_number_text() {
+    let options = ParseOptions { preserve_number_text: true, ..ParseOptions::default() };
+
+    let source = r#"[1.2300,1e2,1E+02,2e-0,0.050,-0.0,12e-3,100,1.5e300,0.000001,-7E-007,0,0e00]"#;
+    let data = parse_with_options(source, options).unwrap();
+
+    assert_eq!(data.dump(), source);
+    assert_eq!(data, parse(source).unwrap());
+    assert_eq!(data[1], 100);
+
+    let pretty = "{\n    \"a\": 1.0E5\n}";
+
+    assert_eq!(parse_with_options(pretty, options).unwrap().pretty(4), pretty);
+
+    // Too many significant digits to be preserved.
+    let data = parse_with_options("123456789012345678901234", options).unwrap();
+
+    assert!(!data.as_number().unwrap().has_source_text());
+    assert_eq!(data.dump(), "1.2345678901234567890e23");
+
+    // Numbers are normalized without the option, or once replaced.
+    let mut data = parse("[1.50, 1e2]").unwrap();
+
+    assert_eq!(data.dump(), "[1.50,100]");
+
+    data = parse_with_options("[1.50, 1e2]", options).unwrap();
+    data[1] = 5.into();
+
+    assert_eq!(data.dump(), "[1.50,5]");
+}
+
+#[test]
+fn parse_inexact_numbers_as_strings() {
+    let options = ParseOptions { inexact_numbers_as_strings: true, ..ParseOptions::default() };
+
+    let data = parse_with_options(r#"{
+        "amount": 12345678901234567890.12,
+        "small": -0.000000000000000000001,
+        "huge": 1e99999,
+        "fine": 123.4500
+    }"#, options).unwrap();
+
+    assert_eq!(data["amount"], "12345678901234567890.12");
+    assert_eq!(data["small"], Number::from_parts(false, 1, -21));
+    assert_eq!(data["huge"], "1e99999");
+    assert_eq!(data["fine"], 123.45);
+    assert!(!data["fine"].as_number().unwrap().has_source_text());
+
+    let options = ParseOptions { preserve_number_text: true, ..options };
+    let data = parse_with_options("[123.4500, 99999999999999999999]", options).unwrap();
+
+    assert_eq!(data.dump(), r#"[123.4500,"99999999999999999999"]"#);
+}
+
+#[test]
+fn parse_big_integers_as_raw() {
+    let options = ParseOptions { big_integers_as_raw: true, ..ParseOptions::default() };
+    let source = "[340282366920938463463374607431768211456,-170141183460469231731687303715884105728,100000000000000000000]";
+
+    let data = parse_with_options(source, options).unwrap();
+
+    assert!(data.members().all(|member| matches!(member, JsonValue::Raw(_))));
+    assert_eq!(data.dump(), source);
+
+    if let JsonValue::Raw(ref raw) = data[1] {
+        assert_eq!(raw.as_i128(), Some(i128::MIN));
+        assert_eq!(raw.as_u128(), None);
+    }
+
+    let data = parse_with_options("[1234567890123456789,12345678901234567890.5]", options).unwrap();
+
+    assert_eq!(data.dump(), "[1234567890123456789,12345678901234567890]");
+
+    let huge = "1".repeat(400);
+
+    assert_eq!(parse_with_options(&huge, options).unwrap().dump(), huge);
+
+    let options = ParseOptions { exact_numbers: true, non_finite_numbers: true, ..options };
+
+    assert!(matches!(parse_with_options("[Infinity,12345678901234567890123]", options).unwrap()[1], JsonValue::Raw(_)));
+    assert!(parse_with_options("1.2345678901234567890123", options).is_err());
+}
+
+#[test]
+fn parse_non_finite_numbers() {
+    use json::number::{ INFINITY, NEG_INFINITY };
+
+    let options = ParseOptions { non_finite_numbers: true, ..ParseOptions::default() };
+    let source = "[NaN, Infinity, -Infinity, 1]";
+
+    assert!(parse(source).is_err());
+
+    let data = parse_with_options(source, options).unwrap();
+
+    assert!(data[0].as_number().unwrap().is_nan());
+    assert_eq!(data[1], INFINITY);
+    assert_eq!(data[2], NEG_INFINITY);
+    assert_eq!(data[2].as_f64(), Some(std::f64::NEG_INFINITY));
+    assert_eq!(data.dump(), "[null,null,null,1]");
+
+    assert_eq!(parse_with_options("Infinit", options), Err(Error::UnexpectedEndOfJson));
+    assert!(parse_with_options("-NaN", options).is_err());
+
+    let options = ParseOptions { preserve_number_text: true, inexact_numbers_as_strings: true, ..options };
+
+    assert_eq!(parse_with_options(source, options).unwrap().dump(), "[null,null,null,1]");
+}
+
+#[test]
+fn parse_lossless_round_trip() {
+    let source = r#"[0,-0,1.0,0.000,-1.50e+0010,2E-3,1e0,123456789012345678,0.1234567890123456789]"#;
+
+    assert_eq!(parse_with_options(source, ParseOptions::lossless()).unwrap().dump(), source);
+
+    for inexact in &["123456789012345678901", "1e99999", "-0.12345678901234567890123"] {
+        assert_eq!(
+            parse_with_options(inexact, ParseOptions::lossless()),
+            Err(Error::InexactNumber(inexact.to_string()))
+        );
+    }
+
+    let options = ParseOptions { exact_numbers: true, ..ParseOptions::default() };
+
+    assert_eq!(parse_with_options("[1e2]", options).unwrap().dump(), "[100]");
+    assert_eq!(
+        parse_with_options("[1, 123456789012345678901]", options).unwrap_err().code_name(),
+        "inexact_number"
+    );
+}
+
+#[test]
+fn parse_bytes_long_strings_and_whitespace() {
+    let text = "zażółć gęślą jaźń ".repeat(10);
+    let source = format!("{{\n{}\"a\"  :\t\"{}\\n{}\"{}}}", " ".repeat(40), text, text, "\r\n".repeat(20));
+
+    let data = json::parse_bytes(source.as_bytes()).unwrap();
+
+    assert_eq!(data, object!{ "a" => format!("{}\n{}", text, text) });
+    assert_eq!(data, parse(&source).unwrap());
+
+    let mut invalid = source.clone().into_bytes();
+    invalid[100] = 0xC0;
+
+    assert_eq!(json::parse_bytes(&invalid), Err(Error::FailedUtf8Parsing));
+
+    let control = format!("[\"{}\u{1}\"]", "a".repeat(40));
+
+    assert_eq!(
+        json::parse_bytes(control.as_bytes()),
+        Err(Error::UnexpectedCharacter { ch: '\u{1}', line: 1, column: 43 })
+    );
+}
+
+#[test]
+fn validate_matches_parse() {
+    use json::{ validate, validate_with_options };
+
+    let sources = [
+        r#"{"a":[1,2.5,-3e-2,"x\"y",{"b":[]}],"c":{"d":false},"e":null}"#,
+        "  [ [ [] ], {}, true, -0, 0.5e+3 ]  ",
+        r#""\ud83e\udd80""#,
+        "", "[", "[1,]", "[1 2]", "{\"a\":1,}", "{\"a\" 1}", "{1:2}", "[1}", "{\"a\":1]",
+        "1 2", "01", "-", "1.", "1e", "nul", "\"\\x\"", "\"\u{1}\"", "[\n  trux]",
+    ];
+
+    for source in sources.iter() {
+        assert_eq!(validate(source), parse(source).map(|_| ()), "{:?}", source);
+    }
+
+    for depth in &[512, 513, 514] {
+        let deep = "[".repeat(*depth) + &"]".repeat(*depth);
+
+        assert_eq!(validate(&deep), parse(&deep).map(|_| ()));
+    }
+
+    assert_eq!(validate(&"[".repeat(600)), Err(Error::ExceededDepthLimit));
+
+    let options = ParseOptions { comments: true, max_nodes: Some(3), ..ParseOptions::default() };
+
+    assert_eq!(validate_with_options("[1, /* two */ 2]", options), Ok(()));
+    assert_eq!(
+        validate_with_options("[1, 2, 3]", options),
+        Err(Error::ExceededSizeLimit(SizeLimit::Nodes))
+    );
+}
@@ -0,0 +1,107 @@
+#[macro_use]
+extern crate json;
+
+use json::{ parse, JsonPool, Parser, ParseOptions };
+
+const SOURCE: &str = r#"{
+    "id": 1,
+    "name": "a string that is longer than any string that could be stored inline",
+    "tags": ["short", "another string that is longer than any string that could be stored inline"],
+    "nested": { "list": [[], [1, 2], {}] }
+}"#;
+
+#[test]
+fn pool_parse_matches_parse() {
+    let mut pool = JsonPool::new();
+
+    for _ in 0..3 {
+        let data = pool.parse(SOURCE).unwrap();
+
+        assert_eq!(data, parse(SOURCE).unwrap());
+
+        pool.recycle(data);
+    }
+
+    assert!(pool.parse("[1,").is_err());
+}
+
+#[test]
+fn pool_recycles_allocations() {
+    let mut pool = JsonPool::new();
+
+    assert!(pool.is_empty());
+
+    pool.recycle(parse(SOURCE).unwrap());
+
+    // 2 long strings, 3 non-empty arrays, 2 non-empty objects. Shared
+    // strings aren't pooled.
+    let strings = if cfg!(feature = "shared_strings") { 0 } else { 2 };
+
+    assert_eq!(pool.len(), strings + 5);
+
+    let data = pool.parse(SOURCE).unwrap();
+
+    assert!(pool.is_empty());
+
+    pool.recycle(data);
+    pool.recycle(array![array![array![]]]);
+
+    assert_eq!(pool.len(), strings + 7);
+
+    pool.clear();
+
+    assert!(pool.is_empty());
+}
+
+#[test]
+fn pool_limit() {
+    let mut pool = JsonPool::with_limit(1);
+
+    pool.recycle(parse(SOURCE).unwrap());
+
+    let strings = if cfg!(feature = "shared_strings") { 0 } else { 1 };
+
+    assert_eq!(pool.len(), strings + 2);
+}
+
+#[test]
+fn parser_reuses_buffers() {
+    let mut parser = Parser::new();
+
+    assert_eq!(parser.capacity(), 0);
+
+    let escaped = r#"{"a\tkey": {"nested": ["escaped\nstring", [{"deep": "é"}]]}}"#;
+
+    for _ in 0..3 {
+        assert_eq!(parser.parse(escaped).unwrap(), parse(escaped).unwrap());
+        assert_eq!(parser.parse(SOURCE).unwrap(), parse(SOURCE).unwrap());
+    }
+
+    let capacity = parser.capacity();
+
+    assert!(capacity > 0);
+
+    parser.parse(escaped).unwrap();
+
+    assert_eq!(parser.capacity(), capacity);
+    assert!(parser.parse("[1,").is_err());
+    assert!(parser.parse(r#"{"a": [{"b": tru}]}"#).is_err());
+    assert_eq!(parser.capacity(), capacity);
+    assert_eq!(parser.parse("[true]").unwrap(), array![true]);
+
+    parser.clear();
+
+    assert_eq!(parser.capacity(), 0);
+}
+
+#[test]
+fn parser_with_options() {
+    let mut parser = Parser::with_options(ParseOptions {
+        comments: true,
+        ..ParseOptions::default()
+    });
+
+    assert!(parser.options().comments);
+    assert_eq!(parser.parse("[1 /* one */]").unwrap(), array![1]);
+    assert!(Parser::new().parse("[1 /* one */]").is_err());
+}
@@ -0,0 +1,81 @@
+#[macro_use]
+extern crate json;
+
+use json::schema;
+use json::Null;
+
+#[test]
+fn schema_infer_scalars() {
+    assert_eq!(schema::infer(&[]), object!{ "$schema" => schema::SCHEMA_VERSION });
+    assert_eq!(schema::infer(&[&Null]), object!{ "$schema" => schema::SCHEMA_VERSION, "type" => "null" });
+
+    let one = json::from(1);
+    let half = json::from(0.5);
+    let whole = json::parse("2.0").unwrap();
+    let text = json::from("a");
+
+    assert_eq!(schema::infer(&[&one, &whole])["type"], "integer");
+    assert_eq!(schema::infer(&[&one, &half])["type"], "number");
+    assert_eq!(schema::infer(&[&text, &Null, &one])["type"], array!["null", "integer", "string"]);
+}
+
+#[test]
+fn schema_infer_formats() {
+    fn format(samples: &[&str]) -> json::JsonValue {
+        let values = samples.iter().map(|&sample| json::from(sample)).collect::<Vec<_>>();
+        let values = values.iter().collect::<Vec<_>>();
+
+        schema::infer(&values)["format"].clone()
+    }
+
+    assert_eq!(format(&["2020-01-31T12:30:00Z", "2020-02-01t08:00:00.123+02:00"]), "date-time");
+    assert_eq!(format(&["2020-01-31", "1999-12-01"]), "date");
+    assert_eq!(format(&["12:30:00", "23:59:60.5Z"]), "time");
+    assert_eq!(format(&["67e55044-10b1-426f-9247-bb680e5fe0c8"]), "uuid");
+    assert_eq!(format(&["joe@example.com"]), "email");
+    assert_eq!(format(&["https://example.com/path?q=1", "ftp://files"]), "uri");
+
+    assert_eq!(format(&["2020-01-31", "joe@example.com"]), Null);
+    assert_eq!(format(&["2020-13-01"]), Null);
+    assert_eq!(format(&["24:00:00"]), Null);
+    assert_eq!(format(&["joe@localhost"]), Null);
+    assert_eq!(format(&["://example.com"]), Null);
+}
+
+#[test]
+fn schema_infer_nested() {
+    let first = object!{
+        "id" => 1,
+        "items" => array![object!{ "sku" => "A1", "qty" => 2 }],
+        "note" => Null
+    };
+    let second = object!{
+        "id" => 2,
+        "items" => array![object!{ "sku" => "B2" }, object!{ "sku" => "C3", "qty" => 1.5 }],
+        "note" => "fragile"
+    };
+    let third = object!{ "id" => 3, "items" => array![] };
+
+    assert_eq!(schema::infer(&[&first, &second, &third]), object!{
+        "$schema" => schema::SCHEMA_VERSION,
+        "type" => "object",
+        "properties" => object!{
+            "id" => object!{ "type" => "integer" },
+            "items" => object!{
+                "type" => "array",
+                "items" => object!{
+                    "type" => "object",
+                    "properties" => object!{
+                        "sku" => object!{ "type" => "string" },
+                        "qty" => object!{ "type" => "number" }
+                    },
+                    "required" => array!["sku"]
+                }
+            },
+            "note" => object!{ "type" => array!["null", "string"] }
+        },
+        "required" => array!["id", "items"]
+    });
+
+    assert_eq!(schema::infer(&[&array![]]), object!{ "$schema" => schema::SCHEMA_VERSION, "type" => "array" });
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "serde")]
+
+#[macro_use]
+extern crate json;
+extern crate serde_test;
+
+use json::JsonValue;
+use json::number::Number;
+use json::object::Object;
+use serde_test::{ assert_tokens, assert_ser_tokens, assert_de_tokens, Token };
+
+#[test]
+fn serde_scalars() {
+    assert_tokens(&JsonValue::Null, &[Token::Unit]);
+    assert_tokens(&JsonValue::from(true), &[Token::Bool(true)]);
+    assert_tokens(&JsonValue::from("foo"), &[Token::Str("foo")]);
+    assert_tokens(&JsonValue::from(42), &[Token::U64(42)]);
+    assert_tokens(&JsonValue::from(-42), &[Token::I64(-42)]);
+    assert_tokens(&JsonValue::from(1.5), &[Token::F64(1.5)]);
+
+    assert_ser_tokens(&Number::from_parts(true, 12, 3), &[Token::U64(12000)]);
+    assert_tokens(&Number::from_i64(i64::MIN), &[Token::I64(i64::MIN)]);
+    assert_ser_tokens(&Number::from_parts(true, u64::MAX, 1), &[Token::F64(u64::MAX as f64 * 10.0)]);
+    assert_ser_tokens(&Number::from(std::f64::NAN), &[Token::Unit]);
+
+    assert_de_tokens(&JsonValue::from("foo"), &[Token::String("foo")]);
+    assert_de_tokens(&JsonValue::Null, &[Token::None]);
+    assert_de_tokens(&JsonValue::from(1), &[Token::Some, Token::U8(1)]);
+    assert_de_tokens(&Number::from(-3), &[Token::I32(-3)]);
+}
+
+#[test]
+fn serde_containers() {
+    let value = object!{
+        "list" => array![1, "two", json::Null],
+        "nested" => object!{ "ok" => true }
+    };
+
+    assert_tokens(&value, &[
+        Token::Map { len: Some(2) },
+        Token::Str("list"),
+        Token::Seq { len: Some(3) },
+        Token::U64(1),
+        Token::Str("two"),
+        Token::Unit,
+        Token::SeqEnd,
+        Token::Str("nested"),
+        Token::Map { len: Some(1) },
+        Token::Str("ok"),
+        Token::Bool(true),
+        Token::MapEnd,
+        Token::MapEnd,
+    ]);
+
+    let mut object = Object::new();
+    object.insert("a", 1.into());
+
+    assert_tokens(&object, &[
+        Token::Map { len: Some(1) },
+        Token::Str("a"),
+        Token::U64(1),
+        Token::MapEnd,
+    ]);
+}
@@ -0,0 +1,292 @@
+#[macro_use]
+extern crate json;
+
+use json::number::Number;
+use json::stream::{ Parser, Event, FeedParser, Status, validate_reader };
+use json::Error;
+
+fn events(source: &str) -> json::Result<Vec<Event>> {
+    Parser::from(source).collect()
+}
+
+#[test]
+fn stream_scalars() {
+    assert_eq!(events(" null ").unwrap(), vec![Event::Null]);
+    assert_eq!(events("true").unwrap(), vec![Event::Boolean(true)]);
+    assert_eq!(events("false").unwrap(), vec![Event::Boolean(false)]);
+    assert_eq!(events("-12.5e2").unwrap(), vec![Event::Number(Number::from(-1250))]);
+    assert_eq!(events(r#""a\nbé""#).unwrap(), vec![Event::String("a\nbé".into())]);
+}
+
+#[test]
+fn stream_nested() {
+    let source = r#"
+        {
+            "empty_object": {},
+            "empty_array": [],
+            "list": [1, [true], {"a": null}],
+            "long": "a string that is longer than thirty bytes"
+        }
+    "#;
+
+    assert_eq!(events(source).unwrap(), vec![
+        Event::StartObject,
+        Event::Key("empty_object".into()),
+        Event::StartObject,
+        Event::EndObject,
+        Event::Key("empty_array".into()),
+        Event::StartArray,
+        Event::EndArray,
+        Event::Key("list".into()),
+        Event::StartArray,
+        Event::Number(Number::from(1)),
+        Event::StartArray,
+        Event::Boolean(true),
+        Event::EndArray,
+        Event::StartObject,
+        Event::Key("a".into()),
+        Event::Null,
+        Event::EndObject,
+        Event::EndArray,
+        Event::Key("long".into()),
+        Event::String("a string that is longer than thirty bytes".into()),
+        Event::EndObject,
+    ]);
+}
+
+#[test]
+fn stream_from_reader() {
+    // Long enough to span multiple reads of the internal buffer.
+    let mut source = String::from("[");
+
+    for i in 0..10000 {
+        if i > 0 {
+            source.push(',');
+        }
+        source.push_str(&format!(r#"{{"id":{},"name":"item {}"}}"#, i, i));
+    }
+    source.push(']');
+
+    let mut parser = Parser::new(std::io::Cursor::new(source.into_bytes()));
+    let mut ids = 0u64;
+
+    assert_eq!(parser.next().unwrap().unwrap(), Event::StartArray);
+    assert_eq!(parser.depth(), 1);
+
+    while let Some(event) = parser.next() {
+        if let Event::Number(number) = event.unwrap() {
+            ids += u64::from(number.as_fixed_point_u64(0).unwrap());
+        }
+    }
+
+    assert_eq!(ids, (0..10000).sum::<u64>());
+    assert_eq!(parser.depth(), 0);
+}
+
+#[test]
+fn stream_errors() {
+    assert_eq!(events(""), Err(Error::UnexpectedEndOfJson));
+    assert_eq!(events("[1,"), Err(Error::UnexpectedEndOfJson));
+    assert_eq!(events("[1 2]"), Err(Error::UnexpectedCharacter { ch: '2', line: 1, column: 4 }));
+    assert_eq!(events("[1,]"), Err(Error::UnexpectedCharacter { ch: ']', line: 1, column: 4 }));
+    assert_eq!(events("{\"a\" 1}"), Err(Error::UnexpectedCharacter { ch: '1', line: 1, column: 6 }));
+    assert_eq!(events("{1:2}"), Err(Error::UnexpectedCharacter { ch: '1', line: 1, column: 2 }));
+    assert_eq!(events("[1}"), Err(Error::UnexpectedCharacter { ch: '}', line: 1, column: 3 }));
+    assert_eq!(events("1 2"), Err(Error::UnexpectedCharacter { ch: '2', line: 1, column: 3 }));
+    assert_eq!(events("[\n  trux]"), Err(Error::UnexpectedCharacter { ch: 'x', line: 2, column: 6 }));
+    assert_eq!(events("[\"ł\", ł]"), Err(Error::UnexpectedCharacter { ch: 'ł', line: 1, column: 7 }));
+
+    // Events before the error are still produced, nothing after it.
+    let mut parser = Parser::from("[true, x, 1]");
+
+    assert_eq!(parser.next(), Some(Ok(Event::StartArray)));
+    assert_eq!(parser.next(), Some(Ok(Event::Boolean(true))));
+    assert!(parser.next().unwrap().is_err());
+    assert_eq!(parser.next(), None);
+}
+
+#[test]
+fn stream_matches_parse() {
+    let source = r#"{"a":[1,2.5,-3e-2,"x\"y",{"b":[]}],"c":{"d":false}}"#;
+
+    // Rebuild the document from events and compare.
+    fn build(parser: &mut Parser<&[u8]>, event: Event) -> json::JsonValue {
+        match event {
+            Event::StartArray => {
+                let mut array = json::JsonValue::new_array();
+                loop {
+                    match parser.next().unwrap().unwrap() {
+                        Event::EndArray => return array,
+                        event => array.push(build(parser, event)).unwrap(),
+                    }
+                }
+            },
+            Event::StartObject => {
+                let mut object = json::JsonValue::new_object();
+                loop {
+                    match parser.next().unwrap().unwrap() {
+                        Event::EndObject => return object,
+                        Event::Key(key) => {
+                            let event = parser.next().unwrap().unwrap();
+                            object[key] = build(parser, event);
+                        },
+                        event => panic!("Unexpected {:?}", event),
+                    }
+                }
+            },
+            Event::String(string) => string.into(),
+            Event::Number(number) => number.into(),
+            Event::Boolean(value) => value.into(),
+            Event::Null => json::Null,
+            event => panic!("Unexpected {:?}", event),
+        }
+    }
+
+    let mut parser = Parser::from(source);
+    let first = parser.next().unwrap().unwrap();
+
+    assert_eq!(build(&mut parser, first), json::parse(source).unwrap());
+    assert_eq!(parser.next(), None);
+}
+
+#[test]
+fn writer_matches_generators() {
+    use json::codegen::{ DumpGenerator, PrettyGenerator, Generator };
+    use json::stream::Writer;
+
+    fn write<G: Generator>(writer: &mut Writer<G>) -> json::Result<()> {
+        writer.begin_object()?;
+        writer.key("empty")?;
+        writer.begin_array()?;
+        writer.end_array()?;
+        writer.key("list")?;
+        writer.begin_array()?;
+        writer.number(1)?;
+        writer.string("two")?;
+        writer.null()?;
+        writer.begin_object()?;
+        writer.end_object()?;
+        writer.value(&json::parse(r#"{"a":[true]}"#).unwrap())?;
+        writer.end_array()?;
+        writer.key("ok")?;
+        writer.boolean(false)?;
+        writer.end_object()
+    }
+
+    let expected = json::parse(r#"{"empty":[],"list":[1,"two",null,{},{"a":[true]}],"ok":false}"#).unwrap();
+
+    let mut writer = Writer::new(DumpGenerator::new());
+    write(&mut writer).unwrap();
+    assert_eq!(writer.finish().unwrap().consume(), expected.dump());
+
+    let mut writer = Writer::new(PrettyGenerator::new(2));
+    write(&mut writer).unwrap();
+    assert_eq!(writer.finish().unwrap().consume(), expected.pretty(2));
+}
+
+#[test]
+fn writer_enforces_well_formedness() {
+    use json::codegen::DumpGenerator;
+    use json::stream::Writer;
+
+    fn writer() -> Writer<DumpGenerator> {
+        Writer::new(DumpGenerator::new())
+    }
+
+    let mut w = writer();
+    assert_eq!(w.key("a").unwrap_err().code_name(), "invalid_write");
+
+    let mut w = writer();
+    w.begin_array().unwrap();
+    assert!(w.key("a").is_err());
+    assert!(w.end_object().is_err());
+    w.end_array().unwrap();
+    assert!(w.null().is_err());
+    assert!(w.finish().is_ok());
+
+    let mut w = writer();
+    w.begin_object().unwrap();
+    assert!(w.number(1).is_err());
+    w.key("a").unwrap();
+    assert!(w.key("b").is_err());
+    assert!(w.end_object().is_err());
+    w.number(1).unwrap();
+    assert!(w.end_array().is_err());
+    assert_eq!(w.depth(), 1);
+    match w.finish() {
+        Err(Error::InvalidWrite(message)) => assert_eq!(message, "not all containers were ended"),
+        _ => panic!("Expected an error"),
+    }
+
+    assert!(writer().finish().is_err());
+    assert!(writer().end_array().is_err());
+}
+
+#[test]
+fn stream_validate_reader() {
+    let source = format!("[{}]", vec![r#"{"a":[1,2.5,"x\"y"]}"#; 1000].join(","));
+
+    assert_eq!(validate_reader(std::io::Cursor::new(source.into_bytes())), Ok(()));
+
+    for invalid in &["", "[1,", "[1 2]", "{\"a\" 1}", "1 2", "[\n  trux]"] {
+        assert_eq!(validate_reader(invalid.as_bytes()), events(invalid).map(|_| ()));
+    }
+}
+
+// Feed `source` to a `FeedParser` in chunks of `size` bytes.
+fn feed(source: &str, size: usize) -> json::Result<json::JsonValue> {
+    let mut parser = FeedParser::new();
+
+    for chunk in source.as_bytes().chunks(size) {
+        parser.feed(chunk)?;
+    }
+
+    parser.finish()
+}
+
+#[test]
+fn feed_matches_parse() {
+    let sources = [
+        r#"{"a":[1,2.5,-3e-2,"x\"y",{"b":[]}],"c":{"d":false},"e":null,"ł":"zażółć \ud83e\udd80"}"#,
+        " [ [ [] ], {}, true, -0, 0.5e+3 ] ",
+        r#"{"a":1,"a":2}"#,
+        "123", "\"x\"", "null",
+        "", "[1,", "[1 2]", "[1,]", "{\"a\" 1}", "{1:2}", "[1}", "1 2", "01", "tru",
+        "[tru]", "[tru ]", "[-]", "[1.]", "[1e+]", "[truł]",
+        "[\n  trux]", "[\"ł\", ł]", "[1, ł]", "{\"a\":1}ł", "\"\\x\"", "[\"a\u{1}\"]",
+    ];
+
+    for source in sources.iter() {
+        for size in 1..source.len() + 1 {
+            assert_eq!(feed(source, size), json::parse(source), "{:?} in chunks of {}", source, size);
+        }
+    }
+
+    assert_eq!(feed(&"[".repeat(600), 7), Err(Error::ExceededDepthLimit));
+}
+
+#[test]
+fn feed_status() {
+    let mut parser = FeedParser::new();
+
+    assert_eq!(parser.feed(b"{\"a\":[1"), Ok(Status::NeedMore));
+    assert_eq!(parser.depth(), 2);
+    assert_eq!(parser.feed(b"]"), Ok(Status::NeedMore));
+    assert_eq!(parser.feed(b"} "), Ok(Status::Complete));
+    assert_eq!(parser.feed(b"\n"), Ok(Status::Complete));
+    assert_eq!(parser.finish(), Ok(object!{ "a" => array![1] }));
+
+    // Numbers at the top level are only complete at the end of input.
+    let mut parser = FeedParser::new();
+
+    assert_eq!(parser.feed(b"12"), Ok(Status::NeedMore));
+    assert_eq!(parser.finish(), Ok(12.into()));
+
+    // Errors stick.
+    let mut parser = FeedParser::new();
+    let error = Error::UnexpectedCharacter { ch: 'x', line: 1, column: 2 };
+
+    assert_eq!(parser.feed(b"[x"), Err(error.clone()));
+    assert_eq!(parser.feed(b"]"), Err(error.clone()));
+    assert_eq!(parser.finish(), Err(error));
+}
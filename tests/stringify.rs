@@ -161,7 +161,7 @@ fn stringify_raw_object() {
 
 #[test]
 fn stringify_btree_map() {
-    let mut map = BTreeMap::new();
+    let mut map: BTreeMap<String, JsonValue> = BTreeMap::new();
 
     map.insert("name".into(), "Maciej".into());
     map.insert("age".into(), 30.into());
@@ -172,7 +172,7 @@ fn stringify_btree_map() {
 
 #[test]
 fn stringify_hash_map() {
-    let mut map = HashMap::new();
+    let mut map: HashMap<String, JsonValue> = HashMap::new();
 
     map.insert("name".into(), "Maciej".into());
     map.insert("age".into(), 30.into());
@@ -247,3 +247,563 @@ fn stringify_pretty_object() {
     assert_eq!(object.pretty(2), expected);
     assert_eq!(stringify_pretty(object, 2), expected);
 }
+
+#[test]
+fn stringify_pretty_aligned_object() {
+    let object = object!{
+        "name" => "Urlich",
+        "age" => 50,
+        "parents" => object!{
+            "mother" => "Helga",
+            "father" => "Brutus",
+            "sibling" => Null
+        }
+    };
+
+    let expected = "{\n  \"name\":    \"Urlich\",\n  \"age\":     50,\n  \"parents\": {\n    \"mother\":  \"Helga\",\n    \"father\":  \"Brutus\",\n    \"sibling\": null\n  }\n}";
+    assert_eq!(object.pretty_aligned(2), expected);
+
+    let mut writer = Vec::new();
+    object.write_pretty_aligned(&mut writer, 2).unwrap();
+    assert_eq!(String::from_utf8(writer).unwrap(), expected);
+}
+
+#[test]
+fn stringify_pretty_aligned_escaped_keys() {
+    use json::StringifyOptions;
+
+    let object = object!{ "a\tb" => 1, "ü" => 2, "c\u{1}" => 3, "defghijk" => 4 };
+
+    assert_eq!(
+        object.pretty_aligned(2),
+        "{\n  \"a\\tb\":     1,\n  \"ü\":        2,\n  \"c\\u0001\":  3,\n  \"defghijk\": 4\n}"
+    );
+
+    assert_eq!(
+        object.dump_with(&StringifyOptions::new().spaces(2).align_values(true).escape_non_ascii(true)),
+        "{\n  \"a\\tb\":     1,\n  \"\\u00fc\":   2,\n  \"c\\u0001\":  3,\n  \"defghijk\": 4\n}"
+    );
+}
+
+#[test]
+fn generator_write_functions() {
+    use json::codegen::{ Generator, DumpGenerator, PrettyGenerator };
+    use json::number::Number;
+
+    let mut gen = DumpGenerator::new();
+
+    gen.write_null().unwrap();
+    gen.write_char(b' ').unwrap();
+    gen.write_bool(true).unwrap();
+    gen.write_char(b' ').unwrap();
+    gen.write_bool(false).unwrap();
+    gen.write_char(b' ').unwrap();
+    gen.write_number(&Number::from(-1.5)).unwrap();
+    gen.write_char(b' ').unwrap();
+    gen.write_string("a\"b").unwrap();
+    gen.write_char(b' ').unwrap();
+    gen.write_array(&[1.into(), "x".into()]).unwrap();
+
+    assert_eq!(gen.consume(), r#"null true false -1.5 "a\"b" [1,"x"]"#);
+
+    let mut gen = PrettyGenerator::new(2);
+
+    gen.write_array(&[array![], object!{ "a" => 1 }]).unwrap();
+
+    assert_eq!(gen.consume(), "[\n  [],\n  {\n    \"a\": 1\n  }\n]");
+}
+
+struct CountingWriter {
+    output: Vec<u8>,
+    writes: usize,
+    vectored_writes: usize,
+}
+
+impl CountingWriter {
+    fn new() -> Self {
+        CountingWriter { output: Vec::new(), writes: 0, vectored_writes: 0 }
+    }
+}
+
+impl std::io::Write for CountingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writes += 1;
+        self.output.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn write_vectored(&mut self, bufs: &[std::io::IoSlice]) -> std::io::Result<usize> {
+        self.vectored_writes += 1;
+
+        // Only write part of the data, to exercise partial writes.
+        let buf = bufs.iter().find(|buf| !buf.is_empty()).map(|buf| &buf[..]).unwrap_or(&[]);
+        let len = std::cmp::min(buf.len(), 1000);
+
+        self.output.extend_from_slice(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn writer_generators_buffer_output() {
+    let mut data = json::JsonValue::new_array();
+
+    for i in 0..1000 {
+        data.push(object!{ "id" => i, "tags" => array!["a", "b", json::Null, true] }).unwrap();
+    }
+
+    let mut writer = CountingWriter::new();
+    data.write(&mut writer).unwrap();
+
+    assert_eq!(writer.output, data.dump().into_bytes());
+    assert!(writer.writes < 10);
+
+    let mut writer = CountingWriter::new();
+    data.write_pretty(&mut writer, 4).unwrap();
+
+    assert_eq!(writer.output, data.pretty(4).into_bytes());
+    assert!(writer.writes < 20);
+}
+
+#[test]
+fn writer_generator_vectored() {
+    use json::codegen::{ Generator, WriterGenerator };
+
+    let long = "x".repeat(20000);
+    let data = array!["short", long.as_str(), 1];
+
+    let mut writer = CountingWriter::new();
+
+    {
+        let mut gen = WriterGenerator::new(&mut writer);
+        gen.vectored(true);
+        gen.write_json(&data).unwrap();
+        gen.finish().unwrap();
+    }
+
+    assert_eq!(writer.output, data.dump().into_bytes());
+    assert!(writer.vectored_writes > 0);
+
+    // Dropping the generator writes out the buffer as well.
+    let mut writer = CountingWriter::new();
+
+    WriterGenerator::new(&mut writer).write_json(&data).unwrap();
+
+    assert_eq!(writer.output, data.dump().into_bytes());
+}
+
+#[test]
+fn writer_generator_get_writer() {
+    use json::codegen::{ Generator, WriterGenerator, PrettyWriterGenerator };
+
+    let data = array![1, 2.5, "three"];
+
+    let mut writer = CountingWriter::new();
+
+    {
+        let mut gen = WriterGenerator::new(&mut writer);
+        gen.write_json(&data).unwrap();
+        gen.finish().unwrap();
+
+        let writer: &mut CountingWriter = gen.get_writer();
+        writer.output.push(b'\n');
+    }
+
+    assert_eq!(writer.output, b"[1,2.5,\"three\"]\n");
+
+    let mut writer = CountingWriter::new();
+
+    {
+        let mut gen = PrettyWriterGenerator::new(&mut writer, 2);
+        gen.write_json(&data).unwrap();
+        gen.finish().unwrap();
+
+        let writer: &mut CountingWriter = gen.get_writer();
+        writer.output.push(b'\n');
+    }
+
+    assert_eq!(writer.output, format!("{}\n", data.pretty(2)).into_bytes());
+}
+
+#[test]
+fn stringify_float_format() {
+    use json::codegen::{ Generator, DumpGenerator, PrettyGenerator, WriterGenerator, FloatFormat };
+
+    let data = array![1.005, -0.004, 9.995, 12345.678, 0.1, 42, -2.5, 1e-30, 1.5e300];
+
+    let dump = |format| {
+        let mut gen = DumpGenerator::new();
+        gen.format_floats(format);
+        gen.write_json(&data).unwrap();
+        gen.consume()
+    };
+
+    assert_eq!(dump(FloatFormat::Shortest), data.dump());
+    assert_eq!(
+        dump(FloatFormat::DecimalPlaces(2)),
+        "[1.01,0.00,10.00,12345.68,0.10,42,-2.50,0.00,1.5e300]"
+    );
+    assert_eq!(
+        dump(FloatFormat::DecimalPlaces(0)),
+        "[1,0,10,12346,0,42,-3,0,1.5e300]"
+    );
+    assert_eq!(
+        dump(FloatFormat::SignificantDigits(3)),
+        "[1.01,-0.004,10,12300,0.1,42,-2.5,1e-30,1.5e300]"
+    );
+
+    let mut gen = PrettyGenerator::new(2);
+    gen.format_floats(FloatFormat::DecimalPlaces(1));
+    gen.write_json(&array![0.25]).unwrap();
+
+    assert_eq!(gen.consume(), "[\n  0.3\n]");
+
+    let mut buffer = Vec::new();
+    {
+        let mut gen = WriterGenerator::new(&mut buffer);
+        gen.format_floats(FloatFormat::SignificantDigits(1));
+        gen.write_json(&array![0.25]).unwrap();
+    }
+
+    assert_eq!(buffer, b"[0.3]");
+}
+
+#[test]
+fn stringify_canonical() {
+    // Examples from RFC 8785.
+    let data = json::parse(r#"{
+        "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+        "string": "€$\u000F\u000aA'B\u0022\u005c\\\"\/",
+        "literals": [null, true, false]
+    }"#).unwrap();
+
+    assert_eq!(
+        data.canonical().unwrap(),
+        r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#
+    );
+
+    let data = json::parse(r#"{
+        "€": "Euro Sign",
+        "\r": "Carriage Return",
+        "דּ": "Hebrew Letter Dalet With Dagesh",
+        "1": "One",
+        "😀": "Emoji: Grinning Face",
+        "\u0080": "Control",
+        "ö": "Latin Small Letter O With Diaeresis"
+    }"#).unwrap();
+
+    let keys: Vec<String> = json::parse(&data.canonical().unwrap()).unwrap()
+        .entries()
+        .map(|(key, _)| key.to_owned())
+        .collect();
+
+    assert_eq!(keys, vec!["\r", "1", "\u{80}", "\u{f6}", "\u{20ac}", "\u{1f600}", "\u{fb33}"]);
+
+    assert_eq!(array![1e21, 1e20, 123e-9, 1e-6, -5, 100].canonical().unwrap(), "[1e+21,100000000000000000000,1.23e-7,0.000001,-5,100]");
+    assert_eq!(
+        JsonValue::from(json::number::NAN).canonical().unwrap_err().code_name(),
+        "invalid_write"
+    );
+
+    // Out of the range of `f64`, which RFC 8785 numbers are limited to.
+    for source in &["1e400", "-1e400", "123456789e308"] {
+        let error = json::parse(source).unwrap().canonical().unwrap_err();
+
+        assert_eq!(error.code_name(), "invalid_write");
+    }
+
+    assert_eq!(json::parse("1e23").unwrap().canonical().unwrap(), "1e+23");
+    assert_eq!(json::parse("1e-400").unwrap().canonical().unwrap(), "0");
+}
+
+#[test]
+fn stringify_escape_non_ascii() {
+    use json::codegen::{ Generator, DumpGenerator, PrettyWriterGenerator };
+
+    let data = object!{ "café" => "naïve 😀 中文\n", "plain" => "ascii" };
+
+    let mut gen = DumpGenerator::new();
+    gen.escape_non_ascii(true);
+    gen.write_json(&data).unwrap();
+
+    let dump = gen.consume();
+
+    assert_eq!(dump, r#"{"caf\u00e9":"na\u00efve \ud83d\ude00 \u4e2d\u6587\n","plain":"ascii"}"#);
+    assert!(dump.is_ascii());
+    assert_eq!(parse(&dump).unwrap(), data);
+
+    let mut buffer = Vec::new();
+    {
+        let mut gen = PrettyWriterGenerator::new(&mut buffer, 2);
+        gen.escape_non_ascii(true);
+        gen.write_json(&array!["€"]).unwrap();
+    }
+
+    assert_eq!(buffer, b"[\n  \"\\u20ac\"\n]");
+}
+
+#[test]
+fn stringify_with_indent() {
+    use json::codegen::{ Generator, PrettyGenerator, PrettyWriterGenerator };
+
+    let data = object!{ "a" => array![1, object!{ "b" => Null }], "c" => array![] };
+
+    let mut gen = PrettyGenerator::with_indent("\t");
+    gen.write_json(&data).unwrap();
+
+    let pretty = gen.consume();
+
+    assert_eq!(pretty, "{\n\t\"a\": [\n\t\t1,\n\t\t{\n\t\t\t\"b\": null\n\t\t}\n\t],\n\t\"c\": []\n}");
+
+    let mut buffer = Vec::new();
+    {
+        let mut gen = PrettyWriterGenerator::with_indent(&mut buffer, "\t");
+        gen.write_json(&data).unwrap();
+    }
+
+    assert_eq!(String::from_utf8(buffer).unwrap(), pretty);
+
+    let mut gen = PrettyGenerator::with_indent("  ");
+    gen.write_json(&data).unwrap();
+
+    assert_eq!(gen.consume(), data.pretty(2));
+}
+
+#[test]
+fn stringify_wrap_at() {
+    use json::codegen::{ Generator, PrettyGenerator, PrettyWriterGenerator };
+
+    let data = object!{
+        "point" => array![1, 2, 3],
+        "name" => "short",
+        "nested" => object!{ "a" => array![true, false], "b" => Null },
+        "long" => array!["a fairly long string", "that won't fit", "on one line"],
+        "empty" => array![]
+    };
+
+    let expected = concat!(
+        "{\n",
+        "  \"point\": [1, 2, 3],\n",
+        "  \"name\": \"short\",\n",
+        "  \"nested\": {\"a\": [true, false], \"b\": null},\n",
+        "  \"long\": [\n",
+        "    \"a fairly long string\",\n",
+        "    \"that won't fit\",\n",
+        "    \"on one line\"\n",
+        "  ],\n",
+        "  \"empty\": []\n",
+        "}"
+    );
+
+    let mut gen = PrettyGenerator::new(2);
+    gen.wrap_at(48);
+    gen.write_json(&data).unwrap();
+
+    assert_eq!(gen.consume(), expected);
+
+    let mut buffer = Vec::new();
+    {
+        let mut gen = PrettyWriterGenerator::new(&mut buffer, 2);
+        gen.wrap_at(48);
+        gen.write_json(&data).unwrap();
+    }
+
+    assert_eq!(String::from_utf8(buffer).unwrap(), expected);
+
+    let mut gen = PrettyGenerator::new(2);
+    gen.wrap_at(80);
+    gen.write_json(&array![1, array![2, 3]]).unwrap();
+
+    assert_eq!(gen.consume(), "[1, [2, 3]]");
+
+    let mut gen = PrettyGenerator::new(2);
+    gen.wrap_at(0);
+    gen.write_json(&array![1]).unwrap();
+
+    assert_eq!(gen.consume(), "[\n  1\n]");
+}
+
+#[test]
+fn stringify_with_options() {
+    use json::StringifyOptions;
+    use json::codegen::FloatFormat;
+
+    let data = object!{
+        "b" => array![1.25, "ü"],
+        "a" => object!{ "z" => 1, "y" => 2 },
+        "c" => Null
+    };
+
+    assert_eq!(data.dump_with(&StringifyOptions::new()), data.dump());
+    assert_eq!(data.dump_with(&StringifyOptions::new().spaces(4)), data.pretty(4));
+    assert_eq!(
+        data.dump_with(&StringifyOptions::new().spaces(2).align_values(true)),
+        data.pretty_aligned(2)
+    );
+
+    let options = StringifyOptions::new()
+        .sort_keys(true)
+        .escape_non_ascii(true)
+        .float_format(FloatFormat::SignificantDigits(2));
+
+    assert_eq!(data.dump_with(&options), r#"{"a":{"y":2,"z":1},"b":[1.3,"\u00fc"],"c":null}"#);
+
+    let options = options.indent("  ").newline("\r\n").wrap_at(30);
+
+    assert_eq!(
+        data.dump_with(&options),
+        "{\r\n  \"a\": {\"y\": 2, \"z\": 1},\r\n  \"b\": [1.3, \"\\u00fc\"],\r\n  \"c\": null\r\n}"
+    );
+
+    let mut buffer = Vec::new();
+    data.write_with(&options, &mut buffer).unwrap();
+
+    assert_eq!(String::from_utf8(buffer).unwrap(), data.dump_with(&options));
+}
+
+#[test]
+fn stringify_sort_keys_wrap_at_nested() {
+    use json::StringifyOptions;
+
+    let data = object!{
+        "z" => object!{ "b" => 1, "a" => 2 },
+        "a" => 1,
+        "list" => array![object!{ "y" => object!{ "d" => 1, "c" => 2 }, "x" => 3 }]
+    };
+
+    let options = StringifyOptions::new().sort_keys(true).spaces(2).wrap_at(60);
+
+    assert_eq!(
+        data.dump_with(&options),
+        "{\n  \"a\": 1,\n  \"list\": [{\"x\": 3, \"y\": {\"c\": 2, \"d\": 1}}],\n  \"z\": {\"a\": 2, \"b\": 1}\n}"
+    );
+
+    let options = options.wrap_at(80);
+
+    assert_eq!(
+        data.dump_with(&options),
+        r#"{"a": 1, "list": [{"x": 3, "y": {"c": 2, "d": 1}}], "z": {"a": 2, "b": 1}}"#
+    );
+}
+
+#[test]
+fn stringify_with_separators() {
+    use json::StringifyOptions;
+
+    let data = object!{
+        "a" => array![1, 2],
+        "b" => object!{ "c" => Null }
+    };
+
+    assert_eq!(
+        data.dump_with(&StringifyOptions::new().separators(", ", ": ")),
+        r#"{"a": [1, 2], "b": {"c": null}}"#
+    );
+
+    let options = StringifyOptions::new().spaces(2).separators(";", " => ");
+
+    assert_eq!(
+        data.dump_with(&options),
+        "{\n  \"a\" => [\n    1;\n    2\n  ];\n  \"b\" => {\n    \"c\" => null\n  }\n}"
+    );
+
+    let options = options.wrap_at(80);
+
+    assert_eq!(data.dump_with(&options), r#"{"a" => [1;2];"b" => {"c" => null}}"#);
+
+    let mut buffer = Vec::new();
+    data.write_with(&options, &mut buffer).unwrap();
+
+    assert_eq!(String::from_utf8(buffer).unwrap(), data.dump_with(&options));
+}
+
+#[test]
+fn stringify_dump_into() {
+    let data = object!{ "a" => array![1, "b"] };
+
+    let mut buffer = String::from("data: ");
+    data.dump_into(&mut buffer);
+
+    assert_eq!(buffer, "data: {\"a\":[1,\"b\"]}");
+
+    let mut bytes = b"> ".to_vec();
+    data.dump_into_vec(&mut bytes);
+    data.dump_into_vec(&mut bytes);
+
+    assert_eq!(bytes, b"> {\"a\":[1,\"b\"]}{\"a\":[1,\"b\"]}".to_vec());
+
+    bytes.clear();
+    let capacity = bytes.capacity();
+    Null.dump_into_vec(&mut bytes);
+
+    assert_eq!(bytes, b"null");
+    assert_eq!(bytes.capacity(), capacity);
+}
+
+#[test]
+fn stringify_dump_fmt() {
+    use std::fmt;
+
+    // Counts the chunks handed to it, every one of which must be a `str`.
+    struct Chunks(String, usize);
+
+    impl fmt::Write for Chunks {
+        fn write_str(&mut self, chunk: &str) -> fmt::Result {
+            self.0.push_str(chunk);
+            self.1 += 1;
+            Ok(())
+        }
+    }
+
+    let long = "żółw ".repeat(3000);
+    let data = object!{ "a" => array![long.as_str(), 1.5, Null], "b" => "ą" };
+
+    let mut chunks = Chunks(String::new(), 0);
+    data.dump_fmt(&mut chunks).unwrap();
+
+    assert_eq!(chunks.0, data.dump());
+    assert!(chunks.1 > 1);
+
+    assert_eq!(format!("{}", data), data.dump());
+    assert_eq!(format!("{:#}", data), data.pretty(4));
+}
+
+#[test]
+fn stringify_raw() {
+    use json::RawJson;
+
+    let raw = RawJson::new("  { \"b\": [1, 2], \"a\": null }\n").unwrap();
+
+    assert_eq!(raw.as_str(), "{ \"b\": [1, 2], \"a\": null }");
+
+    let data = object!{ "raw" => JsonValue::Raw(raw), "plain" => true };
+
+    assert_eq!(data.dump(), r#"{"raw":{ "b": [1, 2], "a": null },"plain":true}"#);
+    assert_eq!(data.pretty(2), "{\n  \"raw\": { \"b\": [1, 2], \"a\": null },\n  \"plain\": true\n}");
+    assert_eq!(data.canonical().unwrap(), r#"{"plain":true,"raw":{"a":null,"b":[1,2]}}"#);
+
+    assert_eq!(data["raw"], object!{ "a" => Null, "b" => array![1, 2] });
+    assert_eq!(RawJson::from_value(&array![1, "two"]).as_str(), r#"[1,"two"]"#);
+    assert_eq!(RawJson::new("[1,").unwrap_err().code_name(), "unexpected_end_of_json");
+    assert!(RawJson::new("1 2").is_err());
+
+    let unchecked = RawJson::new_unchecked("[1, 2]");
+
+    assert_eq!(unchecked, RawJson::new("[1,2]").unwrap());
+    assert_eq!(unchecked.to_value(), Ok(array![1, 2]));
+
+    let invalid = JsonValue::Raw(RawJson::new_unchecked("[1,"));
+
+    assert_eq!(invalid.dump(), "[1,");
+    assert_eq!(invalid, JsonValue::Raw(RawJson::new_unchecked("[1,")));
+    assert_ne!(invalid, array![1]);
+    assert!(invalid.canonical().is_err());
+
+    if let JsonValue::Raw(ref raw) = invalid {
+        assert_eq!(raw.to_value(), Err(json::Error::UnexpectedEndOfJson));
+    }
+}
@@ -0,0 +1,55 @@
+#[macro_use]
+extern crate json;
+
+use json::testing::{ diff, contains_diff };
+
+#[test]
+fn diff_equal_values() {
+    let value = object!{ "a" => array![1, 2.5, "x", json::Null], "b" => object!{} };
+
+    assert!(diff(&value, &value.clone()).is_empty());
+    assert_json_eq!(value, value.clone());
+    assert_json_eq!("foo", json::parse(r#""foo""#).unwrap());
+    assert_json_eq!(1.0, 1);
+}
+
+#[test]
+fn diff_reports_all_differences() {
+    let actual = object!{ "a~b" => 1, "list" => array![1, 2, 3], "x/y" => true };
+    let expected = object!{ "a~b" => "1", "list" => array![1, 5], "z" => json::Null };
+
+    assert_eq!(diff(&actual, &expected), [
+        r#"/a~0b: expected "1", found 1"#,
+        "/list/1: expected 5, found 2",
+        "/list/2: unexpected 3",
+        "/z: missing, expected null",
+        "/x~1y: unexpected true",
+    ]);
+
+    assert_eq!(diff(&json::from(1), &json::from(2)), ["(root): expected 2, found 1"]);
+}
+
+#[test]
+fn contains_ignores_extra_members() {
+    let superset = object!{ "id" => 1, "tags" => array!["a", "b"], "meta" => object!{ "x" => 1, "y" => 2 } };
+
+    assert_json_contains!(superset, object!{ "tags" => array!["a"], "meta" => object!{ "y" => 2 } });
+    assert_json_contains!(superset, object!{});
+
+    assert_eq!(contains_diff(&superset, &object!{ "meta" => object!{ "z" => 3 }, "tags" => array!["b"] }), [
+        "/meta/z: missing, expected 3",
+        r#"/tags/0: expected "b", found "a""#,
+    ]);
+}
+
+#[test]
+#[should_panic(expected = "JSON values are not equal:\n  /a: expected 2, found 1")]
+fn assert_json_eq_panics() {
+    assert_json_eq!(object!{ "a" => 1 }, object!{ "a" => 2 });
+}
+
+#[test]
+#[should_panic(expected = "does not contain the expected subset:\n  /1: missing, expected 2")]
+fn assert_json_contains_panics() {
+    assert_json_contains!(array![1], array![1, 2]);
+}
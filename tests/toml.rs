@@ -0,0 +1,72 @@
+#![cfg(feature = "toml")]
+
+#[macro_use]
+extern crate json;
+
+use json::{ toml, Error, JsonValue, Null };
+use json::number::INFINITY;
+
+#[test]
+fn toml_parse() {
+    let data = toml::parse(r#"
+title = "example"
+ints = [1, -2, 0x10]
+floats = [1.5, 1e3, inf]
+enabled = true
+created = 1979-05-27T07:32:00Z
+local = 1979-05-27T07:32:00
+date = 1979-05-27
+time = 07:32:00
+point = { x = 1, y = 2 }
+
+[server]
+port = 8080
+
+[[products]]
+name = "Hammer"
+
+[[products]]
+name = "Nail"
+"#).unwrap();
+
+    assert_eq!(data, object!{
+        "title" => "example",
+        "ints" => array![1, -2, 16],
+        "floats" => array![1.5, 1000, JsonValue::Number(INFINITY)],
+        "enabled" => true,
+        "created" => "1979-05-27T07:32:00Z",
+        "local" => "1979-05-27T07:32:00",
+        "date" => "1979-05-27",
+        "time" => "07:32:00",
+        "point" => object!{ "x" => 1, "y" => 2 },
+        "server" => object!{ "port" => 8080 },
+        "products" => array![object!{ "name" => "Hammer" }, object!{ "name" => "Nail" }]
+    });
+}
+
+#[test]
+fn toml_round_trip() {
+    let data = object!{
+        "name" => "json",
+        "ratio" => 0.25,
+        "tags" => array!["a", "b"],
+        "owner" => object!{ "name" => "Joe", "nested" => object!{ "deep" => true } },
+        "items" => array![object!{ "id" => 1 }, object!{ "id" => 2 }]
+    };
+
+    let text = toml::to_toml_string(&data).unwrap();
+
+    assert_eq!(toml::parse(&text).unwrap(), data);
+}
+
+#[test]
+fn toml_errors() {
+    assert_eq!(toml::to_toml_string(&array![1]), Err(Error::InvalidToml("document that is not an object".into())));
+    assert_eq!(toml::to_toml_string(&object!{ "a" => Null }), Err(Error::InvalidToml("null value".into())));
+
+    let error = toml::parse("a = ").unwrap_err();
+
+    assert_eq!(error.code(), 16);
+    assert_eq!(error.code_name(), "invalid_toml");
+    assert!(error.to_string().starts_with("Invalid TOML: "));
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "uuid")]
+
+extern crate uuid;
+#[macro_use]
+extern crate json;
+
+use uuid::Uuid;
+use json::JsonValue;
+
+#[test]
+fn uuid_to_json() {
+    let id = Uuid::from_u128(0x67e5504410b1426f9247bb680e5fe0c8);
+
+    assert_eq!(JsonValue::from(id), "67e55044-10b1-426f-9247-bb680e5fe0c8");
+    assert_eq!(object!{ "id" => id }.dump(), r#"{"id":"67e55044-10b1-426f-9247-bb680e5fe0c8"}"#);
+}
+
+#[test]
+fn uuid_from_json() {
+    let id = Uuid::from_u128(0x67e5504410b1426f9247bb680e5fe0c8);
+
+    assert_eq!(JsonValue::from("67e55044-10b1-426f-9247-bb680e5fe0c8").as_uuid(), Some(id));
+    assert_eq!(JsonValue::from("67E55044-10B1-426F-9247-BB680E5FE0C8").as_uuid(), Some(id));
+    assert_eq!(JsonValue::from("67e5504410b1426f9247bb680e5fe0c8").as_uuid(), None);
+    assert_eq!(JsonValue::from("{67e55044-10b1-426f-9247-bb680e5fe0c8}").as_uuid(), None);
+    assert_eq!(JsonValue::from("67e55044-10b1-426f-9247-bb680e5fe0cz").as_uuid(), None);
+    assert_eq!(JsonValue::from(5).as_uuid(), None);
+
+    assert_eq!(array!["67e55044-10b1-426f-9247-bb680e5fe0c8"].expect_type::<Vec<Uuid>>(), Ok(vec![id]));
+
+    let error = JsonValue::from("nope").expect_type::<Uuid>().unwrap_err();
+
+    assert_eq!(error.expected(), "uuid");
+}
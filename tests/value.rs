@@ -1,5 +1,6 @@
 #[macro_use]
 extern crate json;
+extern crate indexmap;
 
 use json::{ parse, JsonValue, JsonError, Null };
 
@@ -596,6 +597,29 @@ fn error_unexpected_token() {
     assert_eq!(format!("{}", err), "Unexpected character: ] at (4:3)");
 }
 
+#[test]
+fn error_codes_and_categories() {
+    use json::ErrorCategory;
+
+    let err = parse("nulX").unwrap_err();
+
+    assert_eq!(err.code(), 1);
+    assert_eq!(err.code_name(), "unexpected_character");
+    assert_eq!(err.category(), ErrorCategory::Syntax);
+
+    let err = JsonError::wrong_type("Array");
+
+    assert_eq!(err.code(), 5);
+    assert_eq!(err.category(), ErrorCategory::Type);
+    assert_eq!(err.category().as_str(), "type");
+
+    let err = JsonError::from(::std::io::Error::new(::std::io::ErrorKind::Other, "boom"));
+
+    assert_eq!(err.code_name(), "io");
+    assert_eq!(err.category(), ErrorCategory::Io);
+    assert_eq!(format!("{}", err), "I/O error: boom");
+}
+
 #[test]
 fn writer_generator() {
     let data = object!{
@@ -633,7 +657,7 @@ fn equality() {
     };
 
     let left_string = object!{
-        "foo" => array![JsonValue::String("bar".to_string()), 100, true]
+        "foo" => array![JsonValue::String("bar".into()), 100, true]
     };
 
     let left_short = object!{
@@ -645,7 +669,7 @@ fn equality() {
     };
 
     let change_string = object!{
-        "foo" => array![JsonValue::String("sna".to_string()), 100, true]
+        "foo" => array![JsonValue::String("sna".into()), 100, true]
     };
 
     let change_short = object!{
@@ -659,3 +683,1288 @@ fn equality() {
     assert_ne!(left, change_string);
     assert_ne!(left, change_short);
 }
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+impl json::ToJson for Point {
+    fn into_json(self) -> JsonValue {
+        object!{
+            "x" => self.x,
+            "y" => self.y
+        }
+    }
+}
+
+// Converts into a `JsonValue`, but isn't `Clone`.
+struct Id(u32);
+
+impl From<Id> for JsonValue {
+    fn from(id: Id) -> JsonValue {
+        JsonValue::from(id.0)
+    }
+}
+
+#[test]
+fn to_json_in_macros() {
+    let mut data = object!{
+        "start" => Point { x: 1, y: 2 },
+        "points" => array![Point { x: 3, y: 4 }],
+        "id" => Id(5),
+        "ids" => array![Id(6)]
+    };
+
+    data.insert("end", Point { x: 7, y: 8 }).unwrap();
+    data.insert("last", Id(9)).unwrap();
+
+    assert_eq!(data["start"]["y"], 2);
+    assert_eq!(data["points"][0]["x"], 3);
+    assert_eq!(data["id"], 5);
+    assert_eq!(data["ids"][0], 6);
+    assert_eq!(data["end"]["x"], 7);
+    assert_eq!(data["last"], 9);
+}
+
+#[test]
+fn to_json_std_types() {
+    use json::ToJson;
+
+    assert_eq!(42.to_json(), 42);
+    assert_eq!("foo".to_json(), "foo");
+    assert_eq!("foo".to_string().to_json(), "foo");
+    assert_eq!(Some(true).to_json(), true);
+    assert_eq!(vec![1, 2].to_json(), array![1, 2]);
+    assert!(None::<i32>.to_json().is_null());
+}
+
+#[test]
+fn object_insert() {
+    let mut data = object!{};
+
+    data.insert("point", Point { x: 1, y: 2 }).unwrap();
+    data.insert("answer", 42).unwrap();
+
+    assert_eq!(data["point"]["x"], 1);
+    assert_eq!(data["answer"], 42);
+    assert!(array![].insert("foo", 1).is_err());
+}
+
+#[test]
+fn from_json_primitives() {
+    use json::FromJson;
+
+    assert_eq!(bool::from_json(&JsonValue::from(true)), Ok(true));
+    assert_eq!(String::from_json(&JsonValue::from("foo")), Ok("foo".to_string()));
+    assert_eq!(u8::from_json(&JsonValue::from(200)), Ok(200));
+    assert_eq!(i64::from_json(&JsonValue::from(-5)), Ok(-5));
+    assert_eq!(f64::from_json(&JsonValue::from(1.5)), Ok(1.5));
+    assert_eq!(Option::<i32>::from_json(&Null), Ok(None));
+    assert_eq!(Option::<i32>::from_json(&JsonValue::from(3)), Ok(Some(3)));
+
+    assert!(u8::from_json(&JsonValue::from(256)).is_err());
+    assert!(u8::from_json(&JsonValue::from(-1)).is_err());
+    assert!(i32::from_json(&JsonValue::from(1.5)).is_err());
+    assert!(i64::from_json(&JsonValue::from(1e20)).is_err());
+    assert!(bool::from_json(&Null).is_err());
+}
+
+#[test]
+fn from_json_integer_bounds() {
+    use json::FromJson;
+
+    let number = |source: &str| json::parse(source).unwrap();
+
+    assert_eq!(i64::from_json(&number("9223372036854775807")), Ok(i64::MAX));
+    assert_eq!(i64::from_json(&number("-9223372036854775808")), Ok(i64::MIN));
+    assert!(i64::from_json(&number("9223372036854775808")).is_err());
+    assert!(i64::from_json(&number("-9223372036854775809")).is_err());
+
+    assert_eq!(u64::from_json(&number("18446744073709551615")), Ok(u64::MAX));
+    assert_eq!(u64::from_json(&number("0")), Ok(0));
+    assert!(u64::from_json(&number("18446744073709551616")).is_err());
+    assert!(u64::from_json(&number("-1")).is_err());
+
+    assert_eq!(i8::from_json(&number("127")), Ok(i8::MAX));
+    assert_eq!(i8::from_json(&number("-128")), Ok(i8::MIN));
+    assert!(i8::from_json(&number("128")).is_err());
+    assert!(i8::from_json(&number("-129")).is_err());
+
+    assert_eq!(u32::from_json(&number("4294967295")), Ok(u32::MAX));
+    assert!(u32::from_json(&number("4294967296")).is_err());
+    assert_eq!(u8::from_json(&number("2.55e2")), Ok(255));
+    assert!(u8::from_json(&number("25.5")).is_err());
+
+    assert_eq!(number("9223372036854775807").as_i64_or_err(), Ok(i64::MAX));
+    assert_eq!(number("18446744073709551615").as_u64_or_err(), Ok(u64::MAX));
+    assert!(number("18446744073709551616").as_u64_or_err().is_err());
+}
+
+#[test]
+fn from_json_error_path() {
+    use std::collections::HashMap;
+    use json::FromJson;
+
+    let data = object!{
+        "ok" => array![1, 2],
+        "a/b" => array![1, "two"]
+    };
+
+    let error = HashMap::<String, Vec<i32>>::from_json(&data).unwrap_err();
+
+    assert_eq!(error.path(), "/a~1b/1");
+    assert_eq!(error.expected(), "i32");
+    assert_eq!(error.actual(), "string");
+    assert_eq!(error.to_string(), "Expected i32, found string at \"/a~1b/1\"");
+
+    let data = object!{ "a" => array![1, 2] };
+    let map = HashMap::<String, Vec<i32>>::from_json(&data).unwrap();
+
+    assert_eq!(map["a"], vec![1, 2]);
+}
+
+#[test]
+fn compact_recursive() {
+    let mut data = object!{
+        "a" => Null,
+        "b" => array![Null, object!{}, array![Null]],
+        "c" => object!{ "d" => object!{ "e" => Null } },
+        "f" => 0,
+        "g" => ""
+    };
+
+    data.compact(json::CompactOptions::default());
+
+    assert_eq!(data, object!{ "f" => 0, "g" => "" });
+}
+
+#[test]
+fn compact_options() {
+    let data = object!{
+        "a" => Null,
+        "b" => array![],
+        "c" => object!{},
+        "d" => object!{ "e" => Null }
+    };
+
+    let mut nulls = data.clone();
+    nulls.compact(json::CompactOptions {
+        drop_nulls: true,
+        drop_empty_strings: false,
+        drop_empty_objects: false,
+        drop_empty_arrays: false,
+        recursive: false,
+    });
+
+    assert_eq!(nulls, object!{ "b" => array![], "c" => object!{}, "d" => object!{ "e" => Null } });
+
+    let mut shallow = data.clone();
+    shallow.compact(json::CompactOptions {
+        recursive: false,
+        ..Default::default()
+    });
+
+    assert_eq!(shallow, object!{ "d" => object!{ "e" => Null } });
+    let mut strings = object!{ "a" => "", "b" => array![""], "c" => "c" };
+    strings.compact(json::CompactOptions {
+        drop_empty_strings: true,
+        ..Default::default()
+    });
+
+    assert_eq!(strings, object!{ "c" => "c" });
+}
+
+#[test]
+fn prune() {
+    let data = object!{
+        "a" => Null,
+        "b" => "",
+        "c" => array!["", array![]],
+        "d" => object!{ "e" => Null, "f" => "" },
+        "g" => 0,
+        "h" => false
+    };
+
+    let mut all = data.clone();
+    all.prune(json::PruneOptions::default());
+
+    assert_eq!(all, object!{ "g" => 0, "h" => false });
+
+    let mut strings = data.clone();
+    strings.prune(json::PruneOptions {
+        drop_nulls: false,
+        drop_empty_arrays: false,
+        drop_empty_objects: false,
+        ..Default::default()
+    });
+
+    assert_eq!(strings, object!{
+        "a" => Null,
+        "c" => array![array![]],
+        "d" => object!{ "e" => Null },
+        "g" => 0,
+        "h" => false
+    });
+
+    let mut owned = array![JsonValue::String("".into())];
+    owned.prune(json::PruneOptions::default());
+
+    assert_eq!(owned, array![]);
+}
+
+#[test]
+fn const_values() {
+    use json::short::Short;
+    use json::number::Number;
+
+    static NULL: JsonValue = JsonValue::Null;
+    static ENABLED: JsonValue = JsonValue::Boolean(true);
+    static NAME: JsonValue = JsonValue::Short(Short::new("default"));
+    static RETRIES: JsonValue = JsonValue::Number(Number::from_u64(3));
+    static OFFSET: JsonValue = JsonValue::Number(Number::from_i64(-42));
+    static RATIO: JsonValue = JsonValue::Number(Number::from_parts(true, 2500, -3));
+    static EMPTY: JsonValue = JsonValue::Array(Vec::new());
+
+    assert!(NULL.is_null());
+    assert_eq!(ENABLED, true);
+    assert_eq!(NAME, "default");
+    assert_eq!(NAME.dump(), r#""default""#);
+    assert_eq!(RETRIES, 3);
+    assert_eq!(OFFSET, -42);
+    assert_eq!(OFFSET.dump(), "-42");
+    assert_eq!(RATIO, 2.5);
+    assert_eq!(RATIO.dump(), "2.5");
+    assert_eq!(EMPTY, array![]);
+}
+
+#[test]
+fn object_positional_access() {
+    let data = object!{
+        "id" => 1,
+        "name" => "Alice",
+        "admin" => false
+    };
+
+    let object = match data {
+        JsonValue::Object(object) => object,
+        _ => unreachable!()
+    };
+
+    assert_eq!(object[0], 1);
+    assert_eq!(object[1], "Alice");
+    assert_eq!(object[2], false);
+    assert_eq!(object[3], Null);
+
+    assert_eq!(object.key_at(2), Some("admin"));
+    assert_eq!(object.key_at(3), None);
+    assert_eq!(object.get_index(0), Some(("id", &JsonValue::from(1))));
+    assert_eq!(object.get_index(3), None);
+}
+
+#[test]
+fn generic_key_lookup() {
+    use std::borrow::Cow;
+    use json::short::Short;
+
+    let mut data = object!{
+        "foo" => 1,
+        "bar" => 2,
+        "baz" => 3
+    };
+
+    let owned = String::from("foo");
+    let borrowed: Cow<str> = Cow::Borrowed("bar");
+    let short = unsafe { Short::from_slice("baz") };
+
+    assert_eq!(data["foo"], 1);
+    assert_eq!(data[&owned], 1);
+    assert_eq!(data[owned.clone()], 1);
+    assert_eq!(data[&borrowed], 2);
+    assert_eq!(data[&short], 3);
+    assert_eq!(data[&&"foo"], 1);
+
+    if let JsonValue::Object(ref mut object) = data {
+        assert_eq!(object.get(&borrowed), Some(&JsonValue::from(2)));
+        assert_eq!(object.get(&short), Some(&JsonValue::from(3)));
+        assert_eq!(object[&owned], 1);
+
+        *object.get_mut(&owned).unwrap() = 10.into();
+    }
+
+    assert_eq!(data.remove(&owned), 10);
+    assert_eq!(data.remove(&borrowed), 2);
+    assert_eq!(data.remove("nope"), Null);
+    assert_eq!(data, object!{ "baz" => 3 });
+}
+
+#[test]
+fn parse_into() {
+    use std::collections::BTreeMap;
+
+    let parsed: BTreeMap<String, Vec<Option<i32>>> = json::parse_into(r#"{"a":[1,null],"b":[]}"#).unwrap();
+
+    assert_eq!(parsed["a"], [Some(1), None]);
+    assert!(parsed["b"].is_empty());
+
+    let error = json::parse_into::<BTreeMap<String, Vec<i32>>>(r#"{"a":[1,"2"]}"#).unwrap_err();
+
+    assert_eq!(error.code(), 7);
+    assert_eq!(error.code_name(), "from_json");
+    assert_eq!(error.category(), json::ErrorCategory::Type);
+
+    match error {
+        json::Error::FromJson(ref inner) => assert_eq!(inner.path(), "/a/1"),
+        _ => panic!("Expected a conversion error"),
+    }
+
+    assert_eq!(json::parse_into::<bool>("tru"), Err(json::Error::UnexpectedEndOfJson));
+}
+
+#[test]
+fn parse_into_skips_fields() {
+    use json::{ FromJson, FromJsonError };
+
+    #[derive(Debug, PartialEq)]
+    struct Seen(Vec<String>);
+
+    impl FromJson for Seen {
+        fn from_json(value: &JsonValue) -> Result<Self, FromJsonError> {
+            Ok(Seen(value.entries().map(|(key, value)| format!("{}={}", key, value.dump())).collect()))
+        }
+
+        fn fields() -> Option<&'static [&'static str]> {
+            Some(&["id", "tags"])
+        }
+    }
+
+    let source = r#"{ "posts": [{"id": 1}], "id": 2, "name": "x", "tags": ["a"], "id": 3 }"#;
+
+    assert_eq!(json::parse_into::<Seen>(source), Ok(Seen(vec!["id=3".into(), r#"tags=["a"]"#.into()])));
+    assert_eq!(json::parse_into::<Option<Seen>>(source).unwrap().unwrap().0.len(), 2);
+    assert_eq!(json::parse_into::<Option<Seen>>("null"), Ok(None));
+    assert_eq!(json::parse_into::<Seen>("[1]"), Ok(Seen(Vec::new())));
+
+    assert!(json::parse_into::<Seen>(r#"{ "posts": [1,], "id": 2 }"#).is_err());
+    assert!(json::parse_into::<Seen>(r#"{ "posts": tru, "id": 2 }"#).is_err());
+    assert!(json::parse_into::<Seen>(r#"{ "id": 2 } 3"#).is_err());
+    assert!(json::parse_into::<Seen>(r#"{ "id": 2, }"#).is_err());
+}
+
+#[test]
+fn json_value_size() {
+    use std::mem::size_of;
+
+    if cfg!(feature = "compact_value") {
+        let size = if cfg!(feature = "short_62") {
+            64
+        } else if cfg!(feature = "short_46") {
+            48
+        } else {
+            32
+        };
+
+        assert_eq!(size_of::<JsonValue>(), size);
+    } else {
+        assert!(size_of::<JsonValue>() > 32);
+    }
+}
+
+#[test]
+fn short_capacity() {
+    use json::short::{ Short, MAX_LEN };
+
+    assert_eq!(Short::capacity(), MAX_LEN);
+
+    let inline = "x".repeat(Short::capacity());
+    let heap = "x".repeat(Short::capacity() + 1);
+
+    assert!(matches!(JsonValue::from(&*inline), JsonValue::Short(_)));
+    assert!(matches!(JsonValue::from(&*heap), JsonValue::String(_)));
+    assert!(matches!(parse(&format!("{:?}", inline)).unwrap(), JsonValue::Short(_)));
+    assert!(matches!(parse(&format!("{:?}", heap)).unwrap(), JsonValue::String(_)));
+}
+
+#[test]
+fn pointer() {
+    let mut data = object!{
+        "foo" => array!["bar", "baz"],
+        "" => 0,
+        "a/b" => 1,
+        "m~n" => 8,
+        " " => 7,
+        "nested" => object!{ "list" => array![object!{ "x" => true }] }
+    };
+
+    // Examples from RFC 6901, section 5.
+    assert_eq!(data.pointer(""), Some(&data.clone()));
+    assert_eq!(data.pointer("/foo").unwrap(), &array!["bar", "baz"]);
+    assert_eq!(data.pointer("/foo/0").unwrap(), "bar");
+    assert_eq!(data.pointer("/").unwrap(), 0);
+    assert_eq!(data.pointer("/a~1b").unwrap(), 1);
+    assert_eq!(data.pointer("/m~0n").unwrap(), 8);
+    assert_eq!(data.pointer("/ ").unwrap(), 7);
+
+    assert_eq!(data.pointer("/nested/list/0/x").unwrap(), true);
+    assert_eq!(data.pointer("foo"), None);
+    assert_eq!(data.pointer("/foo/2"), None);
+    assert_eq!(data.pointer("/foo/01"), None);
+    assert_eq!(data.pointer("/foo/+1"), None);
+    assert_eq!(data.pointer("/foo/-"), None);
+    assert_eq!(data.pointer("/foo/0/bar"), None);
+    assert_eq!(data.pointer("/missing"), None);
+
+    *data.pointer_mut("/nested/list/0/x").unwrap() = false.into();
+    data.pointer_mut("/foo").unwrap().push("qux").unwrap();
+
+    assert_eq!(data["nested"]["list"][0]["x"], false);
+    assert_eq!(data["foo"], array!["bar", "baz", "qux"]);
+    assert!(data.pointer_mut("/nope").is_none());
+}
+
+#[test]
+fn merge_patch() {
+    // Test cases from RFC 7386, appendix A.
+    let cases = [
+        (r#"{"a":"b"}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+        (r#"{"a":"b"}"#, r#"{"b":"c"}"#, r#"{"a":"b","b":"c"}"#),
+        (r#"{"a":"b"}"#, r#"{"a":null}"#, r#"{}"#),
+        (r#"{"a":"b","b":"c"}"#, r#"{"a":null}"#, r#"{"b":"c"}"#),
+        (r#"{"a":["b"]}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+        (r#"{"a":"c"}"#, r#"{"a":["b"]}"#, r#"{"a":["b"]}"#),
+        (r#"{"a":{"b":"c"}}"#, r#"{"a":{"b":"d","c":null}}"#, r#"{"a":{"b":"d"}}"#),
+        (r#"{"a":[{"b":"c"}]}"#, r#"{"a":[1]}"#, r#"{"a":[1]}"#),
+        (r#"["a","b"]"#, r#"["c","d"]"#, r#"["c","d"]"#),
+        (r#"{"a":"b"}"#, r#"["c"]"#, r#"["c"]"#),
+        (r#"{"a":"foo"}"#, r#"null"#, r#"null"#),
+        (r#"{"a":"foo"}"#, r#""bar""#, r#""bar""#),
+        (r#"{"e":null}"#, r#"{"a":1}"#, r#"{"e":null,"a":1}"#),
+        (r#"[1,2]"#, r#"{"a":"b","c":null}"#, r#"{"a":"b"}"#),
+        (r#"{}"#, r#"{"a":{"bb":{"ccc":null}}}"#, r#"{"a":{"bb":{}}}"#),
+    ];
+
+    for &(target, patch, result) in cases.iter() {
+        let mut target = parse(target).unwrap();
+
+        target.merge_patch(&parse(patch).unwrap());
+
+        assert_eq!(target, parse(result).unwrap(), "patch {}", patch);
+    }
+}
+
+#[test]
+fn apply_patch() {
+    use json::patch::PatchOp;
+    use json::FromJson;
+
+    fn patch(source: &str) -> Vec<PatchOp> {
+        Vec::<PatchOp>::from_json(&parse(source).unwrap()).unwrap()
+    }
+
+    let mut data = parse(r#"{"foo":{"bar":"baz","waldo":"fred"},"qux":{"corge":"grault"},"list":[1,2]}"#).unwrap();
+
+    data.apply_patch(&patch(r#"[
+        {"op":"add","path":"/list/1","value":"x"},
+        {"op":"add","path":"/list/-","value":null},
+        {"op":"remove","path":"/list/0"},
+        {"op":"replace","path":"/foo/bar","value":[true]},
+        {"op":"move","from":"/foo/waldo","path":"/qux/thud"},
+        {"op":"copy","from":"/qux/corge","path":"/a~1b"},
+        {"op":"test","path":"/a~1b","value":"grault"},
+        {"op":"add","path":"/foo/bar/0","value":false}
+    ]"#)).unwrap();
+
+    assert_eq!(data, parse(r#"{
+        "foo": {"bar": [false, true]},
+        "qux": {"corge": "grault", "thud": "fred"},
+        "list": ["x", 2, null],
+        "a/b": "grault"
+    }"#).unwrap());
+
+    data.apply_patch(&patch(r#"[{"op":"add","path":"","value":[1]}]"#)).unwrap();
+
+    assert_eq!(data, array![1]);
+}
+
+#[test]
+fn apply_patch_is_atomic() {
+    use json::patch::PatchOp;
+
+    let original = object!{ "a" => array![1, 2], "b" => object!{ "c" => 3 } };
+
+    let failing = [
+        PatchOp::Remove { path: "/missing".into() },
+        PatchOp::Remove { path: "/a/2".into() },
+        PatchOp::Replace { path: "/b/d".into(), value: 1.into() },
+        PatchOp::Add { path: "/a/3".into(), value: 1.into() },
+        PatchOp::Add { path: "/a/01".into(), value: 1.into() },
+        PatchOp::Add { path: "/x/y".into(), value: 1.into() },
+        PatchOp::Add { path: "/b/c/d".into(), value: 1.into() },
+        PatchOp::Move { from: "/b".into(), path: "/b/e".into() },
+        PatchOp::Copy { from: "/nope".into(), path: "/c".into() },
+        PatchOp::Test { path: "/b/c".into(), value: "3".into() },
+        PatchOp::Remove { path: "".into() },
+        PatchOp::Add { path: "nope".into(), value: 1.into() },
+    ];
+
+    for op in failing.iter() {
+        let mut data = original.clone();
+        let ops = [PatchOp::Add { path: "/z".into(), value: 0.into() }, op.clone()];
+        let error = data.apply_patch(&ops).unwrap_err();
+
+        assert_eq!(error.code_name(), "patch_failed", "{:?}", op);
+        assert_eq!(error.category(), json::ErrorCategory::Value);
+        assert_eq!(data, original);
+    }
+}
+
+#[test]
+fn patch_op_from_json_errors() {
+    use json::patch::PatchOp;
+    use json::FromJson;
+
+    let error = Vec::<PatchOp>::from_json(&parse(r#"[{"op":"add","path":"/a","value":null},{"op":"add","path":"/a"}]"#).unwrap()).unwrap_err();
+    assert_eq!(error.path(), "/1/value");
+
+    let error = PatchOp::from_json(&parse(r#"{"op":"frobnicate","path":"/a"}"#).unwrap()).unwrap_err();
+    assert_eq!(error.path(), "/op");
+
+    let error = PatchOp::from_json(&parse(r#"{"op":"move","path":"/a"}"#).unwrap()).unwrap_err();
+    assert_eq!(error.path(), "/from");
+}
+
+#[test]
+fn error_display_context() {
+    let source = "[1,\n\t2,\n\tx]";
+    let err = parse(source).unwrap_err();
+
+    assert_eq!(err.display_context(source), "Unexpected character: x at (3:2)\n  |\n3 | \tx]\n  | \t^\n");
+
+    let source = "{\"a\": [1,\n";
+    let err = parse(source).unwrap_err();
+
+    assert_eq!(err.display_context(source), "Unexpected end of JSON\n  |\n1 | {\"a\": [1,\n  |          ^\n");
+
+    let err = JsonError::wrong_type("Array");
+
+    assert_eq!(err.display_context(""), "Wrong type, expected: Array");
+}
+
+#[test]
+fn object_entry() {
+    use json::object::{ Object, Entry };
+
+    let mut object = Object::new();
+
+    object.entry("list").or_insert_with(JsonValue::new_array).push(1).unwrap();
+    object.entry(String::from("list")).or_insert_with(JsonValue::new_array).push(2).unwrap();
+    object.entry("flag").or_insert(true.into());
+    object.entry("flag").and_modify(|flag| *flag = false.into()).or_insert(Null);
+
+    assert_eq!(object.dump(), r#"{"list":[1,2],"flag":false}"#);
+
+    match object.entry("missing") {
+        Entry::Vacant(entry) => assert_eq!(entry.key(), "missing"),
+        Entry::Occupied(_)   => panic!("Entry should be vacant"),
+    }
+
+    assert_eq!(object.len(), 2);
+}
+
+#[test]
+fn object_sort() {
+    let mut data = object!{ "b" => 1, "a" => 2, "d" => 1, "c" => 2 };
+
+    if let JsonValue::Object(ref mut object) = data {
+        object.sort_by(|_, a, _, b| a.as_i32().cmp(&b.as_i32()));
+    }
+
+    assert_eq!(data.dump(), r#"{"b":1,"d":1,"a":2,"c":2}"#);
+
+    if let JsonValue::Object(ref mut object) = data {
+        object.sort_unstable_by(|a, _, b, _| b.cmp(a));
+    }
+
+    assert_eq!(data.dump(), r#"{"d":1,"c":2,"b":1,"a":2}"#);
+
+    if let JsonValue::Object(ref mut object) = data {
+        object.sort_keys();
+    }
+
+    assert_eq!(data.dump(), r#"{"a":2,"b":1,"c":2,"d":1}"#);
+}
+
+#[test]
+fn object_keys_and_values() {
+    use json::object::Object;
+
+    let mut object = Object::new();
+    object.insert("a", 1.into());
+    object.insert("b", 2.into());
+
+    assert_eq!(object.keys().collect::<Vec<_>>(), vec!["a", "b"]);
+    assert_eq!(object.values().map(|value| value.as_i32().unwrap()).sum::<i32>(), 3);
+
+    for value in object.values_mut() {
+        *value = value.as_i32().unwrap().wrapping_mul(10).into();
+    }
+
+    assert_eq!(object.clone().into_values().collect::<Vec<_>>(), vec![JsonValue::from(10), JsonValue::from(20)]);
+    assert_eq!(object.into_keys().collect::<Vec<_>>(), vec!["a".to_string(), "b".to_string()]);
+}
+
+#[test]
+fn object_get_key_value_and_remove_entry() {
+    use json::object::Object;
+
+    let mut object = Object::new();
+    object.insert("Content-Type", "text/plain".into());
+    object.insert("Accept", "*/*".into());
+
+    assert_eq!(
+        object.get_key_value("Content-Type"),
+        Some((&"Content-Type".to_string(), &JsonValue::from("text/plain")))
+    );
+    assert_eq!(object.get_key_value("content-type"), None);
+
+    assert_eq!(object.remove_entry("Content-Type"), Some(("Content-Type".to_string(), "text/plain".into())));
+    assert_eq!(object.remove_entry("Content-Type"), None);
+    assert_eq!(object.dump(), r#"{"Accept":"*/*"}"#);
+}
+
+#[test]
+fn object_from_iterator_and_extend() {
+    use json::object::Object;
+
+    let mut object: Object = vec![("a", 1), ("b", 2)].into_iter().collect();
+
+    object.extend(vec![(String::from("c"), "three")]);
+    object.extend(Some(("a", Null)));
+
+    assert_eq!(object.dump(), r#"{"a":null,"b":2,"c":"three"}"#);
+
+    let squares: Object = (1..4).map(|n: i32| (n.to_string(), n * n)).collect();
+
+    assert_eq!(JsonValue::Object(squares), object!{ "1" => 1, "2" => 4, "3" => 9 });
+}
+
+#[test]
+fn object_insert_owned() {
+    use json::object::Object;
+
+    let mut object = Object::new();
+    let key = String::from("owned");
+    let pointer = key.as_ptr();
+
+    object.insert_owned(key, 1.into());
+
+    assert_eq!(object.keys().next().unwrap().as_ptr(), pointer);
+    assert_eq!(object["owned"], 1);
+
+    let key = String::from("moved");
+    let borrowed = String::from("borrowed");
+    let data = object!{ key => true, &borrowed => false, "literal" => Null };
+
+    assert_eq!(data.dump(), r#"{"moved":true,"borrowed":false,"literal":null}"#);
+}
+
+#[test]
+fn object_capacity() {
+    use json::object::Object;
+
+    let mut object = Object::new();
+    object.reserve(100);
+
+    assert!(object.capacity() >= 100);
+
+    object.insert("a", Null);
+    object.shrink_to_fit();
+
+    assert!(object.capacity() < 100);
+    assert_eq!(object.len(), 1);
+}
+
+#[test]
+fn object_index_access() {
+    let mut data = object!{ "a" => 1, "b" => 2, "c" => 3, "d" => 4 };
+
+    if let JsonValue::Object(ref mut object) = data {
+        if let Some((key, value)) = object.get_index_mut(1) {
+            assert_eq!(key, "b");
+            *value = 20.into();
+        }
+
+        assert!(object.get_index_mut(4).is_none());
+        assert_eq!(object.swap_remove_index(0), Some(("a".to_string(), 1.into())));
+        assert_eq!(object.shift_remove_index(1), Some(("b".to_string(), 20.into())));
+        assert_eq!(object.shift_remove_index(2), None);
+    }
+
+    assert_eq!(data.dump(), r#"{"d":4,"c":3}"#);
+}
+
+#[test]
+fn hash_consistent_with_eq() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashSet;
+    use std::hash::{ Hash, Hasher };
+    use json::number::{ Number, NAN };
+
+    fn hash(value: &JsonValue) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let pairs = vec![
+        (JsonValue::from("short"), JsonValue::String("short".into())),
+        (JsonValue::from(1.5), parse("1.50").unwrap()),
+        (JsonValue::from(100), parse("1e2").unwrap()),
+        (JsonValue::from(0.0), JsonValue::from(-0.0)),
+        (JsonValue::from(Number::from_parts(true, 0, 5)), JsonValue::from(0)),
+        (JsonValue::from(NAN), JsonValue::from(NAN)),
+        (object!{ "a" => 1, "b" => array![1, 2] }, object!{ "b" => array![1, 2], "a" => 1 }),
+    ];
+
+    for (a, b) in &pairs {
+        assert_eq!(a, b);
+        assert_eq!(hash(a), hash(b), "{} and {} should hash the same", a, b);
+    }
+
+    let set: HashSet<JsonValue> = vec![
+        array![1, 2],
+        array![2, 1],
+        array![1, 2],
+        object!{ "a" => Null },
+        object!{ "a" => false },
+        JsonValue::from("1"),
+        JsonValue::from(1),
+    ].into_iter().collect();
+
+    assert_eq!(set.len(), 6);
+}
+
+#[test]
+fn content_hash() {
+    use json::number::{ Number, NAN };
+
+    let pairs = vec![
+        (JsonValue::from("short"), JsonValue::String("short".into())),
+        (JsonValue::from(1.5), parse("1.50").unwrap()),
+        (JsonValue::from(100), parse("1e2").unwrap()),
+        (JsonValue::from(0.0), JsonValue::from(-0.0)),
+        (JsonValue::from(Number::from_parts(true, 0, 5)), JsonValue::from(0)),
+        (JsonValue::from(NAN), JsonValue::from(NAN)),
+        (object!{ "a" => 1, "b" => array![1, 2] }, object!{ "b" => array![1, 2], "a" => 1 }),
+        (JsonValue::Raw(json::RawJson::new("[1, 2]").unwrap()), array![1, 2]),
+    ];
+
+    for (a, b) in &pairs {
+        assert_eq!(a.content_hash_128(), b.content_hash_128(), "{} and {} should hash the same", a, b);
+        assert_eq!(a.content_hash(), b.content_hash());
+    }
+
+    let distinct = vec![
+        array![1, 2],
+        array![2, 1],
+        array![array![1], 2],
+        object!{ "a" => Null },
+        object!{ "a" => false },
+        object!{ "ab" => "c" },
+        object!{ "a" => "bc" },
+        JsonValue::from("1"),
+        JsonValue::from(1),
+        JsonValue::from(-1),
+        JsonValue::from(10),
+        Null,
+    ];
+
+    for (index, a) in distinct.iter().enumerate() {
+        for b in &distinct[index + 1..] {
+            assert_ne!(a.content_hash_128(), b.content_hash_128(), "{} and {} should hash differently", a, b);
+        }
+    }
+
+    // Digests are stable, and must never change.
+    assert_eq!(Null.content_hash_128(), 0xd228cb69101a8caf78912b704e4a147f);
+    assert_eq!(array![Null, "a"].content_hash_128(), 0x388afba0398bd4c5ba7f0c978b8398a4);
+    assert_eq!(array![Null, "a"].content_hash(), 0x82f5f737b2084c61);
+}
+
+#[test]
+fn replace() {
+    let mut data = object!{ "tree" => array![1, 2, 3] };
+
+    let tree = data["tree"].replace(array![]);
+
+    assert_eq!(tree, array![1, 2, 3]);
+    assert_eq!(data, object!{ "tree" => array![] });
+
+    let old = data.replace(Null);
+
+    assert_eq!(old, object!{ "tree" => array![] });
+    assert!(data.is_null());
+}
+
+#[test]
+fn merge_strategies() {
+    use json::{ MergeStrategy, ArrayMerge, NullMerge };
+
+    let base = object!{
+        "list" => array![object!{ "a" => 1 }, 2],
+        "nested" => object!{ "keep" => true, "drop" => true },
+        "scalar" => "old"
+    };
+    let layer = object!{
+        "list" => array![object!{ "b" => 2 }, 3, 4],
+        "nested" => object!{ "drop" => Null, "new" => 1 },
+        "scalar" => array![]
+    };
+
+    let merged = |strategy| {
+        let mut value = base.clone();
+        value.merge(layer.clone(), strategy);
+        value
+    };
+
+    assert_eq!(merged(MergeStrategy::default()), object!{
+        "list" => array![object!{ "b" => 2 }, 3, 4],
+        "nested" => object!{ "keep" => true, "drop" => Null, "new" => 1 },
+        "scalar" => array![]
+    });
+
+    assert_eq!(merged(MergeStrategy { arrays: ArrayMerge::Concat, nulls: NullMerge::Remove }), object!{
+        "list" => array![object!{ "a" => 1 }, 2, object!{ "b" => 2 }, 3, 4],
+        "nested" => object!{ "keep" => true, "new" => 1 },
+        "scalar" => array![]
+    });
+
+    assert_eq!(merged(MergeStrategy { arrays: ArrayMerge::ByIndex, nulls: NullMerge::Ignore }), object!{
+        "list" => array![object!{ "a" => 1, "b" => 2 }, 3, 4],
+        "nested" => object!{ "keep" => true, "drop" => true, "new" => 1 },
+        "scalar" => array![]
+    });
+
+    let mut value = Null;
+    value.merge(object!{ "a" => Null }, MergeStrategy { nulls: NullMerge::Ignore, ..MergeStrategy::default() });
+
+    assert_eq!(value, object!{});
+}
+
+#[test]
+fn flatten_and_unflatten() {
+    let data = object!{
+        "a" => object!{ "b" => array![1, object!{ "c" => true }], "d" => object!{} },
+        "e" => Null
+    };
+
+    let flat = data.flatten();
+
+    assert_eq!(flat.dump(), r#"{"a.b[0]":1,"a.b[1].c":true,"a.d":{},"e":null}"#);
+    assert_eq!(flat.unflatten().unwrap(), data);
+
+    assert_eq!(array![1, array![2]].flatten(), object!{ "[0]" => 1, "[1][0]" => 2 });
+    assert_eq!(object!{ "[0]" => 1, "[1][0]" => 2 }.unflatten().unwrap(), array![1, array![2]]);
+    assert_eq!(JsonValue::from(5).flatten(), object!{ "" => 5 });
+    assert_eq!(object!{ "" => 5 }.unflatten().unwrap(), 5);
+
+    let invalid = object!{ "list[0]" => "c", "bad[x]" => 1, "a..b" => 2 };
+
+    assert_eq!(invalid.unflatten().unwrap(), object!{
+        "list" => array!["c"],
+        "bad[x]" => 1,
+        "a..b" => 2
+    });
+}
+
+#[test]
+fn unflatten_rejects_gaps_and_conflicts() {
+    let huge = json::parse(r#"{"a[99999999999]":1}"#).unwrap();
+
+    assert_eq!(huge.unflatten(), Err(json::Error::InvalidPath("a[99999999999]".into())));
+    assert_eq!(
+        object!{ "list[0]" => 1, "list[2]" => 3 }.unflatten(),
+        Err(json::Error::InvalidPath("list[2]".into()))
+    );
+
+    // Keys that would overwrite each other, in either order.
+    assert_eq!(object!{ "a" => 1, "a.b" => 2 }.unflatten(), Err(json::Error::InvalidPath("a.b".into())));
+    assert_eq!(object!{ "a.b" => 2, "a" => 1 }.unflatten(), Err(json::Error::InvalidPath("a".into())));
+    assert_eq!(object!{ "a" => Null, "a[0]" => 1 }.unflatten(), Err(json::Error::InvalidPath("a[0]".into())));
+    assert_eq!(object!{ "a.b" => 1, "a[0]" => 2 }.unflatten(), Err(json::Error::InvalidPath("a[0]".into())));
+    assert_eq!(object!{ "a[0]" => 1, "a[00]" => 2 }.unflatten(), Err(json::Error::InvalidPath("a[00]".into())));
+    assert_eq!(object!{ "" => 5, "a" => 1 }.unflatten(), Err(json::Error::InvalidPath("a".into())));
+}
+
+#[test]
+fn walk() {
+    use json::PathSegment::{ Key, Index };
+
+    let data = object!{ "a" => array![1, object!{ "b/c" => true }], "d" => Null };
+
+    let paths: Vec<String> = data.walk().map(|(path, _)| path.to_string()).collect();
+
+    assert_eq!(paths, vec!["", "/a", "/a/0", "/a/1", "/a/1/b~1c", "/d"]);
+
+    let (path, value) = data.walk().nth(4).unwrap();
+
+    assert_eq!(path.segments(), &[Key("a".into()), Index(1), Key("b/c".into())]);
+    assert_eq!(*value, true);
+    assert_eq!(JsonValue::from(5).walk().count(), 1);
+}
+
+#[test]
+fn walk_mut() {
+    let mut data = object!{ "a" => array![1, 2], "b" => object!{ "c" => 3 } };
+    let mut visited = Vec::new();
+
+    data.walk_mut(|path, value| {
+        visited.push(path.to_string());
+
+        if let Some(number) = value.as_i32() {
+            *value = (number * 10).into();
+        }
+    });
+
+    assert_eq!(visited, vec!["", "/a", "/a/0", "/a/1", "/b", "/b/c"]);
+    assert_eq!(data, object!{ "a" => array![10, 20], "b" => object!{ "c" => 30 } });
+}
+
+#[test]
+fn stats() {
+    let data = json::parse(r#"
+        { "a": [1, 2.5, [true, null, []]], "long": "a string longer than thirty-two bytes", "o": {} }
+    "#).unwrap();
+
+    let stats = data.stats();
+
+    assert_eq!(stats.nulls, 1);
+    assert_eq!(stats.strings, 1);
+    assert_eq!(stats.numbers, 2);
+    assert_eq!(stats.booleans, 1);
+    assert_eq!(stats.objects, 2);
+    assert_eq!(stats.arrays, 3);
+    assert_eq!(stats.nodes(), 10);
+    assert_eq!(stats.max_depth, 4);
+    assert_eq!(stats.string_bytes, 37);
+    assert_eq!(stats.largest_array, 3);
+    assert_eq!(stats.largest_object, 3);
+
+    assert_eq!(JsonValue::from(5).stats().max_depth, 0);
+    assert_eq!(JsonValue::Raw(json::RawJson::new("[[1]]").unwrap()).stats().max_depth, 2);
+}
+
+#[test]
+fn diff_report() {
+    use json::{ Difference, JsonPath };
+    use json::PathSegment::{ Key, Index };
+
+    let old = object!{ "a/b" => 1, "list" => array![1, 2, 3], "o" => object!{ "x" => Null } };
+    let new = object!{ "a/b" => "1", "list" => array![1, 5], "o" => array![], "z" => false };
+
+    assert_eq!(json::diff_report(&old, &new), [
+        Difference::Changed { path: JsonPath::from(vec![Key("a/b".into())]), old: 1.into(), new: "1".into() },
+        Difference::Changed { path: JsonPath::from(vec![Key("list".into()), Index(1)]), old: 2.into(), new: 5.into() },
+        Difference::Removed { path: JsonPath::from(vec![Key("list".into()), Index(2)]), value: 3.into() },
+        Difference::Changed { path: JsonPath::from(vec![Key("o".into())]), old: object!{ "x" => Null }, new: array![] },
+        Difference::Added { path: JsonPath::from(vec![Key("z".into())]), value: false.into() },
+    ]);
+
+    let lines: Vec<String> = json::diff_report(&old, &new).iter().map(ToString::to_string).collect();
+
+    assert_eq!(lines, [
+        r#"/a~1b: changed 1 to "1""#,
+        "/list/1: changed 2 to 5",
+        "/list/2: removed 3",
+        r#"/o: changed {"x":null} to []"#,
+        "/z: added false",
+    ]);
+
+    assert!(json::diff_report(&old, &old.clone()).is_empty());
+    assert_eq!(json::diff_report(&1.into(), &2.into())[0].to_string(), "(root): changed 1 to 2");
+}
+
+#[test]
+fn transform() {
+    use json::Visit;
+
+    let mut data = object!{
+        "a" => array![1, Null, 3, Null],
+        "b" => object!{ "c" => Null, "d" => "x" },
+        "e" => object!{ "f" => Null }
+    };
+    let mut removed = Vec::new();
+
+    data.transform(|path, value| {
+        if path.to_string() == "/e" {
+            return Visit::Skip;
+        }
+        if value.is_null() {
+            removed.push(path.to_string());
+            return Visit::Remove;
+        }
+        if let Some(number) = value.as_i32() {
+            *value = (number * 2).into();
+        }
+        Visit::Continue
+    });
+
+    assert_eq!(removed, vec!["/a/1", "/a/3", "/b/c"]);
+    assert_eq!(data, object!{
+        "a" => array![2, 6],
+        "b" => object!{ "d" => "x" },
+        "e" => object!{ "f" => Null }
+    });
+
+    let mut root = array![1];
+
+    root.transform(|_, _| Visit::Remove);
+
+    assert_eq!(root, Null);
+}
+
+#[test]
+fn transform_with_visitor() {
+    use json::{ JsonPath, Visit, Visitor };
+
+    struct Counter(usize);
+
+    impl Visitor for Counter {
+        fn visit(&mut self, _: &JsonPath, value: &mut JsonValue) -> Visit {
+            if value.is_string() {
+                self.0 += 1;
+                *value = "***".into();
+            }
+            Visit::Continue
+        }
+    }
+
+    let mut data = object!{ "a" => "x", "b" => array!["y", 1] };
+    let mut counter = Counter(0);
+
+    data.transform_with(&mut counter);
+
+    assert_eq!(counter.0, 2);
+    assert_eq!(data, object!{ "a" => "***", "b" => array!["***", 1] });
+}
+
+#[test]
+fn get_and_set_path() {
+    use json::PathSegment::{ Key, Index };
+
+    let mut data = object!{ "a" => array![1, object!{ "b" => "c" }], "d" => 5 };
+
+    assert_eq!(data.get_path(&[]), Some(&data.clone()));
+    assert_eq!(data.get_path(&[Key("a".into()), Index(1), Key("b".into())]).unwrap(), "c");
+    assert_eq!(data.get_path(&[Key("a".into()), Index(2)]), None);
+    assert_eq!(data.get_path(&[Key("d".into()), Key("e".into())]), None);
+    assert_eq!(data.get_path(&[Index(0)]), None);
+
+    *data.get_path_mut(&[Key("a".into()), Index(0)]).unwrap() = 10.into();
+    data.set_path(&[Key("d".into()), Key("e".into())], true).unwrap();
+    data.set_path(&[Key("f".into()), Index(0)], "g").unwrap();
+    data.set_path(&[Key("f".into()), Index(1)], "h").unwrap();
+
+    assert_eq!(data, object!{
+        "a" => array![10, object!{ "b" => "c" }],
+        "d" => object!{ "e" => true },
+        "f" => array!["g", "h"]
+    });
+
+    let error = data.set_path(&[Key("f".into()), Index(99999999999)], 1).unwrap_err();
+
+    assert_eq!(error, json::Error::InvalidPath("f[99999999999]".into()));
+    assert_eq!(data["f"].len(), 2);
+
+    let walked: Vec<_> = data.walk().map(|(path, _)| path).collect();
+
+    for path in walked {
+        assert!(data.get_path(path.segments()).is_some());
+    }
+}
+
+#[test]
+fn dot_get_and_set() {
+    let mut data = object!{ "a" => object!{ "b" => array![1, 2, object!{ "c" => "d" }] } };
+
+    assert_eq!(data.dot_get("a.b[2].c").unwrap(), "d");
+    assert_eq!(data.dot_get("a.b[1]").unwrap(), 2);
+    assert_eq!(data.dot_get(""), Some(&data.clone()));
+    assert_eq!(data.dot_get("a.b[3]"), None);
+    assert_eq!(data.dot_get("a..b"), None);
+    assert_eq!(data.dot_get("a.b[-1]"), None);
+
+    *data.dot_get_mut("a.b[0]").unwrap() = 10.into();
+    data.dot_set("a.e", true).unwrap();
+
+    assert_eq!(data, object!{ "a" => object!{ "b" => array![10, 2, object!{ "c" => "d" }], "e" => true } });
+
+    let error = data.dot_set("a.", 1).unwrap_err();
+
+    assert_eq!(error, json::Error::InvalidPath("a.".into()));
+    assert_eq!(error.code(), 13);
+    assert_eq!(error.code_name(), "invalid_path");
+    assert_eq!(error.to_string(), "Invalid path: a.");
+
+    assert!(data.dot_set("a.b[4]", 1).is_err());
+    assert_eq!(data.dot_get("a.b").unwrap().len(), 3);
+}
+
+#[test]
+fn try_from() {
+    use std::convert::TryFrom;
+    use std::collections::HashMap;
+
+    let data = object!{
+        "int" => 42,
+        "float" => 1.5,
+        "flag" => true,
+        "name" => "json",
+        "list" => array![1, 2, 3],
+        "map" => object!{ "a" => 1, "b" => "two" }
+    };
+
+    assert_eq!(u8::try_from(&data["int"]), Ok(42));
+    assert_eq!(i64::try_from(data["int"].clone()), Ok(42));
+    assert_eq!(f64::try_from(&data["float"]), Ok(1.5));
+    assert_eq!(bool::try_from(&data["flag"]), Ok(true));
+    assert_eq!(String::try_from(&data["name"]), Ok("json".to_string()));
+    assert_eq!(String::try_from(data["name"].clone()), Ok("json".to_string()));
+    assert_eq!(Vec::<u32>::try_from(&data["list"]), Ok(vec![1, 2, 3]));
+
+    let error = u8::try_from(&data["float"]).unwrap_err();
+
+    assert_eq!(error.expected(), "u8");
+    assert_eq!(error.actual(), "number");
+
+    let error = HashMap::<String, i32>::try_from(data["map"].clone()).unwrap_err();
+
+    assert_eq!(error.path(), "/b");
+    assert_eq!(error.actual(), "string");
+
+    let error = String::try_from(JsonValue::from(5)).unwrap_err();
+
+    assert_eq!(error.to_string(), "Expected string, found number at \"\"");
+}
+
+#[test]
+fn typed_accessors_or_err() {
+    let data = object!{ "name" => "json", "count" => 3, "ratio" => 0.5, "flag" => true };
+
+    assert_eq!(data["name"].as_str_or_err(), Ok("json"));
+    assert_eq!(data["count"].as_u64_or_err(), Ok(3));
+    assert_eq!(data["count"].as_i64_or_err(), Ok(3));
+    assert_eq!(data["ratio"].as_f64_or_err(), Ok(0.5));
+    assert_eq!(data["flag"].as_bool_or_err(), Ok(true));
+    assert_eq!(data["count"].expect_type::<Option<u8>>(), Ok(Some(3)));
+
+    let error = data["missing"].as_str_or_err().unwrap_err();
+
+    assert_eq!(error.expected(), "string");
+    assert_eq!(error.actual(), "null");
+
+    let error = data["ratio"].as_u64_or_err().unwrap_err();
+
+    assert_eq!(error.expected(), "u64");
+    assert_eq!(error.actual(), "number");
+    assert_eq!(data["name"].as_number_or_err().unwrap_err().to_string(), "Expected number, found string at \"\"");
+}
+
+#[test]
+fn std_collections_round_trip() {
+    use std::convert::TryInto;
+    use std::collections::{ BTreeMap, HashMap };
+
+    let mut hash = HashMap::new();
+    hash.insert("a".to_string(), vec![1, 2]);
+
+    let value = JsonValue::from(hash.clone());
+
+    assert_eq!(value, object!{ "a" => array![1, 2] });
+
+    let back: HashMap<String, Vec<i32>> = value.try_into().unwrap();
+
+    assert_eq!(back, hash);
+
+    let mut tree = BTreeMap::new();
+    tree.insert("b".to_string(), "x");
+    tree.insert("a".to_string(), "y");
+
+    let value = JsonValue::from(tree);
+
+    assert_eq!(value.dump(), r#"{"a":"y","b":"x"}"#);
+
+    let back: BTreeMap<String, String> = (&value).try_into().unwrap();
+
+    assert_eq!(back.get("a").map(String::as_str), Some("y"));
+
+    let list: Vec<f64> = JsonValue::from(vec![1.5, 2.5]).try_into().unwrap();
+
+    assert_eq!(list, vec![1.5, 2.5]);
+
+    let error = TryInto::<Vec<bool>>::try_into(array![true, 1]).unwrap_err();
+
+    assert_eq!(error.path(), "/1");
+}
+
+#[cfg(feature = "shared_strings")]
+#[test]
+fn shared_strings_clone() {
+    let long = "a string too long to be stored inline ".repeat(3);
+    let data = array![&*long, "short"];
+    let copy = data.clone();
+
+    match (&data[0], &copy[0]) {
+        (JsonValue::String(a), JsonValue::String(b)) => assert!(std::sync::Arc::ptr_eq(a, b)),
+        _ => panic!("Expected long strings"),
+    }
+
+    let mut copy = copy;
+    copy[0].clear();
+
+    assert_eq!(copy[0], "");
+    assert_eq!(data[0], &*long);
+    assert_eq!(data[0].clone().take_string().unwrap(), long);
+}
+
+#[test]
+fn object_from_index_map() {
+    use indexmap::IndexMap;
+    use json::object::Object;
+
+    let mut map = IndexMap::new();
+
+    map.insert("b".to_string(), JsonValue::from(1));
+    map.insert("a".to_string(), JsonValue::from(2));
+
+    let object = Object::from(map.clone());
+
+    assert_eq!(object.get("a").unwrap(), 2);
+    assert_eq!(JsonValue::from(map), JsonValue::Object(object));
+    assert_eq!(JsonValue::Object(Object::from(IndexMap::new())).dump(), "{}");
+}
+
+#[cfg(feature = "hash_object")]
+#[test]
+fn hash_object_lookups() {
+    use json::short::Short;
+
+    let mut data = object!{ "c" => 1, "a" => 2, "b" => 3 };
+
+    data["d"] = 4.into();
+
+    assert_eq!(data.dump(), r#"{"c":1,"a":2,"b":3,"d":4}"#);
+    assert_eq!(data, json::parse(&data.dump()).unwrap());
+
+    if let JsonValue::Object(ref mut object) = data {
+        assert_eq!(object[&Short::new("b")], 3);
+        assert_eq!(object.get_index(1), Some(("a", &2.into())));
+
+        object.sort_keys();
+
+        assert_eq!(object.key_at(0), Some("a"));
+        assert_eq!(object.remove_entry("c"), Some(("c".into(), 1.into())));
+        assert_eq!(object.drain(..).count(), 3);
+        assert!(object.is_empty());
+    }
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "xml")]
+
+#[macro_use]
+extern crate json;
+
+use json::{ xml, Error, Null };
+
+#[test]
+fn xml_parse() {
+    let data = xml::parse(r#"<?xml version="1.0"?>
+<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/">
+    <!-- comment -->
+    <soap:Body>
+        <GetPrice xmlns="http://example.com/prices">
+            <Item>Apple &amp; Pear</Item>
+            <Code><![CDATA[<A1>]]></Code>
+            <Empty/>
+            <Note lang="en">  Fresh  </Note>
+        </GetPrice>
+    </soap:Body>
+</soap:Envelope>"#).unwrap();
+
+    assert_eq!(data, object!{
+        "soap:Envelope" => object!{
+            "@xmlns:soap" => "http://schemas.xmlsoap.org/soap/envelope/",
+            "soap:Body" => object!{
+                "GetPrice" => object!{
+                    "@xmlns" => "http://example.com/prices",
+                    "Item" => "Apple & Pear",
+                    "Code" => "<A1>",
+                    "Empty" => Null,
+                    "Note" => object!{ "@lang" => "en", "#text" => "Fresh" }
+                }
+            }
+        }
+    });
+}
+
+#[test]
+fn xml_parse_repeated_elements() {
+    let data = xml::parse("<list><a>1</a><b/><a>2</a><a>3</a>text</list>").unwrap();
+
+    assert_eq!(data, object!{
+        "list" => object!{ "a" => array!["1", "2", "3"], "b" => Null, "#text" => "text" }
+    });
+}
+
+#[test]
+fn xml_parse_errors() {
+    assert!(match xml::parse("<a><b></a>") {
+        Err(Error::InvalidXml(_)) => true,
+        _                         => false,
+    });
+
+    assert!(match xml::parse("") {
+        Err(Error::InvalidXml(_)) => true,
+        _                         => false,
+    });
+}
+
+#[test]
+fn xml_to_string() {
+    let data = object!{
+        "root" => object!{
+            "@id" => 7,
+            "@title" => "\"quoted\" & <b>",
+            "#text" => "a < b",
+            "flag" => true,
+            "list" => array![object!{ "@n" => 1 }, "two"]
+        }
+    };
+
+    assert_eq!(
+        xml::to_xml_string(&data).unwrap(),
+        r#"<root id="7" title="&quot;quoted&quot; &amp; &lt;b&gt;">a &lt; b<flag>true</flag><list n="1"/><list>two</list></root>"#
+    );
+}
+
+#[test]
+fn xml_round_trip() {
+    let source = r#"<a xmlns:x="urn:x" k="v"><x:b>1</x:b><x:b>2</x:b><c/></a>"#;
+    let data = xml::parse(source).unwrap();
+
+    assert_eq!(xml::to_xml_string(&data).unwrap(), source);
+}
+
+#[test]
+fn xml_to_string_errors() {
+    assert_eq!(
+        xml::to_xml_string(&array![]),
+        Err(Error::InvalidXml("document that is not an object with a single key".into()))
+    );
+    assert_eq!(
+        xml::to_xml_string(&object!{ "a" => array![1, 2] }),
+        Err(Error::InvalidXml("more than one root element".into()))
+    );
+    assert_eq!(
+        xml::to_xml_string(&object!{ "a" => object!{ "@b" => array![] } }),
+        Err(Error::InvalidXml("attribute @b that is not a scalar".into()))
+    );
+    assert_eq!(
+        xml::to_xml_string(&object!{ "a" => object!{ "b" => array![array![]] } }),
+        Err(Error::InvalidXml("nested array in element b".into()))
+    );
+}
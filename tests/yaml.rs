@@ -0,0 +1,95 @@
+#![cfg(feature = "yaml")]
+
+#[macro_use]
+extern crate json;
+
+use json::{ yaml, Error, JsonValue, Null };
+use json::number::{ NAN, INFINITY };
+
+#[test]
+fn yaml_scalars() {
+    let data = yaml::parse("
+int: 42
+negative: -7
+hex: 0x1F
+float: 1.5
+exponent: 1e3
+inf: .inf
+nan: .nan
+yes: true
+no: false
+tilde: ~
+null: null
+quoted: '42'
+plain: hello world
+").unwrap();
+
+    assert_eq!(data, object!{
+        "int" => 42,
+        "negative" => -7,
+        "hex" => 31,
+        "float" => 1.5,
+        "exponent" => 1000,
+        "inf" => JsonValue::Number(INFINITY),
+        "nan" => JsonValue::Number(NAN),
+        "yes" => true,
+        "no" => false,
+        "tilde" => Null,
+        "null" => Null,
+        "quoted" => "42",
+        "plain" => "hello world"
+    });
+}
+
+#[test]
+fn yaml_anchors_and_keys() {
+    let data = yaml::parse("
+base: &base { a: 1, b: 2 }
+extra: &extra { c: 3, a: 10 }
+copy: *base
+merged:
+  <<: [*base, *extra]
+  b: 20
+1: one
+true: yes
+~: nothing
+").unwrap();
+
+    assert_eq!(data["copy"], object!{ "a" => 1, "b" => 2 });
+    assert_eq!(data["merged"], object!{ "b" => 20, "a" => 1, "c" => 3 });
+    assert_eq!(data["1"], "one");
+    assert_eq!(data["true"], "yes");
+    assert_eq!(data["null"], "nothing");
+}
+
+#[test]
+fn yaml_errors() {
+    assert_eq!(yaml::parse(""), Ok(Null));
+    assert_eq!(yaml::parse("[1, 2]"), Ok(array![1, 2]));
+    assert_eq!(yaml::parse("? [1, 2]\n: value"), Err(Error::InvalidYaml("key that is not a scalar".into())));
+    assert_eq!(yaml::parse("a: !!int abc"), Err(Error::InvalidYaml("value that doesn't match its tag".into())));
+    assert_eq!(yaml::parse("a: *missing"), Err(Error::InvalidYaml("while parsing node, found unknown anchor at line 1 column 4".into())));
+    assert_eq!(yaml::parse("a: 1\n---\nb: 2"), Err(Error::InvalidYaml("more than one document".into())));
+    assert_eq!(yaml::parse("a: [1"), Err(Error::InvalidYaml("while parsing a flow sequence, expected ',' or ']' at line 2 column 1".into())));
+
+    let error = yaml::parse("a: { <<: 5 }").unwrap_err();
+
+    assert_eq!(error.code(), 15);
+    assert_eq!(error.code_name(), "invalid_yaml");
+    assert_eq!(error.to_string(), "Invalid YAML: merge of a value that is not a mapping");
+}
+
+#[test]
+fn yaml_round_trip() {
+    let data = object!{
+        "name" => "json",
+        "numbers" => array![1, -2, 0.5, 1e300, 18446744073709551615u64],
+        "strings" => array!["true", "null", "42", "", "multi\nline", "quote's"],
+        "nested" => object!{ "empty" => array![], "none" => Null, "flag" => false }
+    };
+
+    let text = yaml::to_yaml_string(&data);
+
+    assert!(text.starts_with("---\nname: json\n"));
+    assert_eq!(yaml::parse(&text).unwrap(), data);
+}